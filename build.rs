@@ -1,5 +1,9 @@
 fn main() {
-    let config = slint_build::CompilerConfiguration::new()
-        .with_style("fluent".to_string());
-    slint_build::compile_with_config("ui/main.slint", config).unwrap();
-}
\ No newline at end of file
+    // 只有桌面界面需要编译 .slint，无界面构建跳过
+    #[cfg(feature = "gui")]
+    {
+        let config = slint_build::CompilerConfiguration::new()
+            .with_style("fluent".to_string());
+        slint_build::compile_with_config("ui/main.slint", config).unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::{Arc, Mutex};
 use slint::ComponentHandle;
-use crate::controllers::{HistoryControllerPointer, DocumentController};
+use crate::controllers::{AnnotationController, ArchiveController, AssistantController, AutoTurnController, BookmarkController, ClipboardController, CropController, HistoryControllerPointer, HistoryExportController, DocumentController, HomeController, GestureController, IdleController, LibraryController, LinkPreviewController, MenuController, MetadataController, PageMenuController, PowerController, PreviewController, RedactionController, SeriesController, ShareController, SimpleModeController, StorageController, TaskController, ThemeController, TimerController, ToolbarController, UiScaleController, VocabController, WatermarkController, WindowProfileController};
 use crate::controllers::history_controller::DefaultHistoryController;
 use crate::ui::MainViewmodel;
 use crate::tts::TtsService;
@@ -12,16 +12,31 @@ use crate::AppWindow;
 pub struct AppHandler {
     history_controller: HistoryControllerPointer,
     document_controller: Rc<RefCell<DocumentController>>,
+    menu_controller: MenuController,
+    library_controller: LibraryController,
+    gesture_controller: GestureController,
+    preview_controller: PreviewController,
+    viewmodel: Rc<RefCell<MainViewmodel>>,
 }
 
 impl AppHandler {
     pub fn new(viewmodel: Rc<RefCell<MainViewmodel>>, tts_service: Arc<Mutex<TtsService>>) -> Self {
         let document_controller = Rc::new(RefCell::new(DocumentController::new(viewmodel.clone(), Arc::clone(&tts_service))));
-        let history_controller: HistoryControllerPointer = Box::new(DefaultHistoryController::new(viewmodel, Rc::clone(&document_controller)));
+        let history_controller: HistoryControllerPointer = Box::new(DefaultHistoryController::new(viewmodel.clone(), Rc::clone(&document_controller)));
+        let library_controller = LibraryController::new(viewmodel.clone(), Rc::clone(&document_controller));
+
+        let menu_controller = MenuController::new(Rc::clone(&document_controller));
+        let gesture_controller = GestureController::new(Rc::clone(&document_controller));
+        let preview_controller = PreviewController::new(Rc::clone(&document_controller));
 
         Self {
             history_controller,
             document_controller,
+            menu_controller,
+            library_controller,
+            gesture_controller,
+            preview_controller,
+            viewmodel,
         }
     }
 
@@ -29,10 +44,42 @@ impl AppHandler {
         self.history_controller.setup_history_callbacks(window);
 
         self.document_controller.borrow().initialize_ui(window);
+        self.menu_controller.setup_menu_callbacks(window);
+        self.gesture_controller.setup_gesture_callbacks(window);
+        self.preview_controller.setup_preview_callbacks(window);
+        ToolbarController::setup_toolbar_callbacks(window);
+        ThemeController::apply(window, &self.document_controller);
+        SimpleModeController::apply(window);
+        StorageController::apply(window);
+        UiScaleController::apply(window);
+        ClipboardController::setup_clipboard_callbacks(window, &self.document_controller);
+        ShareController::setup_share_callbacks(window);
+        SeriesController::setup_series_callbacks(window, &self.document_controller);
+        ArchiveController::setup_archive_callbacks(window);
+        HomeController::setup_home_callbacks(window, &self.viewmodel, &self.document_controller);
+        PageMenuController::setup_page_menu_callbacks(window, &self.document_controller);
+        LinkPreviewController::setup_link_preview_callbacks(window, &self.document_controller);
+        CropController::setup_crop_callbacks(window, &self.document_controller);
+        AssistantController::setup_assistant_callbacks(window);
+        VocabController::setup_vocab_callbacks(window);
+        HistoryExportController::setup_history_export_callbacks(window);
+        WatermarkController::setup_watermark_callbacks(window);
+        MetadataController::setup_metadata_callbacks(window);
+        BookmarkController::setup_bookmark_callbacks(window, &self.document_controller);
+        AnnotationController::setup_annotation_callbacks(window, &self.document_controller);
+        RedactionController::setup_redaction_callbacks(window, &self.document_controller);
+        TimerController::setup_timer_callbacks(window);
+        IdleController::setup_idle_callbacks(window, &self.document_controller);
+        AutoTurnController::setup_auto_turn_callbacks(window, &self.document_controller);
+        PowerController::setup_power_callbacks(&self.document_controller);
+        WindowProfileController::setup_window_profile_callbacks(window, &self.document_controller);
+        TaskController::setup_task_callbacks(window);
+        self.library_controller.setup_library_callbacks(window);
 
         if let Err(e) = self.history_controller.refresh_history_ui(window) {
             log::error!("Failed to refresh history UI: {}", e);
         }
+        self.library_controller.initialize_ui(window);
     }
 
     pub fn document_controller(&self) -> Rc<RefCell<DocumentController>> {
@@ -45,6 +92,7 @@ impl AppHandler {
 
     pub fn save(&self) {
         log::debug!("保存应用状态");
+        TimerController::flush();
     }
 
     pub fn reload(&self) {
@@ -0,0 +1,240 @@
+use log::{error, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+use crate::decoder::comic::ComicArchive;
+
+/// 应用目录名
+const APP_DIR_NAME: &str = "RReader";
+
+/// 便携模式命令行参数
+pub const PORTABLE_FLAG: &str = "--portable";
+/// 可执行文件旁存在该文件时自动进入便携模式
+const PORTABLE_MARKER: &str = "portable.ini";
+/// 便携模式下的状态目录，位于可执行文件旁
+const PORTABLE_DIR_NAME: &str = "RReaderData";
+
+/// 便携模式根目录，启动时确定一次，非便携模式为 None
+static PORTABLE_ROOT: LazyLock<Option<PathBuf>> = LazyLock::new(detect_portable_root);
+
+fn detect_portable_root() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    let by_flag = std::env::args().skip(1).any(|arg| arg == PORTABLE_FLAG);
+    let by_marker = exe_dir.join(PORTABLE_MARKER).is_file();
+    if !by_flag && !by_marker {
+        return None;
+    }
+    let root = exe_dir.join(PORTABLE_DIR_NAME);
+    info!("[AppPaths] 便携模式，状态目录: {:?}", root);
+    Some(root)
+}
+
+/// 是否运行在便携模式
+pub fn is_portable() -> bool {
+    PORTABLE_ROOT.is_some()
+}
+
+/// 缩略图缓存上限
+const THUMBNAIL_QUOTA: u64 = 200 * 1024 * 1024;
+/// reflow 缓存上限
+const REFLOW_QUOTA: u64 = 500 * 1024 * 1024;
+/// 页面元数据缓存上限
+const PAGE_META_QUOTA: u64 = 20 * 1024 * 1024;
+/// rar/7z 漫画解压缓存上限
+const COMIC_QUOTA: u64 = 1024 * 1024 * 1024;
+
+/// 持久数据目录（数据库、设置、批注），删除会丢失用户数据
+/// Linux: ~/.local/share/RReader，macOS: ~/Library/Application Support/RReader，Windows: %APPDATA%\RReader
+/// 便携模式下为可执行文件旁的 RReaderData/data
+pub fn data_dir() -> PathBuf {
+    if let Some(root) = PORTABLE_ROOT.as_ref() {
+        return root.join("data");
+    }
+    dirs::data_dir()
+        .expect("Cannot get data directory")
+        .join(APP_DIR_NAME)
+}
+
+/// 可再生缓存目录（缩略图、reflow），可以随时清空
+/// Linux: ~/.cache/RReader，macOS: ~/Library/Caches/RReader，Windows: %LOCALAPPDATA%\RReader
+/// 便携模式下为可执行文件旁的 RReaderData/cache
+pub fn cache_dir() -> PathBuf {
+    if let Some(root) = PORTABLE_ROOT.as_ref() {
+        return root.join("cache");
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(|| std::env::temp_dir())
+        .join(APP_DIR_NAME)
+}
+
+pub fn database_path() -> PathBuf {
+    data_dir().join("book.db")
+}
+
+pub fn settings_path() -> PathBuf {
+    data_dir().join("settings.json")
+}
+
+/// 存在时表示书库已加密
+pub fn encryption_marker_path() -> PathBuf {
+    data_dir().join("encrypted")
+}
+
+/// 封面缩略图目录
+pub fn thumbnail_dir() -> PathBuf {
+    cache_dir().join("images")
+}
+
+/// 用户自定义的封面，属于用户数据，不随缓存清理
+pub fn custom_cover_dir() -> PathBuf {
+    data_dir().join("covers")
+}
+
+/// reflow 文本缓存目录
+pub fn reflow_dir() -> PathBuf {
+    cache_dir().join("reflow")
+}
+
+/// 页面元数据缓存目录（纠偏角度等逐页分析结果）
+pub fn page_meta_dir() -> PathBuf {
+    cache_dir().join("pages")
+}
+
+/// 在线查到的书籍封面
+pub fn metadata_cover_dir() -> PathBuf {
+    cache_dir().join("covers")
+}
+
+/// 整个文件 SHA-256 的缓存，按路径、大小和修改时间
+pub fn checksum_cache_path() -> PathBuf {
+    cache_dir().join("checksums.json")
+}
+
+/// rar/7z 漫画解压出的图片，每本书一个子目录
+pub fn comic_cache_dir() -> PathBuf {
+    cache_dir().join("comics")
+}
+
+/// 下载中的半截文件，续传时继续写入
+pub fn download_cache_dir() -> PathBuf {
+    cache_dir().join("downloads")
+}
+
+/// 下载完成的文档：系统下载目录下的 RReader 子目录，便携模式下为 RReaderData/downloads
+pub fn downloads_dir() -> PathBuf {
+    if let Some(root) = PORTABLE_ROOT.as_ref() {
+        return root.join("downloads");
+    }
+    dirs::download_dir()
+        .unwrap_or_else(data_dir)
+        .join(APP_DIR_NAME)
+}
+
+/// 旧版本把缓存放在数据目录下，启动时迁移到缓存目录
+pub fn migrate_legacy_caches() {
+    // 便携目录是新建的，不存在旧版布局
+    if is_portable() {
+        return;
+    }
+    for name in ["images", "reflow"] {
+        let old_dir = data_dir().join(name);
+        if !old_dir.is_dir() {
+            continue;
+        }
+        let new_dir = cache_dir().join(name);
+        if let Err(e) = move_dir_contents(&old_dir, &new_dir) {
+            error!("[AppPaths] 迁移缓存失败 {:?} -> {:?}: {}", old_dir, new_dir, e);
+            continue;
+        }
+        let _ = fs::remove_dir(&old_dir);
+        info!("[AppPaths] 已迁移缓存 {:?} -> {:?}", old_dir, new_dir);
+    }
+}
+
+/// 逐个文件移动，跨分区 rename 失败时改为复制后删除
+fn move_dir_contents(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if target.exists() {
+            fs::remove_file(entry.path())?;
+            continue;
+        }
+        if fs::rename(entry.path(), &target).is_err() {
+            fs::copy(entry.path(), &target)?;
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// 清空可再生缓存，切换书库加密时旧缓存不再适用
+pub fn clear_caches() {
+    for dir in [thumbnail_dir(), reflow_dir(), page_meta_dir(), comic_cache_dir(), metadata_cover_dir()] {
+        if dir.is_dir() {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                error!("[AppPaths] 清空缓存失败 {:?}: {}", dir, e);
+            }
+        }
+    }
+    let checksums = checksum_cache_path();
+    if checksums.is_file() {
+        if let Err(e) = fs::remove_file(&checksums) {
+            error!("[AppPaths] 清空缓存失败 {:?}: {}", checksums, e);
+        }
+    }
+}
+
+/// 按配额清理缓存，超出时先删最久未修改的文件；漫画解压缓存按书整目录清理，正在打开的书不删
+pub fn enforce_cache_quotas() {
+    let quotas: [(PathBuf, u64, fn(&Path, u64) -> std::io::Result<usize>); 4] = [
+        (thumbnail_dir(), THUMBNAIL_QUOTA, trim_dir),
+        (reflow_dir(), REFLOW_QUOTA, trim_dir),
+        (page_meta_dir(), PAGE_META_QUOTA, trim_dir),
+        (comic_cache_dir(), COMIC_QUOTA, ComicArchive::trim_cache),
+    ];
+    for (dir, quota, trim) in quotas {
+        match trim(&dir, quota) {
+            Ok(0) => {}
+            Ok(removed) => info!("[AppPaths] 缓存超出配额，已清理 {} 项: {:?}", removed, dir),
+            Err(e) => error!("[AppPaths] 清理缓存失败 {:?}: {}", dir, e),
+        }
+    }
+}
+
+fn trim_dir(dir: &Path, quota: u64) -> std::io::Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), metadata.len(), modified));
+        }
+    }
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= quota {
+        return Ok(0);
+    }
+
+    files.sort_by_key(|(_, _, modified)| *modified);
+    let mut removed = 0;
+    for (path, size, _) in files {
+        if total <= quota {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= size;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
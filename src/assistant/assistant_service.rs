@@ -0,0 +1,157 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use futures::StreamExt;
+use log::{debug, info};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::error::{RReaderError, Result};
+use crate::net::HttpClient;
+use crate::settings::AssistantSettings;
+
+/// 发送给模型的文字上限（字符），整页长文截断，避免超出上下文
+const MAX_INPUT_CHARS: usize = 6000;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 对选中文字的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssistantAction {
+    Summarize,
+    Explain,
+}
+
+impl AssistantAction {
+    pub fn title(self) -> &'static str {
+        match self {
+            AssistantAction::Summarize => "总结",
+            AssistantAction::Explain => "解释",
+        }
+    }
+
+    fn system_prompt(self) -> &'static str {
+        match self {
+            AssistantAction::Summarize => {
+                "Summarize the passage from a document the user is reading. Be concise and keep the key points. \
+                 Answer in the same language as the passage."
+            }
+            AssistantAction::Explain => {
+                "Explain the quoted term or sentence as it is used in the passage that follows it, \
+                 for a reader who is not a specialist. Answer in the same language as the passage."
+            }
+        }
+    }
+}
+
+/// 流式输出的事件
+pub enum AssistantEvent {
+    /// 新生成的一段文字
+    Delta(String),
+    Done,
+    Failed(RReaderError),
+}
+
+/// 进行中的请求，丢弃时中止
+pub struct AssistantRequest {
+    events: Receiver<AssistantEvent>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl AssistantRequest {
+    /// 取出已经到达的事件，不阻塞
+    pub fn try_recv(&self) -> Option<AssistantEvent> {
+        self.events.try_recv().ok()
+    }
+
+    pub fn cancel(&self) {
+        self.handle.abort();
+    }
+}
+
+impl Drop for AssistantRequest {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// OpenAI 兼容的 chat/completions 接口，流式返回
+pub struct AssistantService;
+
+impl AssistantService {
+    /// 在 tokio 运行时中发起请求，输出通过 AssistantRequest 逐段取回
+    pub fn ask(settings: &AssistantSettings, action: AssistantAction, text: &str) -> AssistantRequest {
+        let (event_tx, event_rx) = unbounded();
+        let settings = settings.clone();
+        let api_key = settings.load_api_key();
+        let text: String = text.chars().take(MAX_INPUT_CHARS).collect();
+        info!("[Assistant] {} {} 字，模型 {}", action.title(), text.chars().count(), settings.model);
+
+        let handle = tokio::spawn(async move {
+            let event = match Self::stream(&settings, api_key.as_deref(), action, &text, &event_tx).await {
+                Ok(()) => AssistantEvent::Done,
+                Err(e) => AssistantEvent::Failed(e),
+            };
+            let _ = event_tx.send(event);
+        });
+        AssistantRequest { events: event_rx, handle }
+    }
+
+    async fn stream(settings: &AssistantSettings, api_key: Option<&str>, action: AssistantAction, text: &str, event_tx: &Sender<AssistantEvent>) -> Result<()> {
+        let client = HttpClient::builder()
+            .map_err(Self::error)?
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(Self::error)?;
+        let url = format!("{}/chat/completions", settings.endpoint.trim().trim_end_matches('/'));
+        let body = json!({
+            "model": settings.model,
+            "stream": true,
+            "messages": [
+                { "role": "system", "content": action.system_prompt() },
+                { "role": "user", "content": text },
+            ],
+        });
+
+        let mut request = client.post(&url).json(&body);
+        if let Some(api_key) = api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await.map_err(Self::error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let detail: String = response.text().await.unwrap_or_default().chars().take(200).collect();
+            return Err(RReaderError::Assistant(format!("{} {}", status, detail)));
+        }
+
+        // 服务端推送事件：每行 `data: {...}`，`data: [DONE]` 结束；按字节缓存，避免多字节字符被拆开
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buffer.extend_from_slice(&chunk.map_err(Self::error)?);
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else { continue };
+                if data == "[DONE]" {
+                    return Ok(());
+                }
+                if let Some(delta) = Self::parse_delta(data) {
+                    if event_tx.send(AssistantEvent::Delta(delta)).is_err() {
+                        debug!("[Assistant] 接收方已关闭");
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 取出 choices[0].delta.content
+    fn parse_delta(data: &str) -> Option<String> {
+        let value: Value = serde_json::from_str(data).ok()?;
+        let content = value["choices"][0]["delta"]["content"].as_str()?;
+        (!content.is_empty()).then(|| content.to_string())
+    }
+
+    fn error(e: reqwest::Error) -> RReaderError {
+        RReaderError::Assistant(e.to_string())
+    }
+}
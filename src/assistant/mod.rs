@@ -0,0 +1,3 @@
+pub mod assistant_service;
+
+pub use assistant_service::{AssistantAction, AssistantEvent, AssistantRequest, AssistantService};
@@ -0,0 +1,20 @@
+//! 不带界面的命令行入口，子命令和桌面版的 `rreader <子命令>` 相同
+
+use env_logger::Env;
+use rreader::cli;
+use rreader::storage::FileStore;
+
+fn main() {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+
+    FileStore::init();
+    if FileStore::is_locked() {
+        eprintln!("The library is encrypted but its key is not available in the system keychain");
+        std::process::exit(1);
+    }
+
+    if !cli::run_from_args() {
+        eprintln!("{}", cli::NarrateCommand::USAGE);
+        std::process::exit(2);
+    }
+}
@@ -1,10 +1,17 @@
 use slint::Image;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+use crate::ui::GpuTexture;
+
 pub struct ImageCache {
     cache: Arc<Mutex<HashMap<String, CachedImage>>>,
     max_size: usize,
+    /// 缓存中图像实际占用的 RGBA 字节数
+    total_bytes: AtomicUsize,
+    /// 被 LRU 淘汰的键，由页面状态取走后回收对应页面
+    evicted: Mutex<Vec<String>>,
 }
 
 #[derive(Clone)]
@@ -12,6 +19,16 @@ pub struct CachedImage {
     pub image: Arc<Image>,
     pub timestamp: std::time::Instant,
     pub access_count: u64,
+    /// RGBA 字节数（宽 × 高 × 4）
+    pub bytes: usize,
+    /// 图像借用的纹理，随缓存项一起释放
+    pub texture: Option<Arc<GpuTexture>>,
+}
+
+/// 图像的 RGBA 字节数
+pub fn image_bytes(image: &Image) -> usize {
+    let size = image.size();
+    size.width as usize * size.height as usize * 4
 }
 
 impl ImageCache {
@@ -19,6 +36,8 @@ impl ImageCache {
         Self {
             cache: Arc::new(Mutex::new(HashMap::new())),
             max_size,
+            total_bytes: AtomicUsize::new(0),
+            evicted: Mutex::new(Vec::new()),
         }
     }
 
@@ -35,6 +54,10 @@ impl ImageCache {
     }
 
     pub fn put(&self, key: String, image: Image) -> Arc<Image> {
+        self.put_with_texture(key, image, None)
+    }
+
+    pub fn put_with_texture(&self, key: String, image: Image, texture: Option<GpuTexture>) -> Arc<Image> {
         let mut cache = self.cache.lock().unwrap();
 
         // 如果缓存已满，清理最久未使用的项
@@ -42,26 +65,66 @@ impl ImageCache {
             self.evict_lru(&mut cache);
         }
 
+        let bytes = image_bytes(&image);
         let cached_image = CachedImage {
             image: Arc::new(image),
             timestamp: std::time::Instant::now(),
             access_count: 1,
+            bytes,
+            texture: texture.map(Arc::new),
         };
 
         let image_ref = cached_image.image.clone();
-        cache.insert(key, cached_image);
+        self.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+        if let Some(old) = cache.insert(key, cached_image) {
+            self.total_bytes.fetch_sub(old.bytes, Ordering::Relaxed);
+        }
 
         image_ref
     }
 
     pub fn remove(&self, key: &str) -> bool {
         let mut cache = self.cache.lock().unwrap();
-        cache.remove(key).is_some()
+        match cache.remove(key) {
+            Some(old) => {
+                self.total_bytes.fetch_sub(old.bytes, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 删除满足条件的所有项，返回释放的字节数
+    pub fn remove_where<F: Fn(&str) -> bool>(&self, predicate: F) -> usize {
+        let mut cache = self.cache.lock().unwrap();
+        let mut freed = 0;
+        cache.retain(|key, cached| {
+            if predicate(key) {
+                freed += cached.bytes;
+                false
+            } else {
+                true
+            }
+        });
+        self.total_bytes.fetch_sub(freed, Ordering::Relaxed);
+        freed
     }
 
     pub fn clear(&self) {
         let mut cache = self.cache.lock().unwrap();
         cache.clear();
+        self.total_bytes.store(0, Ordering::Relaxed);
+        self.evicted.lock().unwrap().clear();
+    }
+
+    /// 缓存图像占用的字节数
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    /// 取走自上次调用以来被淘汰的键
+    pub fn take_evicted(&self) -> Vec<String> {
+        std::mem::take(&mut *self.evicted.lock().unwrap())
     }
 
     pub fn size(&self) -> usize {
@@ -82,7 +145,10 @@ impl ImageCache {
         }
 
         if let Some(key) = oldest_key {
-            cache.remove(&key);
+            if let Some(old) = cache.remove(&key) {
+                self.total_bytes.fetch_sub(old.bytes, Ordering::Relaxed);
+            }
+            self.evicted.lock().unwrap().push(key);
         }
     }
 }
@@ -123,12 +189,44 @@ impl PageCache {
         self.thumbnail_cache.put(key, image)
     }
 
+    /// 放入借用 OpenGL 纹理的页面图像
+    pub fn put_thumbnail_texture(&self, key: String, image: Image, texture: GpuTexture) -> Arc<Image> {
+        self.thumbnail_cache.put_with_texture(key, image, Some(texture))
+    }
+
+    /// 删除某一页的所有缓存图像，返回释放的字节数
+    pub fn remove_page(&self, page_index: usize) -> usize {
+        self.image_cache.remove_where(|key| page_index_of_key(key) == Some(page_index))
+            + self.thumbnail_cache.remove_where(|key| page_index_of_key(key) == Some(page_index))
+    }
+
+    /// 取走被淘汰图像对应的页码
+    pub fn take_evicted_pages(&self) -> Vec<usize> {
+        self.image_cache
+            .take_evicted()
+            .into_iter()
+            .chain(self.thumbnail_cache.take_evicted())
+            .filter_map(|key| page_index_of_key(&key))
+            .collect()
+    }
+
+    /// 两级缓存共占用的字节数
+    pub fn total_bytes(&self) -> usize {
+        self.image_cache.total_bytes() + self.thumbnail_cache.total_bytes()
+    }
+
     pub fn clear(&self) {
         self.image_cache.clear();
         self.thumbnail_cache.clear();
     }
 }
 
+/// 从缓存键中解析页码，支持 "page_{index}_{zoom}" 和 "{index}-{w}-{h}" 两种格式
+fn page_index_of_key(key: &str) -> Option<usize> {
+    let key = key.strip_prefix("page_").unwrap_or(key);
+    key.split(|c| c == '_' || c == '-').next()?.parse().ok()
+}
+
 impl Default for ImageCache {
     fn default() -> Self {
         Self::new(10)
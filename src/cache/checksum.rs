@@ -0,0 +1,76 @@
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use crate::app_paths;
+use crate::storage::FileStore;
+use crate::ui::utils::sha256_hex;
+
+/// 超过这么多条时去掉已经不存在的文件
+const MAX_ENTRIES: usize = 2000;
+
+/// 读改写缓存文件时加锁，两个文档同时算完不会互相覆盖
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Entry {
+    size: u64,
+    /// 修改时间，UNIX 纪元以来的纳秒
+    modified: u64,
+    sha256: String,
+}
+
+/// 整个文件的 SHA-256 按路径缓存，文件大小和修改时间都没变时不再重新计算。
+/// 缓存文件里有书的路径，经过 FileStore 读写
+pub struct ChecksumCache;
+
+impl ChecksumCache {
+    /// 文件的 SHA-256（小写十六进制），缓存失效时重新计算，应在后台线程调用
+    pub fn sha256(path: &Path) -> io::Result<String> {
+        let metadata = fs::metadata(path)?;
+        let size = metadata.len();
+        let modified = metadata.modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0);
+        let key = path.to_string_lossy().to_string();
+
+        let cached = Self::load().remove(&key).filter(|entry| entry.size == size && entry.modified == modified);
+        if let Some(entry) = cached {
+            debug!("[Checksum] 使用缓存: {:?}", path);
+            return Ok(entry.sha256);
+        }
+
+        let sha256 = sha256_hex(path)?;
+        let _guard = LOCK.lock().unwrap();
+        let mut entries = Self::load();
+        entries.insert(key, Entry { size, modified, sha256: sha256.clone() });
+        if entries.len() > MAX_ENTRIES {
+            entries.retain(|path, _| Path::new(path).is_file());
+        }
+        if let Err(e) = Self::save(&entries) {
+            error!("[Checksum] 保存缓存失败: {}", e);
+        }
+        Ok(sha256)
+    }
+
+    fn load() -> HashMap<String, Entry> {
+        FileStore::read_to_string(&app_paths::checksum_cache_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(entries: &HashMap<String, Entry>) -> io::Result<()> {
+        let path = app_paths::checksum_cache_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        FileStore::write(&path, serde_json::to_string(entries)?)
+    }
+}
@@ -1,4 +1,13 @@
+#[cfg(feature = "gui")]
 pub mod cache;
+pub mod checksum;
+pub mod page_geometry;
+pub mod page_meta;
 
+#[cfg(feature = "gui")]
 pub use cache::ImageCache;
+#[cfg(feature = "gui")]
 pub use cache::PageCache;
+pub use checksum::ChecksumCache;
+pub use page_geometry::PageGeometryCache;
+pub use page_meta::{PageMeta, PageMetaCache};
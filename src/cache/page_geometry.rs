@@ -0,0 +1,71 @@
+use anyhow::Result;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_paths;
+use crate::decoder::{PageInfo, Rect};
+use crate::storage::FileStore;
+use crate::ui::utils::generate_content_hash;
+
+/// 单页的尺寸和切边范围（页面坐标）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct PageGeometry {
+    width: f32,
+    height: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    crop: Option<[f32; 4]>,
+}
+
+/// 按文档保存的页面尺寸：大文档再次打开时不用逐页加载页面读取尺寸。
+/// 文件以内容hash+文件大小命名，文档变化后自然找不到旧缓存
+pub struct PageGeometryCache;
+
+impl PageGeometryCache {
+    fn cache_path(source: &Path) -> Result<PathBuf> {
+        let file_size = fs::metadata(source)?.len();
+        let content_hash = generate_content_hash(source)?;
+        Ok(app_paths::page_meta_dir().join(format!("{:016x}_{}_geometry.json", content_hash, file_size)))
+    }
+
+    /// 读取缓存的页面信息，页数对不上或文件损坏时返回 None，由调用方重新扫描
+    pub fn load(source: &Path, page_count: usize) -> Option<Vec<PageInfo>> {
+        let path = Self::cache_path(source).ok()?;
+        let content = FileStore::read_to_string(&path).ok()?;
+        let pages: Vec<PageGeometry> = match serde_json::from_str(&content) {
+            Ok(pages) => pages,
+            Err(e) => {
+                debug!("[PageGeometry] 缓存无法解析 {:?}: {}", path, e);
+                return None;
+            }
+        };
+        if pages.len() != page_count {
+            info!("[PageGeometry] 页数不一致（缓存 {}，文档 {}），重新扫描", pages.len(), page_count);
+            return None;
+        }
+        Some(pages.into_iter().enumerate().map(|(index, page)| {
+            let mut info = PageInfo::new(index, page.width, page.height);
+            info.crop_bounds = page.crop.map(|[left, top, right, bottom]| Rect::new(left, top, right, bottom));
+            info
+        }).collect())
+    }
+
+    pub fn save(source: &Path, pages: &[PageInfo]) {
+        let geometry: Vec<PageGeometry> = pages.iter().map(|page| PageGeometry {
+            width: page.width,
+            height: page.height,
+            crop: page.crop_bounds.map(|r| [r.left, r.top, r.right, r.bottom]),
+        }).collect();
+        let result = Self::cache_path(source)
+            .and_then(|path| {
+                fs::create_dir_all(app_paths::page_meta_dir())?;
+                FileStore::write(&path, serde_json::to_string(&geometry)?)?;
+                Ok(path)
+            });
+        match result {
+            Ok(path) => debug!("[PageGeometry] 已保存 {} 页: {:?}", pages.len(), path),
+            Err(e) => error!("[PageGeometry] 保存失败 {:?}: {}", source, e),
+        }
+    }
+}
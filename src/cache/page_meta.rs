@@ -0,0 +1,84 @@
+use anyhow::Result;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_paths;
+use crate::storage::FileStore;
+use crate::ui::utils::generate_content_hash;
+
+/// 每更新多少页写一次文件
+const FLUSH_INTERVAL: usize = 10;
+
+/// 单页的分析结果，分析一次后保存，再次打开时直接使用
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PageMeta {
+    /// 检测到的倾斜角度（度），正值表示文字行向右下倾斜
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deskew_angle: Option<f32>,
+    /// 是否为空白页，渲染过一次后才有结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blank: Option<bool>,
+}
+
+/// 按文档保存的页面元数据缓存，在解码线程中使用
+pub struct PageMetaCache {
+    path: PathBuf,
+    pages: HashMap<usize, PageMeta>,
+    pending: usize,
+}
+
+impl PageMetaCache {
+    /// 缓存文件以内容hash+文件大小命名，和 reflow 缓存一致
+    pub fn open(source: &Path) -> Result<Self> {
+        let file_size = fs::metadata(source)?.len();
+        let content_hash = generate_content_hash(source)?;
+        let path = app_paths::page_meta_dir().join(format!("{:016x}_{}_pages.json", content_hash, file_size));
+        let pages = FileStore::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Ok(Self { path, pages, pending: 0 })
+    }
+
+    pub fn get(&self, page_index: usize) -> Option<&PageMeta> {
+        self.pages.get(&page_index)
+    }
+
+    /// 已知为空白的页面
+    pub fn blank_pages(&self) -> impl Iterator<Item = usize> + '_ {
+        self.pages.iter().filter(|(_, meta)| meta.blank == Some(true)).map(|(index, _)| *index)
+    }
+
+    pub fn update<F: FnOnce(&mut PageMeta)>(&mut self, page_index: usize, f: F) {
+        f(self.pages.entry(page_index).or_default());
+        self.pending += 1;
+        if self.pending >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if self.pending == 0 {
+            return;
+        }
+        self.pending = 0;
+        let result = fs::create_dir_all(app_paths::page_meta_dir())
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(serde_json::to_string(&self.pages)?))
+            .and_then(|content| Ok(FileStore::write(&self.path, content)?));
+        match result {
+            Ok(()) => debug!("[PageMeta] 已保存: {:?}", self.path),
+            Err(e) => error!("[PageMeta] 保存失败 {:?}: {}", self.path, e),
+        }
+    }
+}
+
+impl Drop for PageMetaCache {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
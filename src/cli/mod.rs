@@ -0,0 +1,44 @@
+#[cfg(feature = "tts")]
+pub mod narrate;
+
+use std::path::Path;
+
+use crate::decoder::formats;
+
+#[cfg(feature = "tts")]
+pub use narrate::NarrateCommand;
+
+/// 命令行中要打开的文件（第一个支持格式的参数）
+pub fn file_argument() -> Option<String> {
+    std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--") && formats::is_supported(Path::new(arg)))
+}
+
+/// 解析命令行子命令，返回 true 表示已作为命令行工具执行完毕，不需要启动界面
+pub fn run_from_args() -> bool {
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != crate::app_paths::PORTABLE_FLAG)
+        .collect();
+    match args.first().map(String::as_str) {
+        #[cfg(feature = "tts")]
+        Some("narrate") => {
+            match NarrateCommand::parse(&args[1..]) {
+                Ok(command) => {
+                    if let Err(e) = command.run() {
+                        eprintln!("narrate failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    eprintln!("{}", NarrateCommand::USAGE);
+                    std::process::exit(2);
+                }
+            }
+            true
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use log::{error, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::decoder::formats;
+use crate::reflow::{ReflowCache, ReflowPipeline};
+use crate::settings::AppSettings;
+use crate::tts::TtsService;
+
+/// watch 模式下检查源文件变化的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// `rreader narrate <file> --out <dir>`：无界面地提取、清理文本并合成为逐页音频
+pub struct NarrateCommand {
+    file: PathBuf,
+    out_dir: PathBuf,
+    voice: Option<String>,
+    start_page: usize,
+    rate: f32,
+    watch: bool,
+}
+
+impl NarrateCommand {
+    pub const USAGE: &'static str =
+        "usage: rreader narrate <file> --out <dir> [--voice <name>] [--start <page>] [--rate <0.1-1.0>] [--watch]";
+
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut file = None;
+        let mut out_dir = None;
+        let mut voice = None;
+        let mut start_page = 1;
+        let mut rate = 0.6;
+        let mut watch = false;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--out" | "-o" => out_dir = iter.next().map(PathBuf::from),
+                "--voice" => voice = iter.next().cloned(),
+                "--start" => {
+                    start_page = iter.next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("--start requires a page number"))?;
+                }
+                "--rate" => {
+                    rate = iter.next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("--rate requires a number"))?;
+                }
+                "--watch" => watch = true,
+                other if other.starts_with('-') => anyhow::bail!("unknown option: {}", other),
+                other => file = Some(PathBuf::from(other)),
+            }
+        }
+
+        Ok(Self {
+            file: file.ok_or_else(|| anyhow::anyhow!("missing input file"))?,
+            out_dir: out_dir.ok_or_else(|| anyhow::anyhow!("missing --out <dir>"))?,
+            voice,
+            start_page: start_page.max(1) - 1, // 命令行页码从 1 开始
+            rate,
+            watch,
+        })
+    }
+
+    pub fn run(&self) -> Result<()> {
+        self.narrate()?;
+        if !self.watch {
+            return Ok(());
+        }
+
+        info!("[Narrate] 监视文件变化: {:?}", self.file);
+        let mut last_modified = Self::modified(&self.file);
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            let modified = Self::modified(&self.file);
+            if modified != last_modified {
+                last_modified = modified;
+                info!("[Narrate] 文件已变化，重新生成");
+                if let Err(e) = self.narrate() {
+                    error!("[Narrate] 生成失败: {}", e);
+                }
+            }
+        }
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// 提取 -> 清理 -> 合成，每页一个音频文件，缓存与界面共用
+    fn narrate(&self) -> Result<()> {
+        fs::create_dir_all(&self.out_dir)?;
+        let decoder = formats::open_decoder(&self.file)?;
+        let mut cache = ReflowCache::open(&self.file)?;
+        let settings = AppSettings::get();
+        let mut pipeline = ReflowPipeline::new(&AppSettings::book(&self.file.to_string_lossy()), &settings.tts);
+
+        let page_count = decoder.page_count();
+        let mut written = 0;
+        for page in self.start_page..page_count {
+            let entry = if cache.contains_page(page) {
+                cache.get(page).cloned()
+            } else {
+                let entry = ReflowCache::extract_page(decoder.as_ref(), page)?;
+                cache.insert(page, entry.clone())?;
+                entry
+            };
+            let Some(entry) = entry.and_then(|e| pipeline.process(e)) else {
+                continue;
+            };
+
+            // 未指定语音时按页面语言自动选择；设置里没有时，espeak-ng 直接用语言代码，
+            // 其他平台用系统默认语音
+            let voice = self.voice.clone()
+                .or_else(|| entry.language.as_deref().and_then(TtsService::voice_for_language))
+                .or_else(|| {
+                    let uses_language_code = !cfg!(any(target_os = "macos", target_os = "windows"));
+                    entry.language.clone().filter(|_| uses_language_code)
+                });
+            let output = self.out_dir.join(format!("page_{:04}", page + 1));
+            match TtsService::synthesize_to_file(&entry.data, voice.as_deref(), self.rate, &output) {
+                Ok(path) => {
+                    written += 1;
+                    println!("{}/{} {}", page + 1, page_count, path.display());
+                }
+                Err(e) => error!("[Narrate] 页面 {} 合成失败: {}", page + 1, e),
+            }
+        }
+        if self.start_page == 0 {
+            cache.finish()?;
+        } else {
+            cache.flush()?;
+        }
+
+        info!("[Narrate] 完成，共生成 {} 个音频文件", written);
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use log::{error, info};
+use slint::{ComponentHandle, Model, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::controllers::DocumentController;
+use crate::dao::AnnotationDao;
+use crate::decoder::Rect;
+use crate::entity::Annotation;
+use crate::error::Result;
+use crate::page::PageViewState;
+use crate::AppWindow;
+
+/// 右键菜单中可选的高亮颜色：菜单动作、名称、颜色
+pub const HIGHLIGHT_COLORS: [(&str, &str, &str); 3] = [
+    ("annotation-yellow", "黄色", Annotation::DEFAULT_COLOR),
+    ("annotation-green", "绿色", "#a5d6a7"),
+    ("annotation-pink", "粉色", "#f48fb1"),
+];
+
+thread_local! {
+    /// 当前文档的全部高亮
+    static ANNOTATIONS: RefCell<Vec<Annotation>> = const { RefCell::new(Vec::new()) };
+    /// 笔记对话框正在编辑的高亮
+    static EDITING: RefCell<Option<i32>> = const { RefCell::new(None) };
+}
+
+/// 高亮和笔记：保存在数据库中，按页叠加在页面图像上
+pub struct AnnotationController;
+
+impl AnnotationController {
+    pub fn setup_annotation_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let controller = Rc::clone(document_controller);
+        window.on_save_annotation_note(move |note| {
+            let Some(window) = weak_window.upgrade() else { return };
+            window.set_show_note_dialog(false);
+            let Some(id) = EDITING.with(|editing| editing.borrow_mut().take()) else { return };
+            let result = AnnotationDao::update_sync(id, None, Some(note.trim().to_string()));
+            Self::apply(&window, &controller, result.map(|_| ()));
+        });
+
+        let weak_window = window.as_weak();
+        window.on_close_note_dialog(move || {
+            EDITING.with(|editing| editing.borrow_mut().take());
+            if let Some(window) = weak_window.upgrade() {
+                window.set_show_note_dialog(false);
+            }
+        });
+    }
+
+    /// 读取当前文档的高亮，没有打开文档时清空
+    pub fn load(window: &AppWindow) {
+        let book_path = window.get_file_path().to_string();
+        let annotations = if book_path.is_empty() {
+            Vec::new()
+        } else {
+            AnnotationDao::find_by_book_sync(&book_path).unwrap_or_else(|e| {
+                error!("[Annotation] 读取失败: {}", e);
+                Vec::new()
+            })
+        };
+        info!("[Annotation] {} 处高亮", annotations.len());
+        ANNOTATIONS.with(|current| *current.borrow_mut() = annotations);
+    }
+
+    /// 页面坐标 (x, y) 下的高亮
+    pub fn annotation_at(page_index: usize, x: f32, y: f32) -> Option<i32> {
+        ANNOTATIONS.with(|annotations| {
+            annotations.borrow()
+                .iter()
+                .rev()
+                .find(|annotation| annotation.page == page_index as i32 && annotation.contains(x, y))
+                .map(|annotation| annotation.id)
+        })
+    }
+
+    /// 高亮一组范围（页面坐标），edit_note 为 true 时接着打开笔记对话框
+    pub fn highlight(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, page_index: usize, rects: &[Rect], edit_note: bool) {
+        if rects.is_empty() {
+            return;
+        }
+        info!("[Annotation] 高亮第 {} 页 {} 行", page_index + 1, rects.len());
+        let book_path = window.get_file_path().to_string();
+        let annotation = Annotation::new(book_path, page_index as i32, rects, Annotation::DEFAULT_COLOR.to_string(), String::new());
+        match AnnotationDao::add_sync(annotation) {
+            Ok(saved) => {
+                Self::apply(window, document_controller, Ok(()));
+                if edit_note {
+                    Self::edit_note(window, saved.id);
+                }
+            }
+            Err(e) => Self::apply(window, document_controller, Err(e)),
+        }
+    }
+
+    pub fn edit_note(window: &AppWindow, id: i32) {
+        let note = ANNOTATIONS.with(|annotations| {
+            annotations.borrow().iter().find(|a| a.id == id).map(|a| a.note.clone())
+        });
+        let Some(note) = note else { return };
+        EDITING.with(|editing| *editing.borrow_mut() = Some(id));
+        window.set_note_text(note.into());
+        window.set_show_note_dialog(true);
+    }
+
+    pub fn set_color(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, id: i32, color: &str) {
+        let result = AnnotationDao::update_sync(id, Some(color.to_string()), None);
+        Self::apply(window, document_controller, result.map(|_| ()));
+    }
+
+    pub fn delete(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, id: i32) {
+        info!("[Annotation] 删除高亮 {}", id);
+        Self::apply(window, document_controller, AnnotationDao::delete_sync(id));
+    }
+
+    /// 修改后重新读取并刷新叠加层，失败时提示
+    fn apply(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, result: Result<()>) {
+        if let Err(e) = result {
+            error!("[Annotation] 保存失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+        }
+        Self::load(window);
+        let page_view_state = document_controller.borrow().page_view_state();
+        Self::refresh_highlights(window, &page_view_state.borrow());
+    }
+
+    /// 可见页面上的高亮换算到文档坐标
+    pub fn refresh_highlights(window: &AppWindow, state: &PageViewState) {
+        let highlights: Vec<crate::AnnotationHighlight> = ANNOTATIONS.with(|annotations| {
+            let annotations = annotations.borrow();
+            state.visible_range.iter()
+                .filter_map(|index| state.pages.get(index))
+                .flat_map(|page| {
+                    let scale = page.info.scale;
+                    annotations.iter()
+                        .filter(move |annotation| annotation.page == page.info.index as i32)
+                        .flat_map(move |annotation| {
+                            let [r, g, b] = annotation.rgb();
+                            let color = slint::Color::from_argb_u8(0x70, r, g, b);
+                            let has_note = !annotation.note.is_empty();
+                            annotation.rect_list().into_iter().map(move |rect| crate::AnnotationHighlight {
+                                x: page.bounds.left + rect.left * scale,
+                                y: page.bounds.top + rect.top * scale,
+                                width: rect.width() * scale,
+                                height: rect.height() * scale,
+                                color,
+                                has_note,
+                            })
+                        })
+                })
+                .collect()
+        });
+        if !highlights.is_empty() || window.get_annotation_highlights().row_count() > 0 {
+            window.set_annotation_highlights(ModelRc::from(Rc::new(VecModel::from(highlights))));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{Datelike, Local, TimeZone};
+use log::{error, info};
+use sea_orm::ActiveValue;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+
+use crate::dao::RecentDao;
+use crate::entity::recent::ActiveModel;
+use crate::entity::Recent;
+use crate::AppWindow;
+
+thread_local! {
+    /// 已经提示过归档的文档，同一次阅读只提示一次
+    static SUGGESTED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 读完的书：读到最后一页时提示归入“已读完”，首页单独列出并附上阅读统计，
+/// 和收藏、最近阅读互不影响
+pub struct ArchiveController;
+
+impl ArchiveController {
+    pub fn setup_archive_callbacks(window: &AppWindow) {
+        let weak_window = window.as_weak();
+        window.on_archive_current_book(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            window.set_show_archive_toast(false);
+            let path = window.get_file_path().to_string();
+            if let Err(e) = Self::set_finished(&path, true) {
+                error!("[Archive] 归档 {} 失败: {}", path, e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+        });
+    }
+
+    /// 归入或移出“已读完”
+    pub fn set_finished(path: &str, finished: bool) -> crate::error::Result<()> {
+        let finished_at = if finished { Local::now().timestamp_millis() } else { 0 };
+        info!("[Archive] {} finished={}", path, finished);
+        let active = ActiveModel {
+            finished_at: ActiveValue::Set(finished_at),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 书库右键菜单：已归档的移回书架，其余归档
+    pub fn toggle(path: &str) -> crate::error::Result<()> {
+        let Some(record) = RecentDao::find_by_path_sync(path)? else { return Ok(()) };
+        Self::set_finished(path, !record.is_archived())
+    }
+
+    /// 显示到最后一页时提示归档；系列在提示下一卷时不再提示，免得两个提示条叠在一起
+    pub fn on_page_shown(window: &AppWindow, page: usize, page_count: usize) {
+        if page_count == 0 || page < page_count || window.get_show_next_volume_toast() {
+            return;
+        }
+        let path = window.get_file_path().to_string();
+        if SUGGESTED.with(|suggested| suggested.borrow().as_deref() == Some(path.as_str())) {
+            return;
+        }
+        SUGGESTED.with(|suggested| *suggested.borrow_mut() = Some(path.clone()));
+
+        match RecentDao::find_by_path_sync(&path) {
+            Ok(Some(record)) if !record.is_archived() => {
+                let title = if record.name.is_empty() { path.rsplit(['/', '\\']).next().unwrap_or(&path).to_string() } else { record.name };
+                window.set_archive_toast_text(format!("已读到最后一页，把《{}》归入“已读完”？", title).into());
+                window.set_show_archive_toast(true);
+            }
+            Ok(_) => {}
+            Err(e) => error!("[Archive] 读取 {} 的记录失败: {}", path, e),
+        }
+    }
+
+    /// 读完的本数、今年读完的本数和这些书累计的阅读时长
+    pub fn summary(records: &[Recent]) -> String {
+        if records.is_empty() {
+            return String::new();
+        }
+        let now = Local::now();
+        let year_start = Local.with_ymd_and_hms(now.year(), 1, 1, 0, 0, 0)
+            .earliest()
+            .map(|start| start.timestamp_millis())
+            .unwrap_or(0);
+        let this_year = records.iter().filter(|record| record.finished_at >= year_start).count();
+        let hours = records.iter().map(|record| record.read_seconds).sum::<i64>() as f64 / 3600.0;
+        format!("共 {} 本，今年 {} 本，累计阅读 {:.1} 小时", records.len(), this_year, hours)
+    }
+}
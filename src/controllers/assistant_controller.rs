@@ -0,0 +1,145 @@
+use log::{error, info};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::assistant::{AssistantAction, AssistantEvent, AssistantRequest, AssistantService};
+use crate::controllers::{ClipboardController, DocumentController, VocabController};
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 面板中显示的原文摘录长度
+const EXCERPT_CHARS: usize = 120;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 进行中的请求和轮询定时器
+struct RunningRequest {
+    request: AssistantRequest,
+    timer: slint::Timer,
+}
+
+thread_local! {
+    static RUNNING: RefCell<Option<RunningRequest>> = const { RefCell::new(None) };
+}
+
+/// AI 助手侧边栏：把选中的文字发给用户配置的接口，流式显示回答
+pub struct AssistantController;
+
+impl AssistantController {
+    pub fn setup_assistant_callbacks(window: &AppWindow) {
+        window.set_assistant_enabled(AppSettings::get().assistant.is_ready());
+
+        let weak_window = window.as_weak();
+        window.on_assistant_stop(move || {
+            Self::stop();
+            if let Some(window) = weak_window.upgrade() {
+                window.set_assistant_busy(false);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_assistant_copy(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            if let Err(e) = ClipboardController::copy_text(&window.get_assistant_text()) {
+                error!("[Assistant] 复制失败: {}", e);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_close_assistant(move || {
+            Self::stop();
+            if let Some(window) = weak_window.upgrade() {
+                window.set_show_assistant(false);
+            }
+        });
+    }
+
+    /// 发送文字并在侧边栏显示回答；新的请求会取消上一个
+    pub fn ask(window: &AppWindow, action: AssistantAction, text: &str) {
+        let settings = AppSettings::get().assistant;
+        if !settings.is_ready() {
+            window.set_error_message("AI 助手未开启，请在设置文件中填写接口地址和模型".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        Self::stop();
+        VocabController::clear_lookup(window);
+
+        let excerpt: String = text.chars().take(EXCERPT_CHARS).collect();
+        let ellipsis = if text.chars().count() > EXCERPT_CHARS { "…" } else { "" };
+        window.set_assistant_title(action.title().into());
+        window.set_assistant_excerpt(format!("{}{}", excerpt, ellipsis).into());
+        window.set_assistant_text("".into());
+        window.set_assistant_error("".into());
+        window.set_assistant_busy(true);
+        window.set_show_assistant(true);
+
+        let request = AssistantService::ask(&settings, action, text);
+        let timer = slint::Timer::default();
+        let weak_window = window.as_weak();
+        timer.start(slint::TimerMode::Repeated, POLL_INTERVAL, move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            Self::poll(&window);
+        });
+        RUNNING.with(|running| *running.borrow_mut() = Some(RunningRequest { request, timer }));
+    }
+
+    /// 总结当前页
+    pub fn summarize_page(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        let Some(page) = state.get_first_visible_page() else { return };
+        match state.decode_service.get_page_text(page) {
+            Ok(text) if !text.trim().is_empty() => Self::ask(window, AssistantAction::Summarize, &text),
+            Ok(_) => {
+                window.set_error_message("这一页没有可识别的文字".into());
+                window.set_show_error_dialog(true);
+            }
+            Err(e) => error!("[Assistant] 读取第 {} 页文字失败: {}", page + 1, e),
+        }
+    }
+
+    fn poll(window: &AppWindow) {
+        let mut appended = String::new();
+        let mut finished = None;
+        RUNNING.with(|running| {
+            let running = running.borrow();
+            let Some(running) = running.as_ref() else { return };
+            while let Some(event) = running.request.try_recv() {
+                match event {
+                    AssistantEvent::Delta(text) => appended.push_str(&text),
+                    AssistantEvent::Done => finished = Some(None),
+                    AssistantEvent::Failed(e) => finished = Some(Some(e)),
+                }
+            }
+        });
+
+        if !appended.is_empty() {
+            window.set_assistant_text(format!("{}{}", window.get_assistant_text(), appended).into());
+        }
+        let Some(failure) = finished else { return };
+        // 不在定时器自己的回调里销毁它，推迟到下一轮事件循环
+        let finished = RUNNING.with(|running| running.borrow_mut().take());
+        slint::Timer::single_shot(Duration::ZERO, move || drop(finished));
+        window.set_assistant_busy(false);
+        match failure {
+            Some(e) => {
+                error!("[Assistant] 请求失败: {}", e);
+                window.set_assistant_error(format!("{}：{}", e.user_message(), e).into());
+            }
+            None => info!("[Assistant] 回答完成"),
+        }
+    }
+
+    fn stop() {
+        if let Some(running) = RUNNING.with(|running| running.borrow_mut().take()) {
+            info!("[Assistant] 停止请求");
+            running.request.cancel();
+        }
+    }
+}
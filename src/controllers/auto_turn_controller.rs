@@ -0,0 +1,91 @@
+use log::info;
+use slint::ComponentHandle;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::{DocumentController, IdleController};
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+thread_local! {
+    static TICKER: slint::Timer = slint::Timer::default();
+    /// 上次检查时的滚动偏移，滚动过就重新计时
+    static LAST_OFFSET: Cell<(f32, f32)> = const { Cell::new((0.0, 0.0)) };
+    /// 停在哪一页的页尾，以及已经停了多少秒
+    static DWELL: Cell<Option<(usize, u32)>> = const { Cell::new(None) };
+}
+
+/// 免手动翻页：视口停在页尾附近一段时间后自动翻到下一页，翻页前显示倒计时，
+/// 适合吃饭或运动时看书；手动滚动会重新计时
+pub struct AutoTurnController;
+
+impl AutoTurnController {
+    pub fn setup_auto_turn_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        window.set_auto_turn_enabled(AppSettings::get().auto_turn.enabled);
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        TICKER.with(|ticker| {
+            ticker.start(slint::TimerMode::Repeated, Duration::from_secs(1), move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                Self::tick(&window, &document_controller);
+            });
+        });
+    }
+
+    /// 菜单开关自动翻页
+    pub fn set_enabled(window: &AppWindow, enabled: bool) {
+        info!("[AutoTurn] enabled={}", enabled);
+        AppSettings::update(|settings| settings.auto_turn.enabled = enabled);
+        window.set_auto_turn_enabled(enabled);
+        Self::reset(window);
+    }
+
+    fn reset(window: &AppWindow) {
+        DWELL.with(|dwell| dwell.set(None));
+        if window.get_auto_turn_countdown() != 0 {
+            window.set_auto_turn_countdown(0);
+        }
+    }
+
+    fn tick(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        if !window.get_auto_turn_enabled() || !window.get_document_opened() || window.get_reading_timer().on_break {
+            Self::reset(window);
+            return;
+        }
+        let settings = AppSettings::get().auto_turn;
+        let page_view_state = document_controller.borrow().page_view_state();
+        let (offset, reach, page_count) = {
+            let state = page_view_state.borrow();
+            (state.view_offset, state.page_end_in_reach(settings.threshold), state.pages.len())
+        };
+        let scrolled = LAST_OFFSET.with(|last| last.replace(offset)) != offset;
+
+        // 没到页尾、刚滚动过或已经是最后一页时不计时
+        let page = match reach {
+            Some(page) if !scrolled && page + 1 < page_count => page,
+            _ => {
+                Self::reset(window);
+                return;
+            }
+        };
+        let elapsed = match DWELL.with(Cell::get) {
+            Some((dwell_page, seconds)) if dwell_page == page => seconds + 1,
+            _ => 1,
+        };
+        let dwell_seconds = settings.dwell_seconds.max(1);
+        if elapsed < dwell_seconds {
+            DWELL.with(|dwell| dwell.set(Some((page, elapsed))));
+            window.set_auto_turn_countdown((dwell_seconds - elapsed) as i32);
+            return;
+        }
+
+        info!("[AutoTurn] 第 {} 页停留 {} 秒，翻到下一页", page + 1, dwell_seconds);
+        Self::reset(window);
+        // 自动翻页也算在读，不要因为没有输入就进入空闲
+        IdleController::touch();
+        window.invoke_page_changed((page + 2) as i32);
+        LAST_OFFSET.with(|last| last.set(page_view_state.borrow().view_offset));
+    }
+}
@@ -0,0 +1,119 @@
+use log::{error, info};
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::controllers::DocumentController;
+use crate::dao::BookmarkDao;
+use crate::entity::Bookmark;
+use crate::AppWindow;
+
+/// 书签：记下当前页和页内位置，侧边栏中和大纲并列显示，点击跳回
+pub struct BookmarkController;
+
+impl BookmarkController {
+    pub fn setup_bookmark_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let controller = Rc::clone(document_controller);
+        window.on_bookmark_selected(move |id| {
+            if let Some(window) = weak_window.upgrade() {
+                Self::jump_to(&window, &controller, id);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_remove_bookmark(move |id| {
+            let Some(window) = weak_window.upgrade() else { return };
+            info!("[Bookmark] 删除书签 {}", id);
+            if let Err(e) = BookmarkDao::delete_sync(id) {
+                error!("[Bookmark] 删除失败: {}", e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+            Self::set_bookmarks_to_ui(&window);
+        });
+    }
+
+    /// 在视口顶部所在的位置加书签，标签默认取页码标签
+    pub fn add_bookmark(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let (page, ratio, label) = {
+            let state = page_view_state.borrow();
+            let Some(page) = state.visible_range.anchor() else { return };
+            (page, state.visible_range.anchor_ratio, state.page_label(page).to_string())
+        };
+        info!("[Bookmark] 添加书签: 第 {} 页 ({:.2})", page + 1, ratio);
+        let book_path = window.get_file_path().to_string();
+        if let Err(e) = BookmarkDao::add_sync(Bookmark::new(book_path, page as i32, ratio, label)) {
+            error!("[Bookmark] 保存失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        // 让用户看到刚加的书签
+        window.set_outline_visible(true);
+        window.set_sidebar_tab(1);
+        Self::set_bookmarks_to_ui(window);
+    }
+
+    fn jump_to(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, id: i32) {
+        let book_path = window.get_file_path().to_string();
+        let bookmarks = match BookmarkDao::find_by_book_sync(&book_path) {
+            Ok(bookmarks) => bookmarks,
+            Err(e) => {
+                error!("[Bookmark] 读取失败: {}", e);
+                return;
+            }
+        };
+        let Some(bookmark) = bookmarks.into_iter().find(|b| b.id == id) else { return };
+        let page = bookmark.page.max(0) as usize;
+        info!("[Bookmark] 跳到书签: 第 {} 页", page + 1);
+
+        let page_view_state = document_controller.borrow().page_view_state();
+        let mut state = page_view_state.borrow_mut();
+        if page >= state.pages.len() {
+            return;
+        }
+        // 和跳页一样记下路标，跳错了可以回来
+        if let Some(from) = state.visible_range.anchor() {
+            let ratio = state.visible_range.anchor_ratio;
+            state.waypoints.record_jump(from, ratio, page);
+        }
+        if let Some((offset_x, offset_y)) = state.jump_to_anchor(page, bookmark.anchor_ratio) {
+            window.set_scroll_events_enabled(false);
+            window.set_offset_x(offset_x);
+            window.set_offset_y(offset_y);
+            window.set_scroll_events_enabled(true);
+            state.update_visible_pages();
+            DocumentController::refresh_view(window, &state);
+        }
+    }
+
+    /// 读取当前文档的书签到侧边栏，没有打开文档时清空
+    pub fn set_bookmarks_to_ui(window: &AppWindow) {
+        let book_path = window.get_file_path().to_string();
+        let bookmarks = if book_path.is_empty() {
+            Vec::new()
+        } else {
+            BookmarkDao::find_by_book_sync(&book_path).unwrap_or_else(|e| {
+                error!("[Bookmark] 读取失败: {}", e);
+                Vec::new()
+            })
+        };
+        let items: Vec<crate::BookmarkItem> = bookmarks.iter().map(|bookmark| crate::BookmarkItem {
+            id: bookmark.id,
+            title: bookmark_title(bookmark).into(),
+            page: bookmark.page,
+        }).collect();
+        window.set_bookmark_items(ModelRc::from(Rc::new(VecModel::from(items))));
+    }
+}
+
+/// 有页码标签时一并显示，和路标列表的写法一致
+fn bookmark_title(bookmark: &Bookmark) -> String {
+    if bookmark.label.is_empty() {
+        format!("第 {} 页", bookmark.page + 1)
+    } else {
+        format!("第 {} 页（{}）", bookmark.page + 1, bookmark.label)
+    }
+}
@@ -0,0 +1,71 @@
+use crossbeam_channel::{unbounded, TryRecvError};
+use log::{error, info};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::cache::ChecksumCache;
+
+/// 检查后台计算结果的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+thread_local! {
+    static POLLER: slint::Timer = slint::Timer::default();
+    /// 当前文档的 (路径, SHA-256)
+    static CURRENT: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// 文档校验和：打开后在后台计算整个文件的 SHA-256，作为文档的稳定标识，
+/// 用于属性显示、相同文件匹配和 KOReader 同步；文件没变时直接用缓存的结果
+pub struct ChecksumController;
+
+impl ChecksumController {
+    /// 开始计算，算好后在 UI 线程调用 on_done；打开下一个文档时上一个结果被丢弃
+    pub fn start(path: &str, on_done: impl Fn(&str) + 'static) {
+        Self::stop();
+        let (result_tx, result_rx) = unbounded();
+        let source = PathBuf::from(path);
+        thread::spawn(move || {
+            let _ = result_tx.send(ChecksumCache::sha256(&source));
+        });
+
+        let path = path.to_string();
+        POLLER.with(|poller| {
+            poller.start(slint::TimerMode::Repeated, POLL_INTERVAL, move || {
+                let result = match result_rx.try_recv() {
+                    Ok(result) => result,
+                    Err(TryRecvError::Empty) => return,
+                    Err(TryRecvError::Disconnected) => {
+                        POLLER.with(|poller| poller.stop());
+                        return;
+                    }
+                };
+                POLLER.with(|poller| poller.stop());
+                match result {
+                    Ok(sha256) => {
+                        info!("[Checksum] {} sha256={}", path, sha256);
+                        CURRENT.with(|current| current.replace(Some((path.clone(), sha256.clone()))));
+                        on_done(&sha256);
+                    }
+                    Err(e) => error!("[Checksum] 计算 {} 的 SHA-256 失败: {}", path, e),
+                }
+            });
+        });
+    }
+
+    /// 关闭文档时丢弃正在计算的结果
+    pub fn stop() {
+        POLLER.with(|poller| poller.stop());
+        CURRENT.with(|current| current.replace(None));
+    }
+
+    /// 当前文档的 SHA-256，还没算完时返回 None
+    pub fn current(path: &str) -> Option<String> {
+        CURRENT.with(|current| {
+            current.borrow().as_ref()
+                .filter(|(current_path, _)| current_path == path)
+                .map(|(_, sha256)| sha256.clone())
+        })
+    }
+}
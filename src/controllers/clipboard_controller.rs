@@ -0,0 +1,115 @@
+use log::info;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::{DocumentController, DownloadController};
+use crate::error::RReaderError;
+use crate::platform::{ClipboardCandidate, ClipboardWatcher};
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 运行中的监视器和轮询定时器，关闭监视时一起释放
+struct MonitorState {
+    watcher: ClipboardWatcher,
+    timer: slint::Timer,
+}
+
+thread_local! {
+    static MONITOR: RefCell<Option<MonitorState>> = const { RefCell::new(None) };
+    /// 提示条当前对应的内容
+    static PENDING: RefCell<Option<ClipboardCandidate>> = const { RefCell::new(None) };
+    /// 本程序自己写入剪贴板的文本，监视时不提示
+    static OWN_COPY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 剪贴板监视模式：复制文档路径或网址时弹出提示，由用户决定是否打开（网址先下载）
+pub struct ClipboardController;
+
+impl ClipboardController {
+    pub fn setup_clipboard_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        window.on_clipboard_open(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let Some(candidate) = PENDING.with(|pending| pending.borrow_mut().take()) else { return };
+            info!("[Clipboard] 打开: {:?}", candidate);
+            match candidate {
+                ClipboardCandidate::File(path) => document_controller.borrow().open_document(&window, &path),
+                ClipboardCandidate::Url(url) => DownloadController::download(&window, &document_controller, &url, None),
+            }
+        });
+
+        // 只有用户在设置中同意过才启动
+        Self::apply(window, AppSettings::get().clipboard_monitor);
+    }
+
+    /// 写入剪贴板；监视模式下不会再对这段文本弹出提示
+    pub fn copy_text(text: &str) -> crate::error::Result<()> {
+        let mut clipboard = arboard::Clipboard::new()
+            .map_err(|e| RReaderError::Platform(format!("无法访问剪贴板: {}", e)))?;
+        clipboard
+            .set_text(text)
+            .map_err(|e| RReaderError::Platform(format!("写入剪贴板失败: {}", e)))?;
+        OWN_COPY.with(|own| *own.borrow_mut() = Some(text.to_string()));
+        Ok(())
+    }
+
+    pub fn set_enabled(window: &AppWindow, enabled: bool) {
+        AppSettings::update(|settings| settings.clipboard_monitor = enabled);
+        Self::apply(window, enabled);
+    }
+
+    fn apply(window: &AppWindow, enabled: bool) {
+        window.set_clipboard_monitor(enabled);
+        if !enabled {
+            MONITOR.with(|monitor| monitor.borrow_mut().take());
+            PENDING.with(|pending| pending.borrow_mut().take());
+            window.set_show_clipboard_toast(false);
+            return;
+        }
+        if MONITOR.with(|monitor| monitor.borrow().is_some()) {
+            return;
+        }
+
+        let timer = slint::Timer::default();
+        let weak_window = window.as_weak();
+        timer.start(slint::TimerMode::Repeated, Duration::from_millis(500), move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let candidate = MONITOR.with(|monitor| {
+                monitor.borrow().as_ref().and_then(|state| state.watcher.try_recv())
+            });
+            if let Some(candidate) = candidate {
+                if Self::is_own_copy(&candidate) {
+                    return;
+                }
+                Self::show_toast(&window, candidate);
+            }
+        });
+        MONITOR.with(|monitor| {
+            *monitor.borrow_mut() = Some(MonitorState { watcher: ClipboardWatcher::start(), timer });
+        });
+    }
+
+    fn is_own_copy(candidate: &ClipboardCandidate) -> bool {
+        let text = match candidate {
+            ClipboardCandidate::File(text) | ClipboardCandidate::Url(text) => text,
+        };
+        OWN_COPY.with(|own| own.borrow().as_deref() == Some(text.as_str()))
+    }
+
+    fn show_toast(window: &AppWindow, candidate: ClipboardCandidate) {
+        let text = match &candidate {
+            ClipboardCandidate::File(path) => {
+                let name = Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                format!("剪贴板中有文档：{}", name)
+            }
+            ClipboardCandidate::Url(url) => format!("剪贴板中有文档链接：{}", url),
+        };
+        PENDING.with(|pending| *pending.borrow_mut() = Some(candidate));
+        window.set_clipboard_toast_text(text.into());
+        window.set_show_clipboard_toast(true);
+    }
+}
@@ -0,0 +1,90 @@
+use log::{error, info};
+use sea_orm::ActiveValue;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::controllers::DocumentController;
+use crate::dao::RecentDao;
+use crate::decoder::{CropMargins, Rect};
+use crate::entity::recent::ActiveModel;
+use crate::AppWindow;
+
+/// 参考页预览图的最长边（逻辑像素）
+const REFERENCE_SIZE: f32 = 520.0;
+
+/// 手动切边：在参考页上拖动四条边调整页边距，应用到所有页面，按书保存在阅读记录中
+pub struct CropController;
+
+impl CropController {
+    pub fn setup_crop_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let document_controller_clone = Rc::clone(document_controller);
+        window.on_crop_margins_applied(move |left, top, right, bottom| {
+            let Some(window) = weak_window.upgrade() else { return };
+            window.set_show_crop_dialog(false);
+            let margins = CropMargins::new(left, top, right, bottom);
+            let margins = (!margins.is_empty()).then_some(margins);
+            Self::apply(&window, &document_controller_clone, margins);
+        });
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        window.on_crop_margins_cleared(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            window.set_show_crop_dialog(false);
+            Self::apply(&window, &document_controller, None);
+        });
+    }
+
+    /// 以当前页为参考页打开切边对话框，显示已保存的页边距
+    pub fn show(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        let page_index = (window.get_current_page().max(1) - 1) as usize;
+        let Some(page) = state.pages.get(page_index) else { return };
+        let (width, height) = (page.info.width, page.info.height);
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        // render_region 内部按 2 倍渲染，正好适合高分屏
+        let region = Rect::new(0.0, 0.0, width, height);
+        match state.decode_service.render_region(page_index, region, REFERENCE_SIZE / width.max(height)) {
+            Ok((pixels, image_width, image_height)) => {
+                let image = slint::Image::from_rgba8_premultiplied(
+                    slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(&pixels, image_width, image_height),
+                );
+                let margins = state.crop_margins.unwrap_or_default();
+                window.set_crop_reference(image);
+                window.set_crop_reference_caption(format!("参考页：第 {} 页", page_index + 1).into());
+                window.set_crop_margin_left(margins.left);
+                window.set_crop_margin_top(margins.top);
+                window.set_crop_margin_right(margins.right);
+                window.set_crop_margin_bottom(margins.bottom);
+                window.set_show_crop_dialog(true);
+            }
+            Err(e) => {
+                error!("[Crop] 渲染参考页失败: {}", e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+        }
+    }
+
+    fn apply(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, margins: Option<CropMargins>) {
+        let path = window.get_file_path().to_string();
+        info!("[Crop] {} 手动切边: {:?}", path, margins);
+        document_controller.borrow().set_crop_margins(window, margins);
+
+        let active = ActiveModel {
+            crop_margins: ActiveValue::Set(margins.map(|margins| margins.encode()).unwrap_or_default()),
+            ..Default::default()
+        };
+        if let Err(e) = RecentDao::update_by_path_sync(&path, active) {
+            error!("[Crop] 保存切边失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+        }
+    }
+}
@@ -1,29 +1,63 @@
-use slint::{SharedString, ModelRc, VecModel, Timer, TimerMode, ComponentHandle, Image};
-use crate::ui::MainViewmodel;
+use slint::{SharedString, Model, ModelRc, VecModel, Timer, TimerMode, ComponentHandle, Image};
+use crate::ui::{GpuTextures, MainViewmodel};
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::page::{PageViewState, Orientation};
-use crate::decoder::{PageInfo};
+use crate::page::{LayoutAnchor, PageViewState, Orientation};
+use crate::decoder::{CropMargins, DocumentMetadata, PageInfo, RenderFailure};
 use crate::decoder::pdf::utils::{convert_to_slint_image, generate_thumbnail_key};
 use crate::tts::TtsService;
 use std::sync::Arc;
 use std::sync::Mutex;
-use log::{debug, info, error};
-use crate::controllers::history_controller::{convert_history_records_to_items, set_history_to_ui};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use crossbeam_channel::unbounded;
+use crate::entity::{Recent, ReflowEntry};
+use log::{debug, info, warn, error};
+use crate::controllers::{AnnotationController, ArchiveController, BookmarkController, ChecksumController, HomeController, PowerController, RedactionController, SeriesController, SimpleModeController, StatusController};
+use crate::controllers::history_controller::{
+    convert_history_records_to_items, set_history_to_ui, set_recent_menu_to_ui,
+};
 
 use crate::AppWindow;
+use crate::settings::AppSettings;
+use crate::ui::utils::content_hash_string;
+
+thread_local! {
+    /// 文档视图中的页面，按页码升序
+    static PAGE_MODEL: Rc<VecModel<crate::PageData>> = Rc::new(VecModel::default());
+    /// 大纲面板中已加载的条目，展开、收起时增量更新
+    static OUTLINE_MODEL: Rc<VecModel<crate::OutlineItem>> = Rc::new(VecModel::default());
+}
+
+/// 省电配置下滚动停止多久后才请求渲染
+const LOW_POWER_SCROLL_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(150);
+/// 缩放范围
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 5.0;
+/// 同一缩放下渲染失败这么多次后降低缩放
+const RENDER_FAILURE_LIMIT: usize = 3;
+/// 每次降低缩放的比例
+const ZOOM_STEP_DOWN: f32 = 0.75;
 
 pub struct DocumentController {
     viewmodel: Rc<RefCell<MainViewmodel>>,
     page_view_state: Rc<RefCell<PageViewState>>,
     tts_service: Arc<Mutex<TtsService>>,
+    /// 朗读代数，每次重新朗读递增，用于丢弃旧的reflow推送
+    speak_generation: Arc<AtomicU64>,
     load_timer: RefCell<Option<Timer>>,
+    /// 刚打开的文档在别处有内容相同的阅读记录，等待用户决定是否沿用
+    same_content: Rc<RefCell<Option<Recent>>>,
+    /// 当前缩放下累计的渲染失败次数：(缩放, 次数)
+    render_failures: RefCell<(f32, usize)>,
+    /// 等待搜索结果的定时器
+    search_timer: Rc<RefCell<Option<Timer>>>,
 }
 
 impl DocumentController {
     pub fn new(viewmodel: Rc<RefCell<MainViewmodel>>, tts_service: Arc<Mutex<TtsService>>) -> Self {
         let page_view_state = Rc::new(RefCell::new(PageViewState::new(Orientation::Vertical, 0)));
-        Self { viewmodel, page_view_state, tts_service, load_timer: RefCell::new(None) }
+        Self { viewmodel, page_view_state, tts_service, speak_generation: Arc::new(AtomicU64::new(0)), load_timer: RefCell::new(None), same_content: Rc::new(RefCell::new(None)), render_failures: RefCell::new((0.0, 0)), search_timer: Rc::new(RefCell::new(None)) }
     }
 
     /// 初始化UI，将控制器连接到Slint窗口
@@ -33,6 +67,115 @@ impl DocumentController {
 
     /// 设置文档相关的回调
     fn setup_callbacks(&self, window: &AppWindow) {
+        // 页面模型只创建一次，之后由 refresh_view 增量更新
+        window.set_document_pages(PAGE_MODEL.with(|model| ModelRc::from(Rc::clone(model))));
+        window.set_outline_items(OUTLINE_MODEL.with(|model| ModelRc::from(Rc::clone(model))));
+
+        // 展开或收起大纲节点
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_outline_toggled(move |row| {
+                if row < 0 {
+                    return;
+                }
+                let row = row as usize;
+                let mut state = page_view_state.borrow_mut();
+                OUTLINE_MODEL.with(|model| {
+                    if state.outline_expanded(row) {
+                        for _ in 0..state.collapse_outline(row) {
+                            model.remove(row + 1);
+                        }
+                    } else {
+                        let inserted = state.expand_outline(row);
+                        for i in row + 1..row + 1 + inserted {
+                            model.insert(i, Self::outline_row(&state, i));
+                        }
+                    }
+                    if row < model.row_count() {
+                        model.set_row_data(row, Self::outline_row(&state, row));
+                    }
+                });
+                if let Some(window) = weak_window.upgrade() {
+                    Self::refresh_outline_current(&window, &state);
+                }
+            });
+        }
+
+        // 大纲只显示前几层
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_outline_levels(move |depth| {
+                let mut state = page_view_state.borrow_mut();
+                let shown = state.show_outline_levels(depth.max(1));
+                debug!("[Outline] 显示 {} 层, {} 项", depth, shown);
+                Self::set_outline_to_ui(&state);
+                if let Some(window) = weak_window.upgrade() {
+                    window.set_outline_filter_text("".into());
+                    Self::refresh_outline_current(&window, &state);
+                }
+            });
+        }
+
+        // 按标题过滤大纲，点击条目照常跳转
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_outline_filter(move |query| {
+                let mut state = page_view_state.borrow_mut();
+                let shown = state.set_outline_filter(&query);
+                debug!("[Outline] 过滤 {:?}: {} 项", query, shown);
+                Self::set_outline_to_ui(&state);
+                if let Some(window) = weak_window.upgrade() {
+                    Self::refresh_outline_current(&window, &state);
+                }
+            });
+        }
+
+        // 沿用副本的阅读记录
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let viewmodel = Rc::clone(&self.viewmodel);
+            let same_content = Rc::clone(&self.same_content);
+            let weak_window = window.as_weak();
+            window.on_adopt_same_content(move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                window.set_show_same_content_toast(false);
+                let Some(source) = same_content.borrow_mut().take() else { return };
+                let path = window.get_file_path().to_string();
+                info!("[Document] 沿用 {} 的阅读记录: {}", source.book_path, path);
+                if let Err(e) = viewmodel.borrow().adopt_recent(&path, &source) {
+                    error!("[Document] 沿用阅读记录失败: {}", e);
+                    return;
+                }
+                BookmarkController::set_bookmarks_to_ui(&window);
+                AnnotationController::load(&window);
+
+                let mut state = page_view_state.borrow_mut();
+                let book = AppSettings::book(&path);
+                if book.deskew != window.get_deskew_enabled() {
+                    state.set_deskew(book.deskew);
+                }
+                window.set_deskew_enabled(book.deskew);
+                Self::apply_reading_position(&window, &mut state, source.zoom, source.page, source.anchor_ratio, source.scroll_x, source.scroll_y);
+                state.update_visible_pages();
+                Self::refresh_view(&window, &state);
+            });
+        }
+
+        // 解除渲染失败后记下的缩放上限
+        {
+            let weak_window = window.as_weak();
+            window.on_clear_zoom_cap(move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                window.set_show_zoom_cap_toast(false);
+                let path = window.get_file_path().to_string();
+                info!("[Document] 解除缩放上限: {}", path);
+                AppSettings::update_book(&path, |book| book.max_zoom = None);
+            });
+        }
+
         // View大小变化回调
         {
             let page_view_state = Rc::clone(&self.page_view_state);
@@ -43,22 +186,16 @@ impl DocumentController {
                     return;
                 }
 
-                let mut current_page = None;
+                let anchor = page_view_state.borrow().layout_anchor();
                 {
-                    let borrowed_state = page_view_state.borrow();
-                    if !borrowed_state.pages.is_empty() {
-                        current_page = borrowed_state.get_first_visible_page();
-                    }
-                }
-                {
-                    info!("current_page: width={}", current_page.unwrap_or_default());
+                    info!("viewport changed: anchor={:?}", anchor);
                     let mut state = page_view_state.borrow_mut();
                     let zoom = state.zoom;
                     state.update_view_size(width as f32, height as f32, zoom, false);
 
-                    // 如果当前页面不再可见，则跳转到该页面，大纲显示与隐藏会触发布局变化
-                    if let Some(page) = current_page {
-                        if let Some((new_x, new_y)) = state.jump_to_page(page) {
+                    // 大纲显示与隐藏、窗口缩放会触发布局变化，保持视口中心的内容不动
+                    if let Some(anchor) = anchor {
+                        if let Some((new_x, new_y)) = state.restore_layout_anchor(anchor) {
                             // 同步更新 UI 偏移量，但临时禁用滚动事件
                             if let Some(window) = weak_window.upgrade() {
                                 window.set_scroll_events_enabled(false);
@@ -85,10 +222,14 @@ impl DocumentController {
             let weak_window = window.as_weak();
             window.on_zoom_changed(move |zoom| {
                 if let Some(window) = weak_window.upgrade() {
+                    let zoom = (zoom as f32).min(Self::zoom_limit(&window));
+                    if zoom != window.get_zoom() {
+                        window.set_zoom(zoom);
+                    }
                     let mut state = page_view_state.borrow_mut();
                     
-                    // 获取当前页面，缩放后保持在同一页面
-                    let current_page = state.get_first_visible_page();
+                    // 记录视口中心的内容，缩放后保持在中心
+                    let anchor = state.layout_anchor();
                     
                     state.update_zoom(zoom as f32);
                     
@@ -96,9 +237,9 @@ impl DocumentController {
                     window.set_total_width(state.total_width);
                     window.set_total_height(state.total_height);
                     
-                    // 如果有当前页面，跳转回该页面并同步偏移量
-                    if let Some(page) = current_page {
-                        if let Some((new_x, new_y)) = state.jump_to_page(page) {
+                    // 放回锚点并同步偏移量
+                    if let Some(anchor) = anchor {
+                        if let Some((new_x, new_y)) = state.restore_layout_anchor(anchor) {
                             // 同步更新 UI 偏移量，但临时禁用滚动事件
                             window.set_scroll_events_enabled(false);
                             window.set_offset_x(new_x);
@@ -117,13 +258,35 @@ impl DocumentController {
         {
             let page_view_state = Rc::clone(&self.page_view_state);
             let weak_window = window.as_weak();
+            let debounce = Rc::new(Timer::default());
             window.on_scroll_changed(move |x, y| {
                 if let Some(window) = weak_window.upgrade() {
                     debug!("on_scroll_changed");
                     let mut state = page_view_state.borrow_mut();
-                    state.update_offset(x as f32, y as f32);
-                    state.update_visible_pages();
-                    Self::refresh_view(&window, &state);
+                    // 翻页模式下滚动换成整页翻动
+                    let (paged_x, paged_y) = state.paged_offset(x as f32, y as f32);
+                    if (paged_x, paged_y) != (x as f32, y as f32) {
+                        window.set_scroll_events_enabled(false);
+                        window.set_offset_x(paged_x);
+                        window.set_offset_y(paged_y);
+                        window.set_scroll_events_enabled(true);
+                    }
+                    state.update_offset(paged_x, paged_y);
+                    if !PowerController::is_low_power() {
+                        state.update_visible_pages();
+                        Self::refresh_view(&window, &state);
+                        return;
+                    }
+
+                    // 省电时滚动停下来再请求渲染
+                    let page_view_state = Rc::clone(&page_view_state);
+                    let weak_window = window.as_weak();
+                    debounce.start(TimerMode::SingleShot, LOW_POWER_SCROLL_DEBOUNCE, move || {
+                        let Some(window) = weak_window.upgrade() else { return };
+                        let mut state = page_view_state.borrow_mut();
+                        state.update_visible_pages();
+                        Self::refresh_view(&window, &state);
+                    });
                 }
             });
         }
@@ -135,10 +298,23 @@ impl DocumentController {
             window.on_page_changed(move |page_index| {  // page_index is 1-based from UI
                 let mut state = page_view_state.borrow_mut();
                 info!("on_page_changed.page={:?}", page_index);
-                if state.jump_to_page((page_index - 1) as usize).is_some() {
+                let mut target = (page_index - 1).max(0) as usize;
+                if AppSettings::get().skip_blank_pages {
+                    let from = state.get_first_visible_page().unwrap_or(0);
+                    target = state.skip_blank_target(from, target);
+                }
+                // 链接、大纲、跳页等大跳转前记下路标，误跳后可以回来
+                let recorded = state.visible_range.anchor().is_some_and(|from| {
+                    let ratio = state.visible_range.anchor_ratio;
+                    state.waypoints.record_jump(from, ratio, target)
+                });
+                if state.jump_to_page(target).is_some() {
                     state.update_visible_pages();
 
                     if let Some(window) = weak_window.upgrade() {
+                        if recorded {
+                            Self::set_waypoints_to_ui(&window, &state);
+                        }
                         Self::refresh_view(&window, &state);
 
                         window.set_offset_x(state.view_offset.0);
@@ -148,6 +324,110 @@ impl DocumentController {
             });
         }
 
+        // 全文搜索：在解码线程中搜索整个文档，定时取结果
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let search_timer = Rc::clone(&self.search_timer);
+            let weak_window = window.as_weak();
+            window.on_search_text(move |query| {
+                let Some(window) = weak_window.upgrade() else { return };
+                let query = query.trim().to_string();
+                page_view_state.borrow_mut().search.clear();
+                if query.is_empty() {
+                    window.set_search_status("".into());
+                    Self::refresh_search(&window, &page_view_state.borrow());
+                    return;
+                }
+                let receiver = match page_view_state.borrow().decode_service.search_text(&query) {
+                    Ok(receiver) => receiver,
+                    Err(e) => {
+                        error!("[Search] 搜索失败: {}", e);
+                        return;
+                    }
+                };
+                window.set_search_busy(true);
+
+                let timer = Timer::default();
+                let page_view_state = Rc::clone(&page_view_state);
+                let search_timer_clone = Rc::clone(&search_timer);
+                let weak_window = window.as_weak();
+                timer.start(TimerMode::Repeated, std::time::Duration::from_millis(50), move || {
+                    let Ok(result) = receiver.try_recv() else { return };
+                    // 不在定时器自己的回调里销毁它，推迟到下一轮事件循环
+                    let finished = search_timer_clone.borrow_mut().take();
+                    Timer::single_shot(std::time::Duration::ZERO, move || drop(finished));
+                    let Some(window) = weak_window.upgrade() else { return };
+                    window.set_search_busy(false);
+                    let matches = match result {
+                        Ok(matches) => matches,
+                        Err(e) => {
+                            error!("[Search] 搜索失败: {}", e);
+                            window.set_search_status("搜索失败".into());
+                            return;
+                        }
+                    };
+                    let mut state = page_view_state.borrow_mut();
+                    let from_page = state.get_first_visible_page().unwrap_or(0);
+                    state.search.set(query.clone(), matches, from_page);
+                    info!("[Search] {:?}: {} 处匹配", query, state.search.len());
+                    Self::show_search_hit(&window, &mut state);
+                });
+                // 新的搜索替换旧的定时器，旧结果不再处理
+                search_timer.replace(Some(timer));
+            });
+        }
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_search_step(move |delta| {
+                let Some(window) = weak_window.upgrade() else { return };
+                let mut state = page_view_state.borrow_mut();
+                if state.search.step(delta).is_some() {
+                    Self::show_search_hit(&window, &mut state);
+                }
+            });
+        }
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let search_timer = Rc::clone(&self.search_timer);
+            let weak_window = window.as_weak();
+            window.on_close_search(move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                search_timer.replace(None);
+                window.set_search_busy(false);
+                window.set_show_search_bar(false);
+                window.set_search_status("".into());
+                page_view_state.borrow_mut().search.clear();
+                Self::refresh_search(&window, &page_view_state.borrow());
+            });
+        }
+
+        // 回到路标
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_waypoint_selected(move |index| {
+                let Some(window) = weak_window.upgrade() else { return };
+                let mut state = page_view_state.borrow_mut();
+                let Some(waypoint) = usize::try_from(index).ok().and_then(|index| state.waypoints.get(index)) else { return };
+                info!("[Document] 回到路标: 第 {} 页", waypoint.page + 1);
+                // 回去之前的位置也记下来，选错了还能再回来
+                if let Some(from) = state.visible_range.anchor() {
+                    let ratio = state.visible_range.anchor_ratio;
+                    state.waypoints.record_jump(from, ratio, waypoint.page);
+                }
+                if let Some((offset_x, offset_y)) = state.jump_to_anchor(waypoint.page, waypoint.ratio) {
+                    window.set_scroll_events_enabled(false);
+                    window.set_offset_x(offset_x);
+                    window.set_offset_y(offset_y);
+                    window.set_scroll_events_enabled(true);
+                    state.update_visible_pages();
+                    Self::refresh_view(&window, &state);
+                }
+                Self::set_waypoints_to_ui(&window, &state);
+            });
+        }
+
         // 返回历史回调
         {
             let page_view_state = Rc::clone(&self.page_view_state);
@@ -158,14 +438,15 @@ impl DocumentController {
                     let current_path = window.get_file_path().to_string();
 
                     if !current_path.is_empty() {
-                        // 获取当前可见页的第一页
-                        let page = page_view_state.borrow().get_first_visible_page();
+                        // 按锚点（视口上沿所在页和页内位置）保存
+                        let visible = page_view_state.borrow().visible_range;
                         let zoom = page_view_state.borrow().zoom;
                         let (offset_x, offset_y) = page_view_state.borrow().view_offset;
+                        let page_count = page_view_state.borrow().pages.len();
 
-                        info!("back to history: page:{:?}, zoom:{:?}, offset_x:{:?}, offset_y:{:?}, path:{:?}", page, zoom, offset_x, offset_y, current_path);
+                        info!("back to history: visible:{:?}, zoom:{:?}, offset_x:{:?}, offset_y:{:?}, path:{:?}", visible, zoom, offset_x, offset_y, current_path);
                         // 更新记录的状态
-                        let update_result = viewmodel.borrow().update_recent_with_state(&current_path, page, zoom, offset_x, offset_y);
+                        let update_result = viewmodel.borrow().update_recent_with_state(&current_path, &visible, page_count, zoom, offset_x, offset_y);
                         if let Err(e) = update_result {
                             error!("Failed to update recent state: {e}");
                         }
@@ -176,10 +457,15 @@ impl DocumentController {
                     let history_records = vm_binding.get_current_records();
                     let ui_history_items = convert_history_records_to_items(history_records);
                     set_history_to_ui(&window, ui_history_items);
+                    set_recent_menu_to_ui(&window);
+                    HomeController::set_home_to_ui(&window, &vm_binding);
 
                     // 清空文件路径
                     window.set_file_path(SharedString::from(""));
+                    BookmarkController::set_bookmarks_to_ui(&window);
+                    AnnotationController::load(&window);
                     window.set_document_opened(false);
+                    StatusController::reset_document(&window);
                 }
 
                 // 重置页面状态
@@ -206,7 +492,7 @@ impl DocumentController {
                             None
                         } else if let Some(page) = link.page {
                             debug!("Page link clicked: {}", page);
-                            Self::parse_page_from_param(&page)
+                            state.resolve_page_ref(&page)
                         } else {
                             None
                         }
@@ -232,44 +518,100 @@ impl DocumentController {
             });
         }
 
+        // 跳转到页码或页码标签
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let weak_window = window.as_weak();
+            window.on_goto_page(move |input| {
+                let Some(window) = weak_window.upgrade() else { return };
+                let target = page_view_state.borrow().resolve_page_ref(&input);
+                match target {
+                    Some(index) => {
+                        info!("[Document] 跳转 {:?} -> 第 {} 页", input, index + 1);
+                        window.set_show_goto_dialog(false);
+                        window.set_goto_error("".into());
+                        window.invoke_page_changed(index as i32 + 1);
+                    }
+                    None => window.set_goto_error(format!("找不到页码 “{}”", input.trim()).into()),
+                }
+            });
+        }
+
+        // 文档属性回调
+        {
+            let page_view_state = Rc::clone(&self.page_view_state);
+            let viewmodel = Rc::clone(&self.viewmodel);
+            let weak_window = window.as_weak();
+            window.on_show_properties(move || {
+                let Some(window) = weak_window.upgrade() else {
+                    return;
+                };
+                match page_view_state.borrow().decode_service.get_document_properties() {
+                    Ok(properties) => {
+                        let mut items: Vec<crate::PropertyItem> = properties.into_iter()
+                            .map(|p| crate::PropertyItem {
+                                name: p.name.into(),
+                                value: p.value.into(),
+                            })
+                            .collect();
+                        items.extend(Self::checksum_properties(&viewmodel, &window.get_file_path()));
+                        window.set_document_properties(ModelRc::from(Rc::new(VecModel::from(items))));
+                        window.set_properties_dialog_title("文档属性".into());
+                        window.set_show_properties_dialog(true);
+                    }
+                    Err(e) => {
+                        error!("Failed to get document properties: {e}");
+                    }
+                }
+            });
+        }
+
         // 朗读页面回调
         {
             let page_view_state = Rc::clone(&self.page_view_state);
             let tts_service = Arc::clone(&self.tts_service);
+            let speak_generation = Arc::clone(&self.speak_generation);
             window.on_speak_page(move || {
                 // 如果正在朗读，停止朗读
                 // TODO: 需要添加检查方式，目前简化处理，先停止再开始
-                if let Some(page_index) = page_view_state.borrow().get_first_visible_page() {
-                    match page_view_state.borrow().get_reflow_from_page(page_index) {
-                        Ok(reflow_entries) => {
-                            if !reflow_entries.is_empty() {
-                                info!("[TTS] Speaking reflow text from page {} onwards, {} entries", page_index, reflow_entries.len());
-                                let tts = Arc::clone(&tts_service);
-
-                                // 将所有reflow条目的文本拼接成一个长文本并发送
-                                let combined_text = reflow_entries.into_iter()
-                                    .map(|entry| entry.data)
-                                    .collect::<Vec<String>>()
-                                    .join(" ");
-
-                                if !combined_text.is_empty() {
-                                    let mut tts_locked = tts.lock().unwrap();
-                                    tts_locked.stop_speaking(); // 先停止之前的朗读
-                                    tts_locked.speak_text(combined_text);
-                                } else {
-                                    error!("[TTS] No valid text content to speak");
-                                }
-                            } else {
-                                error!("[TTS] No reflow entries found");
-                            }
+                let Some(page_index) = page_view_state.borrow().get_first_visible_page() else {
+                    error!("[TTS] No visible page found");
+                    return;
+                };
+
+                // 新的朗读会使之前的转发线程失效
+                let generation = speak_generation.fetch_add(1, Ordering::SeqCst) + 1;
+                tts_service.lock().unwrap().stop_speaking(); // 先停止之前的朗读
+
+                // 跳过空白页时，朗读也略过这些页（扫描的空白页可能带有识别出的杂点文字）
+                let blank_pages = AppSettings::get()
+                    .skip_blank_pages
+                    .then(|| page_view_state.borrow().decode_service.blank_pages());
+
+                let (entry_tx, entry_rx) = unbounded::<ReflowEntry>();
+                if let Err(e) = page_view_state.borrow().stream_reflow_from_page(page_index, entry_tx) {
+                    error!("[TTS] Failed to get reflow data: {}", e);
+                    return;
+                }
+
+                info!("[TTS] Speaking reflow text from page {} onwards", page_index);
+                let tts = Arc::clone(&tts_service);
+                let speak_generation = Arc::clone(&speak_generation);
+                // 每提取完一页就送入朗读队列，不等整本书提取完成
+                thread::spawn(move || {
+                    for entry in entry_rx.iter() {
+                        if speak_generation.load(Ordering::SeqCst) != generation {
+                            break;
                         }
-                        Err(e) => {
-                            error!("[TTS] Failed to get reflow data: {}", e);
+                        let is_blank = blank_pages.as_ref().is_some_and(|blank_pages| {
+                            entry.page.parse::<usize>().is_ok_and(|page| blank_pages.lock().unwrap().contains(&page))
+                        });
+                        if !entry.data.is_empty() && !is_blank {
+                            tts.lock().unwrap().speak_text_with_language(entry.data, entry.language);
                         }
                     }
-                } else {
-                    error!("[TTS] No visible page found");
-                }
+                    debug!("[TTS] reflow forwarding finished, generation={}", generation);
+                });
             });
         }
     }
@@ -278,17 +620,19 @@ impl DocumentController {
     pub(crate) fn refresh_view(window: &AppWindow, state: &PageViewState) {
         if state.pages.is_empty() {
             debug!("No pages to refresh");
+            PAGE_MODEL.with(|model| model.set_vec(Vec::new()));
+            GpuTextures::models_replaced();
             return;
         }
 
-        debug!("refresh_view: visible_pages={:?}", state.visible_pages);
+        debug!("refresh_view: visible_range={:?}", state.visible_range);
 
-        let rendered_pages = state.visible_pages
+        let rendered_pages = state.visible_range
             .iter()
-            .filter_map(|&idx| state.pages.get(idx))
+            .filter_map(|idx| state.pages.get(idx))
             .map(|page| {
                 // 尝试从缓存获取图像，如果不存在则使用默认图像
-                let key = crate::decoder::pdf::utils::generate_thumbnail_key(page);
+                let key = state.page_key(page);
                 let image = {
                     if let Some(cached_image) = state.cache.get_thumbnail(&key) {
                         //debug!("从缓存获取图像: key={}, page={}", key, page.info.index);
@@ -307,21 +651,197 @@ impl DocumentController {
                     image,
                     page_index: page.info.index as i32,
                 }
-            })
-            .collect::<Vec<_>>();
+            });
 
-        info!("refresh_view {} page_models", rendered_pages.len());
-        let model = Rc::new(VecModel::from(rendered_pages));
-        window.set_document_pages(ModelRc::from(model));
+        let changed = PAGE_MODEL.with(|model| Self::sync_page_model(model, rendered_pages));
+        GpuTextures::models_replaced();
+        debug!("refresh_view {} page_models, {} changed", state.visible_range.len(), changed);
+        Self::refresh_search(window, state);
+        AnnotationController::refresh_highlights(window, state);
+        RedactionController::refresh_boxes(window, state);
 
         if let Some(first_visible) = state.get_first_visible_page() {
             window.set_current_page((first_visible + 1) as i32);  // UI expects 1-based page numbers
         }
+        Self::refresh_outline_current(window, state);
+        let page = state.get_first_visible_page().map(|p| p + 1).unwrap_or(0);
+        let last_visible = if state.visible_range.is_empty() { 0 } else { state.visible_range.last + 1 };
+        SeriesController::on_page_shown(window, last_visible, state.pages.len());
+        ArchiveController::on_page_shown(window, last_visible, state.pages.len());
+        let page_label = if page > 0 { state.page_label(page - 1).to_string() } else { String::new() };
+        StatusController::update(window, |status| {
+            status.page = page;
+            status.page_label = page_label;
+            status.page_count = state.pages.len();
+            status.zoom = state.zoom;
+        });
+    }
+
+    /// 按页码增量更新页面模型：两边都按页码升序，离开的页删除、进入的页插入，
+    /// 其余只在位置或图像变化时更新，返回变动的行数
+    fn sync_page_model(model: &VecModel<crate::PageData>, pages: impl Iterator<Item = crate::PageData>) -> usize {
+        let pages: Vec<crate::PageData> = pages.collect();
+        let mut changed = 0;
+
+        for row in (0..model.row_count()).rev() {
+            let index = model.row_data(row).map(|page| page.page_index);
+            if !pages.iter().any(|page| Some(page.page_index) == index) {
+                model.remove(row);
+                changed += 1;
+            }
+        }
+
+        for (row, page) in pages.into_iter().enumerate() {
+            match model.row_data(row) {
+                Some(current) if current.page_index == page.page_index => {
+                    if current != page {
+                        model.set_row_data(row, page);
+                        changed += 1;
+                    }
+                }
+                _ => {
+                    model.insert(row, page);
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// 布局变化后把锚点放回视口中心，同步总尺寸和偏移量
+    fn sync_layout(window: &AppWindow, state: &mut PageViewState, anchor: Option<LayoutAnchor>) {
+        window.set_total_width(state.total_width);
+        window.set_total_height(state.total_height);
+        if let Some(anchor) = anchor {
+            if let Some((new_x, new_y)) = state.restore_layout_anchor(anchor) {
+                window.set_scroll_events_enabled(false);
+                window.set_offset_x(new_x);
+                window.set_offset_y(new_y);
+                window.set_scroll_events_enabled(true);
+            }
+        }
+        state.update_visible_pages();
+        Self::refresh_view(window, state);
+    }
+
+    /// 以视口中的一点为中心缩放，缩放后该点下的内容保持不动
+    pub fn zoom_at(&self, window: &AppWindow, zoom: f32, anchor_x: f32, anchor_y: f32) {
+        let mut state = self.page_view_state.borrow_mut();
+        if state.pages.is_empty() || state.zoom <= 0.0 {
+            return;
+        }
+        let zoom = zoom.clamp(MIN_ZOOM, Self::zoom_limit(window));
+        let ratio = zoom / state.zoom;
+        let (offset_x, offset_y) = state.view_offset;
+        state.update_zoom(zoom);
+
+        // 偏移量为负值，范围 [-(总尺寸 - 视口), 0]
+        let max_x = (state.total_width - state.view_size.0).max(0.0);
+        let max_y = (state.total_height - state.view_size.1).max(0.0);
+        let new_x = (anchor_x - (anchor_x - offset_x) * ratio).clamp(-max_x, 0.0);
+        let new_y = (anchor_y - (anchor_y - offset_y) * ratio).clamp(-max_y, 0.0);
+        state.update_offset(new_x, new_y);
+
+        window.set_zoom(zoom);
+        window.set_total_width(state.total_width);
+        window.set_total_height(state.total_height);
+        window.set_scroll_events_enabled(false);
+        window.set_offset_x(new_x);
+        window.set_offset_y(new_y);
+        window.set_scroll_events_enabled(true);
+        state.update_visible_pages();
+        Self::refresh_view(window, &state);
+    }
+
+    /// 切换切边
+    pub fn set_crop(&self, window: &AppWindow, enabled: bool) {
+        let mut state = self.page_view_state.borrow_mut();
+        let anchor = state.layout_anchor();
+        state.set_crop(if enabled { 1 } else { 0 });
+        window.set_crop_enabled(enabled);
+        Self::sync_layout(window, &mut state, anchor);
+    }
+
+    /// 手动切边的页边距，设置时同时打开切边；None 恢复自动切边
+    pub fn set_crop_margins(&self, window: &AppWindow, margins: Option<CropMargins>) {
+        let mut state = self.page_view_state.borrow_mut();
+        let anchor = state.layout_anchor();
+        state.set_crop_margins(margins);
+        if margins.is_some() {
+            state.set_crop(1);
+            window.set_crop_enabled(true);
+        }
+        Self::sync_layout(window, &mut state, anchor);
+    }
+
+    /// 切换双页显示
+    pub fn set_dual_page(&self, window: &AppWindow, enabled: bool) {
+        let mut state = self.page_view_state.borrow_mut();
+        let anchor = state.layout_anchor();
+        state.set_dual_page(enabled);
+        window.set_dual_page(enabled);
+        Self::sync_layout(window, &mut state, anchor);
+    }
+
+    /// 切换单页翻页模式
+    pub fn set_paged(&self, window: &AppWindow, enabled: bool) {
+        let mut state = self.page_view_state.borrow_mut();
+        let anchor = state.layout_anchor();
+        state.set_paged(enabled);
+        window.set_paged_mode(enabled);
+        Self::sync_layout(window, &mut state, anchor);
+    }
+
+    /// 切换从右往左阅读，按书保存
+    pub fn set_rtl(&self, window: &AppWindow, enabled: bool) {
+        let path = window.get_file_path().to_string();
+        AppSettings::update_book(&path, |book| book.rtl = enabled);
+        let mut state = self.page_view_state.borrow_mut();
+        let anchor = state.layout_anchor();
+        state.set_rtl(enabled);
+        window.set_rtl_mode(enabled);
+        Self::sync_layout(window, &mut state, anchor);
+    }
+
+    /// 切换夜间模式
+    pub fn set_night_mode(&self, window: &AppWindow, enabled: bool) {
+        let mut state = self.page_view_state.borrow_mut();
+        state.set_night_mode(enabled);
+        window.set_night_mode(enabled);
+        Self::refresh_view(window, &state);
+    }
+
+    /// 切换当前文档的扫描页纠偏，按书保存
+    pub fn set_deskew(&self, window: &AppWindow, enabled: bool) {
+        let path = window.get_file_path().to_string();
+        AppSettings::update_book(&path, |book| book.deskew = enabled);
+        let mut state = self.page_view_state.borrow_mut();
+        state.set_deskew(enabled);
+        window.set_deskew_enabled(enabled);
+        Self::refresh_view(window, &state);
+    }
+
+    /// 停止朗读，同时丢弃还在推送的reflow内容
+    pub fn stop_speaking(&self) {
+        self.speak_generation.fetch_add(1, Ordering::SeqCst);
+        self.page_view_state.borrow().decode_service.cancel_reflow();
+        self.tts_service.lock().unwrap().stop_speaking();
+    }
+
+    /// 朗读一段文字，打断正在进行的朗读
+    pub fn speak_text(&self, text: String) {
+        self.stop_speaking();
+        let language = crate::reflow::language::detect_language(&text);
+        self.tts_service.lock().unwrap().speak_text_with_language(text, language);
     }
 
     /// 打开文档 - 触发异步文档加载流程
     pub fn open_document(&self, window: &AppWindow, path: &str) {
         info!("Opening document: {}", path);
+        if !SimpleModeController::allows_path(path) {
+            SimpleModeController::deny(window, path);
+            return;
+        }
 
         if let Some(mut old_timer) = self.load_timer.take() {
             old_timer.stop();
@@ -331,6 +851,9 @@ impl DocumentController {
         let path_str = path.to_string();
         let state = Rc::clone(&self.page_view_state);
         let viewmodel_clone = Rc::clone(&self.viewmodel);
+        let same_content = Rc::clone(&self.same_content);
+        same_content.borrow_mut().take();
+        window.set_show_same_content_toast(false);
 
         let timer_active = Rc::new(RefCell::new(true));
         let timer_active_clone = Rc::clone(&timer_active);
@@ -349,7 +872,7 @@ impl DocumentController {
                 *timer_active_clone.borrow_mut() = false;
                 
                 if let Some(window) = weak_window.upgrade() {
-                    Self::handle_document_opened(&window, result, &path_str, Rc::clone(&state), Rc::clone(&viewmodel_clone));
+                    Self::handle_document_opened(&window, result, &path_str, Rc::clone(&state), Rc::clone(&viewmodel_clone), &same_content);
                 }
             }
         });
@@ -359,7 +882,7 @@ impl DocumentController {
         let open_result = self.page_view_state.borrow_mut().open_document(path);
     }
 
-    fn handle_document_opened(window: &AppWindow, result: Result<Vec<PageInfo>, anyhow::Error>, path: &str, page_view_state: Rc<RefCell<PageViewState>>, viewmodel: Rc<RefCell<MainViewmodel>>) {
+    fn handle_document_opened(window: &AppWindow, result: crate::error::Result<Vec<PageInfo>>, path: &str, page_view_state: Rc<RefCell<PageViewState>>, viewmodel: Rc<RefCell<MainViewmodel>>, same_content: &Rc<RefCell<Option<Recent>>>) {
         match result {
             Ok(pages) => {
                 let mut state = page_view_state.borrow_mut();
@@ -368,37 +891,40 @@ impl DocumentController {
                 // 先查询数据库是否存在记录
                 let existing_recent = viewmodel.borrow().get_recent_by_path(path).unwrap_or(None);
 
-                let (zoom, page, scroll_x, scroll_y) = if let Some(ref rec) = existing_recent {
-                    (rec.zoom, rec.page, rec.scroll_x, rec.scroll_y)
+                let (zoom, page, anchor_ratio, scroll_x, scroll_y) = if let Some(ref rec) = existing_recent {
+                    (rec.zoom, rec.page, rec.anchor_ratio, rec.scroll_x, rec.scroll_y)
                 } else {
-                    (1.0, 1, 0, 0) // 默认值
+                    (1.0, 1, 0.0, 0, 0) // 默认值
                 };
 
                 window.set_file_path(path.into());
-                window.set_zoom(zoom);
-                window.set_current_page(page);
+                AnnotationController::load(window);
+                window.set_show_search_bar(false);
+                window.set_search_status("".into());
+                let book = AppSettings::book(path);
+                window.set_deskew_enabled(book.deskew);
+                // 阅读方向按书保存，要在恢复位置之前设好
+                state.set_rtl(book.rtl);
+                window.set_rtl_mode(book.rtl);
+                // 手动切边的页边距保存在阅读记录中
+                if let Some(margins) = existing_recent.as_ref().and_then(|rec| CropMargins::decode(&rec.crop_margins)) {
+                    state.set_crop(1);
+                    state.set_crop_margins(Some(margins));
+                    window.set_crop_enabled(true);
+                }
                 window.set_document_opened(true);
                 window.set_page_count(state.pages.len() as i32);
+                Self::apply_reading_position(window, &mut state, zoom, page, anchor_ratio, scroll_x, scroll_y);
 
-                let width = state.view_size.0;
-                let height = state.view_size.1;
+                window.set_outline_filter_text("".into());
+                Self::set_outline_to_ui(&state);
+                Self::refresh_outline_current(window, &state);
+                Self::set_waypoints_to_ui(window, &state);
+                BookmarkController::set_bookmarks_to_ui(window);
 
-                state.update_view_size(
-                    width,
-                    height,
-                    zoom,
-                    true
-                );
-                let (total_width, total_height) = (state.total_width, state.total_height);
-                window.set_total_width(total_width);
-                window.set_total_height(total_height);
-
-                if state.jump_to_page((page - 1) as usize).is_some() {
-                }
-                window.set_offset_x(scroll_x as f32);
-                window.set_offset_y(scroll_y as f32);
-
-                Self::set_outline_to_ui(window, &state);
+                // 内容hash用来在书被复制、移动后找回阅读记录。它只采样文件首尾，打开时马上就能算出；
+                // 完整的 SHA-256 大文件要算很久，不能等它，算好后在 start_checksum 中确认或否定这里的匹配
+                let content_hash = content_hash_string(std::path::Path::new(path)).unwrap_or_default();
 
                 if existing_recent.is_none() {
                     let recent = crate::entity::Recent::encode(
@@ -417,19 +943,55 @@ impl DocumentController {
                         0, // read_times
                         1, // progress
                         0, // favorited
-                        0, // in_recent
+                        1, // in_recent
                     );
+                    let mut recent = recent;
+                    recent.content_hash = sea_orm::ActiveValue::Set(content_hash.clone());
+                    SeriesController::apply(&mut recent, std::path::Path::new(path));
                     if let Err(e) = viewmodel.borrow().add_recent(recent) {
                         error!("Failed to add recent: {e}");
                     }
+
+                    match viewmodel.borrow().find_same_content(path, &content_hash) {
+                        Ok(Some(source)) => Self::offer_same_content(window, same_content, path, source),
+                        Ok(None) => {}
+                        Err(e) => error!("[Document] 查找相同内容的记录失败: {e}"),
+                    }
+                } else if let Some(rec) = existing_recent.as_ref().filter(|rec| rec.is_deleted()) {
+                    if let Err(e) = viewmodel.borrow().restore_recent(rec.id) {
+                        error!("[Document] 恢复历史记录失败: {e}");
+                    }
+                }
+                if existing_recent.as_ref().is_some_and(|rec| rec.in_recent == 0) {
+                    if let Err(e) = viewmodel.borrow().mark_recent(path) {
+                        error!("[Document] 加入最近打开失败: {e}");
+                    }
                 }
+                // 没有算过、旧格式或文件改过时更新
+                if existing_recent.as_ref().is_some_and(|rec| rec.content_hash != content_hash) && !content_hash.is_empty() {
+                    if let Err(e) = viewmodel.borrow().set_content_hash(path, &content_hash) {
+                        error!("[Document] 保存内容hash失败: {e}");
+                    }
+                }
+                // 页数、文件大小和文档自带的书名、作者，书架上显示
+                let metadata = state.decode_service.get_document_metadata().unwrap_or_else(|e| {
+                    error!("[Document] 读取文档元数据失败: {e}");
+                    DocumentMetadata::default()
+                });
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                if let Err(e) = viewmodel.borrow().set_document_info(path, &metadata, state.pages.len(), size) {
+                    error!("[Document] 保存文档信息失败: {e}");
+                }
+                Self::start_checksum(window, path, existing_recent.is_none(), Rc::clone(&viewmodel), same_content);
+                crate::platform::note_recent_document(std::path::Path::new(path));
+                set_recent_menu_to_ui(window);
 
                 state.update_visible_pages();
                 Self::refresh_view(window, &state);
             }
             Err(err) => {
                 error!("Failed to open PDF: {err}");
-                window.set_error_message("打开文档失败".into());
+                window.set_error_message(err.user_message().into());
                 window.set_show_error_dialog(true);
                 let mut borrowed_state = page_view_state.borrow_mut();
                 borrowed_state.shutdown();
@@ -437,10 +999,157 @@ impl DocumentController {
         }
     }
 
+    /// 属性中的校验和，以及书库里的同一文件的其他副本
+    fn checksum_properties(viewmodel: &Rc<RefCell<MainViewmodel>>, path: &str) -> Vec<crate::PropertyItem> {
+        let Some(sha256) = ChecksumController::current(path) else {
+            return vec![crate::PropertyItem { name: "SHA-256".into(), value: "计算中…".into() }];
+        };
+        let copies = viewmodel.borrow().find_same_file(path, &sha256).unwrap_or_default();
+        let mut items = vec![crate::PropertyItem { name: "SHA-256".into(), value: sha256.into() }];
+        if !copies.is_empty() {
+            let paths = copies.iter().map(|copy| copy.book_path.as_str()).collect::<Vec<_>>().join("\n");
+            items.push(crate::PropertyItem { name: "相同文件".into(), value: paths.into() });
+        }
+        items
+    }
+
+    fn offer_same_content(window: &AppWindow, same_content: &Rc<RefCell<Option<Recent>>>, path: &str, source: Recent) {
+        info!("[Document] {} 与 {} 内容相同", path, source.book_path);
+        window.set_same_content_toast_text(
+            format!("这本书读到过第 {} 页（{}），沿用之前的阅读记录？", source.page.max(1), source.book_path).into(),
+        );
+        window.set_show_same_content_toast(true);
+        same_content.replace(Some(source));
+    }
+
+    /// 后台计算 SHA-256 并存入阅读记录；采样的内容hash可能误判，算好后用完整的校验和确认，
+    /// 第一次打开且采样没有匹配上时，再按校验和查找同一文件的副本
+    fn start_checksum(window: &AppWindow, path: &str, first_open: bool, viewmodel: Rc<RefCell<MainViewmodel>>, same_content: &Rc<RefCell<Option<Recent>>>) {
+        let weak_window = window.as_weak();
+        let path_str = path.to_string();
+        let same_content = Rc::clone(same_content);
+        let sampled_match = same_content.borrow().is_some();
+        ChecksumController::start(path, move |sha256| {
+            if let Err(e) = viewmodel.borrow().set_sha256(&path_str, sha256) {
+                error!("[Document] 保存 SHA-256 失败: {e}");
+            }
+            let Some(window) = weak_window.upgrade() else { return };
+            let mismatch = same_content.borrow().as_ref()
+                .is_some_and(|source| !source.sha256.is_empty() && source.sha256 != sha256);
+            if mismatch {
+                info!("[Document] {} 与之前的记录采样相同但 SHA-256 不同，不再提示沿用", path_str);
+                same_content.replace(None);
+                window.set_show_same_content_toast(false);
+            } else if sampled_match {
+                return;
+            }
+            if !first_open {
+                return;
+            }
+            match viewmodel.borrow().find_same_file(&path_str, sha256) {
+                Ok(copies) => {
+                    if let Some(source) = copies.into_iter().next() {
+                        Self::offer_same_content(&window, &same_content, &path_str, source);
+                    }
+                }
+                Err(e) => error!("[Document] 按 SHA-256 查找相同文件失败: {e}"),
+            }
+        });
+    }
+
+    /// 恢复阅读位置：缩放、页码（从 1 开始）和页内位置；旧记录没有页内位置（anchor_ratio < 0），按滚动偏移恢复
+    fn apply_reading_position(window: &AppWindow, state: &mut PageViewState, zoom: f32, page: i32, anchor_ratio: f32, scroll_x: i32, scroll_y: i32) {
+        let zoom = zoom.min(Self::zoom_limit(window));
+        window.set_zoom(zoom);
+        window.set_current_page(page);
+
+        let width = state.view_size.0;
+        let height = state.view_size.1;
+
+        state.update_view_size(
+            width,
+            height,
+            zoom,
+            true
+        );
+        let (total_width, total_height) = (state.total_width, state.total_height);
+        window.set_total_width(total_width);
+        window.set_total_height(total_height);
+
+        let page_index = (page.max(1) - 1) as usize;
+        let (offset_x, offset_y) = if anchor_ratio >= 0.0 {
+            state.jump_to_anchor(page_index, anchor_ratio).unwrap_or((scroll_x as f32, scroll_y as f32))
+        } else {
+            state.jump_to_page(page_index);
+            (scroll_x as f32, scroll_y as f32)
+        };
+        window.set_offset_x(offset_x);
+        window.set_offset_y(offset_y);
+    }
+
+    /// 当前文档允许的最大缩放，渲染反复失败过的文档有更低的上限
+    fn zoom_limit(window: &AppWindow) -> f32 {
+        let path = window.get_file_path();
+        AppSettings::book(&path).max_zoom.map_or(MAX_ZOOM, |cap| cap.clamp(MIN_ZOOM, MAX_ZOOM))
+    }
+
+    /// 处理解码线程报告的渲染失败；同一缩放下反复失败（mupdf 出错、内存不足）时降低缩放，
+    /// 并把降低后的缩放记为这本书的上限，下次打开直接使用
+    pub fn handle_render_failures(&self, window: &AppWindow) {
+        let failures: Vec<RenderFailure> = {
+            let state = self.page_view_state.borrow();
+            std::iter::from_fn(|| state.decode_service.try_recv_failure()).collect()
+        };
+        if failures.is_empty() {
+            return;
+        }
+        for failure in &failures {
+            warn!("[Document] 页面 {} 在 {:.2} 倍下渲染失败: {}", failure.page_index, failure.scale, failure.message);
+        }
+
+        let zoom = self.page_view_state.borrow().zoom;
+        let count = {
+            let mut render_failures = self.render_failures.borrow_mut();
+            if render_failures.0 != zoom {
+                *render_failures = (zoom, 0);
+            }
+            render_failures.1 += failures.len();
+            render_failures.1
+        };
+        if count < RENDER_FAILURE_LIMIT {
+            return;
+        }
+        self.render_failures.replace((0.0, 0));
+        if zoom <= MIN_ZOOM {
+            error!("[Document] 最小缩放下仍然渲染失败，无法继续降低");
+            return;
+        }
+
+        let capped = (zoom * ZOOM_STEP_DOWN).max(MIN_ZOOM);
+        let path = window.get_file_path().to_string();
+        info!("[Document] 渲染反复失败，缩放从 {:.2} 降到 {:.2}: {}", zoom, capped, path);
+        AppSettings::update_book(&path, |book| book.max_zoom = Some(capped));
+
+        let (width, height) = self.page_view_state.borrow().view_size;
+        self.zoom_at(window, capped, width / 2.0, height / 2.0);
+        window.set_zoom_cap_toast_text(
+            format!("页面在 {:.0}% 下渲染失败，已降到 {:.0}%，这本书的缩放不再超过此值", zoom * 100.0, capped * 100.0).into(),
+        );
+        window.set_show_zoom_cap_toast(true);
+    }
+
     pub fn close_document(&self, window: &AppWindow) {
         let mut state = self.page_view_state.borrow_mut();
         state.reset();
+        self.search_timer.replace(None);
+        ChecksumController::stop();
+        window.set_show_search_bar(false);
+        window.set_search_highlights(ModelRc::default());
+        StatusController::reset_document(window);
         window.set_file_path(SharedString::from(""));
+        BookmarkController::set_bookmarks_to_ui(window);
+        AnnotationController::load(window);
+        RedactionController::exit(window);
         window.set_document_opened(false);
     }
 
@@ -448,29 +1157,77 @@ impl DocumentController {
         Rc::clone(&self.page_view_state)
     }
 
-    /// 设置大纲项到UI
-    fn set_outline_to_ui(app: &AppWindow, page_view_state: &PageViewState) {
-        let ui_outline_items: Vec<crate::OutlineItem> = page_view_state.outline_items.iter().map(|oi| crate::OutlineItem {
-            title: oi.title.clone().into(),
-            page: oi.page,
-            level: oi.level,
-        }).collect();
-        app.set_outline_items(ModelRc::from(Rc::new(VecModel::from(ui_outline_items))));
+    /// 滚动到当前搜索匹配并更新高亮和计数
+    fn show_search_hit(window: &AppWindow, state: &mut PageViewState) {
+        if let Some((page_index, rect)) = state.search.current() {
+            if let Some((offset_x, offset_y)) = state.jump_to_rect(page_index, rect) {
+                window.set_scroll_events_enabled(false);
+                window.set_offset_x(offset_x);
+                window.set_offset_y(offset_y);
+                window.set_scroll_events_enabled(true);
+                state.update_visible_pages();
+            }
+        }
+        Self::refresh_view(window, state);
     }
 
-    fn parse_page_from_param(page_param: &str) -> Option<usize> {
-        if page_param.starts_with("#page=") {
-            // 以 '&' 为分割符，得到第一个元素
-            let first_part = page_param.split('&').next()?;
-            // 以 '=' 为分割符，得到第二个元素（数字部分）
-            let parts: Vec<&str> = first_part.split('=').collect();
-            if parts.len() == 2 {
-                parts[1].parse::<usize>().ok()
+    /// 更新搜索高亮和“第几个/共几个”
+    fn refresh_search(window: &AppWindow, state: &PageViewState) {
+        let highlights: Vec<crate::SearchHighlight> = state.search_highlights()
+            .into_iter()
+            .map(|(x, y, width, height, current)| crate::SearchHighlight { x, y, width, height, current })
+            .collect();
+        if !highlights.is_empty() || window.get_search_highlights().row_count() > 0 {
+            window.set_search_highlights(ModelRc::from(Rc::new(VecModel::from(highlights))));
+        }
+        if !state.search.query.is_empty() {
+            let status = if state.search.is_empty() {
+                "没有找到".to_string()
             } else {
-                None
+                format!("{}/{}", state.search.position(), state.search.len())
+            };
+            window.set_search_status(status.into());
+        }
+    }
+
+    /// 设置路标列表到UI
+    fn set_waypoints_to_ui(window: &AppWindow, page_view_state: &PageViewState) {
+        let items: Vec<SharedString> = page_view_state.waypoints.iter().map(|waypoint| {
+            let label = page_view_state.page_label(waypoint.page);
+            if label.is_empty() {
+                format!("第 {} 页", waypoint.page + 1).into()
+            } else {
+                format!("第 {} 页（{}）", waypoint.page + 1, label).into()
             }
-        } else {
-            None
+        }).collect();
+        window.set_waypoint_items(ModelRc::from(Rc::new(VecModel::from(items))));
+    }
+
+    /// 设置大纲项到UI
+    fn set_outline_to_ui(page_view_state: &PageViewState) {
+        let ui_outline_items: Vec<crate::OutlineItem> = (0..page_view_state.outline_items.len())
+            .map(|row| Self::outline_row(page_view_state, row))
+            .collect();
+        OUTLINE_MODEL.with(|model| model.set_vec(ui_outline_items));
+    }
+
+    /// 大纲中高亮当前页所在的条目
+    fn refresh_outline_current(window: &AppWindow, page_view_state: &PageViewState) {
+        let row = page_view_state.get_first_visible_page()
+            .and_then(|page| page_view_state.outline_row_for_page(page))
+            .map_or(-1, |row| row as i32);
+        window.set_outline_current_row(row);
+    }
+
+    fn outline_row(page_view_state: &PageViewState, row: usize) -> crate::OutlineItem {
+        let oi = &page_view_state.outline_items[row];
+        crate::OutlineItem {
+            title: oi.title.clone().into(),
+            page: oi.page,
+            label: if oi.page >= 0 { page_view_state.page_label(oi.page as usize).into() } else { SharedString::new() },
+            level: oi.level,
+            has_children: oi.has_children,
+            expanded: page_view_state.outline_expanded(row),
         }
     }
 }
@@ -0,0 +1,331 @@
+use log::{error, info};
+use slint::{ModelRc, VecModel};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::controllers::{DocumentController, TaskController, TaskStatus};
+use crate::dao::AnnotationDao;
+use crate::decoder::pdf::{rewrite_pdf_in_background, save_with_highlights_in_background, PdfChapter, PdfHighlight, RewriteOptions, RewriteStats, SplitEvent, SplitJob, Watermark};
+use crate::decoder::{VerifyEvent, VerifyJob, VerifyReport};
+use crate::{AppWindow, PropertyItem};
+
+/// 报告里最多列出的问题数
+const MAX_REPORTED_PROBLEMS: usize = 50;
+
+/// 文档工具：完整性检查、修复、优化导出等针对文件本身的操作，在后台运行并显示进度
+pub struct DocumentToolsController;
+
+impl DocumentToolsController {
+    /// 逐页检查当前文档，结束后显示检查报告
+    pub fn verify_document(window: &AppWindow) {
+        let path = PathBuf::from(window.get_file_path().to_string());
+        info!("[Tools] 检查文档: {:?}", path);
+        let job = Rc::new(VerifyJob::start(path));
+        let job_for_cancel = Rc::clone(&job);
+        TaskController::start(
+            window,
+            "正在检查文档…",
+            move || job_for_cancel.cancel(),
+            move |window| {
+                let mut status = None;
+                while let Some(event) = job.try_recv() {
+                    match event {
+                        VerifyEvent::Progress { checked, total } => {
+                            status = Some(TaskStatus::Running(format!("正在检查文档… {}/{}", checked, total)));
+                        }
+                        VerifyEvent::Finished(report) => {
+                            let summary = Self::summary(&report);
+                            Self::show_report(window, &report);
+                            return Some(TaskStatus::Done(summary));
+                        }
+                    }
+                }
+                status
+            },
+        );
+    }
+
+    /// 通过 mupdf 的 pdf 写入器重写文档，保存为修复后的副本
+    pub fn save_repaired_copy(window: &AppWindow, linearize: bool) {
+        let source = PathBuf::from(window.get_file_path().to_string());
+        if !Self::is_pdf(&source) {
+            Self::show_error(window, "只能修复 PDF 文档");
+            return;
+        }
+        let Some(target) = Self::pick_target(&source, "repaired") else { return };
+        if target == source {
+            Self::show_error(window, "请另存为新文件，不要覆盖正在阅读的文档");
+            return;
+        }
+
+        info!("[Tools] 保存修复副本: {:?} -> {:?}, linearize={}", source, target, linearize);
+        let result_rx = rewrite_pdf_in_background(source, target, RewriteOptions { linearize, image_quality: None });
+        // 重写由 mupdf 一次完成，无法中途取消
+        TaskController::start(window, "正在保存修复后的副本…", || {}, move |_| {
+            let result = result_rx.try_recv().ok()?;
+            Some(TaskStatus::Done(match result {
+                Ok(stats) => format!("已保存修复后的副本（{}）", Self::size_change(&stats)),
+                Err(e) => {
+                    error!("[Tools] 修复失败: {:#}", e);
+                    "修复失败，文件损坏过于严重".to_string()
+                }
+            }))
+        });
+    }
+
+    /// 把 RReader 中的高亮和笔记写成 PDF 注释，保存为副本，其他阅读器也能看到
+    pub fn save_annotated_copy(window: &AppWindow) {
+        let source = PathBuf::from(window.get_file_path().to_string());
+        if !Self::is_pdf(&source) {
+            Self::show_error(window, "只能把高亮保存到 PDF 文档");
+            return;
+        }
+        let annotations = match AnnotationDao::find_by_book_sync(&source.to_string_lossy()) {
+            Ok(annotations) => annotations,
+            Err(e) => {
+                error!("[Tools] 读取高亮失败: {}", e);
+                Self::show_error(window, e.user_message());
+                return;
+            }
+        };
+        if annotations.is_empty() {
+            Self::show_error(window, "这本书还没有高亮");
+            return;
+        }
+        let Some(target) = Self::pick_target(&source, "annotated") else { return };
+        if target == source {
+            Self::show_error(window, "请另存为新文件，不要覆盖正在阅读的文档");
+            return;
+        }
+
+        let highlights: Vec<PdfHighlight> = annotations.iter().map(|annotation| PdfHighlight {
+            page: annotation.page.max(0) as usize,
+            rects: annotation.rect_list(),
+            color: annotation.rgb().map(|c| c as f32 / 255.0),
+            note: annotation.note.clone(),
+        }).collect();
+        info!("[Tools] 保存带高亮的副本: {:?} -> {:?}, {} 处", source, target, highlights.len());
+        let result_rx = save_with_highlights_in_background(source, target, highlights);
+        TaskController::start(window, "正在保存带高亮的副本…", || {}, move |_| {
+            let result = result_rx.try_recv().ok()?;
+            Some(TaskStatus::Done(match result {
+                Ok(count) => format!("已保存副本，写入 {} 处高亮", count),
+                Err(e) => {
+                    error!("[Tools] 保存高亮失败: {:#}", e);
+                    "保存失败".to_string()
+                }
+            }))
+        });
+    }
+
+    /// 导出体积优化的副本：按质量重新压缩图像、清理无用对象并线性化，完成后报告前后大小
+    pub fn export_optimized(window: &AppWindow, image_quality: u8) {
+        let source = PathBuf::from(window.get_file_path().to_string());
+        if !Self::is_pdf(&source) {
+            Self::show_error(window, "只能优化 PDF 文档");
+            return;
+        }
+        let Some(target) = Self::pick_target(&source, "optimized") else { return };
+        if target == source {
+            Self::show_error(window, "请另存为新文件，不要覆盖正在阅读的文档");
+            return;
+        }
+
+        info!("[Tools] 导出优化副本: {:?} -> {:?}, quality={}", source, target, image_quality);
+        let options = RewriteOptions { linearize: true, image_quality: Some(image_quality) };
+        let result_rx = rewrite_pdf_in_background(source, target, options);
+        TaskController::start(window, "正在导出优化后的副本…", || {}, move |window| {
+            let result = result_rx.try_recv().ok()?;
+            Some(TaskStatus::Done(match result {
+                Ok(stats) => {
+                    Self::show_size_report(window, image_quality, &stats);
+                    format!("已导出（{}）", Self::size_change(&stats))
+                }
+                Err(e) => {
+                    error!("[Tools] 导出失败: {:#}", e);
+                    "导出失败".to_string()
+                }
+            }))
+        });
+    }
+
+    /// 按大纲的顶层章节拆分，每章保存为一个以章节标题命名的 PDF，设置了水印时一并加上；
+    /// 第一章之前的页面另存一个文件
+    pub fn split_by_chapters(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let source = PathBuf::from(window.get_file_path().to_string());
+        if !Self::is_pdf(&source) {
+            Self::show_error(window, "只能拆分 PDF 文档");
+            return;
+        }
+        let chapters = {
+            let page_view_state = document_controller.borrow().page_view_state();
+            let state = page_view_state.borrow();
+            // 大纲的顶层条目，不受大纲面板的展开和过滤影响，无法解析目标页的跳过
+            let outline = state.decode_service.get_outline().unwrap_or_default();
+            let top_level = outline.iter().map(|item| item.level).min().unwrap_or(0);
+            let starts: Vec<(String, usize)> = outline.iter()
+                .filter(|item| item.level == top_level)
+                .filter_map(|item| {
+                    let page = if item.page >= 0 {
+                        Some(item.page as usize)
+                    } else {
+                        item.uri.as_deref().and_then(|uri| state.resolve_page_ref(uri))
+                    };
+                    page.map(|page| (item.title.trim().to_string(), page))
+                })
+                .collect();
+            PdfChapter::from_starts(&starts, state.pages.len())
+        };
+        if chapters.len() < 2 {
+            Self::show_error(window, "文档的大纲中没有可以拆分的章节");
+            return;
+        }
+
+        let mut dialog = rfd::FileDialog::new().set_title("Choose a Folder for the Chapters");
+        if let Some(dir) = source.parent() {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(dir) = dialog.pick_folder() else { return };
+
+        // 目录里已有同名文件时先确认，不静默覆盖
+        let existing = chapters.iter()
+            .enumerate()
+            .filter(|(i, chapter)| dir.join(chapter.file_name(i + 1)).exists())
+            .count();
+        if existing > 0 {
+            let confirmed = rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title("Replace Existing Files")
+                .set_description(format!("目录中已有 {} 个同名的章节文件，继续会覆盖它们。\n\n确定要继续吗？", existing))
+                .set_buttons(rfd::MessageButtons::OkCancel)
+                .show();
+            if confirmed != rfd::MessageDialogResult::Ok {
+                info!("[Tools] 取消拆分: {} 个文件已存在", existing);
+                return;
+            }
+        }
+
+        info!("[Tools] 按章拆分: {:?} -> {:?}, {} 章", source, dir, chapters.len());
+        let total = chapters.len();
+        let job = Rc::new(SplitJob::start(source, dir, chapters, Watermark::from_settings()));
+        let job_for_cancel = Rc::clone(&job);
+        TaskController::start(
+            window,
+            &format!("正在拆分章节… 0/{}", total),
+            move || job_for_cancel.cancel(),
+            move |_| {
+                let mut status = None;
+                while let Some(event) = job.try_recv() {
+                    match event {
+                        SplitEvent::Progress { done, total } => {
+                            status = Some(TaskStatus::Running(format!("正在拆分章节… {}/{}", done, total)));
+                        }
+                        SplitEvent::Finished { written, error } => {
+                            return Some(TaskStatus::Done(match error {
+                                Some(e) => {
+                                    error!("[Tools] 拆分失败: {}", e);
+                                    format!("拆分失败，已保存 {}/{} 章", written.len(), total)
+                                }
+                                None if written.len() < total => format!("已取消，已保存 {}/{} 章", written.len(), total),
+                                None => format!("已拆分为 {} 个文件", written.len()),
+                            }));
+                        }
+                    }
+                }
+                status
+            },
+        );
+    }
+
+    fn show_size_report(window: &AppWindow, image_quality: u8, stats: &RewriteStats) {
+        let mb = |bytes: u64| format!("{:.2} MB", bytes as f64 / 1024.0 / 1024.0);
+        let saved = if stats.before > 0 {
+            (stats.before as f64 - stats.after as f64) / stats.before as f64 * 100.0
+        } else {
+            0.0
+        };
+        let items = vec![
+            PropertyItem { name: "原始大小".into(), value: mb(stats.before).into() },
+            PropertyItem { name: "导出大小".into(), value: mb(stats.after).into() },
+            PropertyItem { name: "减少".into(), value: format!("{:.1}%", saved).into() },
+            PropertyItem { name: "图像质量".into(), value: image_quality.to_string().into() },
+            PropertyItem { name: "重新压缩的图像".into(), value: stats.images_recompressed.to_string().into() },
+        ];
+        window.set_properties_dialog_title("优化导出".into());
+        window.set_document_properties(ModelRc::from(Rc::new(VecModel::from(items))));
+        window.set_show_properties_dialog(true);
+    }
+
+    fn is_pdf(path: &Path) -> bool {
+        path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+    }
+
+    /// 另存为对话框，默认文件名为“原名-后缀.pdf”
+    fn pick_target(source: &Path, suffix: &str) -> Option<PathBuf> {
+        let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("Save Copy")
+            .add_filter("PDF", &["pdf"])
+            .set_file_name(format!("{}-{}.pdf", stem, suffix));
+        if let Some(dir) = source.parent() {
+            dialog = dialog.set_directory(dir);
+        }
+        dialog.save_file()
+    }
+
+    fn size_change(stats: &RewriteStats) -> String {
+        let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+        format!("{:.2} MB → {:.2} MB", mb(stats.before), mb(stats.after))
+    }
+
+    fn show_error(window: &AppWindow, message: &str) {
+        window.set_error_message(message.into());
+        window.set_show_error_dialog(true);
+    }
+
+    fn summary(report: &VerifyReport) -> String {
+        if report.open_error.is_some() {
+            "文件无法打开，可能已损坏".to_string()
+        } else if !report.is_complete() {
+            format!("检查已取消，已检查 {}/{} 页", report.checked, report.page_count)
+        } else if report.is_ok() {
+            format!("检查完成，{} 页均正常", report.page_count)
+        } else {
+            format!("检查完成，发现 {} 处问题", report.problems.len())
+        }
+    }
+
+    fn show_report(window: &AppWindow, report: &VerifyReport) {
+        let mut items = vec![
+            PropertyItem { name: "结果".into(), value: Self::summary(report).into() },
+            PropertyItem { name: "页数".into(), value: report.page_count.to_string().into() },
+            PropertyItem { name: "已检查".into(), value: report.checked.to_string().into() },
+        ];
+        if let Some(error) = &report.open_error {
+            items.push(PropertyItem { name: "打开失败".into(), value: error.as_str().into() });
+        }
+        if !report.is_ok() {
+            // 文件本身解析不了时，其他阅读器通常也会出错
+            items.push(PropertyItem {
+                name: "说明".into(),
+                value: "以上错误来自文档解析库，通常说明文件本身已损坏，可以尝试保存修复后的副本".into(),
+            });
+        }
+        for problem in report.problems.iter().take(MAX_REPORTED_PROBLEMS) {
+            items.push(PropertyItem {
+                name: format!("第 {} 页 · {}", problem.page_index + 1, problem.stage).into(),
+                value: problem.message.as_str().into(),
+            });
+        }
+        if report.problems.len() > MAX_REPORTED_PROBLEMS {
+            items.push(PropertyItem {
+                name: "…".into(),
+                value: format!("另有 {} 处问题未列出", report.problems.len() - MAX_REPORTED_PROBLEMS).into(),
+            });
+        }
+        window.set_properties_dialog_title("文档检查".into());
+        window.set_document_properties(ModelRc::from(Rc::new(VecModel::from(items))));
+        window.set_show_properties_dialog(true);
+    }
+}
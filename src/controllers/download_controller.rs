@@ -0,0 +1,50 @@
+use log::{error, info};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::controllers::{DocumentController, TaskController, TaskStatus};
+use crate::net::{Checksum, DownloadEvent, DownloadJob};
+use crate::AppWindow;
+
+/// 下载网上的文档，完成后直接打开；显示在底部任务提示条中，可以取消，下次续传
+pub struct DownloadController;
+
+impl DownloadController {
+    pub fn download(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, url: &str, checksum: Option<Checksum>) {
+        info!("[Download] 开始下载: {}", url);
+        let job = Rc::new(DownloadJob::start(url.to_string(), checksum));
+        let job_for_cancel = Rc::clone(&job);
+        let job_for_pause = Rc::clone(&job);
+        let document_controller = Rc::clone(document_controller);
+        let started = TaskController::start(window, "正在下载…", move || job_for_cancel.cancel(), move |window| {
+            Self::poll(window, &job, &document_controller)
+        });
+        if started {
+            TaskController::set_pause_handler(window, move |paused| job_for_pause.set_paused(paused));
+        }
+    }
+
+    fn poll(window: &AppWindow, job: &DownloadJob, document_controller: &Rc<RefCell<DocumentController>>) -> Option<TaskStatus> {
+        let mut status = None;
+        while let Some(event) = job.try_recv() {
+            match event {
+                DownloadEvent::Progress { received, total: Some(total) } if total > 0 => {
+                    status = Some(TaskStatus::Running(format!("正在下载… {}%", received * 100 / total)));
+                }
+                DownloadEvent::Progress { received, .. } => {
+                    status = Some(TaskStatus::Running(format!("正在下载… {:.1} MB", received as f64 / 1024.0 / 1024.0)));
+                }
+                DownloadEvent::Finished(Ok(path)) => {
+                    document_controller.borrow().open_document(window, &path.to_string_lossy());
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    return Some(TaskStatus::Done(format!("已下载 {}", name)));
+                }
+                DownloadEvent::Finished(Err(e)) => {
+                    error!("[Download] 下载失败: {}", e);
+                    return Some(TaskStatus::Done(format!("下载失败：{}", e)));
+                }
+            }
+        }
+        status
+    }
+}
@@ -0,0 +1,68 @@
+use log::{debug, warn};
+use std::path::Path;
+use std::process;
+
+use crate::controllers::ClipboardController;
+use crate::error::{RReaderError, Result};
+use crate::platform::open_with_system;
+
+/// 单个文档的文件操作：在文件管理器中显示、复制路径
+pub struct FileActions;
+
+impl FileActions {
+    /// 在 Finder/资源管理器/文件管理器中显示并选中文件
+    pub fn reveal_in_folder(path: &str) -> Result<()> {
+        let file = Path::new(path);
+        if !file.exists() {
+            return Err(RReaderError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("file not found: {}", path),
+            )));
+        }
+        debug!("[FileActions] reveal: {}", path);
+
+        if cfg!(target_os = "macos") {
+            process::Command::new("open").arg("-R").arg(path).spawn()?;
+        } else if cfg!(target_os = "windows") {
+            // explorer 要求 /select, 和路径拼在同一个参数里
+            process::Command::new("explorer").arg(format!("/select,{}", path)).spawn()?;
+        } else if !Self::reveal_with_file_manager1(path) {
+            // 文件管理器不支持 FileManager1 接口时只打开所在目录
+            let parent = file.parent().unwrap_or(file);
+            open_with_system(&parent.to_string_lossy())?;
+        }
+        Ok(())
+    }
+
+    /// 复制文件的绝对路径
+    pub fn copy_path(path: &str) -> Result<String> {
+        let absolute = std::fs::canonicalize(path)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string());
+        ClipboardController::copy_text(&absolute)?;
+        Ok(absolute)
+    }
+
+    /// Linux 桌面通过 D-Bus 的 org.freedesktop.FileManager1 选中文件
+    fn reveal_with_file_manager1(path: &str) -> bool {
+        let uri = format!("array:string:file://{}", path);
+        let status = process::Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(uri)
+            .arg("string:")
+            .status();
+        match status {
+            Ok(status) => status.success(),
+            Err(e) => {
+                warn!("[FileActions] dbus-send 不可用: {}", e);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use log::debug;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::controllers::DocumentController;
+use crate::AppWindow;
+
+/// 捏合缩放灵敏度：每像素滚动量对应的缩放比例（对数），缩放按指数变化，再大的滚动量也不会为零或负
+const PINCH_SENSITIVITY: f32 = 0.01;
+/// 单次捏合事件缩放比例的对数上限，异常大的滚动量不会一次缩放到极限
+const MAX_PINCH_STEP: f32 = 2.0;
+/// 触控板横向滑动翻页的最小累计距离
+const SWIPE_DISTANCE: f32 = 120.0;
+/// 翻页需要的最小速度（像素/毫秒），慢速拖动视为平移
+const SWIPE_MIN_VELOCITY: f32 = 0.3;
+/// 两次横向滚动间隔超过该值视为新手势
+const SWIPE_GESTURE_GAP: Duration = Duration::from_millis(150);
+/// 翻页后的冷却时间，避免惯性滚动连续翻多页
+const PAGE_TURN_COOLDOWN: Duration = Duration::from_millis(400);
+/// 双击放大时的默认缩放
+const DOUBLE_TAP_ZOOM: f32 = 2.0;
+
+#[derive(Default)]
+struct GestureState {
+    /// 当前横向手势累计位移
+    swipe_distance: f32,
+    swipe_started: Option<Instant>,
+    last_swipe_event: Option<Instant>,
+    last_page_turn: Option<Instant>,
+    /// 双击适应宽度前的缩放，再次双击时恢复
+    zoom_before_fit: Option<f32>,
+}
+
+/// 手势控制器：把触控板/触屏手势转换为缩放、翻页和适应宽度
+pub struct GestureController {
+    document_controller: Rc<RefCell<DocumentController>>,
+    state: Rc<RefCell<GestureState>>,
+}
+
+impl GestureController {
+    pub fn new(document_controller: Rc<RefCell<DocumentController>>) -> Self {
+        Self {
+            document_controller,
+            state: Rc::new(RefCell::new(GestureState::default())),
+        }
+    }
+
+    pub fn setup_gesture_callbacks(&self, window: &AppWindow) {
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(&self.document_controller);
+        let state = Rc::clone(&self.state);
+        window.on_gesture_scroll(move |x, y, dx, dy, pinch| {
+            let Some(window) = weak_window.upgrade() else { return false };
+            if pinch {
+                // 触控板捏合在 Windows/Linux 上以 Ctrl+滚轮的形式到达
+                let zoom = Self::pinch_zoom(window.get_zoom(), dy);
+                document_controller.borrow().zoom_at(&window, zoom, x, y);
+                return true;
+            }
+            Self::handle_swipe_scroll(&window, &mut state.borrow_mut(), dx, dy)
+        });
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(&self.document_controller);
+        let state = Rc::clone(&self.state);
+        window.on_gesture_double_tap(move |x, y| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let mut state = state.borrow_mut();
+            let zoom = window.get_zoom();
+            // 缩放 1.0 即适应宽度
+            let target = if (zoom - 1.0).abs() > 0.01 {
+                state.zoom_before_fit = Some(zoom);
+                1.0
+            } else {
+                state.zoom_before_fit.take().unwrap_or(DOUBLE_TAP_ZOOM)
+            };
+            debug!("[Gesture] double tap at ({}, {}), zoom {} -> {}", x, y, zoom, target);
+            document_controller.borrow().zoom_at(&window, target, x, y);
+        });
+
+        let weak_window = window.as_weak();
+        let state = Rc::clone(&self.state);
+        window.on_gesture_swiped(move |dx, elapsed_ms| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let velocity = dx.abs() / (elapsed_ms.max(1) as f32);
+            debug!("[Gesture] swipe dx={}, {}ms, velocity={}", dx, elapsed_ms, velocity);
+            if dx.abs() >= SWIPE_DISTANCE && velocity >= SWIPE_MIN_VELOCITY {
+                Self::turn_page(&window, &mut state.borrow_mut(), dx < 0.0);
+            }
+        });
+    }
+
+    /// 捏合后的缩放：同样的滚动量放大和缩小的比例互为倒数
+    fn pinch_zoom(zoom: f32, dy: f32) -> f32 {
+        zoom * (dy * PINCH_SENSITIVITY).clamp(-MAX_PINCH_STEP, MAX_PINCH_STEP).exp()
+    }
+
+    /// 双指横向滑动翻页，返回 false 时交给滚动视图处理
+    fn handle_swipe_scroll(window: &AppWindow, state: &mut GestureState, dx: f32, dy: f32) -> bool {
+        // 以纵向为主或内容比视口宽时，是普通滚动
+        let fits_width = window.get_total_width() <= window.get_viewport_width() + 1.0;
+        if !fits_width || dx.abs() <= dy.abs() {
+            state.swipe_distance = 0.0;
+            state.swipe_started = None;
+            return false;
+        }
+
+        let now = Instant::now();
+        let new_gesture = state.last_swipe_event
+            .map(|last| now.duration_since(last) > SWIPE_GESTURE_GAP)
+            .unwrap_or(true);
+        state.last_swipe_event = Some(now);
+        if new_gesture {
+            state.swipe_distance = 0.0;
+            state.swipe_started = Some(now);
+        }
+        state.swipe_distance += dx;
+
+        let elapsed = state.swipe_started
+            .map(|start| now.duration_since(start).as_millis().max(1) as f32)
+            .unwrap_or(1.0);
+        let velocity = state.swipe_distance.abs() / elapsed;
+        if state.swipe_distance.abs() >= SWIPE_DISTANCE && velocity >= SWIPE_MIN_VELOCITY {
+            // 内容向左移动（dx < 0）表示下一页
+            let forward = state.swipe_distance < 0.0;
+            state.swipe_distance = 0.0;
+            state.swipe_started = None;
+            Self::turn_page(window, state, forward);
+        }
+        true
+    }
+
+    fn turn_page(window: &AppWindow, state: &mut GestureState, forward: bool) {
+        let now = Instant::now();
+        if state.last_page_turn.is_some_and(|last| now.duration_since(last) < PAGE_TURN_COOLDOWN) {
+            return;
+        }
+        state.last_page_turn = Some(now);
+
+        // 从右往左阅读时，内容向右移动才是下一页
+        let forward = forward != window.get_rtl_mode();
+        let current_page = window.get_current_page();
+        let page = if forward {
+            (current_page + 1).min(window.get_page_count())
+        } else {
+            (current_page - 1).max(1)
+        };
+        if page != current_page {
+            window.invoke_page_changed(page);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinch_zoom_stays_positive_for_large_deltas() {
+        for dy in [-100.0, -1000.0, -1.0e6] {
+            let zoom = GestureController::pinch_zoom(2.0, dy);
+            assert!(zoom > 0.0 && zoom < 2.0, "dy={} zoom={}", dy, zoom);
+        }
+        assert!(GestureController::pinch_zoom(2.0, 100.0) > 2.0);
+        // 放大再缩小同样的量回到原来的缩放
+        let zoom = GestureController::pinch_zoom(GestureController::pinch_zoom(1.5, 40.0), -40.0);
+        assert!((zoom - 1.5).abs() < 1e-4);
+    }
+}
@@ -6,12 +6,27 @@ use crate::ui::MainViewmodel;
 use std::cell::RefCell;
 use std::rc::Rc as StdRc;
 use crate::decoder::pdf::utils::convert_to_slint_image;
+use crate::storage::FileStore;
 use crate::ui::utils::get_thumbnail_path;
-use crate::controllers::DocumentController;
+use crate::controllers::{DocumentController, SimpleModeController};
 use log::{debug};
 
 static HISTORY_VIEWPORT_WIDTH: LazyLock<RwLock<f32>> = LazyLock::new(|| RwLock::new(1024.0));
 
+/// 移除的历史记录在回收站保留的天数
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+thread_local! {
+    /// 最近一次移除的记录，撤销提示条用
+    static LAST_REMOVED: std::cell::RefCell<Vec<i32>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn now_millis() -> crate::error::Result<i64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_millis() as i64)
+}
+
 /// 将历史记录转换为UI项目
 pub fn convert_history_records_to_items(records: &[Recent]) -> Vec<crate::UIRecent> {
     records
@@ -21,7 +36,10 @@ pub fn convert_history_records_to_items(records: &[Recent]) -> Vec<crate::UIRece
             let cache_path = get_thumbnail_path(&path);
 
             let (thumbnail, has_thumbnail) = if !cache_path.is_empty() {
-                if let Ok(dynamic_image) = image::open(&cache_path) {
+                let decoded = FileStore::read(std::path::Path::new(&cache_path))
+                    .ok()
+                    .and_then(|data| image::load_from_memory(&data).ok());
+                if let Some(dynamic_image) = decoded {
                     (convert_to_slint_image(&dynamic_image), true)
                 } else {
                     (slint::Image::default(), false)
@@ -32,46 +50,91 @@ pub fn convert_history_records_to_items(records: &[Recent]) -> Vec<crate::UIRece
 
             crate::UIRecent {
                 title: record.name.clone().into(),
+                author: record.author.clone().into(),
                 path: path.into(),
                 thumbnail,
                 has_thumbnail,
                 page: record.page,
+                favorited: record.favorited > 0,
+                custom_cover: crate::library::CustomCover::exists(&record.book_path),
             }
         })
         .collect()
 }
 
+/// 文件菜单“最近打开”显示的数量
+const RECENT_MENU_LIMIT: usize = 10;
+
+/// 从数据库刷新文件菜单的“最近打开”
+pub fn set_recent_menu_to_ui(app: &crate::AppWindow) {
+    let records = match crate::dao::RecentDao::find_all_ordered_by_update_at_desc_sync() {
+        Ok(records) => records,
+        Err(e) => {
+            log::error!("Failed to load recent menu: {}", e);
+            return;
+        }
+    };
+    let items: Vec<crate::RecentMenuItem> = records
+        .iter()
+        .filter(|record| record.in_recent != 0)
+        .take(RECENT_MENU_LIMIT)
+        .map(|record| {
+            let title = if record.name.is_empty() {
+                record.book_path.rsplit(['/', '\\']).next().unwrap_or(&record.book_path).to_string()
+            } else {
+                record.name.clone()
+            };
+            crate::RecentMenuItem {
+                title: title.into(),
+                path: record.book_path.clone().into(),
+            }
+        })
+        .collect();
+    app.set_recent_menu_items(ModelRc::from(Rc::new(VecModel::from(items))));
+}
+
 /// 设置历史记录到UI
 pub fn set_history_to_ui(app: &crate::AppWindow, ui_history_items: Vec<crate::UIRecent>) {
     let history_model = Rc::new(VecModel::from(ui_history_items.clone()));
     app.set_history_items(ModelRc::from(history_model));
 
+    let history_rows_model = Rc::new(VecModel::from(group_into_rows(&ui_history_items)));
+    app.set_history_rows(ModelRc::from(history_rows_model));
+}
+
+/// 按首页宽度把书分成网格的行
+pub fn group_into_rows(items: &[crate::UIRecent]) -> Vec<crate::HistoryRow> {
     let width = *HISTORY_VIEWPORT_WIDTH.read().unwrap();
     let columns = (width / 188.0).floor().max(1.0) as usize;
-    let grouped: Vec<Vec<crate::UIRecent>> = ui_history_items.chunks(columns).map(|c| c.to_vec()).collect();
-    let rows: Vec<crate::HistoryRow> = grouped.into_iter().map(|vec| crate::HistoryRow { items: ModelRc::from(Rc::new(VecModel::from(vec))) }).collect();
-    let history_rows_model = Rc::new(VecModel::from(rows));
-    app.set_history_rows(ModelRc::from(history_rows_model));
+    items.chunks(columns)
+        .map(|chunk| crate::HistoryRow { items: ModelRc::from(Rc::new(VecModel::from(chunk.to_vec()))) })
+        .collect()
 }
 
 pub trait HistoryController {
     /// 获取所有历史记录
-    fn get_history_items(&self) -> Result<Vec<Recent>, Box<dyn std::error::Error>>;
+    fn get_history_items(&self) -> crate::error::Result<Vec<Recent>>;
 
     /// 添加或更新历史记录
-    fn add_or_update_history(&self, path: &str, name: &str) -> Result<(), Box<dyn std::error::Error>>;
+    fn add_or_update_history(&self, path: &str, name: &str) -> crate::error::Result<()>;
+
+    /// 删除历史记录，移入回收站
+    fn remove_history(&self, id: i32) -> crate::error::Result<()>;
 
-    /// 删除历史记录
-    fn remove_history(&self, id: i32) -> Result<(), Box<dyn std::error::Error>>;
+    /// 清空所有历史记录，移入回收站，返回移除的记录 id
+    fn clear_history(&self) -> crate::error::Result<Vec<i32>>;
 
-    /// 清空所有历史记录
-    fn clear_history(&self) -> Result<(), Box<dyn std::error::Error>>;
+    /// 从回收站恢复
+    fn restore_history(&self, ids: &[i32]) -> crate::error::Result<()>;
+
+    /// 彻底删除回收站中的记录，返回删除数量
+    fn purge_history_trash(&self) -> crate::error::Result<u64>;
 
     /// 获取最近使用的文档
-    fn get_recent_documents(&self, limit: usize) -> Result<Vec<Recent>, Box<dyn std::error::Error>>;
+    fn get_recent_documents(&self, limit: usize) -> crate::error::Result<Vec<Recent>>;
 
     /// 刷新历史记录UI显示
-    fn refresh_history_ui(&self, window: &crate::AppWindow) -> Result<(), Box<dyn std::error::Error>>;
+    fn refresh_history_ui(&self, window: &crate::AppWindow) -> crate::error::Result<()>;
 
     /// 设置历史记录相关回调
     fn setup_history_callbacks(&self, window: &crate::AppWindow);
@@ -92,13 +155,13 @@ impl DefaultHistoryController {
 }
 
 impl HistoryController for DefaultHistoryController {
-    fn get_history_items(&self) -> Result<Vec<Recent>, Box<dyn std::error::Error>> {
+    fn get_history_items(&self) -> crate::error::Result<Vec<Recent>> {
         let binding = self.viewmodel.borrow();
         let records = binding.get_current_records();
         Ok(records.to_vec())
     }
 
-    fn add_or_update_history(&self, path: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn add_or_update_history(&self, path: &str, name: &str) -> crate::error::Result<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
             .as_millis() as i64;
@@ -116,25 +179,36 @@ impl HistoryController for DefaultHistoryController {
         Ok(())
     }
 
-    fn remove_history(&self, id: i32) -> Result<(), Box<dyn std::error::Error>> {
-        crate::dao::RecentDao::delete_sync(id)?;
-        Ok(())
+    fn remove_history(&self, id: i32) -> crate::error::Result<()> {
+        crate::dao::RecentDao::trash_sync(&[id], now_millis()?)
     }
 
-    fn clear_history(&self) -> Result<(), Box<dyn std::error::Error>> {
-        crate::dao::RecentDao::clear_all_sync()?;
-        Ok(())
+    fn clear_history(&self) -> crate::error::Result<Vec<i32>> {
+        crate::dao::RecentDao::trash_all_sync(now_millis()?)
+    }
+
+    fn restore_history(&self, ids: &[i32]) -> crate::error::Result<()> {
+        crate::dao::RecentDao::restore_sync(ids)
     }
 
-    fn get_recent_documents(&self, limit: usize) -> Result<Vec<Recent>, Box<dyn std::error::Error>> {
+    fn purge_history_trash(&self) -> crate::error::Result<u64> {
+        crate::dao::RecentDao::purge_trashed_sync(i64::MAX)
+    }
+
+    fn get_recent_documents(&self, limit: usize) -> crate::error::Result<Vec<Recent>> {
         let records = crate::dao::RecentDao::find_all_ordered_by_update_at_desc_sync()?;
         Ok(records.into_iter().take(limit).collect())
     }
 
-    fn refresh_history_ui(&self, window: &crate::AppWindow) -> Result<(), Box<dyn std::error::Error>> {
+    fn refresh_history_ui(&self, window: &crate::AppWindow) -> crate::error::Result<()> {
+        let page = self.viewmodel.borrow().page_index;
+        self.viewmodel.borrow_mut().load_history(page)?;
+        window.set_history_trash_count(crate::dao::RecentDao::count_trashed_sync()? as i32);
         let history_items = self.get_history_items()?;
         let ui_history_items = convert_history_records_to_items(&history_items);
         set_history_to_ui(window, ui_history_items);
+        set_recent_menu_to_ui(window);
+        crate::controllers::HomeController::set_home_to_ui(window, &self.viewmodel.borrow());
         Ok(())
     }
 
@@ -159,6 +233,66 @@ impl HistoryController for DefaultHistoryController {
             }
         });
 
+        // 超过保留期的回收站记录在启动时清理
+        let expired_before = now_millis().unwrap_or(0) - TRASH_RETENTION_DAYS * 24 * 3600 * 1000;
+        match crate::dao::RecentDao::purge_trashed_sync(expired_before) {
+            Ok(0) => {}
+            Ok(count) => log::info!("[History] 清理过期的回收站记录: {}", count),
+            Err(e) => log::error!("[History] 清理回收站失败: {}", e),
+        }
+        window.set_history_trash_count(crate::dao::RecentDao::count_trashed_sync().unwrap_or(0) as i32);
+
+        let weak_window5 = window.as_weak();
+        let viewmodel = StdRc::clone(&self.viewmodel);
+        window.on_history_item_action(move |action, path| {
+            let Some(window) = weak_window5.upgrade() else { return };
+            let controller = unsafe { &*history_controller };
+            if SimpleModeController::is_enabled() {
+                SimpleModeController::deny(&window, &action);
+                return;
+            }
+            let result = match action.as_str() {
+                "reveal-in-folder" => crate::controllers::FileActions::reveal_in_folder(&path),
+                "copy-path" => crate::controllers::FileActions::copy_path(&path).map(|_| ()),
+                "book-info" => crate::controllers::MetadataController::show(&window, &path),
+                "choose-cover" => Self::choose_cover(&path)
+                    .and_then(|_| controller.refresh_history_ui(&window)),
+                "reset-cover" => crate::library::CustomCover::remove(&path)
+                    .and_then(|_| controller.refresh_history_ui(&window)),
+                "remove" => Self::remove_with_undo(controller, &window, &path),
+                "toggle-finished" => crate::controllers::ArchiveController::toggle(&path)
+                    .and_then(|_| controller.refresh_history_ui(&window)),
+                "toggle-favorite" => {
+                    // 刷新时要重新加载书架，先放开 viewmodel 的借用
+                    let toggled = Self::toggle_favorite(&viewmodel.borrow(), &path);
+                    toggled.and_then(|_| controller.refresh_history_ui(&window))
+                }
+                _ => {
+                    log::warn!("[History] 未知动作: {}", action);
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                log::error!("[History] {} 失败: {}", action, e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+        });
+
+        let weak_window4 = window.as_weak();
+        let document_controller2 = Rc::clone(&self.document_controller);
+        window.on_open_recent(move |path| {
+            let path_str = path.to_string();
+            let Some(window) = weak_window4.upgrade() else { return };
+            if std::path::Path::new(&path_str).exists() {
+                document_controller2.borrow().open_document(&window, &path_str);
+            } else {
+                log::error!("File does not exist: {}", path_str);
+                window.set_error_message("文件不存在".into());
+                window.set_show_error_dialog(true);
+            }
+        });
+
         let viewmodel = StdRc::clone(&self.viewmodel);
         window.on_history_viewport_changed(move |width, height| {
             debug!("[Main] on_history_viewport_changed.width: {:?}, height: {:?}", width, height);
@@ -172,6 +306,7 @@ impl HistoryController for DefaultHistoryController {
                     let history_records = viewmodel_binding.get_current_records();
                     let ui_history_items = convert_history_records_to_items(history_records);
                     set_history_to_ui(&window, ui_history_items);
+                    crate::controllers::HomeController::set_home_to_ui(&window, &viewmodel_binding);
                     debug!("[Main] Updated history column count for new viewport width: {}", width);
                 }
             }
@@ -179,15 +314,117 @@ impl HistoryController for DefaultHistoryController {
 
         window.on_clear_history(move || {
             let controller = unsafe { &*history_controller };
-            if let Err(e) = controller.clear_history() {
-                log::warn!("Failed to clear history: {}", e);
+            let Some(window) = weak_window3.upgrade() else { return };
+            if SimpleModeController::is_enabled() {
+                SimpleModeController::deny(&window, "clear-history");
+                return;
             }
-
-            if let Some(window) = weak_window3.upgrade() {
-                if let Err(e) = unsafe { &*history_controller }.refresh_history_ui(&window) {
-                    log::error!("Failed to refresh history after clear: {}", e);
+            match controller.clear_history() {
+                Ok(ids) if !ids.is_empty() => {
+                    Self::show_undo_toast(&window, format!("已清空 {} 条历史记录", ids.len()), ids);
                 }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to clear history: {}", e),
+            }
+
+            if let Err(e) = controller.refresh_history_ui(&window) {
+                log::error!("Failed to refresh history after clear: {}", e);
             }
         });
+
+        let weak_window_recent = window.as_weak();
+        window.on_clear_recent_menu(move || {
+            let Some(window) = weak_window_recent.upgrade() else { return };
+            if SimpleModeController::is_enabled() {
+                SimpleModeController::deny(&window, "clear-recent-menu");
+                return;
+            }
+            match crate::dao::RecentDao::clear_recent_list_sync() {
+                Ok(count) => log::info!("[History] 已清空最近打开菜单: {} 条", count),
+                Err(e) => log::error!("Failed to clear recent menu: {}", e),
+            }
+            set_recent_menu_to_ui(&window);
+        });
+
+        let weak_window8 = window.as_weak();
+        window.on_history_changed(move || {
+            let controller = unsafe { &*history_controller };
+            let Some(window) = weak_window8.upgrade() else { return };
+            if let Err(e) = controller.refresh_history_ui(&window) {
+                log::error!("Failed to refresh history: {}", e);
+            }
+        });
+
+        let weak_window6 = window.as_weak();
+        window.on_undo_history_removal(move || {
+            let controller = unsafe { &*history_controller };
+            let Some(window) = weak_window6.upgrade() else { return };
+            window.set_show_undo_toast(false);
+            let ids = LAST_REMOVED.with(|removed| std::mem::take(&mut *removed.borrow_mut()));
+            if ids.is_empty() {
+                return;
+            }
+            log::info!("[History] 撤销移除: {:?}", ids);
+            if let Err(e) = controller.restore_history(&ids) {
+                log::error!("[History] 恢复失败: {}", e);
+            }
+            if let Err(e) = controller.refresh_history_ui(&window) {
+                log::error!("Failed to refresh history after undo: {}", e);
+            }
+        });
+
+        let weak_window7 = window.as_weak();
+        window.on_empty_history_trash(move || {
+            let controller = unsafe { &*history_controller };
+            let Some(window) = weak_window7.upgrade() else { return };
+            if SimpleModeController::is_enabled() {
+                SimpleModeController::deny(&window, "empty-trash");
+                return;
+            }
+            match controller.purge_history_trash() {
+                Ok(count) => log::info!("[History] 清空回收站: {}", count),
+                Err(e) => log::error!("[History] 清空回收站失败: {}", e),
+            }
+            // 回收站清空后无法再撤销
+            LAST_REMOVED.with(|removed| removed.borrow_mut().clear());
+            window.set_show_undo_toast(false);
+            if let Err(e) = controller.refresh_history_ui(&window) {
+                log::error!("Failed to refresh history after purge: {}", e);
+            }
+        });
+    }
+}
+
+impl DefaultHistoryController {
+    /// 从历史中移除一本书，可在提示条中撤销
+    fn remove_with_undo(controller: &dyn HistoryController, window: &crate::AppWindow, path: &str) -> crate::error::Result<()> {
+        let Some(record) = crate::dao::RecentDao::find_by_path_sync(path)? else { return Ok(()) };
+        controller.remove_history(record.id)?;
+        let title = if record.name.is_empty() { path.rsplit(['/', '\\']).next().unwrap_or(path).to_string() } else { record.name };
+        Self::show_undo_toast(window, format!("已从历史中移除《{}》", title), vec![record.id]);
+        controller.refresh_history_ui(window)
+    }
+
+    fn toggle_favorite(viewmodel: &MainViewmodel, path: &str) -> crate::error::Result<()> {
+        let Some(record) = viewmodel.get_recent_by_path(path)? else { return Ok(()) };
+        viewmodel.set_favorited(path, record.favorited == 0)
+    }
+
+    /// 选一张图片作为书架封面，取消选择时不做改动
+    fn choose_cover(path: &str) -> crate::error::Result<()> {
+        let picked = rfd::FileDialog::new()
+            .set_title("Choose Cover Image")
+            .add_filter("Images", &["png", "jpg", "jpeg", "webp", "gif", "bmp"])
+            .pick_file();
+        match picked {
+            Some(image_path) => crate::library::CustomCover::set_from_file(path, &image_path),
+            None => Ok(()),
+        }
+    }
+
+    fn show_undo_toast(window: &crate::AppWindow, text: String, ids: Vec<i32>) {
+        LAST_REMOVED.with(|removed| *removed.borrow_mut() = ids);
+        window.set_undo_toast_text(text.into());
+        window.set_show_undo_toast(true);
     }
 }
@@ -0,0 +1,310 @@
+use chrono::{Local, TimeZone};
+use log::{debug, error, info};
+use serde_json::{json, Map, Value};
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::controllers::{OrderedList, SimpleModeController};
+use crate::dao::RecentDao;
+use crate::entity::Recent;
+use crate::error::Result;
+use crate::settings::{AppSettings, OrderedItem};
+use crate::AppWindow;
+
+/// 可导出的列（列 id、标题、默认是否导出），顺序即默认顺序；列 id 同时作为 CSV 表头和 JSON 字段名
+const EXPORT_COLUMNS: OrderedList = OrderedList::new(&[
+    ("title", "书名", true),
+    ("author", "作者", true),
+    ("path", "文件路径", false),
+    ("format", "格式", true),
+    ("size", "文件大小（字节）", false),
+    ("pages", "页数", true),
+    ("page", "读到第几页", true),
+    ("progress", "进度（%）", true),
+    ("read_times", "打开次数", false),
+    ("reading_minutes", "阅读时长（分钟）", true),
+    ("favorited", "收藏", false),
+    ("added", "添加时间", false),
+    ("last_read", "最后阅读", true),
+    ("finished", "读完时间", true),
+    ("series", "系列", false),
+    ("series_index", "卷号", false),
+    ("sha256", "SHA-256", false),
+]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// 导出阅读记录：每本书的进度、阅读时长和读完时间，导出为 CSV 或 JSON，
+/// 方便在表格或 Goodreads 一类的工具里整理；导出的列可以勾选和排序。
+/// 暂不导出逐次的阅读会话：数据库只累计每本书的阅读时长（read_seconds），
+/// 没有保存每次阅读的起止时间，要先加会话表才能导出
+pub struct HistoryExportController;
+
+impl HistoryExportController {
+    /// 设置中的列，已不存在的列去掉，新增的列不导出、放在末尾
+    fn columns() -> Vec<OrderedItem> {
+        EXPORT_COLUMNS.merge(AppSettings::get().history_export.columns)
+    }
+
+    fn save_columns(columns: Vec<OrderedItem>) {
+        AppSettings::update(|settings| settings.history_export.columns = columns);
+    }
+
+    fn set_columns_to_ui(window: &AppWindow) {
+        let columns = EXPORT_COLUMNS.to_ui(&Self::columns());
+        window.set_export_columns(ModelRc::from(Rc::new(VecModel::from(columns))));
+    }
+
+    pub fn setup_history_export_callbacks(window: &AppWindow) {
+        Self::set_columns_to_ui(window);
+
+        let weak_window = window.as_weak();
+        window.on_export_column_toggled(move |id, visible| {
+            debug!("[Export] toggle {} -> {}", id, visible);
+            Self::save_columns(EXPORT_COLUMNS.toggled(AppSettings::get().history_export.columns, &id, visible));
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_columns_to_ui(&window);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_export_column_moved(move |id, delta| {
+            debug!("[Export] move {} by {}", id, delta);
+            Self::save_columns(EXPORT_COLUMNS.moved(AppSettings::get().history_export.columns, &id, delta));
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_columns_to_ui(&window);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_export_columns_reset(move || {
+            Self::save_columns(Vec::new());
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_columns_to_ui(&window);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_export_history(move |format| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let format = if format == "json" { ExportFormat::Json } else { ExportFormat::Csv };
+            Self::export(&window, format);
+        });
+    }
+
+    fn export(window: &AppWindow, format: ExportFormat) {
+        if SimpleModeController::is_enabled() {
+            SimpleModeController::deny(window, "export-history");
+            return;
+        }
+        if let Err(e) = Self::write_export(window, format) {
+            error!("[Export] 导出阅读记录失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+        }
+    }
+
+    fn write_export(window: &AppWindow, format: ExportFormat) -> Result<()> {
+        let records: Vec<Recent> = RecentDao::find_all_ordered_by_update_at_desc_sync()?
+            .into_iter()
+            .filter(|record| !record.is_deleted())
+            .collect();
+        if records.is_empty() {
+            window.set_error_message("还没有阅读记录".into());
+            window.set_show_error_dialog(true);
+            return Ok(());
+        }
+        let columns: Vec<String> = Self::columns().into_iter()
+            .filter(|column| column.visible)
+            .map(|column| column.id)
+            .collect();
+        if columns.is_empty() {
+            window.set_error_message("请至少选择一列".into());
+            window.set_show_error_dialog(true);
+            return Ok(());
+        }
+
+        let Some(path) = Self::pick_export_target(format) else { return Ok(()) };
+        let content = match format {
+            ExportFormat::Csv => to_csv(&records, &columns),
+            ExportFormat::Json => to_json(&records, &columns),
+        };
+        fs::write(&path, content)?;
+        info!("[Export] 导出 {} 条阅读记录 ({} 列): {:?}", records.len(), columns.len(), path);
+        Ok(())
+    }
+
+    fn pick_export_target(format: ExportFormat) -> Option<PathBuf> {
+        let (filter, extension) = match format {
+            ExportFormat::Csv => ("CSV", "csv"),
+            ExportFormat::Json => ("JSON", "json"),
+        };
+        rfd::FileDialog::new()
+            .set_title("Export Reading History")
+            .add_filter(filter, &[extension])
+            .set_file_name(format!("reading-history.{}", extension))
+            .save_file()
+    }
+}
+
+fn format_time(millis: i64) -> String {
+    if millis <= 0 {
+        return String::new();
+    }
+    Local.timestamp_millis_opt(millis)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// 一本书在某一列的值；没有数据的时间列为空字符串
+fn column_value(id: &str, record: &Recent) -> Value {
+    match id {
+        "title" => {
+            let title = if record.name.is_empty() {
+                Path::new(&record.book_path).file_stem().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+            } else {
+                record.name.clone()
+            };
+            json!(title)
+        }
+        "author" => json!(record.author),
+        "path" => json!(record.book_path),
+        "format" => json!(record.ext),
+        "size" => json!(record.size),
+        "pages" => json!(record.page_count),
+        "page" => json!(record.page),
+        "progress" => json!(record.progress),
+        "read_times" => json!(record.read_times),
+        "reading_minutes" => json!(record.read_seconds / 60),
+        "favorited" => json!(record.favorited != 0),
+        "added" => json!(format_time(record.create_at)),
+        "last_read" => json!(format_time(record.update_at)),
+        "finished" => json!(format_time(record.finished_at)),
+        "series" => json!(record.series),
+        "series_index" => json!(record.series_index),
+        "sha256" => json!(record.sha256),
+        _ => Value::Null,
+    }
+}
+
+/// CSV 字段中有逗号、引号或换行时加引号，引号写两遍
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn to_csv(records: &[Recent], columns: &[String]) -> String {
+    // 带 BOM，Excel 打开中文书名时不会乱码
+    let mut out = String::from("\u{feff}");
+    out.push_str(&columns.join(","));
+    out.push_str("\r\n");
+    for record in records {
+        let row: Vec<String> = columns.iter().map(|id| csv_field(&column_value(id, record))).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// JSON 中除了每本书的记录，还附上总的阅读统计
+fn to_json(records: &[Recent], columns: &[String]) -> String {
+    let books: Vec<Value> = records.iter()
+        .map(|record| {
+            let fields: Map<String, Value> = columns.iter().map(|id| (id.clone(), column_value(id, record))).collect();
+            Value::Object(fields)
+        })
+        .collect();
+    let read_seconds: i64 = records.iter().map(|record| record.read_seconds).sum();
+    let export = json!({
+        "exported_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        "stats": {
+            "books": records.len(),
+            "finished": records.iter().filter(|record| record.is_finished()).count(),
+            "in_progress": records.iter().filter(|record| record.read_times > 0 && !record.is_finished()).count(),
+            "reading_minutes": read_seconds / 60,
+        },
+        "books": books,
+    });
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, page: i32) -> Recent {
+        Recent {
+            id: 1,
+            book_path: format!("/books/{}.pdf", name),
+            update_at: 0,
+            page,
+            page_count: 100,
+            create_at: 0,
+            crop: 1,
+            reflow: 0,
+            scroll_ori: 1,
+            zoom: 1.0,
+            scroll_x: 0,
+            scroll_y: 0,
+            name: name.to_string(),
+            ext: "pdf".to_string(),
+            size: 2048,
+            read_times: 3,
+            progress: 42,
+            favorited: 0,
+            in_recent: 1,
+            content_hash: String::new(),
+            deleted_at: 0,
+            series: String::new(),
+            series_index: 0.0,
+            read_seconds: 600,
+            anchor_ratio: -1.0,
+            sha256: String::new(),
+            finished_at: 0,
+            crop_margins: String::new(),
+            author: String::new(),
+            subject: String::new(),
+        }
+    }
+
+    #[test]
+    fn csv_field_quotes_special_characters() {
+        assert_eq!(csv_field(&json!("plain")), "plain");
+        assert_eq!(csv_field(&json!("a,b")), "\"a,b\"");
+        assert_eq!(csv_field(&json!("say \"hi\"")), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field(&json!("line1\nline2")), "\"line1\nline2\"");
+        assert_eq!(csv_field(&json!(12)), "12");
+        assert_eq!(csv_field(&Value::Null), "");
+    }
+
+    #[test]
+    fn csv_follows_column_order() {
+        let records = [record("第一本, 上", 5), record("Plain", 7)];
+        let columns: Vec<String> = ["page", "title", "reading_minutes"].iter().map(|id| id.to_string()).collect();
+        let csv = to_csv(&records, &columns);
+        assert_eq!(csv, "\u{feff}page,title,reading_minutes\r\n5,\"第一本, 上\",10\r\n7,Plain,10\r\n");
+    }
+
+    #[test]
+    fn json_keeps_selected_columns() {
+        let columns: Vec<String> = ["title", "pages"].iter().map(|id| id.to_string()).collect();
+        let export: Value = serde_json::from_str(&to_json(&[record("Book", 1)], &columns)).unwrap();
+        assert_eq!(export["books"][0], json!({ "title": "Book", "pages": 100 }));
+        assert_eq!(export["stats"]["reading_minutes"], json!(10));
+    }
+}
@@ -0,0 +1,201 @@
+use log::{debug, error, info};
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::controllers::history_controller::{convert_history_records_to_items, group_into_rows};
+use crate::controllers::{ArchiveController, DocumentController, OrderedList, SeriesController};
+use crate::entity::Recent;
+use crate::settings::{AppSettings, OrderedItem};
+use crate::ui::MainViewmodel;
+use crate::AppWindow;
+
+/// 首页可显示的分区（分区 id、标题、默认是否显示），顺序即默认顺序
+const HOME_SECTIONS: OrderedList = OrderedList::new(&[
+    ("continue-reading", "继续阅读", true),
+    ("series", "系列", true),
+    ("favorites", "收藏", true),
+    ("recently-added", "最近添加", false),
+    ("finished", "已读完", true),
+    ("random-pick", "随便翻翻", false),
+]);
+
+/// 首页“继续阅读”显示的数量
+const CONTINUE_READING_LIMIT: usize = 3;
+/// 首页“最近添加”显示的数量
+const RECENTLY_ADDED_LIMIT: usize = 8;
+
+/// 首页：按设置的顺序生成各个分区，处理自定义对话框的修改
+pub struct HomeController;
+
+impl HomeController {
+    /// 设置中的配置，已不存在的分区去掉，新增的分区隐藏在末尾
+    pub fn items() -> Vec<OrderedItem> {
+        HOME_SECTIONS.merge(AppSettings::get().home.sections)
+    }
+
+    fn save_items(items: Vec<OrderedItem>) {
+        AppSettings::update(|settings| settings.home.sections = items);
+    }
+
+    fn empty_section(id: &str) -> crate::HomeSection {
+        crate::HomeSection {
+            id: id.into(),
+            title: HOME_SECTIONS.title_of(id).unwrap_or_default().into(),
+            subtitle: Default::default(),
+            cards: ModelRc::default(),
+            series: ModelRc::default(),
+            rows: ModelRc::default(),
+        }
+    }
+
+    fn grid_section(id: &str, records: &[Recent]) -> crate::HomeSection {
+        let items = convert_history_records_to_items(records);
+        crate::HomeSection {
+            rows: ModelRc::from(Rc::new(VecModel::from(group_into_rows(&items)))),
+            ..Self::empty_section(id)
+        }
+    }
+
+    /// 生成一个分区，没有内容时返回 None
+    fn build_section(id: &str, viewmodel: &MainViewmodel) -> crate::error::Result<Option<crate::HomeSection>> {
+        let section = match id {
+            "continue-reading" => {
+                let records = viewmodel.continue_reading(CONTINUE_READING_LIMIT)?;
+                let cards: Vec<crate::ContinueReadingItem> = convert_history_records_to_items(&records)
+                    .into_iter()
+                    .zip(records.iter())
+                    .map(|(item, record)| crate::ContinueReadingItem {
+                        recent: item,
+                        page_count: record.page_count,
+                        progress: record.progress as f32 / 100.0,
+                    })
+                    .collect();
+                (!cards.is_empty()).then(|| crate::HomeSection {
+                    cards: ModelRc::from(Rc::new(VecModel::from(cards))),
+                    ..Self::empty_section(id)
+                })
+            }
+            "series" => {
+                let series = SeriesController::series_items();
+                (!series.is_empty()).then(|| crate::HomeSection {
+                    series: ModelRc::from(Rc::new(VecModel::from(series))),
+                    ..Self::empty_section(id)
+                })
+            }
+            "favorites" => {
+                let records = viewmodel.favorites()?;
+                (!records.is_empty()).then(|| Self::grid_section(id, &records))
+            }
+            "recently-added" => {
+                let records = viewmodel.recently_added(RECENTLY_ADDED_LIMIT)?;
+                (!records.is_empty()).then(|| Self::grid_section(id, &records))
+            }
+            "finished" => {
+                let records = viewmodel.finished()?;
+                (!records.is_empty()).then(|| crate::HomeSection {
+                    subtitle: ArchiveController::summary(&records).into(),
+                    ..Self::grid_section(id, &records)
+                })
+            }
+            "random-pick" => viewmodel.random_pick()?
+                .map(|record| Self::grid_section(id, std::slice::from_ref(&record))),
+            _ => None,
+        };
+        Ok(section)
+    }
+
+    /// 刷新首页的分区：只显示打开了且有内容的分区
+    pub fn set_home_to_ui(window: &AppWindow, viewmodel: &MainViewmodel) {
+        let sections: Vec<crate::HomeSection> = Self::items()
+            .into_iter()
+            .filter(|item| item.visible)
+            .filter_map(|item| match Self::build_section(&item.id, viewmodel) {
+                Ok(section) => section,
+                Err(e) => {
+                    error!("[Home] 读取分区 {} 失败: {}", item.id, e);
+                    None
+                }
+            })
+            .collect();
+        window.set_home_sections(ModelRc::from(Rc::new(VecModel::from(sections))));
+        window.set_home_config(ModelRc::from(Rc::new(VecModel::from(HOME_SECTIONS.to_ui(&Self::items())))));
+    }
+
+    /// 打开快捷动作挑出的书，没有可挑的书时提示 empty_message
+    fn open_picked(
+        window: &AppWindow,
+        document_controller: &Rc<RefCell<DocumentController>>,
+        picked: crate::error::Result<Option<Recent>>,
+        empty_message: &str,
+    ) {
+        let message = match picked {
+            Ok(Some(record)) if Path::new(&record.book_path).is_file() => {
+                info!("[Home] 快捷打开: {}", record.book_path);
+                document_controller.borrow().open_document(window, &record.book_path);
+                return;
+            }
+            Ok(Some(record)) => {
+                error!("[Home] 文件不存在: {}", record.book_path);
+                "文件不存在"
+            }
+            Ok(None) => empty_message,
+            Err(e) => {
+                error!("[Home] 挑选书失败: {}", e);
+                e.user_message()
+            }
+        };
+        window.set_error_message(message.into());
+        window.set_show_error_dialog(true);
+    }
+
+    pub fn setup_home_callbacks(window: &AppWindow, viewmodel: &Rc<RefCell<MainViewmodel>>, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let viewmodel_clone = Rc::clone(viewmodel);
+        let document_controller_clone = Rc::clone(document_controller);
+        window.on_open_random_book(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let picked = viewmodel_clone.borrow().random_unread();
+            Self::open_picked(&window, &document_controller_clone, picked, "书架上的书都读过了");
+        });
+
+        let weak_window = window.as_weak();
+        let viewmodel_clone = Rc::clone(viewmodel);
+        let document_controller_clone = Rc::clone(document_controller);
+        window.on_resume_oldest_book(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let picked = viewmodel_clone.borrow().oldest_in_progress();
+            Self::open_picked(&window, &document_controller_clone, picked, "没有读到一半的书");
+        });
+
+        let weak_window = window.as_weak();
+        let viewmodel_clone = Rc::clone(viewmodel);
+        window.on_home_section_toggled(move |id, visible| {
+            debug!("[Home] toggle {} -> {}", id, visible);
+            Self::save_items(HOME_SECTIONS.toggled(AppSettings::get().home.sections, &id, visible));
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_home_to_ui(&window, &viewmodel_clone.borrow());
+            }
+        });
+
+        let weak_window = window.as_weak();
+        let viewmodel_clone = Rc::clone(viewmodel);
+        window.on_home_section_moved(move |id, delta| {
+            debug!("[Home] move {} by {}", id, delta);
+            Self::save_items(HOME_SECTIONS.moved(AppSettings::get().home.sections, &id, delta));
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_home_to_ui(&window, &viewmodel_clone.borrow());
+            }
+        });
+
+        let weak_window = window.as_weak();
+        let viewmodel = Rc::clone(viewmodel);
+        window.on_home_reset(move || {
+            Self::save_items(Vec::new());
+            if let Some(window) = weak_window.upgrade() {
+                Self::set_home_to_ui(&window, &viewmodel.borrow());
+            }
+        });
+    }
+}
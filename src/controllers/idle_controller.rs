@@ -0,0 +1,87 @@
+use log::info;
+use slint::ComponentHandle;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::controllers::DocumentController;
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 检查是否空闲的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+type IdleListener = Box<dyn Fn(bool)>;
+
+thread_local! {
+    static LAST_ACTIVITY: Cell<Instant> = Cell::new(Instant::now());
+    static IDLE: Cell<bool> = const { Cell::new(false) };
+    /// 空闲状态变化时通知，参数为是否进入空闲
+    static LISTENERS: RefCell<Vec<IdleListener>> = const { RefCell::new(Vec::new()) };
+    static CHECKER: slint::Timer = slint::Timer::default();
+}
+
+/// 空闲检测：一段时间没有滚动、点击或按键时暂停计时和预加载，释放屏幕外页面，有输入时立即恢复
+pub struct IdleController;
+
+impl IdleController {
+    pub fn setup_idle_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        window.on_user_activity(|| Self::touch());
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        Self::on_idle_changed(move |idle| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let page_view_state = document_controller.borrow().page_view_state();
+            if idle {
+                let freed = page_view_state.borrow_mut().release_offscreen_pages();
+                info!("[Idle] 进入空闲，释放屏幕外页面 {} KB", freed / 1024);
+            } else {
+                info!("[Idle] 恢复活动");
+                page_view_state.borrow_mut().update_visible_pages();
+                DocumentController::refresh_view(&window, &page_view_state.borrow());
+            }
+        });
+
+        CHECKER.with(|checker| {
+            checker.start(slint::TimerMode::Repeated, CHECK_INTERVAL, Self::check);
+        });
+    }
+
+    /// 记录一次用户操作，空闲时立即恢复
+    pub fn touch() {
+        LAST_ACTIVITY.with(|last| last.set(Instant::now()));
+        if IDLE.with(|idle| idle.replace(false)) {
+            Self::notify(false);
+        }
+    }
+
+    pub fn is_idle() -> bool {
+        IDLE.with(Cell::get)
+    }
+
+    /// 注册空闲状态变化的回调
+    pub fn on_idle_changed(listener: impl Fn(bool) + 'static) {
+        LISTENERS.with(|listeners| listeners.borrow_mut().push(Box::new(listener)));
+    }
+
+    fn check() {
+        let minutes = AppSettings::get().reading_timer.idle_minutes;
+        if minutes == 0 || Self::is_idle() {
+            return;
+        }
+        let elapsed = LAST_ACTIVITY.with(Cell::get).elapsed();
+        if elapsed >= Duration::from_secs(u64::from(minutes) * 60) {
+            IDLE.with(|idle| idle.set(true));
+            Self::notify(true);
+        }
+    }
+
+    fn notify(idle: bool) {
+        LISTENERS.with(|listeners| {
+            for listener in listeners.borrow().iter() {
+                listener(idle);
+            }
+        });
+    }
+}
@@ -0,0 +1,289 @@
+use log::{error, info};
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::history_controller::{
+    convert_history_records_to_items, set_history_to_ui, set_recent_menu_to_ui,
+};
+use crate::controllers::{DocumentController, HomeController, SimpleModeController, StatusController, ThemeController};
+use crate::dao::RecentDao;
+use crate::library::{CoverEvent, CoverGenerator, LibraryScanner, ScanEvent};
+use crate::settings::{AppSettings, ThemeMode, ViewMode};
+use crate::ui::MainViewmodel;
+use crate::AppWindow;
+
+/// 封面生成期间，每生成多少个刷新一次书架
+const COVER_REFRESH_STEP: usize = 12;
+
+/// 书库与首次启动引导：管理书库目录、后台扫描、封面预生成和进度显示
+pub struct LibraryController {
+    viewmodel: Rc<RefCell<MainViewmodel>>,
+    document_controller: Rc<RefCell<DocumentController>>,
+    scanner: Rc<RefCell<Option<LibraryScanner>>>,
+    scan_timer: Rc<slint::Timer>,
+    covers: Rc<CoverJob>,
+}
+
+/// 进行中的封面生成任务
+#[derive(Default)]
+struct CoverJob {
+    generator: RefCell<Option<CoverGenerator>>,
+    timer: slint::Timer,
+}
+
+impl LibraryController {
+    pub fn new(viewmodel: Rc<RefCell<MainViewmodel>>, document_controller: Rc<RefCell<DocumentController>>) -> Self {
+        Self {
+            viewmodel,
+            document_controller,
+            scanner: Rc::new(RefCell::new(None)),
+            scan_timer: Rc::new(slint::Timer::default()),
+            covers: Rc::new(CoverJob::default()),
+        }
+    }
+
+    pub fn setup_library_callbacks(&self, window: &AppWindow) {
+        // 引导中选择的目录先放在界面上，完成时才写入设置
+        let weak_window = window.as_weak();
+        window.on_onboarding_add_folder(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            if let Some(folder) = Self::pick_folder() {
+                let mut folders = Self::ui_folders(&window);
+                if !folders.contains(&folder) {
+                    folders.push(folder);
+                }
+                Self::set_ui_folders(&window, folders);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_onboarding_remove_folder(move |folder| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let folders = Self::ui_folders(&window)
+                .into_iter()
+                .filter(|f| f.as_str() != folder.as_str())
+                .collect();
+            Self::set_ui_folders(&window, folders);
+        });
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(&self.document_controller);
+        let scanner = Rc::clone(&self.scanner);
+        let scan_timer = Rc::clone(&self.scan_timer);
+        let viewmodel = Rc::clone(&self.viewmodel);
+        let covers = Rc::clone(&self.covers);
+        window.on_onboarding_finish(move |theme_index, view_mode_index| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let folders = Self::ui_folders(&window);
+            let mode = match theme_index {
+                1 => ThemeMode::Light,
+                2 => ThemeMode::Dark,
+                _ => ThemeMode::System,
+            };
+            let view_mode = match view_mode_index {
+                1 => ViewMode::Dual,
+                2 => ViewMode::Paged,
+                _ => ViewMode::Continuous,
+            };
+            info!("[Library] 完成引导: folders={:?}, theme={:?}, view={:?}", folders, mode, view_mode);
+
+            AppSettings::update(|settings| {
+                settings.library.folders = folders.clone();
+                settings.theme.mode = mode;
+                settings.default_view_mode = view_mode;
+                settings.onboarded = true;
+            });
+            window.set_show_onboarding(false);
+
+            ThemeController::apply(&window, &document_controller);
+            Self::apply_view_mode(&window, &document_controller);
+            if !folders.is_empty() {
+                Self::start_scan(&window, &scanner, &scan_timer, &viewmodel, &covers, &document_controller, folders);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_onboarding_skip(move || {
+            AppSettings::update(|settings| settings.onboarded = true);
+            if let Some(window) = weak_window.upgrade() {
+                window.set_show_onboarding(false);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        let scanner = Rc::clone(&self.scanner);
+        let scan_timer = Rc::clone(&self.scan_timer);
+        let viewmodel = Rc::clone(&self.viewmodel);
+        let covers = Rc::clone(&self.covers);
+        let document_controller = Rc::clone(&self.document_controller);
+        window.on_add_library_folder(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            if SimpleModeController::is_enabled() {
+                SimpleModeController::deny(&window, "add-library-folder");
+                return;
+            }
+            let Some(folder) = Self::pick_folder() else { return };
+            AppSettings::update(|settings| {
+                if !settings.library.folders.contains(&folder) {
+                    settings.library.folders.push(folder.clone());
+                }
+            });
+            Self::start_scan(&window, &scanner, &scan_timer, &viewmodel, &covers, &document_controller, vec![folder]);
+        });
+    }
+
+    /// 启动时调用：首次启动显示引导，否则应用默认视图模式
+    pub fn initialize_ui(&self, window: &AppWindow) {
+        Self::apply_view_mode(window, &self.document_controller);
+        Self::start_cover_generation(window, &self.covers, &self.viewmodel, &self.document_controller);
+
+        let settings = AppSettings::get();
+        if settings.onboarded {
+            return;
+        }
+        // 旧版本升级上来的用户已经有书架，不再打扰
+        if self.viewmodel.borrow().get_total_records() > 0 {
+            AppSettings::update(|settings| settings.onboarded = true);
+            return;
+        }
+        let theme_index = match settings.theme.mode {
+            ThemeMode::System | ThemeMode::Scheduled => 0,
+            ThemeMode::Light => 1,
+            ThemeMode::Dark => 2,
+        };
+        window.set_show_onboarding(true);
+        Self::set_ui_folders(window, settings.library.folders);
+        window.set_onboarding_theme_index(theme_index);
+    }
+
+    fn apply_view_mode(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let view_mode = AppSettings::get().default_view_mode;
+        document_controller.borrow().set_dual_page(window, view_mode == ViewMode::Dual);
+        document_controller.borrow().set_paged(window, view_mode == ViewMode::Paged);
+    }
+
+    fn pick_folder() -> Option<String> {
+        rfd::FileDialog::new()
+            .set_title("Select Library Folder")
+            .pick_folder()
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    fn ui_folders(window: &AppWindow) -> Vec<String> {
+        use slint::Model;
+        window.get_onboarding_folders().iter().map(|f| f.to_string()).collect()
+    }
+
+    fn set_ui_folders(window: &AppWindow, folders: Vec<String>) {
+        let items: Vec<SharedString> = folders.into_iter().map(Into::into).collect();
+        window.set_onboarding_folders(ModelRc::from(Rc::new(VecModel::from(items))));
+    }
+
+    fn start_scan(
+        window: &AppWindow,
+        scanner: &Rc<RefCell<Option<LibraryScanner>>>,
+        scan_timer: &Rc<slint::Timer>,
+        viewmodel: &Rc<RefCell<MainViewmodel>>,
+        covers: &Rc<CoverJob>,
+        document_controller: &Rc<RefCell<DocumentController>>,
+        folders: Vec<String>,
+    ) {
+        *scanner.borrow_mut() = Some(LibraryScanner::start(folders));
+        window.set_library_scan_status("正在查找文档…".into());
+
+        let weak_window = window.as_weak();
+        let scanner = Rc::clone(scanner);
+        let timer = Rc::downgrade(scan_timer);
+        let viewmodel = Rc::clone(viewmodel);
+        let covers = Rc::clone(covers);
+        let document_controller = Rc::clone(document_controller);
+        scan_timer.start(slint::TimerMode::Repeated, Duration::from_millis(200), move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let mut finished = false;
+            if let Some(scanner) = scanner.borrow().as_ref() {
+                while let Some(event) = scanner.try_recv() {
+                    match event {
+                        ScanEvent::Progress { found, added } => {
+                            window.set_library_scan_status(format!("已找到 {} 个文档，新增 {}", found, added).into());
+                        }
+                        ScanEvent::Finished { found, added } => {
+                            info!("[Library] 扫描结束: found={}, added={}", found, added);
+                            finished = true;
+                        }
+                    }
+                }
+            }
+            if finished {
+                scanner.borrow_mut().take();
+                window.set_library_scan_status("".into());
+                Self::reload_history(&window, &viewmodel);
+                Self::start_cover_generation(&window, &covers, &viewmodel, &document_controller);
+                if let Some(timer) = timer.upgrade() {
+                    timer.stop();
+                }
+            }
+        });
+    }
+
+    /// 为书架上缺少封面的书在后台生成封面，已有任务时重新开始以包含新加入的书
+    fn start_cover_generation(
+        window: &AppWindow,
+        covers: &Rc<CoverJob>,
+        viewmodel: &Rc<RefCell<MainViewmodel>>,
+        document_controller: &Rc<RefCell<DocumentController>>,
+    ) {
+        let paths: Vec<String> = match RecentDao::find_all_sync() {
+            Ok(records) => records.into_iter().filter(|recent| !recent.is_deleted()).map(|recent| recent.book_path).collect(),
+            Err(e) => {
+                error!("[Library] 读取书架失败: {}", e);
+                return;
+            }
+        };
+        let activity = document_controller.borrow().page_view_state().borrow().decode_service.activity_handle();
+        *covers.generator.borrow_mut() = Some(CoverGenerator::start(paths, activity));
+
+        let weak_window = window.as_weak();
+        let weak_covers = Rc::downgrade(covers);
+        let viewmodel = Rc::clone(viewmodel);
+        let mut since_refresh = 0;
+        covers.timer.start(slint::TimerMode::Repeated, Duration::from_millis(500), move || {
+            let (Some(window), Some(covers)) = (weak_window.upgrade(), weak_covers.upgrade()) else { return };
+            let mut finished = false;
+            let mut progress = None;
+            if let Some(generator) = covers.generator.borrow().as_ref() {
+                while let Some(event) = generator.try_recv() {
+                    match event {
+                        CoverEvent::Progress { done, total } => progress = Some((done, total)),
+                        CoverEvent::Generated(_) => since_refresh += 1,
+                        CoverEvent::Finished { .. } => finished = true,
+                    }
+                }
+            }
+            if let Some(progress) = progress {
+                StatusController::update(&window, |status| status.cover_progress = Some(progress));
+            }
+            if since_refresh >= COVER_REFRESH_STEP || (finished && since_refresh > 0) {
+                since_refresh = 0;
+                Self::reload_history(&window, &viewmodel);
+            }
+            if finished {
+                covers.generator.borrow_mut().take();
+                covers.timer.stop();
+                StatusController::update(&window, |status| status.cover_progress = None);
+            }
+        });
+    }
+
+    fn reload_history(window: &AppWindow, viewmodel: &Rc<RefCell<MainViewmodel>>) {
+        if let Err(e) = viewmodel.borrow_mut().load_history(0) {
+            error!("[Library] 刷新书架失败: {}", e);
+            return;
+        }
+        let items = convert_history_records_to_items(viewmodel.borrow().get_current_records());
+        set_history_to_ui(window, items);
+        set_recent_menu_to_ui(window);
+        HomeController::set_home_to_ui(window, &viewmodel.borrow());
+    }
+}
@@ -0,0 +1,98 @@
+use log::{debug, error};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::DocumentController;
+use crate::decoder::{Link, Rect};
+use crate::AppWindow;
+
+/// 悬停多久后显示预览
+const HOVER_DELAY: Duration = Duration::from_millis(350);
+/// 预览图的显示宽度（逻辑像素）
+const PREVIEW_WIDTH: f32 = 360.0;
+/// 预览的目标区域高度（页面坐标）
+const PREVIEW_HEIGHT: f32 = 160.0;
+/// 目标位置上方多留的一点，避免标题贴边
+const PREVIEW_MARGIN: f32 = 12.0;
+
+thread_local! {
+    /// 当前悬停的内部链接目标
+    static HOVERED: RefCell<Option<String>> = const { RefCell::new(None) };
+    static TIMER: slint::Timer = slint::Timer::default();
+}
+
+/// 悬停在脚注、参考文献等内部链接上时，显示目标位置附近的预览
+pub struct LinkPreviewController;
+
+impl LinkPreviewController {
+    pub fn setup_link_preview_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        window.on_page_hovered(move |x, y, page_index| {
+            let Some(window) = weak_window.upgrade() else { return };
+            // page_index 为 -1 表示指针离开了页面
+            // 指针下的页在批量重新渲染时最先出图
+            let page = usize::try_from(page_index).ok();
+            document_controller.borrow().page_view_state().borrow_mut().set_cursor(page, x, y);
+            let link = (page_index >= 0)
+                .then(|| document_controller.borrow().page_view_state().borrow().link_at(page_index as usize, x, y))
+                .flatten()
+                .filter(|link| link.page.is_some());
+
+            let key = link.as_ref().and_then(|link| link.page.clone());
+            if HOVERED.with(|hovered| *hovered.borrow() == key) {
+                return;
+            }
+            HOVERED.with(|hovered| *hovered.borrow_mut() = key);
+            window.set_show_link_preview(false);
+            TIMER.with(|timer| timer.stop());
+
+            let Some(link) = link else { return };
+            let weak_window = window.as_weak();
+            let document_controller = Rc::clone(&document_controller);
+            TIMER.with(|timer| {
+                timer.start(slint::TimerMode::SingleShot, HOVER_DELAY, move || {
+                    let Some(window) = weak_window.upgrade() else { return };
+                    Self::show_preview(&window, &document_controller, &link);
+                });
+            });
+        });
+    }
+
+    fn show_preview(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, link: &Link) {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        let Some(target) = link.page.as_deref().and_then(|page| state.resolve_page_ref(page)) else { return };
+        let Some(page) = state.pages.get(target) else { return };
+        let (width, height) = (page.info.width, page.info.height);
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        // 没有目标坐标时预览页首
+        let top = (link.target_top().unwrap_or(0.0) - PREVIEW_MARGIN).clamp(0.0, (height - PREVIEW_HEIGHT).max(0.0));
+        let region = Rect::new(0.0, top, width, (top + PREVIEW_HEIGHT).min(height));
+        debug!("[LinkPreview] {:?} -> 第 {} 页 {:?}", link.page, target + 1, region);
+
+        // render_region 内部按 2 倍渲染，正好适合高分屏
+        match state.decode_service.render_region(target, region, PREVIEW_WIDTH / width) {
+            Ok((pixels, image_width, image_height)) => {
+                let image = slint::Image::from_rgba8_premultiplied(
+                    slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(&pixels, image_width, image_height),
+                );
+                let label = state.page_label(target);
+                let caption = if label.is_empty() {
+                    format!("第 {} 页", target + 1)
+                } else {
+                    format!("第 {} 页（{}）", target + 1, label)
+                };
+                window.set_link_preview(image);
+                window.set_link_preview_caption(caption.into());
+                window.set_show_link_preview(true);
+            }
+            Err(e) => error!("[LinkPreview] 渲染预览失败: {}", e),
+        }
+    }
+}
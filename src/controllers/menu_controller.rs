@@ -0,0 +1,321 @@
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+use log::{error, info, warn};
+
+use crate::app_paths;
+use crate::controllers::{AssistantController, AutoTurnController, BookmarkController, ClipboardController, CropController, DocumentController, DocumentToolsController, FileActions, IdleController, RedactionController, SeriesController, ShareController, SimpleModeController, StorageController, SyncController, ThemeController, TimerController, UiScaleController, ViewportTextController, WatermarkController};
+use crate::settings::{AppSettings, ThemeMode};
+use crate::AppWindow;
+
+/// 菜单项动作，菜单在 slint 中以字符串 id 触发
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    Open,
+    CloseDocument,
+    Quit,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    ToggleCrop,
+    AdjustCrop,
+    ToggleDualPage,
+    TogglePagedMode,
+    ToggleRtl,
+    ToggleNightMode,
+    ToggleDeskew,
+    ToggleOutline,
+    ThemeSystem,
+    ThemeLight,
+    ThemeDark,
+    ThemeScheduled,
+    ToggleScheduledTheme,
+    ToggleDarkPages,
+    UiScaleUp,
+    UiScaleDown,
+    UiScaleReset,
+    FirstPage,
+    PrevPage,
+    NextPage,
+    LastPage,
+    GotoPage,
+    Find,
+    AddBookmark,
+    ToggleSkipBlankPages,
+    TogglePomodoro,
+    ToggleAutoTurn,
+    ContinueSeries,
+    SpeakPage,
+    StopSpeaking,
+    SummarizePage,
+    CopyVisibleText,
+    ToggleClipboardMonitor,
+    ToggleLibraryEncryption,
+    VerifyDocument,
+    SaveRepairedCopy,
+    SaveRepairedCopyLinearized,
+    SaveAnnotatedCopy,
+    Redact,
+    SplitByChapters,
+    ExportWatermark,
+    ExportOptimizedHigh,
+    ExportOptimizedMedium,
+    ExportOptimizedSmall,
+    Properties,
+    RevealInFolder,
+    CopyPath,
+    ShareEmail,
+    ShareSendToDevice,
+    ShareChooseDevice,
+    ShareSheet,
+    ExportKoreader,
+    ImportKoreader,
+    About,
+}
+
+impl MenuAction {
+    pub fn from_id(id: &str) -> Option<Self> {
+        let action = match id {
+            "open" => MenuAction::Open,
+            "close-document" => MenuAction::CloseDocument,
+            "quit" => MenuAction::Quit,
+            "zoom-in" => MenuAction::ZoomIn,
+            "zoom-out" => MenuAction::ZoomOut,
+            "zoom-reset" => MenuAction::ZoomReset,
+            "toggle-crop" => MenuAction::ToggleCrop,
+            "adjust-crop" => MenuAction::AdjustCrop,
+            "toggle-dual-page" => MenuAction::ToggleDualPage,
+            "toggle-paged-mode" => MenuAction::TogglePagedMode,
+            "toggle-rtl" => MenuAction::ToggleRtl,
+            "toggle-night-mode" => MenuAction::ToggleNightMode,
+            "toggle-deskew" => MenuAction::ToggleDeskew,
+            "toggle-outline" => MenuAction::ToggleOutline,
+            "theme-system" => MenuAction::ThemeSystem,
+            "theme-light" => MenuAction::ThemeLight,
+            "theme-dark" => MenuAction::ThemeDark,
+            "theme-scheduled" => MenuAction::ThemeScheduled,
+            "toggle-scheduled-theme" => MenuAction::ToggleScheduledTheme,
+            "toggle-dark-pages" => MenuAction::ToggleDarkPages,
+            "ui-scale-up" => MenuAction::UiScaleUp,
+            "ui-scale-down" => MenuAction::UiScaleDown,
+            "ui-scale-reset" => MenuAction::UiScaleReset,
+            "first-page" => MenuAction::FirstPage,
+            "prev-page" => MenuAction::PrevPage,
+            "next-page" => MenuAction::NextPage,
+            "last-page" => MenuAction::LastPage,
+            "goto-page" => MenuAction::GotoPage,
+            "find" => MenuAction::Find,
+            "add-bookmark" => MenuAction::AddBookmark,
+            "toggle-skip-blank-pages" => MenuAction::ToggleSkipBlankPages,
+            "continue-series" => MenuAction::ContinueSeries,
+            "speak-page" => MenuAction::SpeakPage,
+            "stop-speaking" => MenuAction::StopSpeaking,
+            "summarize-page" => MenuAction::SummarizePage,
+            "copy-visible-text" => MenuAction::CopyVisibleText,
+            "toggle-pomodoro" => MenuAction::TogglePomodoro,
+            "toggle-auto-turn" => MenuAction::ToggleAutoTurn,
+            "toggle-clipboard-monitor" => MenuAction::ToggleClipboardMonitor,
+            "toggle-library-encryption" => MenuAction::ToggleLibraryEncryption,
+            "verify-document" => MenuAction::VerifyDocument,
+            "save-repaired-copy" => MenuAction::SaveRepairedCopy,
+            "save-repaired-copy-linearized" => MenuAction::SaveRepairedCopyLinearized,
+            "save-annotated-copy" => MenuAction::SaveAnnotatedCopy,
+            "redact" => MenuAction::Redact,
+            "split-by-chapters" => MenuAction::SplitByChapters,
+            "export-watermark" => MenuAction::ExportWatermark,
+            "export-optimized-high" => MenuAction::ExportOptimizedHigh,
+            "export-optimized-medium" => MenuAction::ExportOptimizedMedium,
+            "export-optimized-small" => MenuAction::ExportOptimizedSmall,
+            "properties" => MenuAction::Properties,
+            "reveal-in-folder" => MenuAction::RevealInFolder,
+            "copy-path" => MenuAction::CopyPath,
+            "share-email" => MenuAction::ShareEmail,
+            "share-send-to-device" => MenuAction::ShareSendToDevice,
+            "share-choose-device" => MenuAction::ShareChooseDevice,
+            "share-sheet" => MenuAction::ShareSheet,
+            "export-koreader" => MenuAction::ExportKoreader,
+            "import-koreader" => MenuAction::ImportKoreader,
+            "about" => MenuAction::About,
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    /// 需要打开文档才能执行的动作
+    fn requires_document(&self) -> bool {
+        !matches!(self,
+            MenuAction::Open | MenuAction::Quit | MenuAction::About
+                | MenuAction::ThemeSystem | MenuAction::ThemeLight | MenuAction::ThemeDark
+                | MenuAction::ThemeScheduled | MenuAction::ToggleScheduledTheme
+                | MenuAction::ToggleDarkPages
+                | MenuAction::UiScaleUp | MenuAction::UiScaleDown | MenuAction::UiScaleReset
+                | MenuAction::ToggleClipboardMonitor | MenuAction::ToggleLibraryEncryption | MenuAction::ShareChooseDevice | MenuAction::ExportWatermark
+                | MenuAction::ToggleSkipBlankPages | MenuAction::TogglePomodoro | MenuAction::ToggleAutoTurn)
+    }
+}
+
+/// 菜单栏：把菜单动作分发到各控制器
+pub struct MenuController {
+    document_controller: Rc<RefCell<DocumentController>>,
+}
+
+impl MenuController {
+    pub fn new(document_controller: Rc<RefCell<DocumentController>>) -> Self {
+        Self { document_controller }
+    }
+
+    pub fn setup_menu_callbacks(&self, window: &AppWindow) {
+        window.set_skip_blank_pages(AppSettings::get().skip_blank_pages);
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(&self.document_controller);
+        window.on_menu_action(move |id| {
+            let Some(window) = weak_window.upgrade() else { return };
+            match MenuAction::from_id(&id) {
+                Some(action) => Self::dispatch(&window, &document_controller, action),
+                None => warn!("[Menu] 未知菜单动作: {}", id),
+            }
+        });
+    }
+
+    fn dispatch(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, action: MenuAction) {
+        info!("[Menu] {:?}", action);
+        IdleController::touch();
+        if action.requires_document() && !window.get_document_opened() {
+            return;
+        }
+        if !SimpleModeController::allows_action(&action) {
+            SimpleModeController::deny(window, &format!("{:?}", action));
+            return;
+        }
+
+        let zoom = window.get_zoom();
+        let current_page = window.get_current_page();
+        match action {
+            MenuAction::Open => window.invoke_open_file(),
+            MenuAction::CloseDocument => window.invoke_back_to_history(),
+            MenuAction::Quit => {
+                // 先走返回历史的流程保存阅读进度
+                if window.get_document_opened() {
+                    window.invoke_back_to_history();
+                }
+                let _ = slint::quit_event_loop();
+            }
+            MenuAction::ZoomIn => Self::set_zoom(window, zoom + 0.1),
+            MenuAction::ZoomOut => Self::set_zoom(window, zoom - 0.1),
+            MenuAction::ZoomReset => Self::set_zoom(window, 1.0),
+            MenuAction::ToggleCrop => {
+                document_controller.borrow().set_crop(window, !window.get_crop_enabled());
+            }
+            MenuAction::AdjustCrop => CropController::show(window, document_controller),
+            MenuAction::ToggleDualPage => {
+                document_controller.borrow().set_dual_page(window, !window.get_dual_page());
+            }
+            MenuAction::TogglePagedMode => {
+                document_controller.borrow().set_paged(window, !window.get_paged_mode());
+            }
+            MenuAction::ToggleRtl => {
+                document_controller.borrow().set_rtl(window, !window.get_rtl_mode());
+            }
+            MenuAction::ToggleNightMode => {
+                document_controller.borrow().set_night_mode(window, !window.get_night_mode());
+            }
+            MenuAction::ToggleDeskew => {
+                document_controller.borrow().set_deskew(window, !window.get_deskew_enabled());
+            }
+            MenuAction::ToggleOutline => window.set_outline_visible(!window.get_outline_visible()),
+            MenuAction::ThemeSystem => ThemeController::set_mode(window, document_controller, ThemeMode::System),
+            MenuAction::ThemeLight => ThemeController::set_mode(window, document_controller, ThemeMode::Light),
+            MenuAction::ThemeDark => ThemeController::set_mode(window, document_controller, ThemeMode::Dark),
+            MenuAction::ThemeScheduled => ThemeController::set_mode(window, document_controller, ThemeMode::Scheduled),
+            MenuAction::ToggleScheduledTheme => ThemeController::toggle_schedule_override(window, document_controller),
+            MenuAction::ToggleDarkPages => {
+                ThemeController::set_dark_pages(window, document_controller, !window.get_dark_pages());
+            }
+            MenuAction::UiScaleUp => UiScaleController::step(window, 1),
+            MenuAction::UiScaleDown => UiScaleController::step(window, -1),
+            MenuAction::UiScaleReset => UiScaleController::set_scale(window, 1.0),
+            MenuAction::FirstPage => window.invoke_page_changed(1),
+            MenuAction::PrevPage => window.invoke_page_changed((current_page - 1).max(1)),
+            MenuAction::NextPage => window.invoke_page_changed((current_page + 1).min(window.get_page_count())),
+            MenuAction::LastPage => window.invoke_page_changed(window.get_page_count()),
+            MenuAction::GotoPage => {
+                window.set_goto_error("".into());
+                window.set_show_goto_dialog(true);
+            }
+            MenuAction::Find => window.set_show_search_bar(true),
+            MenuAction::AddBookmark => BookmarkController::add_bookmark(window, document_controller),
+            MenuAction::ToggleSkipBlankPages => {
+                let enabled = !window.get_skip_blank_pages();
+                AppSettings::update(|settings| settings.skip_blank_pages = enabled);
+                window.set_skip_blank_pages(enabled);
+            }
+            MenuAction::ContinueSeries => SeriesController::continue_series(window, document_controller),
+            MenuAction::SpeakPage => window.invoke_speak_page(),
+            MenuAction::StopSpeaking => document_controller.borrow().stop_speaking(),
+            MenuAction::TogglePomodoro => TimerController::set_pomodoro(window, !window.get_pomodoro_enabled()),
+            MenuAction::ToggleAutoTurn => AutoTurnController::set_enabled(window, !window.get_auto_turn_enabled()),
+            MenuAction::SummarizePage => AssistantController::summarize_page(window, document_controller),
+            MenuAction::CopyVisibleText => ViewportTextController::copy_visible_text(window, document_controller),
+            MenuAction::ToggleClipboardMonitor => {
+                ClipboardController::set_enabled(window, !window.get_clipboard_monitor());
+            }
+            MenuAction::ToggleLibraryEncryption => StorageController::toggle_library_encryption(window),
+            MenuAction::VerifyDocument => DocumentToolsController::verify_document(window),
+            MenuAction::SaveRepairedCopy => DocumentToolsController::save_repaired_copy(window, false),
+            MenuAction::SaveRepairedCopyLinearized => DocumentToolsController::save_repaired_copy(window, true),
+            MenuAction::SaveAnnotatedCopy => DocumentToolsController::save_annotated_copy(window),
+            MenuAction::Redact => RedactionController::enter(window),
+            MenuAction::SplitByChapters => DocumentToolsController::split_by_chapters(window, document_controller),
+            MenuAction::ExportWatermark => WatermarkController::show(window),
+            MenuAction::ExportOptimizedHigh => DocumentToolsController::export_optimized(window, 85),
+            MenuAction::ExportOptimizedMedium => DocumentToolsController::export_optimized(window, 70),
+            MenuAction::ExportOptimizedSmall => DocumentToolsController::export_optimized(window, 50),
+            MenuAction::Properties => window.invoke_show_properties(),
+            MenuAction::RevealInFolder => {
+                if let Err(e) = FileActions::reveal_in_folder(&window.get_file_path()) {
+                    error!("[Menu] 无法在文件夹中显示: {}", e);
+                    Self::show_error(window, e.user_message());
+                }
+            }
+            MenuAction::CopyPath => {
+                if let Err(e) = FileActions::copy_path(&window.get_file_path()) {
+                    error!("[Menu] 复制路径失败: {}", e);
+                    Self::show_error(window, e.user_message());
+                }
+            }
+            MenuAction::ShareEmail => ShareController::email(window),
+            MenuAction::ShareSendToDevice => ShareController::send_to_device(window),
+            MenuAction::ShareChooseDevice => {
+                ShareController::choose_device_folder();
+            }
+            MenuAction::ShareSheet => ShareController::share_sheet(window),
+            MenuAction::ExportKoreader => SyncController::export_koreader(window),
+            MenuAction::ImportKoreader => SyncController::import_koreader(window),
+            MenuAction::About => Self::show_about(window),
+        }
+    }
+
+    fn show_error(window: &AppWindow, message: &str) {
+        window.set_error_message(message.into());
+        window.set_show_error_dialog(true);
+    }
+
+    fn set_zoom(window: &AppWindow, zoom: f32) {
+        let zoom = zoom.clamp(0.5, 5.0);
+        window.set_zoom(zoom);
+        window.invoke_zoom_changed(zoom);
+    }
+
+    fn show_about(window: &AppWindow) {
+        let items = vec![
+            crate::PropertyItem { name: "版本".into(), value: env!("CARGO_PKG_VERSION").into() },
+            crate::PropertyItem { name: "数据目录".into(), value: app_paths::data_dir().to_string_lossy().to_string().into() },
+            crate::PropertyItem { name: "缓存目录".into(), value: app_paths::cache_dir().to_string_lossy().to_string().into() },
+            crate::PropertyItem { name: "便携模式".into(), value: if app_paths::is_portable() { "是" } else { "否" }.into() },
+        ];
+        window.set_properties_dialog_title("关于 R-Reader".into());
+        window.set_document_properties(ModelRc::from(Rc::new(VecModel::from(items))));
+        window.set_show_properties_dialog(true);
+    }
+}
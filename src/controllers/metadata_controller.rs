@@ -0,0 +1,285 @@
+use crossbeam_channel::Receiver;
+use log::{error, info};
+use sea_orm::ActiveValue;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::app_paths;
+use crate::dao::{MetadataDao, RecentDao};
+use crate::decoder::pdf::utils::convert_to_slint_image;
+use crate::entity::book_metadata::ActiveModel;
+use crate::entity::{recent, BookMetadata};
+use crate::error::{RReaderError, Result};
+use crate::library::CustomCover;
+use crate::net::{find_isbn, MetadataQuery, OnlineMetadata, OpenLibrary};
+use crate::storage::FileStore;
+use crate::ui::utils::generate_thumbnail_hash;
+use crate::AppWindow;
+
+/// 检查查询结果的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+type LookupResult = std::result::Result<Option<OnlineMetadata>, String>;
+
+/// 对话框中的书除了可编辑字段以外的信息
+#[derive(Debug, Clone, Default)]
+struct Draft {
+    book_path: String,
+    source_key: String,
+    cover_path: String,
+}
+
+thread_local! {
+    static POLLER: slint::Timer = slint::Timer::default();
+    static DRAFT: RefCell<Draft> = RefCell::new(Draft::default());
+    /// 进行中的查询和发起查询的书
+    static LOOKUP: RefCell<Option<(String, Receiver<LookupResult>)>> = const { RefCell::new(None) };
+}
+
+/// 书籍信息：在 OpenLibrary 上按 ISBN 或书名查询规范书名、作者、简介和封面，
+/// 可以手动修正；查到的结果和修正都保存在数据库中，只在用户点查询时联网
+pub struct MetadataController;
+
+impl MetadataController {
+    pub fn setup_metadata_callbacks(window: &AppWindow) {
+        let weak_window = window.as_weak();
+        window.on_metadata_lookup(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            Self::lookup(&window);
+        });
+
+        let weak_window = window.as_weak();
+        window.on_metadata_save(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            if let Err(e) = Self::save(&window) {
+                error!("[Metadata] 保存书籍信息失败: {}", e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+        });
+    }
+
+    /// 打开书籍信息对话框，有保存过的信息时直接显示，否则以书架上的书名起步
+    pub fn show(window: &AppWindow, book_path: &str) -> Result<()> {
+        let saved = MetadataDao::find_by_path_sync(book_path)?;
+        let (draft, status) = match &saved {
+            Some(metadata) => {
+                window.set_metadata_title(metadata.title.clone().into());
+                window.set_metadata_author(metadata.author.clone().into());
+                window.set_metadata_isbn(metadata.isbn.clone().into());
+                window.set_metadata_description(metadata.description.clone().into());
+                let status = if metadata.is_edited() { "已手动修正" } else { "来自 OpenLibrary 的查询结果" };
+                let draft = Draft {
+                    book_path: book_path.to_string(),
+                    source_key: metadata.source_key.clone(),
+                    cover_path: metadata.cover_path.clone(),
+                };
+                (draft, status)
+            }
+            None => {
+                let file_name = Path::new(book_path).file_stem().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                let recent = RecentDao::find_by_path_sync(book_path)?;
+                let title = recent.as_ref()
+                    .map(|recent| recent.name.clone())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| file_name.clone());
+                let author = recent.map(|recent| recent.author).unwrap_or_default();
+                window.set_metadata_title(title.into());
+                window.set_metadata_author(author.into());
+                window.set_metadata_isbn(find_isbn(&file_name).unwrap_or_default().into());
+                window.set_metadata_description("".into());
+                let draft = Draft { book_path: book_path.to_string(), ..Default::default() };
+                (draft, "尚未查询，可以在 OpenLibrary 上查找")
+            }
+        };
+
+        Self::set_cover_to_ui(window, &draft.cover_path);
+        DRAFT.with(|current| current.replace(draft));
+        window.set_metadata_path(book_path.into());
+        window.set_metadata_use_cover(false);
+        window.set_metadata_status(status.into());
+        window.set_metadata_busy(Self::is_looking_up(book_path));
+        window.set_show_metadata_dialog(true);
+        Ok(())
+    }
+
+    fn is_looking_up(book_path: &str) -> bool {
+        LOOKUP.with(|lookup| lookup.borrow().as_ref().is_some_and(|(path, _)| path == book_path))
+    }
+
+    fn set_cover_to_ui(window: &AppWindow, cover_path: &str) {
+        let cover = (!cover_path.is_empty())
+            .then(|| FileStore::read(Path::new(cover_path)).ok())
+            .flatten()
+            .and_then(|data| image::load_from_memory(&data).ok());
+        match cover {
+            Some(cover) => {
+                window.set_metadata_cover(convert_to_slint_image(&cover));
+                window.set_metadata_has_cover(true);
+            }
+            None => {
+                window.set_metadata_cover(slint::Image::default());
+                window.set_metadata_has_cover(false);
+            }
+        }
+    }
+
+    fn lookup(window: &AppWindow) {
+        let book_path = window.get_metadata_path().to_string();
+        let isbn_text = window.get_metadata_isbn().to_string();
+        let isbn = find_isbn(&isbn_text);
+        let query = MetadataQuery {
+            isbn: isbn.clone(),
+            title: window.get_metadata_title().trim().to_string(),
+            author: window.get_metadata_author().trim().to_string(),
+        };
+        if !isbn_text.trim().is_empty() && isbn.is_none() {
+            window.set_metadata_status("ISBN 格式不正确".into());
+            return;
+        }
+        if query.isbn.is_none() && query.title.is_empty() {
+            window.set_metadata_status("请填写书名或 ISBN".into());
+            return;
+        }
+
+        info!("[Metadata] {} 在 OpenLibrary 上查询: {:?}", book_path, query);
+        LOOKUP.with(|lookup| lookup.replace(Some((book_path, OpenLibrary::lookup(query)))));
+        window.set_metadata_busy(true);
+        window.set_metadata_status("正在查询…".into());
+
+        let weak_window = window.as_weak();
+        POLLER.with(|poller| {
+            poller.start(slint::TimerMode::Repeated, POLL_INTERVAL, move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                let finished = LOOKUP.with(|lookup| {
+                    let lookup = lookup.borrow();
+                    let (book_path, result_rx) = lookup.as_ref()?;
+                    result_rx.try_recv().ok().map(|result| (book_path.clone(), result))
+                });
+                let Some((book_path, result)) = finished else { return };
+                LOOKUP.with(|lookup| lookup.replace(None));
+                POLLER.with(|poller| poller.stop());
+                Self::finish_lookup(&window, &book_path, result);
+            });
+        });
+    }
+
+    /// 查到后填入对话框并缓存；对话框已换成别的书时只缓存
+    fn finish_lookup(window: &AppWindow, book_path: &str, result: LookupResult) {
+        let showing = window.get_show_metadata_dialog() && window.get_metadata_path() == book_path;
+        if showing {
+            window.set_metadata_busy(false);
+        }
+
+        let book = match result {
+            Ok(Some(book)) => book,
+            Ok(None) => {
+                if showing {
+                    window.set_metadata_status("OpenLibrary 上没有找到这本书，可以改一下书名或作者再试".into());
+                }
+                return;
+            }
+            Err(e) => {
+                error!("[Metadata] 查询失败 {}: {}", book_path, e);
+                if showing {
+                    window.set_metadata_status(format!("查询失败：{}", e).into());
+                }
+                return;
+            }
+        };
+
+        let cover_path = book.cover.as_ref()
+            .and_then(|cover| Self::store_cover(book_path, cover))
+            .unwrap_or_default();
+        if let Err(e) = Self::cache_lookup(book_path, &book, &cover_path) {
+            error!("[Metadata] 缓存查询结果失败: {}", e);
+        }
+        if !showing {
+            return;
+        }
+
+        window.set_metadata_title(book.title.clone().into());
+        window.set_metadata_author(book.author.clone().into());
+        window.set_metadata_isbn(book.isbn.clone().into());
+        window.set_metadata_description(book.description.clone().into());
+        Self::set_cover_to_ui(window, &cover_path);
+        window.set_metadata_status(format!("已在 OpenLibrary 上找到 {}，确认无误后保存", book.key).into());
+        DRAFT.with(|draft| {
+            let mut draft = draft.borrow_mut();
+            draft.source_key = book.key;
+            draft.cover_path = cover_path;
+        });
+    }
+
+    fn store_cover(book_path: &str, cover: &[u8]) -> Option<String> {
+        let dir = app_paths::metadata_cover_dir();
+        let path = dir.join(format!("{}.jpg", generate_thumbnail_hash(book_path)));
+        match fs::create_dir_all(&dir).and_then(|_| FileStore::write(&path, cover)) {
+            Ok(()) => Some(path.to_string_lossy().to_string()),
+            Err(e) => {
+                error!("[Metadata] 保存封面失败 {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// 缓存查询结果，但不覆盖手动修正过的信息
+    fn cache_lookup(book_path: &str, book: &OnlineMetadata, cover_path: &str) -> Result<()> {
+        if MetadataDao::find_by_path_sync(book_path)?.is_some_and(|saved| saved.is_edited()) {
+            return Ok(());
+        }
+        let mut metadata = BookMetadata::new(book_path.to_string(), book.title.clone(), book.author.clone(), book.description.clone());
+        metadata.isbn = ActiveValue::Set(book.isbn.clone());
+        metadata.source_key = ActiveValue::Set(book.key.clone());
+        metadata.cover_path = ActiveValue::Set(cover_path.to_string());
+        MetadataDao::save_sync(metadata)?;
+        Ok(())
+    }
+
+    /// 保存为手动修正的信息，书名和作者同步到书架，勾选时把封面设为书架封面
+    fn save(window: &AppWindow) -> Result<()> {
+        let title = window.get_metadata_title().trim().to_string();
+        if title.is_empty() {
+            window.set_metadata_status("书名不能为空".into());
+            return Ok(());
+        }
+        let draft = DRAFT.with(|draft| draft.borrow().clone());
+        let author = window.get_metadata_author().trim().to_string();
+        let mut metadata: ActiveModel = BookMetadata::new(
+            draft.book_path.clone(),
+            title.clone(),
+            author.clone(),
+            window.get_metadata_description().trim().to_string(),
+        );
+        metadata.isbn = ActiveValue::Set(window.get_metadata_isbn().trim().to_string());
+        metadata.source_key = ActiveValue::Set(draft.source_key.clone());
+        metadata.cover_path = ActiveValue::Set(draft.cover_path.clone());
+        metadata.edited = ActiveValue::Set(1);
+        MetadataDao::save_sync(metadata)?;
+
+        let recent = recent::ActiveModel {
+            name: ActiveValue::Set(title),
+            author: ActiveValue::Set(author),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(&draft.book_path, recent)?;
+
+        if window.get_metadata_use_cover() && !draft.cover_path.is_empty() {
+            Self::use_as_cover(&draft.book_path, &draft.cover_path)?;
+        }
+        info!("[Metadata] 保存书籍信息: {}", draft.book_path);
+        window.set_show_metadata_dialog(false);
+        window.invoke_history_changed();
+        Ok(())
+    }
+
+    /// 查到的封面设为书架封面
+    fn use_as_cover(book_path: &str, cover_path: &str) -> Result<()> {
+        let cover = image::load_from_memory(&FileStore::read(Path::new(cover_path))?)
+            .map_err(|e| RReaderError::decode(e.to_string()))?;
+        CustomCover::set_image(book_path, &cover)
+    }
+}
@@ -1,5 +1,83 @@
+pub mod annotation_controller;
+pub mod archive_controller;
+pub mod assistant_controller;
+pub mod auto_turn_controller;
+pub mod bookmark_controller;
+pub mod checksum_controller;
+pub mod clipboard_controller;
+pub mod crop_controller;
 pub mod document_controller;
+pub mod document_tools_controller;
+pub mod download_controller;
+pub mod file_actions;
+pub mod gesture_controller;
 pub mod history_controller;
+pub mod history_export_controller;
+pub mod home_controller;
+pub mod idle_controller;
+pub mod library_controller;
+pub mod link_preview_controller;
+pub mod menu_controller;
+pub mod metadata_controller;
+pub mod ordered_list;
+pub mod page_menu_controller;
+pub mod power_controller;
+pub mod preview_controller;
+pub mod redaction_controller;
+pub mod series_controller;
+pub mod share_controller;
+pub mod simple_mode_controller;
+pub mod status_controller;
+pub mod storage_controller;
+pub mod sync_controller;
+pub mod task_controller;
+pub mod theme_controller;
+pub mod timer_controller;
+pub mod toolbar_controller;
+pub mod ui_scale_controller;
+pub mod viewport_text_controller;
+pub mod vocab_controller;
+pub mod watermark_controller;
+pub mod window_profile_controller;
 
+pub use annotation_controller::AnnotationController;
+pub use archive_controller::ArchiveController;
+pub use assistant_controller::AssistantController;
+pub use auto_turn_controller::AutoTurnController;
+pub use bookmark_controller::BookmarkController;
+pub use checksum_controller::ChecksumController;
+pub use clipboard_controller::ClipboardController;
+pub use crop_controller::CropController;
 pub use document_controller::DocumentController;
+pub use document_tools_controller::DocumentToolsController;
+pub use download_controller::DownloadController;
+pub use file_actions::FileActions;
+pub use gesture_controller::GestureController;
 pub use history_controller::{HistoryController, HistoryControllerPointer};
+pub use history_export_controller::HistoryExportController;
+pub use home_controller::HomeController;
+pub use idle_controller::IdleController;
+pub use library_controller::LibraryController;
+pub use link_preview_controller::LinkPreviewController;
+pub use menu_controller::{MenuAction, MenuController};
+pub use metadata_controller::MetadataController;
+pub use ordered_list::OrderedList;
+pub use page_menu_controller::PageMenuController;
+pub use power_controller::PowerController;
+pub use preview_controller::PreviewController;
+pub use redaction_controller::RedactionController;
+pub use series_controller::SeriesController;
+pub use share_controller::ShareController;
+pub use simple_mode_controller::SimpleModeController;
+pub use status_controller::{StatusBarModel, StatusController};
+pub use storage_controller::StorageController;
+pub use sync_controller::SyncController;
+pub use task_controller::{TaskController, TaskStatus};
+pub use theme_controller::ThemeController;
+pub use timer_controller::TimerController;
+pub use toolbar_controller::ToolbarController;
+pub use ui_scale_controller::UiScaleController;
+pub use viewport_text_controller::ViewportTextController;
+pub use vocab_controller::VocabController;
+pub use watermark_controller::WatermarkController;
+pub use window_profile_controller::WindowProfileController;
@@ -0,0 +1,106 @@
+use crate::settings::OrderedItem;
+
+/// 常量表描述的可勾选、可排序列表，工具栏按钮、首页分区、导出的列共用。
+/// 表中每项为（id、标题、默认是否勾选），表的顺序即默认顺序；设置中只保存 id 和勾选状态
+pub struct OrderedList {
+    table: &'static [(&'static str, &'static str, bool)],
+}
+
+impl OrderedList {
+    pub const fn new(table: &'static [(&'static str, &'static str, bool)]) -> Self {
+        Self { table }
+    }
+
+    pub fn title_of(&self, id: &str) -> Option<&'static str> {
+        self.table.iter()
+            .find(|(item_id, _, _)| *item_id == id)
+            .map(|(_, title, _)| *title)
+    }
+
+    pub fn defaults(&self) -> Vec<OrderedItem> {
+        self.table.iter()
+            .map(|(id, _, visible)| OrderedItem { id: id.to_string(), visible: *visible })
+            .collect()
+    }
+
+    /// 合并设置中保存的列表：为空时用默认，去掉表里已不存在的项，新增的项以不勾选的状态追加到末尾
+    pub fn merge(&self, saved: Vec<OrderedItem>) -> Vec<OrderedItem> {
+        if saved.is_empty() {
+            return self.defaults();
+        }
+
+        let mut items: Vec<OrderedItem> = saved.into_iter()
+            .filter(|item| self.title_of(&item.id).is_some())
+            .collect();
+        for (id, _, _) in self.table {
+            if !items.iter().any(|item| item.id == *id) {
+                items.push(OrderedItem { id: id.to_string(), visible: false });
+            }
+        }
+        items
+    }
+
+    /// 勾选或取消一项
+    pub fn toggled(&self, saved: Vec<OrderedItem>, id: &str, visible: bool) -> Vec<OrderedItem> {
+        let mut items = self.merge(saved);
+        if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+            item.visible = visible;
+        }
+        items
+    }
+
+    /// 把一项前后移动 delta 位，超出两端时停在端点
+    pub fn moved(&self, saved: Vec<OrderedItem>, id: &str, delta: i32) -> Vec<OrderedItem> {
+        let mut items = self.merge(saved);
+        if let Some(from) = items.iter().position(|item| item.id == id) {
+            let to = (from as i32 + delta).clamp(0, items.len() as i32 - 1) as usize;
+            let item = items.remove(from);
+            items.insert(to, item);
+        }
+        items
+    }
+
+    /// 自定义对话框使用的模型
+    pub fn to_ui(&self, items: &[OrderedItem]) -> Vec<crate::ToggleItem> {
+        items.iter()
+            .map(|item| crate::ToggleItem {
+                id: item.id.clone().into(),
+                title: self.title_of(&item.id).unwrap_or_default().into(),
+                visible: item.visible,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: OrderedList = OrderedList::new(&[("a", "A", true), ("b", "B", false), ("c", "C", true)]);
+
+    fn item(id: &str, visible: bool) -> OrderedItem {
+        OrderedItem { id: id.to_string(), visible }
+    }
+
+    fn ids(items: &[OrderedItem]) -> Vec<&str> {
+        items.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn merge_drops_unknown_and_appends_new_items_hidden() {
+        assert_eq!(LIST.merge(Vec::new()), LIST.defaults());
+
+        let merged = LIST.merge(vec![item("c", true), item("gone", true), item("a", false)]);
+        assert_eq!(merged, vec![item("c", true), item("a", false), item("b", false)]);
+    }
+
+    #[test]
+    fn toggle_and_move_start_from_defaults() {
+        let toggled = LIST.toggled(Vec::new(), "b", true);
+        assert_eq!(toggled, vec![item("a", true), item("b", true), item("c", true)]);
+
+        assert_eq!(ids(&LIST.moved(Vec::new(), "a", 1)), ["b", "a", "c"]);
+        assert_eq!(ids(&LIST.moved(Vec::new(), "b", -5)), ["b", "a", "c"]);
+        assert_eq!(ids(&LIST.moved(Vec::new(), "b", 5)), ["a", "c", "b"]);
+    }
+}
@@ -0,0 +1,288 @@
+use log::{error, info};
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::assistant::AssistantAction;
+use crate::controllers::annotation_controller::HIGHLIGHT_COLORS;
+use crate::controllers::{AnnotationController, AssistantController, ClipboardController, DocumentController, SimpleModeController, VocabController};
+use crate::decoder::pdf::Watermark;
+use crate::decoder::{Link, PageHit, Rect};
+use crate::error::{RReaderError, Result};
+use crate::library::CustomCover;
+use crate::platform::{is_safe_link, link_scheme, open_with_system};
+use crate::reflow::hyphenation::join_lines;
+use crate::reflow::language::detect_language;
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 保存图片时的渲染比例（解码器内部还会再乘 2）
+const IMAGE_EXPORT_SCALE: f32 = 2.0;
+/// 设为封面时渲染的最长边（解码器内部还会再乘 2）
+const COVER_RENDER_SIZE: f32 = 300.0;
+
+/// 右键时光标下的内容，菜单项执行时使用
+struct MenuTarget {
+    page_index: usize,
+    link: Option<Link>,
+    hit: PageHit,
+    /// 光标下已有的高亮
+    annotation: Option<i32>,
+}
+
+thread_local! {
+    static TARGET: RefCell<Option<MenuTarget>> = const { RefCell::new(None) };
+}
+
+/// 页面右键菜单：按光标下的链接、图片、文字组合菜单项
+pub struct PageMenuController;
+
+impl PageMenuController {
+    pub fn setup_page_menu_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let document_controller_clone = Rc::clone(document_controller);
+        window.on_page_context_menu(move |x, y, page_index| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let target = Self::hit(&document_controller_clone, page_index as usize, x, y);
+            let items = target.as_ref().map(Self::build_items).unwrap_or_default();
+            window.set_page_menu_items(ModelRc::from(Rc::new(VecModel::from(items))));
+            TARGET.with(|current| *current.borrow_mut() = target);
+        });
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        window.on_page_menu_action(move |action| {
+            let Some(window) = weak_window.upgrade() else { return };
+            let Some(target) = TARGET.with(|current| current.borrow_mut().take()) else { return };
+            info!("[PageMenu] {} (第 {} 页)", action, target.page_index + 1);
+            if let Err(e) = Self::run(&window, &document_controller, &action, &target) {
+                error!("[PageMenu] {} 失败: {}", action, e);
+                window.set_error_message(e.to_string().into());
+                window.set_show_error_dialog(true);
+            }
+        });
+    }
+
+    /// 查找光标下的链接、图片和单词；x、y 是页面内的像素位置
+    fn hit(document_controller: &Rc<RefCell<DocumentController>>, page_index: usize, x: f32, y: f32) -> Option<MenuTarget> {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        let link = state.handle_click(page_index, x, y);
+        let (page_x, page_y) = state.to_page_point(page_index, x, y)?;
+        let hit = state.decode_service.hit_test(page_index, page_x, page_y).unwrap_or_else(|e| {
+            error!("[PageMenu] 命中检测失败: {}", e);
+            PageHit::default()
+        });
+        let annotation = AnnotationController::annotation_at(page_index, page_x, page_y);
+        Some(MenuTarget { page_index, link, hit, annotation })
+    }
+
+    fn build_items(target: &MenuTarget) -> Vec<crate::PageMenuItem> {
+        let mut groups: Vec<Vec<(String, &str)>> = Vec::new();
+        if let Some(link) = &target.link {
+            if link.uri.is_some() {
+                groups.push(vec![("打开链接".into(), "open-link"), ("复制链接".into(), "copy-link")]);
+            } else if link.page.is_some() {
+                groups.push(vec![("跳转到链接位置".into(), "follow-link"), ("复制链接".into(), "copy-link")]);
+            }
+        }
+        if target.hit.image.is_some() && !SimpleModeController::is_enabled() {
+            groups.push(vec![("保存图片…".into(), "save-image")]);
+        }
+        if !SimpleModeController::is_enabled() {
+            groups.push(vec![("导出本页图片…".into(), "export-page-image"), ("设为书架封面".into(), "set-cover")]);
+        }
+        if target.annotation.is_some() {
+            let mut group = vec![("编辑笔记…".to_string(), "edit-note")];
+            group.extend(HIGHLIGHT_COLORS.iter().map(|(action, name, _)| (format!("改为{}", name), *action)));
+            group.push(("删除高亮".into(), "delete-annotation"));
+            groups.push(group);
+        } else if target.hit.word.as_ref().is_some_and(|word| !word.lines.is_empty()) {
+            groups.push(vec![("高亮段落".into(), "highlight"), ("高亮并添加笔记…".into(), "highlight-note")]);
+        }
+        if let Some(word) = &target.hit.word {
+            groups.push(vec![
+                (format!("复制“{}”", word.text), "copy-word"),
+                ("复制段落".into(), "copy-paragraph"),
+                ("朗读段落".into(), "speak-paragraph"),
+                ("加入生词本".into(), "save-vocab"),
+            ]);
+            if AppSettings::get().assistant.is_ready() {
+                groups.push(vec![
+                    (format!("解释“{}”", word.text), "explain-word"),
+                    ("总结段落".into(), "summarize-paragraph"),
+                ]);
+            }
+        }
+
+        // action 为空的项是分隔线
+        let mut items = Vec::new();
+        for (i, group) in groups.into_iter().enumerate() {
+            if i > 0 {
+                items.push(crate::PageMenuItem::default());
+            }
+            items.extend(group.into_iter().map(|(title, action)| crate::PageMenuItem {
+                title: title.into(),
+                action: action.into(),
+            }));
+        }
+        items
+    }
+
+    /// 打开文档里的外部链接：网页和邮件直接打开，其他协议（本地文件、网络共享、自定义协议）先询问
+    fn open_link(uri: &str) -> Result<()> {
+        if !is_safe_link(uri) {
+            let scheme = link_scheme(uri).unwrap_or_else(|| "未知".into());
+            let confirmed = rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title("Open Link")
+                .set_description(format!("这个链接使用 {} 协议，可能会打开本地文件或其他程序：\n{}\n\n确定要打开吗？", scheme, uri))
+                .set_buttons(rfd::MessageButtons::OkCancel)
+                .show();
+            if confirmed != rfd::MessageDialogResult::Ok {
+                info!("[PageMenu] 取消打开链接: {}", uri);
+                return Ok(());
+            }
+        }
+        open_with_system(uri)?;
+        Ok(())
+    }
+
+    fn run(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, action: &str, target: &MenuTarget) -> Result<()> {
+        let link = target.link.as_ref();
+        let paragraph = || {
+            let text = target.hit.word.as_ref().map(|word| word.paragraph.as_str()).unwrap_or_default();
+            join_lines(text, detect_language(text).as_deref())
+        };
+        match action {
+            "open-link" => {
+                if let Some(uri) = link.and_then(|link| link.uri.as_deref()) {
+                    Self::open_link(uri)?;
+                }
+            }
+            "follow-link" => {
+                let page_view_state = document_controller.borrow().page_view_state();
+                let index = link
+                    .and_then(|link| link.page.as_deref())
+                    .and_then(|page| page_view_state.borrow().resolve_page_ref(page));
+                if let Some(index) = index {
+                    window.invoke_page_changed(index as i32 + 1);
+                }
+            }
+            "copy-link" => {
+                if let Some(text) = link.and_then(|link| link.uri.as_deref().or(link.page.as_deref())) {
+                    ClipboardController::copy_text(text)?;
+                }
+            }
+            "save-image" => {
+                if let Some(bounds) = target.hit.image {
+                    Self::save_image(document_controller, target.page_index, bounds)?;
+                }
+            }
+            "export-page-image" => Self::export_page_image(document_controller, target.page_index)?,
+            "set-cover" => Self::set_cover(window, document_controller, target.page_index)?,
+            "copy-word" => {
+                if let Some(word) = &target.hit.word {
+                    ClipboardController::copy_text(&word.text)?;
+                }
+            }
+            "copy-paragraph" => ClipboardController::copy_text(&paragraph())?,
+            "speak-paragraph" => document_controller.borrow().speak_text(paragraph()),
+            "save-vocab" => {
+                if let Some(word) = &target.hit.word {
+                    VocabController::save_word(window, &word.text, &word.paragraph, target.page_index);
+                }
+            }
+            "explain-word" => {
+                if let Some(word) = &target.hit.word {
+                    AssistantController::ask(window, AssistantAction::Explain, &format!("“{}”\n\n{}", word.text, paragraph()));
+                    VocabController::set_lookup(window, &word.text, &word.paragraph, target.page_index);
+                }
+            }
+            "summarize-paragraph" => AssistantController::ask(window, AssistantAction::Summarize, &paragraph()),
+            "highlight" | "highlight-note" => {
+                if let Some(word) = &target.hit.word {
+                    AnnotationController::highlight(window, document_controller, target.page_index, &word.lines, action == "highlight-note");
+                }
+            }
+            "edit-note" => {
+                if let Some(id) = target.annotation {
+                    AnnotationController::edit_note(window, id);
+                }
+            }
+            "delete-annotation" => {
+                if let Some(id) = target.annotation {
+                    AnnotationController::delete(window, document_controller, id);
+                }
+            }
+            _ => {
+                let color = HIGHLIGHT_COLORS.iter().find(|(id, _, _)| *id == action).map(|(_, _, color)| *color);
+                if let (Some(id), Some(color)) = (target.annotation, color) {
+                    AnnotationController::set_color(window, document_controller, id, color);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 按图片范围重新渲染，另存为 PNG
+    fn save_image(document_controller: &Rc<RefCell<DocumentController>>, page_index: usize, bounds: crate::decoder::Rect) -> Result<()> {
+        let Some(path) = Self::pick_image_target(page_index) else { return Ok(()) };
+        Self::export_region(document_controller, page_index, bounds, &path)
+    }
+
+    /// 整页渲染，另存为 PNG
+    fn export_page_image(document_controller: &Rc<RefCell<DocumentController>>, page_index: usize) -> Result<()> {
+        let bounds = {
+            let page_view_state = document_controller.borrow().page_view_state();
+            let state = page_view_state.borrow();
+            let Some(page) = state.pages.get(page_index) else { return Ok(()) };
+            Rect::new(0.0, 0.0, page.info.width, page.info.height)
+        };
+        let Some(path) = Self::pick_image_target(page_index) else { return Ok(()) };
+        Self::export_region(document_controller, page_index, bounds, &path)
+    }
+
+    /// 渲染页面中的一块区域保存为图片，设置了水印时画进图片
+    fn export_region(document_controller: &Rc<RefCell<DocumentController>>, page_index: usize, bounds: Rect, path: &Path) -> Result<()> {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let (pixels, width, height) = page_view_state.borrow().decode_service.render_region(page_index, bounds, IMAGE_EXPORT_SCALE)?;
+        let mut image = image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| RReaderError::decode("图片数据不完整"))?;
+        if let Some(watermark) = Watermark::from_settings() {
+            watermark.apply_to_image(&mut image)
+                .map_err(|e| RReaderError::decode(format!("添加水印失败: {}", e)))?;
+        }
+        image.save(path).map_err(|e| RReaderError::decode(format!("保存图片失败: {}", e)))?;
+        info!("[PageMenu] 图片已保存: {:?} ({}x{})", path, width, height);
+        Ok(())
+    }
+
+    /// 把整页渲染为书架封面
+    fn set_cover(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, page_index: usize) -> Result<()> {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        let Some(page) = state.pages.get(page_index) else { return Ok(()) };
+        let (width, height) = (page.info.width, page.info.height);
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(());
+        }
+        // render_region 内部按 2 倍渲染，缩放到封面大小即可
+        let scale = COVER_RENDER_SIZE / width.max(height);
+        let (pixels, image_width, image_height) = state.decode_service.render_region(page_index, Rect::new(0.0, 0.0, width, height), scale)?;
+        let image = image::RgbaImage::from_raw(image_width, image_height, pixels)
+            .ok_or_else(|| RReaderError::decode("图片数据不完整"))?;
+        CustomCover::set_image(&window.get_file_path(), &image::DynamicImage::ImageRgba8(image))?;
+        window.invoke_history_changed();
+        Ok(())
+    }
+
+    fn pick_image_target(page_index: usize) -> Option<PathBuf> {
+        rfd::FileDialog::new()
+            .set_title("Save Image")
+            .add_filter("PNG", &["png"])
+            .set_file_name(format!("page-{}.png", page_index + 1))
+            .save_file()
+    }
+}
@@ -0,0 +1,53 @@
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::DocumentController;
+use crate::platform::power;
+use crate::settings::{AppSettings, PowerMode};
+
+/// 检查电源状态的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// 预加载的屏数
+const PRELOAD_SCREENS: f32 = 1.0;
+const LOW_POWER_PRELOAD_SCREENS: f32 = 0.25;
+
+thread_local! {
+    static CHECKER: slint::Timer = slint::Timer::default();
+}
+
+/// 电源感知：用电池时自动切换到省电配置，接通电源后恢复，设置中可以固定某一种配置
+pub struct PowerController;
+
+impl PowerController {
+    pub fn setup_power_callbacks(document_controller: &Rc<RefCell<DocumentController>>) {
+        Self::apply(document_controller);
+
+        let document_controller = Rc::clone(document_controller);
+        CHECKER.with(|checker| {
+            checker.start(slint::TimerMode::Repeated, CHECK_INTERVAL, move || Self::apply(&document_controller));
+        });
+    }
+
+    pub fn is_low_power() -> bool {
+        power::is_low_power()
+    }
+
+    /// 按设置和电源状态选择配置，变化时应用到阅读视图
+    pub fn apply(document_controller: &Rc<RefCell<DocumentController>>) {
+        let low_power = match AppSettings::get().power_mode {
+            PowerMode::Auto => power::on_battery().unwrap_or(false),
+            PowerMode::Performance => false,
+            PowerMode::LowPower => true,
+        };
+        if low_power == power::is_low_power() {
+            return;
+        }
+        info!("[Power] 切换到{}配置", if low_power { "省电" } else { "正常" });
+        power::set_low_power(low_power);
+
+        let page_view_state = document_controller.borrow().page_view_state();
+        page_view_state.borrow_mut().preload_screens = if low_power { LOW_POWER_PRELOAD_SCREENS } else { PRELOAD_SCREENS };
+    }
+}
@@ -0,0 +1,115 @@
+use crossbeam_channel::Receiver;
+use log::{error, info};
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::controllers::DocumentController;
+use crate::decoder::decode_service::PreviewImage;
+use crate::error::Result;
+use crate::AppWindow;
+
+/// 预览的页数
+const PREVIEW_PAGES: usize = 4;
+/// 预览图最长边
+const PREVIEW_MAX_SIZE: f32 = 600.0;
+
+/// 书架快速预览：在解码线程用临时解码器渲染前几页，不写最近记录也不改动阅读状态
+pub struct PreviewController {
+    document_controller: Rc<RefCell<DocumentController>>,
+    pending: Rc<RefCell<Option<Receiver<Result<Vec<PreviewImage>>>>>>,
+    timer: Rc<slint::Timer>,
+}
+
+impl PreviewController {
+    pub fn new(document_controller: Rc<RefCell<DocumentController>>) -> Self {
+        Self {
+            document_controller,
+            pending: Rc::new(RefCell::new(None)),
+            timer: Rc::new(slint::Timer::default()),
+        }
+    }
+
+    pub fn setup_preview_callbacks(&self, window: &AppWindow) {
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(&self.document_controller);
+        let pending = Rc::clone(&self.pending);
+        let timer = Rc::clone(&self.timer);
+        window.on_history_preview_requested(move |path| {
+            let Some(window) = weak_window.upgrade() else { return };
+            info!("[Preview] 预览: {}", path);
+            let title = Path::new(path.as_str())
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            window.set_preview_title(title.into());
+            window.set_preview_path(path.clone());
+            window.set_preview_pages(ModelRc::default());
+            window.set_preview_error("".into());
+            window.set_preview_loading(true);
+            window.set_show_preview(true);
+
+            let state = document_controller.borrow().page_view_state();
+            let response = state.borrow().decode_service.render_preview(path.as_str(), PREVIEW_PAGES, PREVIEW_MAX_SIZE);
+            match response {
+                Ok(response_rx) => {
+                    *pending.borrow_mut() = Some(response_rx);
+                    Self::poll(&window, &pending, &timer);
+                }
+                Err(e) => Self::show_error(&window, &e),
+            }
+        });
+    }
+
+    /// 等待解码线程返回，期间界面保持响应
+    fn poll(window: &AppWindow, pending: &Rc<RefCell<Option<Receiver<Result<Vec<PreviewImage>>>>>>, timer: &Rc<slint::Timer>) {
+        let weak_window = window.as_weak();
+        let pending = Rc::clone(pending);
+        let weak_timer = Rc::downgrade(timer);
+        timer.start(slint::TimerMode::Repeated, Duration::from_millis(50), move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let result = match pending.borrow().as_ref().map(|rx| rx.try_recv()) {
+                Some(Ok(result)) => result,
+                Some(Err(crossbeam_channel::TryRecvError::Empty)) => return,
+                // 通道断开或没有请求
+                _ => {
+                    if let Some(timer) = weak_timer.upgrade() {
+                        timer.stop();
+                    }
+                    return;
+                }
+            };
+            pending.borrow_mut().take();
+            if let Some(timer) = weak_timer.upgrade() {
+                timer.stop();
+            }
+
+            match result {
+                Ok(images) => {
+                    let pages: Vec<slint::Image> = images.iter().map(Self::to_slint_image).collect();
+                    window.set_preview_pages(ModelRc::from(Rc::new(VecModel::from(pages))));
+                    window.set_preview_loading(false);
+                }
+                Err(e) => Self::show_error(&window, &e),
+            }
+        });
+    }
+
+    fn to_slint_image(image: &PreviewImage) -> slint::Image {
+        slint::Image::from_rgba8_premultiplied(
+            slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(
+                &image.image_data,
+                image.image_width,
+                image.image_height,
+            ),
+        )
+    }
+
+    fn show_error(window: &AppWindow, e: &crate::error::RReaderError) {
+        error!("[Preview] 预览失败: {}", e);
+        window.set_preview_loading(false);
+        window.set_preview_error(e.user_message().into());
+    }
+}
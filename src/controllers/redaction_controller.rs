@@ -0,0 +1,188 @@
+use log::{error, info};
+use slint::{ComponentHandle, Model, ModelRc, VecModel};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::controllers::{DocumentController, TaskController, TaskStatus};
+use crate::decoder::pdf::{save_redacted_in_background, PdfRedaction, Watermark};
+use crate::decoder::Rect;
+use crate::page::PageViewState;
+use crate::AppWindow;
+
+/// 小于这个尺寸（页面坐标）的框视为误触
+const MIN_BOX_SIZE: f32 = 2.0;
+
+thread_local! {
+    /// 待涂黑的区域，页面坐标，按添加顺序
+    static BOXES: RefCell<Vec<PdfRedaction>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 涂黑：在页面上拖出要遮盖的区域，导出的副本中这些区域的文字和图像真正被删除，
+/// 而不只是盖上黑框；原文件不变
+pub struct RedactionController;
+
+impl RedactionController {
+    pub fn setup_redaction_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let weak_window = window.as_weak();
+        let controller = Rc::clone(document_controller);
+        window.on_redaction_drawn(move |x0, y0, x1, y1, page_index| {
+            let Some(window) = weak_window.upgrade() else { return };
+            if page_index < 0 {
+                return;
+            }
+            let page_view_state = controller.borrow().page_view_state();
+            let state = page_view_state.borrow();
+            let Some(rect) = Self::to_page_rect(&state, page_index as usize, x0, y0, x1, y1) else { return };
+            if rect.width() < MIN_BOX_SIZE || rect.height() < MIN_BOX_SIZE {
+                return;
+            }
+            BOXES.with(|boxes| boxes.borrow_mut().push(PdfRedaction { page: page_index as usize, rect }));
+            Self::refresh_boxes(&window, &state);
+        });
+
+        let weak_window = window.as_weak();
+        let controller = Rc::clone(document_controller);
+        window.on_redaction_undo(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            BOXES.with(|boxes| boxes.borrow_mut().pop());
+            Self::refresh_boxes(&window, &controller.borrow().page_view_state().borrow());
+        });
+
+        let weak_window = window.as_weak();
+        let controller = Rc::clone(document_controller);
+        window.on_redaction_clear(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            BOXES.with(|boxes| boxes.borrow_mut().clear());
+            Self::refresh_boxes(&window, &controller.borrow().page_view_state().borrow());
+        });
+
+        let weak_window = window.as_weak();
+        window.on_redaction_export(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            Self::export(&window);
+        });
+
+        let weak_window = window.as_weak();
+        window.on_redaction_exit(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            Self::exit(&window);
+        });
+    }
+
+    /// 进入涂黑模式，只支持 PDF
+    pub fn enter(window: &AppWindow) {
+        let path = window.get_file_path().to_string();
+        if !Self::is_pdf(Path::new(&path)) {
+            window.set_error_message("只能涂黑 PDF 文档".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        info!("[Redaction] 进入涂黑模式: {}", path);
+        BOXES.with(|boxes| boxes.borrow_mut().clear());
+        window.set_redaction_boxes(ModelRc::default());
+        window.set_redaction_count(0);
+        window.set_redact_mode(true);
+    }
+
+    /// 退出涂黑模式，丢弃未导出的区域
+    pub fn exit(window: &AppWindow) {
+        BOXES.with(|boxes| boxes.borrow_mut().clear());
+        window.set_redaction_boxes(ModelRc::default());
+        window.set_redaction_count(0);
+        window.set_redact_mode(false);
+    }
+
+    /// 页面内的像素范围换算为页面坐标；切边时页面图像从切边范围的左上角开始
+    fn to_page_rect(state: &PageViewState, index: usize, x0: f32, y0: f32, x1: f32, y1: f32) -> Option<Rect> {
+        let (left, top) = state.to_page_point(index, x0.min(x1), y0.min(y1))?;
+        let (right, bottom) = state.to_page_point(index, x0.max(x1), y0.max(y1))?;
+        let (dx, dy) = Self::crop_offset(state, index);
+        Some(Rect::new(left + dx, top + dy, right + dx, bottom + dy))
+    }
+
+    fn crop_offset(state: &PageViewState, index: usize) -> (f32, f32) {
+        if state.crop != 1 {
+            return (0.0, 0.0);
+        }
+        state.pages.get(index)
+            .and_then(|page| page.info.crop_bounds)
+            .map(|bounds| (bounds.left, bounds.top))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// 可见页面上待涂黑的区域换算到文档坐标
+    pub fn refresh_boxes(window: &AppWindow, state: &PageViewState) {
+        let (marks, count) = BOXES.with(|boxes| {
+            let boxes = boxes.borrow();
+            let marks: Vec<crate::RedactionBox> = state.visible_range.iter()
+                .filter_map(|index| state.pages.get(index))
+                .flat_map(|page| {
+                    let scale = page.info.scale;
+                    let (dx, dy) = Self::crop_offset(state, page.info.index);
+                    boxes.iter()
+                        .filter(move |redaction| redaction.page == page.info.index)
+                        .map(move |redaction| crate::RedactionBox {
+                            x: page.bounds.left + (redaction.rect.left - dx) * scale,
+                            y: page.bounds.top + (redaction.rect.top - dy) * scale,
+                            width: redaction.rect.width() * scale,
+                            height: redaction.rect.height() * scale,
+                        })
+                })
+                .collect();
+            (marks, boxes.len())
+        });
+        window.set_redaction_count(count as i32);
+        if !marks.is_empty() || window.get_redaction_boxes().row_count() > 0 {
+            window.set_redaction_boxes(ModelRc::from(Rc::new(VecModel::from(marks))));
+        }
+    }
+
+    fn export(window: &AppWindow) {
+        let redactions = BOXES.with(|boxes| boxes.borrow().clone());
+        if redactions.is_empty() {
+            window.set_error_message("请先在页面上拖出要涂黑的区域".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        let source = PathBuf::from(window.get_file_path().to_string());
+        let Some(target) = Self::pick_target(&source) else { return };
+        if target == source {
+            window.set_error_message("请另存为新文件，不要覆盖正在阅读的文档".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+
+        info!("[Redaction] 导出涂黑副本: {:?} -> {:?}, {} 处", source, target, redactions.len());
+        let result_rx = save_redacted_in_background(source, target, redactions, Watermark::from_settings());
+        TaskController::start(window, "正在导出涂黑后的副本…", || {}, move |window| {
+            let result = result_rx.try_recv().ok()?;
+            Some(TaskStatus::Done(match result {
+                Ok(count) => {
+                    Self::exit(window);
+                    format!("已导出副本，删除了 {} 处内容", count)
+                }
+                Err(e) => {
+                    error!("[Redaction] 导出失败: {:#}", e);
+                    "导出失败".to_string()
+                }
+            }))
+        });
+    }
+
+    fn is_pdf(path: &Path) -> bool {
+        path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+    }
+
+    fn pick_target(source: &Path) -> Option<PathBuf> {
+        let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("Export Redacted Copy")
+            .add_filter("PDF", &["pdf"])
+            .set_file_name(format!("{}-redacted.pdf", stem));
+        if let Some(dir) = source.parent() {
+            dialog = dialog.set_directory(dir);
+        }
+        dialog.save_file()
+    }
+}
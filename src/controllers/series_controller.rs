@@ -0,0 +1,187 @@
+use log::{error, info};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::controllers::history_controller::convert_history_records_to_items;
+use crate::controllers::DocumentController;
+use crate::dao::RecentDao;
+use crate::entity::recent::ActiveModel;
+use crate::entity::Recent;
+use crate::library::SeriesInfo;
+use crate::AppWindow;
+
+/// 首页“系列”显示的数量
+const SERIES_LIMIT: usize = 6;
+
+thread_local! {
+    /// 已经提示过下一卷的文档，同一次阅读只提示一次
+    static SUGGESTED: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// 提示条对应的下一卷
+    static NEXT_VOLUME: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 漫画系列：按文件名识别系列，读完一卷时提示下一卷
+pub struct SeriesController;
+
+impl SeriesController {
+    pub fn setup_series_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        Self::backfill();
+
+        let weak_window = window.as_weak();
+        let document_controller_clone = Rc::clone(document_controller);
+        window.on_open_next_volume(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            window.set_show_next_volume_toast(false);
+            let Some(path) = NEXT_VOLUME.with(|next| next.borrow_mut().take()) else { return };
+            Self::open_volume(&window, &document_controller_clone, &path);
+        });
+
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        window.on_series_item_clicked(move |path| {
+            let Some(window) = weak_window.upgrade() else { return };
+            Self::open_volume(&window, &document_controller, &path);
+        });
+    }
+
+    /// 为没有识别过系列的旧记录补上系列信息
+    fn backfill() {
+        let records = match RecentDao::find_all_sync() {
+            Ok(records) => records,
+            Err(e) => {
+                error!("[Series] 读取记录失败: {}", e);
+                return;
+            }
+        };
+        let mut updated = 0;
+        for record in records.iter().filter(|record| record.series.is_empty()) {
+            let mut active = ActiveModel::default();
+            if !Self::apply(&mut active, Path::new(&record.book_path)) {
+                continue;
+            }
+            match RecentDao::update_by_path_sync(&record.book_path, active) {
+                Ok(()) => updated += 1,
+                Err(e) => error!("[Series] 更新 {} 失败: {}", record.book_path, e),
+            }
+        }
+        if updated > 0 {
+            info!("[Series] 识别出系列的记录: {}", updated);
+        }
+    }
+
+    /// 把文件名中识别出的系列写入记录，返回是否属于系列
+    pub fn apply(record: &mut ActiveModel, path: &Path) -> bool {
+        let Some(info) = SeriesInfo::detect(path) else { return false };
+        record.series = sea_orm::ActiveValue::Set(info.name);
+        record.series_index = sea_orm::ActiveValue::Set(info.volume);
+        true
+    }
+
+    /// 同一系列中卷号更大、文件还在的下一卷
+    pub fn next_volume(path: &str) -> crate::error::Result<Option<Recent>> {
+        let Some(current) = RecentDao::find_by_path_sync(path)? else { return Ok(None) };
+        if current.series.is_empty() {
+            return Ok(None);
+        }
+        Ok(RecentDao::find_by_series_sync(&current.series)?
+            .into_iter()
+            .find(|record| record.series_index > current.series_index && Path::new(&record.book_path).is_file()))
+    }
+
+    /// 菜单“继续阅读系列”：打开当前文档的下一卷
+    pub fn continue_series(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let path = window.get_file_path().to_string();
+        match Self::next_volume(&path) {
+            Ok(Some(next)) => Self::open_volume(window, document_controller, &next.book_path),
+            Ok(None) => {
+                window.set_error_message("没有找到这个系列的下一卷".into());
+                window.set_show_error_dialog(true);
+            }
+            Err(e) => error!("[Series] 查找下一卷失败: {}", e),
+        }
+    }
+
+    /// 显示到最后一页时提示下一卷
+    pub fn on_page_shown(window: &AppWindow, page: usize, page_count: usize) {
+        if page_count == 0 || page < page_count {
+            return;
+        }
+        let path = window.get_file_path().to_string();
+        if SUGGESTED.with(|suggested| suggested.borrow().as_deref() == Some(path.as_str())) {
+            return;
+        }
+        SUGGESTED.with(|suggested| *suggested.borrow_mut() = Some(path.clone()));
+
+        match Self::next_volume(&path) {
+            Ok(Some(next)) => {
+                info!("[Series] {} 读完，下一卷: {}", path, next.book_path);
+                window.set_next_volume_toast_text(
+                    format!("已读完本卷，继续阅读《{}》第 {} 卷？", next.series, SeriesInfo::volume_label(next.series_index)).into(),
+                );
+                window.set_show_next_volume_toast(true);
+                NEXT_VOLUME.with(|next_volume| *next_volume.borrow_mut() = Some(next.book_path));
+            }
+            Ok(None) => {}
+            Err(e) => error!("[Series] 查找下一卷失败: {}", e),
+        }
+    }
+
+    fn open_volume(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, path: &str) {
+        if !Path::new(path).is_file() {
+            error!("[Series] 文件不存在: {}", path);
+            window.set_error_message("文件不存在".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        if window.get_document_opened() {
+            window.invoke_back_to_history();
+        }
+        document_controller.borrow().open_document(window, path);
+    }
+
+    /// 首页“系列”：至少两卷且没有全部读完的系列，显示下一本该读的卷
+    pub fn series_items() -> Vec<crate::SeriesItem> {
+        let records = match RecentDao::find_all_ordered_by_update_at_desc_sync() {
+            Ok(records) => records,
+            Err(e) => {
+                error!("[Series] 读取记录失败: {}", e);
+                return Vec::new();
+            }
+        };
+
+        // 记录按最近阅读排序，系列按第一次出现的顺序排列
+        let mut order: Vec<String> = Vec::new();
+        let mut groups: HashMap<String, Vec<Recent>> = HashMap::new();
+        for record in records.into_iter().filter(|record| !record.series.is_empty()) {
+            if !groups.contains_key(&record.series) {
+                order.push(record.series.clone());
+            }
+            groups.entry(record.series.clone()).or_default().push(record);
+        }
+
+        order
+            .into_iter()
+            .filter_map(|series| {
+                let mut volumes = groups.remove(&series)?;
+                if volumes.len() < 2 {
+                    return None;
+                }
+                volumes.sort_by(|a, b| a.series_index.total_cmp(&b.series_index));
+                let finished = volumes.iter().filter(|volume| volume.is_finished()).count();
+                let next = volumes.iter().find(|volume| !volume.is_finished())?.clone();
+                let recent = convert_history_records_to_items(std::slice::from_ref(&next)).pop()?;
+                Some(crate::SeriesItem {
+                    name: series.into(),
+                    volume_count: volumes.len() as i32,
+                    finished_count: finished as i32,
+                    next_volume: SeriesInfo::volume_label(next.series_index).into(),
+                    recent,
+                })
+            })
+            .take(SERIES_LIMIT)
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+use log::{error, info};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::controllers::{TaskController, TaskStatus};
+use crate::platform::{email_file, share_sheet_available, show_share_sheet, CopyEvent, CopyJob};
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 分享/发送到设备：邮件、复制到设备目录、系统分享面板
+pub struct ShareController;
+
+impl ShareController {
+    pub fn setup_share_callbacks(window: &AppWindow) {
+        window.set_share_sheet_available(share_sheet_available());
+    }
+
+    pub fn email(window: &AppWindow) {
+        let path = window.get_file_path().to_string();
+        if let Err(e) = email_file(Path::new(&path)) {
+            error!("[Share] 无法发送邮件: {}", e);
+            Self::show_error(window, "无法打开邮件程序");
+        }
+    }
+
+    pub fn share_sheet(window: &AppWindow) {
+        let path = window.get_file_path().to_string();
+        if !show_share_sheet(Path::new(&path)) {
+            Self::show_error(window, "当前系统不支持分享面板");
+        }
+    }
+
+    /// 选择发送目标目录并保存
+    pub fn choose_device_folder() -> Option<PathBuf> {
+        let folder = rfd::FileDialog::new().set_title("Select Device Folder").pick_folder()?;
+        AppSettings::update(|settings| settings.share.device_folder = folder.to_string_lossy().to_string());
+        Some(folder)
+    }
+
+    /// 复制当前文档到设备目录，未配置时先询问
+    pub fn send_to_device(window: &AppWindow) {
+        if TaskController::is_running() {
+            Self::show_error(window, "已有任务正在进行");
+            return;
+        }
+        let configured = AppSettings::get().share.device_folder;
+        let folder = if !configured.is_empty() && Path::new(&configured).is_dir() {
+            PathBuf::from(configured)
+        } else {
+            match Self::choose_device_folder() {
+                Some(folder) => folder,
+                None => return,
+            }
+        };
+
+        let source = PathBuf::from(window.get_file_path().to_string());
+        info!("[Share] 发送 {:?} 到 {:?}", source, folder);
+        let job = Rc::new(CopyJob::start(source, folder));
+        let job_for_cancel = Rc::clone(&job);
+        TaskController::start(window, "正在发送… 0%", move || job_for_cancel.cancel(), move |_| Self::poll(&job));
+    }
+
+    fn poll(job: &CopyJob) -> Option<TaskStatus> {
+        let mut status = None;
+        while let Some(event) = job.try_recv() {
+            match event {
+                CopyEvent::Progress { copied, total } => {
+                    let percent = if total > 0 { copied * 100 / total } else { 100 };
+                    status = Some(TaskStatus::Running(format!("正在发送… {}%", percent)));
+                }
+                CopyEvent::Finished(Ok(target)) => {
+                    let folder = target.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                    return Some(TaskStatus::Done(format!("已发送到 {}", folder)));
+                }
+                CopyEvent::Finished(Err(e)) => {
+                    error!("[Share] 发送失败: {}", e);
+                    return Some(TaskStatus::Done("发送失败或已取消".to_string()));
+                }
+            }
+        }
+        status
+    }
+
+    fn show_error(window: &AppWindow, message: &str) {
+        window.set_error_message(message.into());
+        window.set_show_error_dialog(true);
+    }
+}
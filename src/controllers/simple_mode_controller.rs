@@ -0,0 +1,70 @@
+use log::info;
+use std::path::{Path, PathBuf};
+
+use crate::controllers::MenuAction;
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 简易模式：不能删除记录，只能打开允许目录中的文档，隐藏导出、分享等会写文件或外发的功能，界面放大
+/// 在设置文件（每个数据目录一份，如便携版或单独的系统账户）中开启
+pub struct SimpleModeController;
+
+impl SimpleModeController {
+    pub fn apply(window: &AppWindow) {
+        let enabled = Self::is_enabled();
+        if enabled {
+            info!("[SimpleMode] 简易模式已开启");
+        }
+        window.set_simple_mode(enabled);
+    }
+
+    pub fn is_enabled() -> bool {
+        AppSettings::get().simple_mode.enabled
+    }
+
+    /// 界面缩放，简易模式下不低于设置的下限
+    pub fn ui_scale(scale: f32) -> f32 {
+        let settings = AppSettings::get().simple_mode;
+        if settings.enabled { scale.max(settings.min_ui_scale) } else { scale }
+    }
+
+    /// 文件对话框的起始目录
+    pub fn start_folder() -> Option<PathBuf> {
+        let settings = AppSettings::get().simple_mode;
+        if !settings.enabled {
+            return None;
+        }
+        settings.allowed_folders.first().map(PathBuf::from)
+    }
+
+    /// 简易模式下只能打开允许目录中的文档
+    pub fn allows_path(path: &str) -> bool {
+        let settings = AppSettings::get().simple_mode;
+        if !settings.enabled || settings.allowed_folders.is_empty() {
+            return true;
+        }
+        let path = Path::new(path).canonicalize().unwrap_or_else(|_| PathBuf::from(path));
+        settings.allowed_folders.iter().any(|folder| {
+            let folder = Path::new(folder).canonicalize().unwrap_or_else(|_| PathBuf::from(folder));
+            path.starts_with(folder)
+        })
+    }
+
+    /// 删除、导出、分享、修复等动作在简易模式下不可用
+    pub fn allows_action(action: &MenuAction) -> bool {
+        !Self::is_enabled() || !matches!(action,
+            MenuAction::VerifyDocument | MenuAction::SaveRepairedCopy | MenuAction::SaveRepairedCopyLinearized | MenuAction::SaveAnnotatedCopy | MenuAction::Redact | MenuAction::SplitByChapters
+                | MenuAction::ExportOptimizedHigh | MenuAction::ExportOptimizedMedium | MenuAction::ExportOptimizedSmall
+                | MenuAction::RevealInFolder | MenuAction::CopyPath
+                | MenuAction::ShareEmail | MenuAction::ShareSendToDevice | MenuAction::ShareChooseDevice | MenuAction::ShareSheet
+                | MenuAction::ExportKoreader | MenuAction::ImportKoreader
+                | MenuAction::ToggleClipboardMonitor | MenuAction::ToggleLibraryEncryption | MenuAction::SummarizePage)
+    }
+
+    /// 被拦下的操作给出提示
+    pub fn deny(window: &AppWindow, what: &str) {
+        info!("[SimpleMode] 已拦截: {}", what);
+        window.set_error_message("简易模式下不能使用此功能".into());
+        window.set_show_error_dialog(true);
+    }
+}
@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::sync::atomic::Ordering;
+
+use crate::decoder::decode_service::BackgroundActivity;
+use crate::AppWindow;
+
+/// 状态栏数据，各控制器只修改这一个结构，再整体推送到界面
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusBarModel {
+    /// 当前页（从 1 开始）
+    pub page: usize,
+    /// 当前页的页码标签，文档没有定义时为空
+    pub page_label: String,
+    pub page_count: usize,
+    pub zoom: f32,
+    /// 选中文本的词数
+    pub selected_words: usize,
+    /// 排队中的渲染任务
+    pub pending_renders: usize,
+    pub extracting_text: bool,
+    pub speaking: bool,
+    /// 书库封面预生成进度（已处理, 总数）
+    pub cover_progress: Option<(usize, usize)>,
+}
+
+impl StatusBarModel {
+    fn background_jobs(&self) -> usize {
+        self.pending_renders + self.extracting_text as usize + self.speaking as usize + self.cover_progress.is_some() as usize
+    }
+
+    fn job_summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.pending_renders > 0 {
+            parts.push(format!("渲染 {}", self.pending_renders));
+        }
+        if self.extracting_text {
+            parts.push("提取文本".to_string());
+        }
+        if self.speaking {
+            parts.push("朗读中".to_string());
+        }
+        if let Some((done, total)) = self.cover_progress {
+            parts.push(format!("生成封面 {}/{}", done, total));
+        }
+        parts.join(" · ")
+    }
+
+    fn to_ui(&self) -> crate::StatusInfo {
+        crate::StatusInfo {
+            page: self.page as i32,
+            page_label: self.page_label.clone().into(),
+            page_count: self.page_count as i32,
+            zoom_percent: (self.zoom * 100.0).round() as i32,
+            selected_words: self.selected_words as i32,
+            background_jobs: self.background_jobs() as i32,
+            job_summary: self.job_summary().into(),
+        }
+    }
+}
+
+thread_local! {
+    static STATUS: RefCell<StatusBarModel> = RefCell::new(StatusBarModel::default());
+}
+
+/// 状态栏控制器，界面线程使用
+pub struct StatusController;
+
+impl StatusController {
+    /// 修改状态并在有变化时刷新界面
+    pub fn update<F: FnOnce(&mut StatusBarModel)>(window: &AppWindow, f: F) {
+        let changed = STATUS.with(|status| {
+            let mut status = status.borrow_mut();
+            let old = status.clone();
+            f(&mut status);
+            (*status != old).then(|| status.to_ui())
+        });
+        if let Some(info) = changed {
+            window.set_status(info);
+        }
+    }
+
+    /// 同步后台任务状态，由主循环定时调用
+    pub fn update_activity(window: &AppWindow, activity: &BackgroundActivity, speaking: bool) {
+        let pending_renders = activity.pending_renders.load(Ordering::Relaxed);
+        let extracting_text = activity.reflow_running.load(Ordering::Relaxed);
+        Self::update(window, |status| {
+            status.pending_renders = pending_renders;
+            status.extracting_text = extracting_text;
+            status.speaking = speaking;
+        });
+    }
+
+    /// 关闭文档时清空文档相关的状态
+    pub fn reset_document(window: &AppWindow) {
+        Self::update(window, |status| {
+            status.page = 0;
+            status.page_label.clear();
+            status.page_count = 0;
+            status.zoom = 1.0;
+            status.selected_words = 0;
+        });
+    }
+}
@@ -0,0 +1,24 @@
+use log::error;
+
+use crate::storage::FileStore;
+use crate::AppWindow;
+
+/// 书库存储设置：书库加密开关
+pub struct StorageController;
+
+impl StorageController {
+    pub fn apply(window: &AppWindow) {
+        window.set_library_encrypted(FileStore::is_encrypted());
+    }
+
+    /// 开关书库加密，数据库在退出时按新状态保存
+    pub fn toggle_library_encryption(window: &AppWindow) {
+        let enabled = !FileStore::is_encrypted();
+        if let Err(e) = FileStore::set_encrypted(enabled) {
+            error!("[Storage] 切换书库加密失败: {}", e);
+            window.set_error_message("无法访问系统钥匙串，书库加密未改变".into());
+            window.set_show_error_dialog(true);
+        }
+        window.set_library_encrypted(FileStore::is_encrypted());
+    }
+}
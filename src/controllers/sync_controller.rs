@@ -0,0 +1,93 @@
+use log::{error, info};
+use std::path::Path;
+
+use crate::controllers::{BookmarkController, ChecksumController};
+use crate::dao::BookmarkDao;
+use crate::entity::Bookmark;
+use crate::sync::{KoreaderSidecar, SyncBookmark, SyncRecord};
+use crate::AppWindow;
+
+/// 与其他阅读器同步：导出、导入 KOReader 侧车文件中的阅读位置和书签
+pub struct SyncController;
+
+impl SyncController {
+    /// 导出阅读位置和书签到 KOReader 侧车文件
+    pub fn export_koreader(window: &AppWindow) {
+        let path = window.get_file_path().to_string();
+        let bookmarks = match BookmarkDao::find_by_book_sync(&path) {
+            Ok(bookmarks) => bookmarks,
+            Err(e) => {
+                error!("[Sync] 读取书签失败: {}", e);
+                Self::show_error(window, e.user_message());
+                return;
+            }
+        };
+        let record = SyncRecord {
+            page: (window.get_current_page() - 1).max(0) as usize,
+            page_count: window.get_page_count().max(0) as usize,
+            bookmarks: bookmarks.into_iter()
+                .map(|bookmark| SyncBookmark { page: bookmark.page.max(0) as usize, note: bookmark.label })
+                .collect(),
+            sha256: ChecksumController::current(&path).unwrap_or_default(),
+        };
+        if let Err(e) = KoreaderSidecar::export(Path::new(&path), &record) {
+            error!("[Sync] 导出 KOReader 元数据失败: {}", e);
+            Self::show_error(window, e.user_message());
+        }
+    }
+
+    /// 从 KOReader 侧车文件恢复阅读位置，并补上还没有的书签
+    pub fn import_koreader(window: &AppWindow) {
+        let path = window.get_file_path().to_string();
+        match KoreaderSidecar::import(Path::new(&path)) {
+            Ok(Some(record)) => {
+                // 侧车文件旁边的书被替换成了别的版本，页码已经对不上
+                let current = ChecksumController::current(&path).unwrap_or_default();
+                if !record.sha256.is_empty() && !current.is_empty() && record.sha256 != current {
+                    info!("[Sync] KOReader 元数据的 SHA-256 与当前文档不同，不导入");
+                    Self::show_error(window, "KOReader 元数据属于另一个版本的文档");
+                    return;
+                }
+                Self::import_bookmarks(window, &path, &record.bookmarks);
+                let page = (record.page as i32 + 1).clamp(1, window.get_page_count().max(1));
+                window.invoke_page_changed(page);
+            }
+            Ok(None) => Self::show_error(window, "没有找到 KOReader 元数据"),
+            Err(e) => {
+                error!("[Sync] 导入 KOReader 元数据失败: {}", e);
+                Self::show_error(window, e.user_message());
+            }
+        }
+    }
+
+    /// 已经有书签的页不重复添加，备注作为书签标签
+    fn import_bookmarks(window: &AppWindow, path: &str, bookmarks: &[SyncBookmark]) {
+        let mut existing: Vec<i32> = match BookmarkDao::find_by_book_sync(path) {
+            Ok(existing) => existing.iter().map(|bookmark| bookmark.page).collect(),
+            Err(e) => {
+                error!("[Sync] 读取书签失败: {}", e);
+                return;
+            }
+        };
+        let page_count = window.get_page_count().max(0) as usize;
+        let mut added = 0;
+        for bookmark in bookmarks.iter().filter(|bookmark| bookmark.page < page_count) {
+            if existing.contains(&(bookmark.page as i32)) {
+                continue;
+            }
+            if let Err(e) = BookmarkDao::add_sync(Bookmark::new(path.to_string(), bookmark.page as i32, 0.0, bookmark.note.clone())) {
+                error!("[Sync] 导入书签失败: {}", e);
+                break;
+            }
+            existing.push(bookmark.page as i32);
+            added += 1;
+        }
+        info!("[Sync] 从 KOReader 导入 {} 个书签", added);
+        BookmarkController::set_bookmarks_to_ui(window);
+    }
+
+    fn show_error(window: &AppWindow, message: &str) {
+        window.set_error_message(message.into());
+        window.set_show_error_dialog(true);
+    }
+}
@@ -0,0 +1,110 @@
+use log::info;
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::AppWindow;
+
+/// 后台任务的轮询结果
+pub enum TaskStatus {
+    /// 仍在进行，附带进度文字
+    Running(String),
+    /// 已结束，附带结果文字
+    Done(String),
+}
+
+/// 进行中的任务：取消回调、可选的暂停回调和进度定时器
+struct RunningTask {
+    cancel: Box<dyn Fn()>,
+    pause: Option<Box<dyn Fn(bool)>>,
+    timer: slint::Timer,
+}
+
+thread_local! {
+    static TASK: RefCell<Option<RunningTask>> = const { RefCell::new(None) };
+}
+
+/// 底部任务提示条：同一时间只运行一个耗时的文件任务（发送、检查、导出等），
+/// 定时轮询进度并显示，用户可以取消
+pub struct TaskController;
+
+impl TaskController {
+    pub fn setup_task_callbacks(window: &AppWindow) {
+        window.on_task_cancel(|| {
+            TASK.with(|task| {
+                if let Some(task) = task.borrow().as_ref() {
+                    info!("[Task] 取消任务");
+                    (task.cancel)();
+                }
+            });
+        });
+
+        let weak_window = window.as_weak();
+        window.on_task_pause_toggled(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let paused = !window.get_task_paused();
+            TASK.with(|task| {
+                if let Some(pause) = task.borrow().as_ref().and_then(|task| task.pause.as_ref()) {
+                    info!("[Task] 暂停: {}", paused);
+                    pause(paused);
+                    window.set_task_paused(paused);
+                }
+            });
+        });
+    }
+
+    /// 给正在运行的任务加上暂停/继续按钮（如下载）
+    pub fn set_pause_handler<F: Fn(bool) + 'static>(window: &AppWindow, pause: F) {
+        TASK.with(|task| {
+            if let Some(task) = task.borrow_mut().as_mut() {
+                task.pause = Some(Box::new(pause));
+                window.set_task_pausable(true);
+            }
+        });
+    }
+
+    pub fn is_running() -> bool {
+        TASK.with(|task| task.borrow().is_some())
+    }
+
+    /// 启动任务提示；已有任务在运行时返回 false
+    pub fn start<C, P>(window: &AppWindow, text: &str, cancel: C, poll: P) -> bool
+    where
+        C: Fn() + 'static,
+        P: FnMut(&AppWindow) -> Option<TaskStatus> + 'static,
+    {
+        if Self::is_running() {
+            window.set_error_message("已有任务正在进行".into());
+            window.set_show_error_dialog(true);
+            return false;
+        }
+
+        window.set_task_toast_text(text.into());
+        window.set_task_in_progress(true);
+        window.set_task_pausable(false);
+        window.set_task_paused(false);
+        window.set_show_task_toast(true);
+
+        let poll = Rc::new(RefCell::new(poll));
+        let timer = slint::Timer::default();
+        let weak_window = window.as_weak();
+        timer.start(slint::TimerMode::Repeated, Duration::from_millis(200), move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let status = (poll.borrow_mut())(&window);
+            match status {
+                Some(TaskStatus::Running(text)) => window.set_task_toast_text(text.into()),
+                Some(TaskStatus::Done(text)) => {
+                    window.set_task_toast_text(text.into());
+                    window.set_task_in_progress(false);
+                    // 不在定时器自己的回调里销毁它，推迟到下一轮事件循环
+                    let finished = TASK.with(|task| task.borrow_mut().take());
+                    slint::Timer::single_shot(Duration::ZERO, move || drop(finished));
+                }
+                None => {}
+            }
+        });
+        TASK.with(|task| *task.borrow_mut() = Some(RunningTask { cancel: Box::new(cancel), pause: None, timer }));
+        true
+    }
+}
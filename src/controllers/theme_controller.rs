@@ -0,0 +1,146 @@
+use chrono::Local;
+use log::{debug, info};
+use std::cell::{Cell, RefCell};
+use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::thread;
+use std::time::Duration;
+
+use crate::controllers::DocumentController;
+use crate::settings::{AppSettings, ThemeMode, ThemeSettings};
+use crate::AppWindow;
+
+/// 系统深色模式检测间隔
+const SYSTEM_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 最近一次检测到的系统深色模式，由后台线程更新
+static SYSTEM_DARK: AtomicBool = AtomicBool::new(false);
+static WATCH_STARTED: Once = Once::new();
+
+thread_local! {
+    /// 最近一次应用到界面的深浅色，用于判断系统设置是否变化
+    static APPLIED_DARK: Cell<Option<bool>> = const { Cell::new(None) };
+    /// 定时模式下的手动切换：(手动选择的深浅色, 切换时计划的深浅色)，计划翻转后失效
+    static SCHEDULE_OVERRIDE: Cell<Option<(bool, bool)>> = const { Cell::new(None) };
+}
+
+/// 主题控制器：解析主题设置与系统偏好，推送到界面并协调页面反色
+pub struct ThemeController;
+
+impl ThemeController {
+    /// 读取系统深色模式设置，读取失败视为浅色
+    pub fn detect_system_dark() -> bool {
+        if cfg!(target_os = "macos") {
+            // 浅色模式下该键不存在，命令返回非 0
+            process::Command::new("defaults")
+                .args(["read", "-g", "AppleInterfaceStyle"])
+                .output()
+                .map(|out| out.status.success() && String::from_utf8_lossy(&out.stdout).trim() == "Dark")
+                .unwrap_or(false)
+        } else if cfg!(target_os = "windows") {
+            process::Command::new("reg")
+                .args([
+                    "query",
+                    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+                    "/v",
+                    "AppsUseLightTheme",
+                ])
+                .output()
+                .map(|out| String::from_utf8_lossy(&out.stdout).contains("0x0"))
+                .unwrap_or(false)
+        } else {
+            process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+                .output()
+                .map(|out| String::from_utf8_lossy(&out.stdout).contains("prefer-dark"))
+                .unwrap_or(false)
+        }
+    }
+
+    /// 启动后台线程定期检测系统设置，避免在界面线程执行外部命令
+    fn start_system_watch() {
+        WATCH_STARTED.call_once(|| {
+            SYSTEM_DARK.store(Self::detect_system_dark(), Ordering::Relaxed);
+            thread::spawn(|| loop {
+                thread::sleep(SYSTEM_POLL_INTERVAL);
+                SYSTEM_DARK.store(Self::detect_system_dark(), Ordering::Relaxed);
+            });
+        });
+    }
+
+    fn resolve_dark(theme: &ThemeSettings) -> bool {
+        match theme.mode {
+            ThemeMode::System => SYSTEM_DARK.load(Ordering::Relaxed),
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            ThemeMode::Scheduled => {
+                let scheduled = theme.schedule.is_dark_at(Local::now());
+                match SCHEDULE_OVERRIDE.with(|o| o.get()) {
+                    Some((dark, at)) if at == scheduled => dark,
+                    Some(_) => {
+                        // 到了下一次计划切换，手动选择失效
+                        SCHEDULE_OVERRIDE.with(|o| o.set(None));
+                        scheduled
+                    }
+                    None => scheduled,
+                }
+            }
+        }
+    }
+
+    /// 按当前设置应用主题，启动时和设置变化后调用
+    pub fn apply(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        Self::start_system_watch();
+
+        let theme = AppSettings::get().theme;
+        let dark = Self::resolve_dark(&theme);
+        info!("[Theme] mode={:?}, dark={}, dark_pages={}", theme.mode, dark, theme.dark_pages);
+
+        window.set_theme_mode(theme.mode.as_str().into());
+        window.set_dark_pages(theme.dark_pages);
+        window.set_dark_theme(dark);
+        APPLIED_DARK.with(|applied| applied.set(Some(dark)));
+
+        // 深色主题可选"深色界面、白色页面"或页面一起反色
+        document_controller.borrow().set_night_mode(window, dark && theme.dark_pages);
+    }
+
+    /// 跟随系统或定时切换时检查深浅色是否该变化，由界面线程定时调用
+    pub fn poll_system(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let theme = AppSettings::get().theme;
+        if !matches!(theme.mode, ThemeMode::System | ThemeMode::Scheduled) {
+            return;
+        }
+        let dark = Self::resolve_dark(&theme);
+        if APPLIED_DARK.with(|applied| applied.get()) != Some(dark) {
+            debug!("[Theme] 主题变化: mode={:?}, dark={}", theme.mode, dark);
+            Self::apply(window, document_controller);
+        }
+    }
+
+    /// 定时模式下临时切换深浅色，保持到下一次计划切换
+    pub fn toggle_schedule_override(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let theme = AppSettings::get().theme;
+        if theme.mode != ThemeMode::Scheduled {
+            return;
+        }
+        let scheduled = theme.schedule.is_dark_at(Local::now());
+        let dark = !Self::resolve_dark(&theme);
+        info!("[Theme] 手动切换到 dark={}，直到下一次计划切换", dark);
+        SCHEDULE_OVERRIDE.with(|o| o.set((dark != scheduled).then_some((dark, scheduled))));
+        Self::apply(window, document_controller);
+    }
+
+    pub fn set_mode(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, mode: ThemeMode) {
+        SCHEDULE_OVERRIDE.with(|o| o.set(None));
+        AppSettings::update(|settings| settings.theme.mode = mode);
+        Self::apply(window, document_controller);
+    }
+
+    pub fn set_dark_pages(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, enabled: bool) {
+        AppSettings::update(|settings| settings.theme.dark_pages = enabled);
+        Self::apply(window, document_controller);
+    }
+}
@@ -0,0 +1,177 @@
+use log::{error, info};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::time::Duration;
+
+use crate::controllers::IdleController;
+use crate::dao::RecentDao;
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 累计多少秒写一次数据库
+const FLUSH_SECONDS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    Break,
+}
+
+/// 阅读计时状态，每秒更新一次
+struct TimerState {
+    /// 正在计时的文档，没有打开文档时为空
+    path: String,
+    /// 还没写入数据库的秒数
+    unsaved: i64,
+    /// 这次打开文档后的阅读秒数
+    session: u64,
+    /// 连续阅读的秒数，休息或提醒后清零
+    continuous: u64,
+    phase: Phase,
+    /// 番茄钟当前阶段剩余秒数
+    phase_left: u64,
+}
+
+impl Default for TimerState {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            unsaved: 0,
+            session: 0,
+            continuous: 0,
+            phase: Phase::Work,
+            phase_left: u64::from(AppSettings::get().reading_timer.work_minutes) * 60,
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<TimerState> = RefCell::new(TimerState::default());
+    static TICKER: slint::Timer = slint::Timer::default();
+}
+
+/// 阅读计时：按文档累计阅读时长，可选番茄钟浮层，休息时暂停计时，连续阅读过久时提醒
+pub struct TimerController;
+
+impl TimerController {
+    pub fn setup_timer_callbacks(window: &AppWindow) {
+        window.set_pomodoro_enabled(AppSettings::get().reading_timer.pomodoro);
+
+        window.on_skip_break(|| {
+            info!("[Timer] 跳过休息");
+            STATE.with(|state| Self::start_work(&mut state.borrow_mut()));
+        });
+
+        let weak_window = window.as_weak();
+        TICKER.with(|ticker| {
+            ticker.start(slint::TimerMode::Repeated, Duration::from_secs(1), move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                Self::tick(&window);
+            });
+        });
+    }
+
+    /// 菜单开关番茄钟，重新从专注阶段开始
+    pub fn set_pomodoro(window: &AppWindow, enabled: bool) {
+        AppSettings::update(|settings| settings.reading_timer.pomodoro = enabled);
+        window.set_pomodoro_enabled(enabled);
+        STATE.with(|state| Self::start_work(&mut state.borrow_mut()));
+        Self::tick_ui(window);
+    }
+
+    /// 把未保存的阅读时长写入数据库，退出时调用
+    pub fn flush() {
+        STATE.with(|state| Self::flush_state(&mut state.borrow_mut()));
+    }
+
+    fn tick(window: &AppWindow) {
+        let path = if window.get_document_opened() { window.get_file_path().to_string() } else { String::new() };
+        // 最小化或离开时都不计时
+        let away = window.window().is_minimized() || IdleController::is_idle();
+        let settings = AppSettings::get().reading_timer;
+        let mut nudge = None;
+
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            if state.path != path {
+                Self::flush_state(&mut state);
+                state.path = path;
+                state.session = 0;
+            }
+            if state.path.is_empty() || away {
+                return;
+            }
+
+            // 休息时不计阅读时长
+            if state.phase == Phase::Break {
+                state.phase_left = state.phase_left.saturating_sub(1);
+                if state.phase_left == 0 {
+                    info!("[Timer] 休息结束");
+                    Self::start_work(&mut state);
+                }
+                return;
+            }
+
+            state.unsaved += 1;
+            state.session += 1;
+            state.continuous += 1;
+            if state.unsaved >= FLUSH_SECONDS {
+                Self::flush_state(&mut state);
+            }
+
+            if settings.pomodoro {
+                state.phase_left = state.phase_left.saturating_sub(1);
+                if state.phase_left == 0 {
+                    info!("[Timer] 专注结束，开始休息 {} 分钟", settings.break_minutes);
+                    state.phase = Phase::Break;
+                    state.phase_left = u64::from(settings.break_minutes.max(1)) * 60;
+                    state.continuous = 0;
+                    Self::flush_state(&mut state);
+                }
+            } else if settings.nudge_minutes > 0 && state.continuous >= u64::from(settings.nudge_minutes) * 60 {
+                state.continuous = 0;
+                nudge = Some(settings.nudge_minutes);
+            }
+        });
+
+        if let Some(minutes) = nudge {
+            window.set_reading_nudge_text(format!("已经连续阅读 {} 分钟了，休息一下眼睛吧", minutes).into());
+            window.set_show_reading_nudge(true);
+        }
+        Self::tick_ui(window);
+    }
+
+    fn tick_ui(window: &AppWindow) {
+        let pomodoro = window.get_pomodoro_enabled();
+        let info = STATE.with(|state| {
+            let state = state.borrow();
+            crate::ReadingTimerInfo {
+                visible: pomodoro && !state.path.is_empty(),
+                on_break: pomodoro && state.phase == Phase::Break,
+                remaining: Self::format_clock(state.phase_left).into(),
+                session: format!("本次 {} 分钟", state.session / 60).into(),
+            }
+        });
+        window.set_reading_timer(info);
+    }
+
+    fn start_work(state: &mut TimerState) {
+        state.phase = Phase::Work;
+        state.phase_left = u64::from(AppSettings::get().reading_timer.work_minutes.max(1)) * 60;
+        state.continuous = 0;
+    }
+
+    fn flush_state(state: &mut TimerState) {
+        if state.path.is_empty() || state.unsaved == 0 {
+            return;
+        }
+        if let Err(e) = RecentDao::add_read_seconds_sync(&state.path, state.unsaved) {
+            error!("[Timer] 保存阅读时长失败: {}", e);
+        }
+        state.unsaved = 0;
+    }
+
+    fn format_clock(seconds: u64) -> String {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,79 @@
+use slint::{ComponentHandle, ModelRc, VecModel};
+use std::rc::Rc;
+use log::debug;
+
+use crate::controllers::OrderedList;
+use crate::settings::{AppSettings, OrderedItem};
+use crate::AppWindow;
+
+/// 可放到工具栏的动作（菜单动作 id、按钮文字、默认是否显示），顺序即默认顺序
+const TOOLBAR_ACTIONS: OrderedList = OrderedList::new(&[
+    ("properties", "Info", true),
+    ("speak-page", "Speak Page", true),
+    ("stop-speaking", "Stop", false),
+    ("first-page", "First", false),
+    ("prev-page", "Previous", true),
+    ("next-page", "Next", true),
+    ("last-page", "Last", false),
+    ("zoom-out", "Zoom -", true),
+    ("zoom-in", "Zoom +", true),
+    ("zoom-reset", "100%", false),
+    ("toggle-crop", "Crop", false),
+    ("toggle-dual-page", "Dual Page", false),
+    ("toggle-paged-mode", "Paged", false),
+    ("toggle-rtl", "RTL", false),
+    ("toggle-night-mode", "Night", false),
+    ("toggle-outline", "Outline", false),
+]);
+
+/// 阅读工具栏：按设置生成按钮模型，处理自定义对话框的修改
+pub struct ToolbarController;
+
+impl ToolbarController {
+    /// 设置中的配置，已不存在的动作去掉，新增的动作隐藏在末尾
+    pub fn items() -> Vec<OrderedItem> {
+        TOOLBAR_ACTIONS.merge(AppSettings::get().toolbar.items)
+    }
+
+    fn save_items(items: Vec<OrderedItem>) {
+        AppSettings::update(|settings| settings.toolbar.items = items);
+    }
+
+    /// 刷新工具栏按钮和自定义对话框的列表
+    pub fn refresh_toolbar_ui(window: &AppWindow) {
+        let all = TOOLBAR_ACTIONS.to_ui(&Self::items());
+        let visible: Vec<crate::ToggleItem> = all.iter().filter(|a| a.visible).cloned().collect();
+        window.set_toolbar_actions(ModelRc::from(Rc::new(VecModel::from(visible))));
+        window.set_toolbar_config(ModelRc::from(Rc::new(VecModel::from(all))));
+    }
+
+    pub fn setup_toolbar_callbacks(window: &AppWindow) {
+        let weak_window = window.as_weak();
+        window.on_toolbar_item_toggled(move |id, visible| {
+            debug!("[Toolbar] toggle {} -> {}", id, visible);
+            Self::save_items(TOOLBAR_ACTIONS.toggled(AppSettings::get().toolbar.items, &id, visible));
+            if let Some(window) = weak_window.upgrade() {
+                Self::refresh_toolbar_ui(&window);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_toolbar_item_moved(move |id, delta| {
+            debug!("[Toolbar] move {} by {}", id, delta);
+            Self::save_items(TOOLBAR_ACTIONS.moved(AppSettings::get().toolbar.items, &id, delta));
+            if let Some(window) = weak_window.upgrade() {
+                Self::refresh_toolbar_ui(&window);
+            }
+        });
+
+        let weak_window = window.as_weak();
+        window.on_toolbar_reset(move || {
+            Self::save_items(Vec::new());
+            if let Some(window) = weak_window.upgrade() {
+                Self::refresh_toolbar_ui(&window);
+            }
+        });
+
+        Self::refresh_toolbar_ui(window);
+    }
+}
@@ -0,0 +1,35 @@
+use log::info;
+
+use crate::controllers::SimpleModeController;
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+const MIN_SCALE: f32 = 0.75;
+const MAX_SCALE: f32 = 3.0;
+/// 每次快捷键调整的步长
+const SCALE_STEP: f32 = 0.1;
+
+/// 界面缩放控制器：缩放整个界面的字号和栏高，不影响文档缩放
+pub struct UiScaleController;
+
+impl UiScaleController {
+    /// 把设置中的缩放和最小字号推送到界面
+    pub fn apply(window: &AppWindow) {
+        let accessibility = AppSettings::get().accessibility;
+        window.set_ui_scale(SimpleModeController::ui_scale(accessibility.ui_scale).clamp(MIN_SCALE, MAX_SCALE));
+        window.set_min_font_size(accessibility.min_font_size.max(0.0));
+    }
+
+    pub fn step(window: &AppWindow, steps: i32) {
+        let scale = AppSettings::get().accessibility.ui_scale + steps as f32 * SCALE_STEP;
+        Self::set_scale(window, scale);
+    }
+
+    pub fn set_scale(window: &AppWindow, scale: f32) {
+        // 按步长取整，避免多次累加出现 1.2000001
+        let scale = ((scale / SCALE_STEP).round() * SCALE_STEP).clamp(MIN_SCALE, MAX_SCALE);
+        info!("[UiScale] 界面缩放: {:.2}", scale);
+        AppSettings::update(|settings| settings.accessibility.ui_scale = scale);
+        Self::apply(window);
+    }
+}
@@ -0,0 +1,46 @@
+use log::{error, info};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::controllers::{ClipboardController, DocumentController};
+use crate::AppWindow;
+
+/// 读屏辅助：按需取出视口内可见部分的文字，复制到剪贴板并作为文档视图的无障碍标签
+/// 在文档视图完整支持读屏之前，读屏软件至少可以读到当前屏幕上的内容
+pub struct ViewportTextController;
+
+impl ViewportTextController {
+    pub fn copy_visible_text(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let text = Self::visible_text(document_controller);
+        window.set_viewport_text(text.as_str().into());
+        if text.trim().is_empty() {
+            window.set_error_message("当前屏幕上没有可识别的文字".into());
+            window.set_show_error_dialog(true);
+            return;
+        }
+        info!("[ViewportText] 复制 {} 个字符", text.chars().count());
+        if let Err(e) = ClipboardController::copy_text(&text) {
+            error!("[ViewportText] 复制失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+        }
+    }
+
+    /// 每个可见页面露出部分的文字，页之间空一行
+    fn visible_text(document_controller: &Rc<RefCell<DocumentController>>) -> String {
+        let page_view_state = document_controller.borrow().page_view_state();
+        let state = page_view_state.borrow();
+        state.viewport_regions()
+            .into_iter()
+            .filter_map(|(page, region)| match state.decode_service.get_region_text(page, region) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    error!("[ViewportText] 读取第 {} 页文字失败: {}", page + 1, e);
+                    None
+                }
+            })
+            .filter(|text| !text.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
@@ -0,0 +1,153 @@
+use log::{error, info};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::controllers::SimpleModeController;
+use crate::dao::VocabDao;
+use crate::entity::VocabEntry;
+use crate::error::Result;
+use crate::AppWindow;
+
+/// 正在由 AI 助手解释的单词，解释完成后可以连同释义加入生词本
+struct Lookup {
+    word: String,
+    book_path: String,
+    page: i32,
+    context: String,
+}
+
+thread_local! {
+    static LOOKUP: RefCell<Option<Lookup>> = const { RefCell::new(None) };
+}
+
+/// 生词本：保存查过的单词和所在句子，导出为 Anki 可以导入的 TSV
+pub struct VocabController;
+
+impl VocabController {
+    pub fn setup_vocab_callbacks(window: &AppWindow) {
+        let weak_window = window.as_weak();
+        window.on_assistant_save_vocab(move || {
+            let Some(window) = weak_window.upgrade() else { return };
+            let Some(lookup) = LOOKUP.with(|lookup| lookup.borrow_mut().take()) else { return };
+            window.set_assistant_vocab_word("".into());
+            let definition = window.get_assistant_text().trim().to_string();
+            Self::save(&window, lookup.word, definition, lookup.book_path, lookup.page, lookup.context);
+        });
+
+        let weak_window = window.as_weak();
+        window.on_export_vocab(move || {
+            if let Some(window) = weak_window.upgrade() {
+                window.set_show_vocab_toast(false);
+                Self::export(&window);
+            }
+        });
+    }
+
+    /// 直接加入生词本，还没有释义
+    pub fn save_word(window: &AppWindow, word: &str, paragraph: &str, page_index: usize) {
+        let book_path = window.get_file_path().to_string();
+        Self::save(window, word.to_string(), String::new(), book_path, page_index as i32, sentence_around(paragraph, word));
+    }
+
+    /// 记下正在解释的单词，助手面板上出现“加入生词本”
+    pub fn set_lookup(window: &AppWindow, word: &str, paragraph: &str, page_index: usize) {
+        let lookup = Lookup {
+            word: word.to_string(),
+            book_path: window.get_file_path().to_string(),
+            page: page_index as i32,
+            context: sentence_around(paragraph, word),
+        };
+        window.set_assistant_vocab_word(lookup.word.as_str().into());
+        LOOKUP.with(|current| *current.borrow_mut() = Some(lookup));
+    }
+
+    pub fn clear_lookup(window: &AppWindow) {
+        LOOKUP.with(|lookup| lookup.borrow_mut().take());
+        window.set_assistant_vocab_word("".into());
+    }
+
+    fn save(window: &AppWindow, word: String, definition: String, book_path: String, page: i32, context: String) {
+        info!("[Vocab] 加入生词本: {} (第 {} 页)", word, page + 1);
+        match VocabDao::save_sync(VocabEntry::new(word, definition, book_path, page, context)) {
+            Ok(entry) => {
+                window.set_vocab_toast_text(format!("已加入生词本：{}", entry.word).into());
+                window.set_show_vocab_toast(true);
+            }
+            Err(e) => {
+                error!("[Vocab] 保存失败: {}", e);
+                window.set_error_message(e.user_message().into());
+                window.set_show_error_dialog(true);
+            }
+        }
+    }
+
+    /// 导出全部生词为 TSV：单词、释义、例句、出处
+    pub fn export(window: &AppWindow) {
+        if SimpleModeController::is_enabled() {
+            SimpleModeController::deny(window, "export-vocab");
+            return;
+        }
+        if let Err(e) = Self::write_tsv(window) {
+            error!("[Vocab] 导出失败: {}", e);
+            window.set_error_message(e.user_message().into());
+            window.set_show_error_dialog(true);
+        }
+    }
+
+    fn write_tsv(window: &AppWindow) -> Result<()> {
+        let entries = VocabDao::find_all_sync()?;
+        if entries.is_empty() {
+            window.set_error_message("生词本是空的".into());
+            window.set_show_error_dialog(true);
+            return Ok(());
+        }
+        let Some(path) = Self::pick_export_target() else { return Ok(()) };
+        fs::write(&path, to_anki_tsv(&entries))?;
+        info!("[Vocab] 导出 {} 个生词: {:?}", entries.len(), path);
+        Ok(())
+    }
+
+    fn pick_export_target() -> Option<PathBuf> {
+        rfd::FileDialog::new()
+            .set_title("Export Vocabulary")
+            .add_filter("Anki TSV", &["tsv", "txt"])
+            .set_file_name("vocabulary.tsv")
+            .save_file()
+    }
+}
+
+/// 段落中包含单词的那一句，找不到时返回整段
+fn sentence_around(paragraph: &str, word: &str) -> String {
+    let text = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    let Some(position) = text.find(word) else { return text };
+    let is_end = |c: char| matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | ';' | '；');
+    let start = text[..position].rfind(is_end).map_or(0, |i| i + text[i..].chars().next().map_or(1, char::len_utf8));
+    let end = text[position..].find(is_end).map_or(text.len(), |i| {
+        let i = position + i;
+        i + text[i..].chars().next().map_or(1, char::len_utf8)
+    });
+    text[start..end].trim().to_string()
+}
+
+/// Anki 的纯文本导入格式，字段中的制表符和换行换成空格
+fn to_anki_tsv(entries: &[VocabEntry]) -> String {
+    let clean = |text: &str| text.split(['\t', '\n', '\r']).map(str::trim).filter(|s| !s.is_empty()).collect::<Vec<_>>().join(" ");
+    let mut out = String::from("#separator:tab\n#html:false\n#columns:Word\tDefinition\tContext\tSource\n");
+    for entry in entries {
+        let book = Path::new(&entry.book_path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let source = format!("{} p.{}", book, entry.page + 1);
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            clean(&entry.word),
+            clean(&entry.definition),
+            clean(&entry.context),
+            clean(&source)
+        ));
+    }
+    out
+}
@@ -0,0 +1,32 @@
+use log::info;
+use slint::ComponentHandle;
+
+use crate::settings::AppSettings;
+use crate::AppWindow;
+
+/// 导出水印：导出页面图片和 PDF 副本时加的文字，保存在设置中
+pub struct WatermarkController;
+
+impl WatermarkController {
+    pub fn setup_watermark_callbacks(window: &AppWindow) {
+        let weak_window = window.as_weak();
+        window.on_watermark_saved(move |text, opacity| {
+            let text = text.trim().to_string();
+            info!("[Watermark] 导出水印: {:?}, 不透明度 {:.2}", text, opacity);
+            AppSettings::update(|settings| {
+                settings.watermark.text = text;
+                settings.watermark.opacity = opacity.clamp(0.05, 1.0);
+            });
+            if let Some(window) = weak_window.upgrade() {
+                window.set_show_watermark_dialog(false);
+            }
+        });
+    }
+
+    pub fn show(window: &AppWindow) {
+        let settings = AppSettings::get().watermark;
+        window.set_watermark_text(settings.text.into());
+        window.set_watermark_opacity(settings.opacity);
+        window.set_show_watermark_dialog(true);
+    }
+}
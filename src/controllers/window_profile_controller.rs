@@ -0,0 +1,135 @@
+use crossbeam_channel::{unbounded, Receiver};
+use log::{debug, info};
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use crate::controllers::{DocumentController, UiScaleController};
+use crate::platform::{display_profile_key, display_signature};
+use crate::settings::{AppSettings, ViewMode, WindowProfile};
+use crate::AppWindow;
+
+/// 检查窗口大小和显示器变化的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// 重新识别显示器组合的间隔
+const DISPLAY_INTERVAL: Duration = Duration::from_secs(30);
+
+thread_local! {
+    static TICKER: slint::Timer = slint::Timer::default();
+    /// 最近一次识别到的显示器组合
+    static SIGNATURE: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// 当前显示器组合的键和已保存的配置
+    static CURRENT: RefCell<Option<(String, WindowProfile)>> = const { RefCell::new(None) };
+}
+
+/// 按显示器组合记住窗口大小、视图模式和界面缩放：笔记本接上外接显示器或拔掉时，
+/// 自动换成上次在这种组合下用的配置
+pub struct WindowProfileController;
+
+impl WindowProfileController {
+    pub fn setup_window_profile_callbacks(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let signatures = Self::watch_displays();
+        let weak_window = window.as_weak();
+        let document_controller = Rc::clone(document_controller);
+        TICKER.with(|ticker| {
+            ticker.start(slint::TimerMode::Repeated, CHECK_INTERVAL, move || {
+                let Some(window) = weak_window.upgrade() else { return };
+                if let Some(signature) = signatures.try_iter().last() {
+                    SIGNATURE.with(|current| current.replace(signature));
+                }
+                Self::tick(&window, &document_controller);
+            });
+        });
+    }
+
+    /// 后台定时识别显示器组合，macOS 上要调用外部命令，放在 UI 线程会卡顿
+    fn watch_displays() -> Receiver<Option<String>> {
+        let (signature_tx, signature_rx) = unbounded();
+        thread::spawn(move || {
+            while signature_tx.send(display_signature()).is_ok() {
+                thread::sleep(DISPLAY_INTERVAL);
+            }
+        });
+        signature_rx
+    }
+
+    fn tick(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>) {
+        let Some(signature) = SIGNATURE.with(|signature| signature.borrow().clone()) else { return };
+        // 窗口拖到另一块屏幕时系统缩放可能变化，也算换了组合
+        let key = display_profile_key(&signature, window.window().scale_factor());
+        let current = CURRENT.with(|current| current.borrow().clone());
+        match current {
+            Some((current_key, saved)) if current_key == key => {
+                let profile = Self::snapshot(window, &saved);
+                if profile != saved {
+                    debug!("[WindowProfile] 保存 {}: {:?}", key, profile);
+                    AppSettings::update(|settings| {
+                        settings.window_profiles.insert(key.clone(), profile.clone());
+                    });
+                    CURRENT.with(|current| current.replace(Some((key, profile))));
+                }
+            }
+            _ => Self::switch_to(window, document_controller, key),
+        }
+    }
+
+    /// 换到新的显示器组合：有保存的配置就恢复，没有就以当前状态建一份
+    fn switch_to(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, key: String) {
+        let saved = AppSettings::get().window_profiles.get(&key).cloned();
+        let profile = match saved {
+            Some(profile) => {
+                info!("[WindowProfile] 显示器组合 {}，恢复配置: {:?}", key, profile);
+                Self::apply(window, document_controller, &profile);
+                profile
+            }
+            None => {
+                let profile = Self::snapshot(window, &WindowProfile::default());
+                info!("[WindowProfile] 新的显示器组合 {}: {:?}", key, profile);
+                AppSettings::update(|settings| {
+                    settings.window_profiles.insert(key.clone(), profile.clone());
+                });
+                profile
+            }
+        };
+        CURRENT.with(|current| current.replace(Some((key, profile))));
+    }
+
+    fn apply(window: &AppWindow, document_controller: &Rc<RefCell<DocumentController>>, profile: &WindowProfile) {
+        let slint_window = window.window();
+        if profile.width > 0.0 && profile.height > 0.0 && !slint_window.is_maximized() && !slint_window.is_fullscreen() {
+            slint_window.set_size(slint::LogicalSize::new(profile.width, profile.height));
+        }
+        if profile.ui_scale > 0.0 {
+            UiScaleController::set_scale(window, profile.ui_scale);
+        }
+        AppSettings::update(|settings| settings.default_view_mode = profile.view_mode);
+        document_controller.borrow().set_dual_page(window, profile.view_mode == ViewMode::Dual);
+        document_controller.borrow().set_paged(window, profile.view_mode == ViewMode::Paged);
+    }
+
+    /// 当前的窗口配置；最大化或全屏时保留原来记住的窗口大小
+    fn snapshot(window: &AppWindow, saved: &WindowProfile) -> WindowProfile {
+        let slint_window = window.window();
+        let (width, height) = if slint_window.is_maximized() || slint_window.is_fullscreen() {
+            (saved.width, saved.height)
+        } else {
+            let size = slint_window.size().to_logical(slint_window.scale_factor());
+            (size.width.round(), size.height.round())
+        };
+        let view_mode = if window.get_dual_page() {
+            ViewMode::Dual
+        } else if window.get_paged_mode() {
+            ViewMode::Paged
+        } else {
+            ViewMode::Continuous
+        };
+        WindowProfile {
+            width,
+            height,
+            ui_scale: AppSettings::get().accessibility.ui_scale,
+            view_mode,
+        }
+    }
+}
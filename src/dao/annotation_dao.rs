@@ -0,0 +1,172 @@
+use sea_orm::*;
+
+use crate::entity::annotation::{ActiveModel, Column, Entity, Model as Annotation};
+
+pub struct AnnotationDao;
+
+impl AnnotationDao {
+    pub async fn add(annotation: ActiveModel) -> Result<Annotation, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        annotation.insert(&*db).await
+    }
+
+    /// 一本书的全部高亮，按页排序
+    pub async fn find_by_book(book_path: &str) -> Result<Vec<Annotation>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::find()
+            .filter(Column::BookPath.eq(book_path))
+            .order_by_asc(Column::Page)
+            .order_by_asc(Column::Id)
+            .all(&*db)
+            .await
+    }
+
+    /// 修改颜色或笔记，传 None 的字段保持不变
+    pub async fn update(id: i32, color: Option<String>, note: Option<String>) -> Result<Annotation, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let existing = Entity::find_by_id(id)
+            .one(&*db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("annotation {}", id)))?;
+        let mut update: ActiveModel = existing.into();
+        if let Some(color) = color {
+            update.color = Set(color);
+        }
+        if let Some(note) = note {
+            update.note = Set(note);
+        }
+        update.update_at = Set(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64);
+        update.update(&*db).await
+    }
+
+    /// 把一本书的高亮和笔记复制到另一个路径（同一本书的副本），同页同范围已有的跳过，返回复制的条数
+    pub async fn copy_to_book(from: &str, to: &str) -> Result<usize, DbErr> {
+        let existing = Self::find_by_book(to).await?;
+        let mut copied = 0;
+        for annotation in Self::find_by_book(from).await? {
+            if existing.iter().any(|a| a.page == annotation.page && a.rects == annotation.rects) {
+                continue;
+            }
+            let copy = ActiveModel {
+                id: NotSet,
+                book_path: Set(to.to_string()),
+                page: Set(annotation.page),
+                rects: Set(annotation.rects),
+                color: Set(annotation.color),
+                note: Set(annotation.note),
+                create_at: Set(annotation.create_at),
+                update_at: Set(annotation.update_at),
+            };
+            Self::add(copy).await?;
+            copied += 1;
+        }
+        Ok(copied)
+    }
+
+    pub async fn delete(id: i32) -> Result<(), DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::delete_by_id(id).exec(&*db).await?;
+        Ok(())
+    }
+
+    pub fn add_sync(annotation: ActiveModel) -> crate::error::Result<Annotation> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::add(annotation).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_by_book_sync(book_path: &str) -> crate::error::Result<Vec<Annotation>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_by_book(book_path).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn update_sync(id: i32, color: Option<String>, note: Option<String>) -> crate::error::Result<Annotation> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::update(id, color, note).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn copy_to_book_sync(from: &str, to: &str) -> crate::error::Result<usize> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::copy_to_book(from, to).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn delete_sync(id: i32) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::delete(id).await.map_err(Into::into)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_support::setup_memory_db;
+    use crate::decoder::Rect;
+
+    #[tokio::test]
+    async fn rects_round_trip_and_update_keeps_other_fields() {
+        let _db = setup_memory_db().await;
+
+        let rects = [Rect::new(10.0, 20.0, 200.0, 32.5), Rect::new(10.0, 34.0, 120.0, 46.5)];
+        let saved = AnnotationDao::add(Annotation::new("/books/a.pdf".into(), 4, &rects, Annotation::DEFAULT_COLOR.into(), "".into())).await.unwrap();
+        assert_eq!(saved.rect_list().len(), 2);
+        assert!(saved.contains(100.0, 40.0));
+        assert!(!saved.contains(150.0, 40.0));
+
+        let updated = AnnotationDao::update(saved.id, None, Some("remember this".into())).await.unwrap();
+        assert_eq!(updated.note, "remember this");
+        assert_eq!(updated.color, Annotation::DEFAULT_COLOR);
+        let updated = AnnotationDao::update(saved.id, Some("#a5d6a7".into()), None).await.unwrap();
+        assert_eq!(updated.note, "remember this");
+        assert_eq!(updated.color, "#a5d6a7");
+    }
+
+    #[tokio::test]
+    async fn find_by_book_and_delete() {
+        let _db = setup_memory_db().await;
+
+        let rect = [Rect::new(0.0, 0.0, 10.0, 10.0)];
+        let first = AnnotationDao::add(Annotation::new("/books/a.pdf".into(), 7, &rect, Annotation::DEFAULT_COLOR.into(), "".into())).await.unwrap();
+        AnnotationDao::add(Annotation::new("/books/a.pdf".into(), 2, &rect, Annotation::DEFAULT_COLOR.into(), "".into())).await.unwrap();
+        AnnotationDao::add(Annotation::new("/books/b.pdf".into(), 0, &rect, Annotation::DEFAULT_COLOR.into(), "".into())).await.unwrap();
+
+        let pages: Vec<i32> = AnnotationDao::find_by_book("/books/a.pdf").await.unwrap().iter().map(|a| a.page).collect();
+        assert_eq!(pages, [2, 7]);
+
+        AnnotationDao::delete(first.id).await.unwrap();
+        assert_eq!(AnnotationDao::find_by_book("/books/a.pdf").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn copy_to_book_keeps_notes_and_skips_duplicates() {
+        let _db = setup_memory_db().await;
+
+        let rect = [Rect::new(0.0, 0.0, 10.0, 10.0)];
+        AnnotationDao::add(Annotation::new("/books/a.pdf".into(), 1, &rect, "#a5d6a7".into(), "note".into())).await.unwrap();
+        AnnotationDao::add(Annotation::new("/books/a.pdf".into(), 4, &rect, Annotation::DEFAULT_COLOR.into(), "".into())).await.unwrap();
+
+        assert_eq!(AnnotationDao::copy_to_book("/books/a.pdf", "/copy/a.pdf").await.unwrap(), 2);
+        assert_eq!(AnnotationDao::copy_to_book("/books/a.pdf", "/copy/a.pdf").await.unwrap(), 0);
+        let copied = AnnotationDao::find_by_book("/copy/a.pdf").await.unwrap();
+        assert_eq!(copied.len(), 2);
+        assert_eq!((copied[0].color.as_str(), copied[0].note.as_str()), ("#a5d6a7", "note"));
+        assert_eq!(copied[1].rect_list().len(), 1);
+        assert_eq!(AnnotationDao::find_by_book("/books/a.pdf").await.unwrap().len(), 2);
+    }
+}
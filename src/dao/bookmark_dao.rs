@@ -0,0 +1,124 @@
+use sea_orm::*;
+
+use crate::entity::bookmark::{ActiveModel, Column, Entity, Model as Bookmark};
+
+pub struct BookmarkDao;
+
+impl BookmarkDao {
+    pub async fn add(bookmark: ActiveModel) -> Result<Bookmark, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        bookmark.insert(&*db).await
+    }
+
+    /// 一本书的书签，按在书中的位置排序
+    pub async fn find_by_book(book_path: &str) -> Result<Vec<Bookmark>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::find()
+            .filter(Column::BookPath.eq(book_path))
+            .order_by_asc(Column::Page)
+            .order_by_asc(Column::AnchorRatio)
+            .order_by_asc(Column::Id)
+            .all(&*db)
+            .await
+    }
+
+    /// 把一本书的书签复制到另一个路径（同一本书的副本），同一位置已有的跳过，返回复制的条数
+    pub async fn copy_to_book(from: &str, to: &str) -> Result<usize, DbErr> {
+        let existing = Self::find_by_book(to).await?;
+        let mut copied = 0;
+        for bookmark in Self::find_by_book(from).await? {
+            if existing.iter().any(|b| b.page == bookmark.page && b.anchor_ratio == bookmark.anchor_ratio) {
+                continue;
+            }
+            let mut copy = Bookmark::new(to.to_string(), bookmark.page, bookmark.anchor_ratio, bookmark.label);
+            copy.create_at = Set(bookmark.create_at);
+            Self::add(copy).await?;
+            copied += 1;
+        }
+        Ok(copied)
+    }
+
+    pub async fn delete(id: i32) -> Result<(), DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::delete_by_id(id).exec(&*db).await?;
+        Ok(())
+    }
+
+    pub fn add_sync(bookmark: ActiveModel) -> crate::error::Result<Bookmark> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::add(bookmark).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_by_book_sync(book_path: &str) -> crate::error::Result<Vec<Bookmark>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_by_book(book_path).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn copy_to_book_sync(from: &str, to: &str) -> crate::error::Result<usize> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::copy_to_book(from, to).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn delete_sync(id: i32) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::delete(id).await.map_err(Into::into)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_support::setup_memory_db;
+
+    #[tokio::test]
+    async fn bookmarks_are_per_book_and_ordered_by_position() {
+        let _db = setup_memory_db().await;
+
+        BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 12, 0.5, "Chapter 2".into())).await.unwrap();
+        BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 12, 0.1, "".into())).await.unwrap();
+        BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 3, 0.9, "".into())).await.unwrap();
+        BookmarkDao::add(Bookmark::new("/books/b.pdf".into(), 0, 0.0, "".into())).await.unwrap();
+
+        let bookmarks = BookmarkDao::find_by_book("/books/a.pdf").await.unwrap();
+        let positions: Vec<(i32, f32)> = bookmarks.iter().map(|b| (b.page, b.anchor_ratio)).collect();
+        assert_eq!(positions, [(3, 0.9), (12, 0.1), (12, 0.5)]);
+        assert_eq!(bookmarks[2].label, "Chapter 2");
+    }
+
+    #[tokio::test]
+    async fn copy_to_book_skips_existing_positions() {
+        let _db = setup_memory_db().await;
+
+        BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 3, 0.2, "Intro".into())).await.unwrap();
+        BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 9, 0.0, "".into())).await.unwrap();
+        BookmarkDao::add(Bookmark::new("/copy/a.pdf".into(), 9, 0.0, "mine".into())).await.unwrap();
+
+        assert_eq!(BookmarkDao::copy_to_book("/books/a.pdf", "/copy/a.pdf").await.unwrap(), 1);
+        let copied = BookmarkDao::find_by_book("/copy/a.pdf").await.unwrap();
+        let labels: Vec<(i32, &str)> = copied.iter().map(|b| (b.page, b.label.as_str())).collect();
+        assert_eq!(labels, [(3, "Intro"), (9, "mine")]);
+        // 原来的书签保留
+        assert_eq!(BookmarkDao::find_by_book("/books/a.pdf").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn delete_removes_bookmark() {
+        let _db = setup_memory_db().await;
+
+        let bookmark = BookmarkDao::add(Bookmark::new("/books/a.pdf".into(), 1, 0.0, "".into())).await.unwrap();
+        BookmarkDao::delete(bookmark.id).await.unwrap();
+        assert!(BookmarkDao::find_by_book("/books/a.pdf").await.unwrap().is_empty());
+    }
+}
@@ -5,11 +5,14 @@ use tokio::sync::Mutex;
 use std::path::Path;
 use log::{debug, info};
 
+/// 数据库被占用时的最长等待时间
+const BUSY_TIMEOUT_MS: u32 = 5000;
+
 lazy_static! {
     static ref DATABASE: Mutex<Option<Arc<DatabaseConnection>>> = Mutex::new(None);
 }
 
-/// 确保数据库文件和表存在，如果不存在则创建
+/// 确保数据库文件和表存在，如果不存在则创建；已有的数据库升级到当前的表结构
 pub async fn ensure_database_ready(db_path: &Path) -> Result<(), DbErr> {
     info!("ensure_database_ready:{:?}", db_path);
     if !db_path.exists() {
@@ -17,23 +20,26 @@ pub async fn ensure_database_ready(db_path: &Path) -> Result<(), DbErr> {
         if let Err(e) = std::fs::File::create(db_path) {
             return Err(sea_orm::DbErr::Custom(format!("Failed to create database file: {}", e)));
         }
-
-        // 连接数据库
-        let db_path_str = db_path.to_string_lossy();
-        let database_url = format!("sqlite:///{}", db_path_str);
-
-        let db = Database::connect(&database_url).await?;
-        *DATABASE.lock().await = Some(Arc::new(db));
-
-        // 创建表
-        create_tables().await?;
     }
 
+    // 连接数据库
+    let db_path_str = db_path.to_string_lossy();
+    let database_url = format!("sqlite:///{}", db_path_str);
+    init_db(&database_url).await
+}
+
+/// WAL 模式允许读写并发，busy_timeout 让多个连接或进程争用时等待而不是直接报错
+async fn configure_connection(db: &DatabaseConnection) -> Result<(), DbErr> {
+    db.execute_unprepared("PRAGMA journal_mode=WAL").await?;
+    db.execute_unprepared(&format!("PRAGMA busy_timeout={}", BUSY_TIMEOUT_MS)).await?;
     Ok(())
 }
 
+/// 连接数据库，每次连接都执行迁移，旧版本的数据库会补上新加的表和列
 pub async fn init_db(database_url: &str) -> Result<(), DbErr> {
     let db = Database::connect(database_url).await?;
+    configure_connection(&db).await?;
+    run_migrations(&db).await?;
     *DATABASE.lock().await = Some(Arc::new(db));
     Ok(())
 }
@@ -46,9 +52,36 @@ pub async fn get_connection() -> Result<Arc<DatabaseConnection>, DbErr> {
     }
 }
 
+/// 关闭全局连接，退出前把 WAL 合并回主文件。
+/// 还有其他地方持有连接时返回错误，调用方不能再假定数据库文件已经完整
+pub async fn close_db() -> Result<(), DbErr> {
+    let Some(db) = DATABASE.lock().await.take() else { return Ok(()) };
+    db.execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)").await?;
+    match Arc::try_unwrap(db) {
+        Ok(db) => db.close().await,
+        Err(db) => Err(DbErr::Custom(format!("database connection still held by {} other owner(s)", Arc::strong_count(&db) - 1))),
+    }
+}
+
+/// 单独打开数据库文件，把上次异常退出留下的 WAL 合并回主文件后关闭
+pub async fn checkpoint_database(db_path: &Path) -> Result<(), DbErr> {
+    let db = Database::connect(format!("sqlite:///{}", db_path.to_string_lossy())).await?;
+    db.execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)").await?;
+    db.close().await
+}
+
+/// 替换全局连接，测试中用来注入内存数据库
+pub(crate) async fn set_connection(db: DatabaseConnection) {
+    *DATABASE.lock().await = Some(Arc::new(db));
+}
+
 pub async fn create_tables() -> Result<(), DbErr> {
     let db = get_connection().await?;
+    run_migrations(&db).await
+}
 
+/// 建表/升级，可重复执行
+pub async fn run_migrations(db: &DatabaseConnection) -> Result<(), DbErr> {
     // 检查表是否存在，如果不存在则创建
     let check_stmt = Statement::from_string(
         db.get_database_backend(),
@@ -83,10 +116,119 @@ pub async fn create_tables() -> Result<(), DbErr> {
                 read_times INTEGER DEFAULT 0,
                 progress INTEGER DEFAULT 0,
                 favorited INTEGER DEFAULT 0,
-                in_recent INTEGER DEFAULT 0
+                in_recent INTEGER DEFAULT 0,
+                content_hash TEXT DEFAULT '',
+                deleted_at INTEGER DEFAULT 0,
+                series TEXT DEFAULT '',
+                series_index REAL DEFAULT 0,
+                read_seconds INTEGER DEFAULT 0,
+                anchor_ratio REAL DEFAULT -1,
+                sha256 TEXT DEFAULT '',
+                finished_at INTEGER DEFAULT 0,
+                crop_margins TEXT DEFAULT '',
+                author TEXT DEFAULT '',
+                subject TEXT DEFAULT ''
             )
         "#).await?;
     }
 
+    // 旧版本的表缺少后来加入的列
+    let columns: Vec<String> = db.query_all(Statement::from_string(
+        db.get_database_backend(),
+        "PRAGMA table_info(recents)".to_string(),
+    )).await?
+        .iter()
+        .filter_map(|row| row.try_get("", "name").ok())
+        .collect();
+    for (name, definition) in [("content_hash", "TEXT DEFAULT ''"), ("deleted_at", "INTEGER DEFAULT 0"),
+        ("series", "TEXT DEFAULT ''"), ("series_index", "REAL DEFAULT 0"), ("read_seconds", "INTEGER DEFAULT 0"),
+        ("anchor_ratio", "REAL DEFAULT -1"), ("sha256", "TEXT DEFAULT ''"),
+        ("finished_at", "INTEGER DEFAULT 0"), ("crop_margins", "TEXT DEFAULT ''"),
+        ("author", "TEXT DEFAULT ''"), ("subject", "TEXT DEFAULT ''")] {
+        if !columns.iter().any(|c| c == name) {
+            debug!("run_migrations.添加 {} 列", name);
+            db.execute_unprepared(&format!("ALTER TABLE recents ADD COLUMN {} {}", name, definition)).await?;
+        }
+    }
+    // 版本 1：in_recent 表示是否在“打开最近”菜单中，此前一直没有使用，已有的记录都放进菜单
+    let version: i32 = db.query_one(Statement::from_string(
+        db.get_database_backend(),
+        "PRAGMA user_version".to_string(),
+    )).await?
+        .and_then(|row| row.try_get("", "user_version").ok())
+        .unwrap_or(0);
+    if version < 1 {
+        debug!("run_migrations.升级到版本 1");
+        db.execute_unprepared("UPDATE recents SET in_recent = 1").await?;
+        db.execute_unprepared("PRAGMA user_version = 1").await?;
+    }
+    // 版本 2：内容hash改为采样的 SHA-256，旧的 DefaultHasher 值（16 个字符）作废，下次打开时重新计算
+    if version < 2 {
+        debug!("run_migrations.升级到版本 2");
+        db.execute_unprepared("UPDATE recents SET content_hash = '' WHERE length(content_hash) = 16").await?;
+        db.execute_unprepared("PRAGMA user_version = 2").await?;
+    }
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_recents_content_hash ON recents(content_hash)").await?;
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_recents_sha256 ON recents(sha256)").await?;
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_recents_series ON recents(series)").await?;
+
+    // 生词本
+    db.execute_unprepared(r#"
+        CREATE TABLE IF NOT EXISTS vocab (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            word TEXT NOT NULL,
+            definition TEXT DEFAULT '',
+            book_path TEXT NOT NULL,
+            page INTEGER DEFAULT 0,
+            context TEXT DEFAULT '',
+            create_at INTEGER NOT NULL
+        )
+    "#).await?;
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_vocab_word_book ON vocab(word, book_path)").await?;
+
+    // 书签
+    db.execute_unprepared(r#"
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            book_path TEXT NOT NULL,
+            page INTEGER DEFAULT 0,
+            anchor_ratio REAL DEFAULT 0,
+            label TEXT DEFAULT '',
+            create_at INTEGER NOT NULL
+        )
+    "#).await?;
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_bookmarks_book ON bookmarks(book_path)").await?;
+
+    // 高亮和笔记
+    db.execute_unprepared(r#"
+        CREATE TABLE IF NOT EXISTS annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            book_path TEXT NOT NULL,
+            page INTEGER DEFAULT 0,
+            rects TEXT NOT NULL DEFAULT '[]',
+            color TEXT DEFAULT '#ffeb3b',
+            note TEXT DEFAULT '',
+            create_at INTEGER NOT NULL,
+            update_at INTEGER NOT NULL
+        )
+    "#).await?;
+    db.execute_unprepared("CREATE INDEX IF NOT EXISTS idx_annotations_book ON annotations(book_path)").await?;
+
+    // 在线查到或手动修正的书籍信息
+    db.execute_unprepared(r#"
+        CREATE TABLE IF NOT EXISTS book_metadata (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            book_path TEXT NOT NULL UNIQUE,
+            title TEXT DEFAULT '',
+            author TEXT DEFAULT '',
+            description TEXT DEFAULT '',
+            isbn TEXT DEFAULT '',
+            source_key TEXT DEFAULT '',
+            cover_path TEXT DEFAULT '',
+            edited INTEGER DEFAULT 0,
+            update_at INTEGER NOT NULL
+        )
+    "#).await?;
+
     Ok(())
 }
@@ -0,0 +1,72 @@
+use sea_orm::*;
+
+use crate::entity::book_metadata::{ActiveModel, Column, Entity, Model as BookMetadata};
+
+pub struct MetadataDao;
+
+impl MetadataDao {
+    pub async fn find_by_path(book_path: &str) -> Result<Option<BookMetadata>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::find()
+            .filter(Column::BookPath.eq(book_path))
+            .one(&*db)
+            .await
+    }
+
+    /// 保存一本书的信息，已有记录时整条替换
+    pub async fn save(metadata: ActiveModel) -> Result<BookMetadata, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let ActiveValue::Set(book_path) = &metadata.book_path else {
+            return Err(DbErr::Custom("book metadata needs book_path".to_string()));
+        };
+        let Some(existing) = Self::find_by_path(book_path).await? else {
+            return metadata.insert(&*db).await;
+        };
+
+        let mut update = metadata;
+        update.id = Set(existing.id);
+        update.update(&*db).await
+    }
+
+    pub fn find_by_path_sync(book_path: &str) -> crate::error::Result<Option<BookMetadata>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_by_path(book_path).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn save_sync(metadata: ActiveModel) -> crate::error::Result<BookMetadata> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::save(metadata).await.map_err(Into::into)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_support::setup_memory_db;
+
+    #[tokio::test]
+    async fn save_replaces_same_book() {
+        let _db = setup_memory_db().await;
+
+        let mut looked_up = BookMetadata::new("/books/a.pdf".into(), "Dune".into(), "Frank Herbert".into(), "Desert planet.".into());
+        looked_up.source_key = Set("/works/OL893415W".into());
+        let first = MetadataDao::save(looked_up).await.unwrap();
+
+        let mut edited = BookMetadata::new("/books/a.pdf".into(), "沙丘".into(), "Frank Herbert".into(), "".into());
+        edited.edited = Set(1);
+        let second = MetadataDao::save(edited).await.unwrap();
+        assert_eq!(first.id, second.id);
+
+        let found = MetadataDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(found.title, "沙丘");
+        assert!(found.is_edited());
+        assert!(found.source_key.is_empty());
+        assert!(MetadataDao::find_by_path("/books/b.pdf").await.unwrap().is_none());
+    }
+}
@@ -1,5 +1,16 @@
+pub mod annotation_dao;
+pub mod bookmark_dao;
 pub mod db_utils;
+pub mod metadata_dao;
 pub mod recent_dao;
+pub mod vocab_dao;
 
-pub use db_utils::{create_tables, ensure_database_ready, get_connection, init_db};
-pub use recent_dao::RecentDao;
\ No newline at end of file
+#[cfg(test)]
+pub(crate) mod test_support;
+
+pub use annotation_dao::AnnotationDao;
+pub use bookmark_dao::BookmarkDao;
+pub use db_utils::{checkpoint_database, close_db, create_tables, ensure_database_ready, get_connection, init_db, run_migrations};
+pub use metadata_dao::MetadataDao;
+pub use recent_dao::RecentDao;
+pub use vocab_dao::VocabDao;
\ No newline at end of file
@@ -35,12 +35,52 @@ impl RecentDao {
     pub async fn find_all_ordered_by_update_at_desc() -> Result<Vec<Recent>, DbErr> {
         let db = crate::dao::get_connection().await?;
         let results = Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
             .order_by_desc(crate::entity::recent::Column::UpdateAt)
             .all(&*db)
             .await?;
         Ok(results)
     }
 
+    /// 归入“已读完”的记录，最近读完的在前
+    pub async fn find_archived() -> Result<Vec<Recent>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let results = Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .filter(crate::entity::recent::Column::FinishedAt.gt(0))
+            .order_by_desc(crate::entity::recent::Column::FinishedAt)
+            .all(&*db)
+            .await?;
+        Ok(results)
+    }
+
+    /// 随机一本没读过的书：加入书架后从没打开过，也没有归档
+    pub async fn find_random_unread() -> Result<Option<Recent>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .filter(crate::entity::recent::Column::FinishedAt.eq(0))
+            .filter(crate::entity::recent::Column::ReadTimes.eq(0))
+            .order_by(Expr::cust("RANDOM()"), Order::Asc)
+            .one(&*db)
+            .await?;
+        Ok(result)
+    }
+
+    /// 搁置最久的在读的书：读过、没读完，最后一次阅读最早
+    pub async fn find_oldest_in_progress() -> Result<Option<Recent>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .filter(crate::entity::recent::Column::FinishedAt.eq(0))
+            .filter(crate::entity::recent::Column::ReadTimes.gt(0))
+            .filter(crate::entity::recent::Column::Progress.lt(100))
+            .order_by_asc(crate::entity::recent::Column::UpdateAt)
+            .one(&*db)
+            .await?;
+        Ok(result)
+    }
+
     pub async fn update(id: i32, update_data: ActiveModel) -> Result<(), DbErr> {
         let db = crate::dao::get_connection().await?;
         update_data.update(&*db).await?;
@@ -62,6 +102,50 @@ impl RecentDao {
         Ok(result)
     }
 
+    /// 查找内容相同但路径不同的记录，多条时取最近阅读的
+    pub async fn find_by_content_hash(content_hash: &str, exclude_path: &str) -> Result<Option<Recent>, DbErr> {
+        if content_hash.is_empty() {
+            return Ok(None);
+        }
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::find()
+            .filter(crate::entity::recent::Column::ContentHash.eq(content_hash))
+            .filter(crate::entity::recent::Column::BookPath.ne(exclude_path))
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .order_by_desc(crate::entity::recent::Column::UpdateAt)
+            .one(&*db)
+            .await?;
+        Ok(result)
+    }
+
+    /// 查找 SHA-256 相同但路径不同的记录（同一文件的副本），最近阅读的在前
+    pub async fn find_by_sha256(sha256: &str, exclude_path: &str) -> Result<Vec<Recent>, DbErr> {
+        if sha256.is_empty() {
+            return Ok(Vec::new());
+        }
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::find()
+            .filter(crate::entity::recent::Column::Sha256.eq(sha256))
+            .filter(crate::entity::recent::Column::BookPath.ne(exclude_path))
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .order_by_desc(crate::entity::recent::Column::UpdateAt)
+            .all(&*db)
+            .await?;
+        Ok(result)
+    }
+
+    /// 系列中的所有卷，按卷号排序
+    pub async fn find_by_series(series: &str) -> Result<Vec<Recent>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let results = Entity::find()
+            .filter(crate::entity::recent::Column::Series.eq(series))
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .order_by_asc(crate::entity::recent::Column::SeriesIndex)
+            .all(&*db)
+            .await?;
+        Ok(results)
+    }
+
     pub async fn update_by_path(
         other_path: &str,
         update_data: ActiveModel,
@@ -96,6 +180,9 @@ impl RecentDao {
         if let ActiveValue::Set(ref val) = update_data.scroll_y {
             updater = updater.col_expr(crate::entity::recent::Column::ScrollY, Expr::value(*val));
         }
+        if let ActiveValue::Set(ref val) = update_data.anchor_ratio {
+            updater = updater.col_expr(crate::entity::recent::Column::AnchorRatio, Expr::value(*val));
+        }
         if let ActiveValue::Set(ref val) = update_data.name {
             updater = updater.col_expr(crate::entity::recent::Column::Name, Expr::value(val.clone()));
         }
@@ -117,6 +204,33 @@ impl RecentDao {
         if let ActiveValue::Set(ref val) = update_data.in_recent {
             updater = updater.col_expr(crate::entity::recent::Column::InRecent, Expr::value(*val));
         }
+        if let ActiveValue::Set(ref val) = update_data.content_hash {
+            updater = updater.col_expr(crate::entity::recent::Column::ContentHash, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.finished_at {
+            updater = updater.col_expr(crate::entity::recent::Column::FinishedAt, Expr::value(*val));
+        }
+        if let ActiveValue::Set(ref val) = update_data.crop_margins {
+            updater = updater.col_expr(crate::entity::recent::Column::CropMargins, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.author {
+            updater = updater.col_expr(crate::entity::recent::Column::Author, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.subject {
+            updater = updater.col_expr(crate::entity::recent::Column::Subject, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.sha256 {
+            updater = updater.col_expr(crate::entity::recent::Column::Sha256, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.deleted_at {
+            updater = updater.col_expr(crate::entity::recent::Column::DeletedAt, Expr::value(*val));
+        }
+        if let ActiveValue::Set(ref val) = update_data.series {
+            updater = updater.col_expr(crate::entity::recent::Column::Series, Expr::value(val.clone()));
+        }
+        if let ActiveValue::Set(ref val) = update_data.series_index {
+            updater = updater.col_expr(crate::entity::recent::Column::SeriesIndex, Expr::value(*val));
+        }
 
         updater
             .filter(crate::entity::recent::Column::BookPath.eq(other_path))
@@ -134,67 +248,165 @@ impl RecentDao {
         Ok(())
     }
 
+    /// 移入回收站，记录删除时间
+    pub async fn trash(ids: &[i32], deleted_at: i64) -> Result<(), DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::update_many()
+            .col_expr(crate::entity::recent::Column::DeletedAt, Expr::value(deleted_at))
+            .filter(crate::entity::recent::Column::Id.is_in(ids.iter().copied()))
+            .exec(&*db)
+            .await?;
+        Ok(())
+    }
+
+    /// 全部移入回收站，返回这次移入的记录 id
+    pub async fn trash_all(deleted_at: i64) -> Result<Vec<i32>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let ids: Vec<i32> = Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.eq(0))
+            .all(&*db)
+            .await?
+            .into_iter()
+            .map(|recent| recent.id)
+            .collect();
+        Self::trash(&ids, deleted_at).await?;
+        Ok(ids)
+    }
+
+    /// 清空“打开最近”菜单，阅读记录保留，返回移出菜单的记录数
+    pub async fn clear_recent_list() -> Result<u64, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::update_many()
+            .col_expr(crate::entity::recent::Column::InRecent, Expr::value(0))
+            .filter(crate::entity::recent::Column::InRecent.ne(0))
+            .exec(&*db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 从回收站恢复
+    pub async fn restore(ids: &[i32]) -> Result<(), DbErr> {
+        Self::trash(ids, 0).await
+    }
+
+    pub async fn count_trashed() -> Result<u64, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::find()
+            .filter(crate::entity::recent::Column::DeletedAt.gt(0))
+            .count(&*db)
+            .await
+    }
+
+    /// 彻底删除在 before 之前移入回收站的记录，返回删除数量
+    pub async fn purge_trashed(before: i64) -> Result<u64, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let result = Entity::delete_many()
+            .filter(crate::entity::recent::Column::DeletedAt.gt(0))
+            .filter(crate::entity::recent::Column::DeletedAt.lt(before))
+            .exec(&*db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 累加阅读时长，不改动其他列（包括 update_at）
+    pub async fn add_read_seconds(path: &str, seconds: i64) -> Result<(), DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::update_many()
+            .col_expr(
+                crate::entity::recent::Column::ReadSeconds,
+                Expr::col(crate::entity::recent::Column::ReadSeconds).add(seconds),
+            )
+            .filter(crate::entity::recent::Column::BookPath.eq(path))
+            .exec(&*db)
+            .await?;
+        Ok(())
+    }
+
     // Synchronous versions using join handle for compatibility
-    pub fn init_sync() -> Result<(), Box<dyn std::error::Error>> {
+    pub fn init_sync() -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::init().await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn insert_sync(other_recent: ActiveModel) -> crate::error::Result<Recent> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::insert(other_recent).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_by_id_sync(other_id: i32) -> crate::error::Result<Option<Recent>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_by_id(other_id).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_all_sync() -> crate::error::Result<Vec<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::init().await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_all().await.map_err(Into::into)
             })
         })
     }
 
-    pub fn insert_sync(other_recent: ActiveModel) -> Result<Recent, Box<dyn std::error::Error>> {
+    pub fn find_all_ordered_by_update_at_desc_sync() -> crate::error::Result<Vec<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::insert(other_recent).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_all_ordered_by_update_at_desc().await.map_err(Into::into)
             })
         })
     }
 
-    pub fn find_by_id_sync(other_id: i32) -> Result<Option<Recent>, Box<dyn std::error::Error>> {
+    pub fn update_sync(id: i32, update_data: ActiveModel) -> crate::error::Result<()> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::find_by_id(other_id).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::update(id, update_data).await.map_err(Into::into)
             })
         })
     }
 
-    pub fn find_all_sync() -> Result<Vec<Recent>, Box<dyn std::error::Error>> {
+    pub fn delete_sync(other_id: i32) -> crate::error::Result<()> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::find_all().await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::delete(other_id).await.map_err(Into::into)
             })
         })
     }
 
-    pub fn find_all_ordered_by_update_at_desc_sync() -> Result<Vec<Recent>, Box<dyn std::error::Error>> {
+    pub fn find_by_path_sync(other_path: &str) -> crate::error::Result<Option<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::find_all_ordered_by_update_at_desc().await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_by_path(other_path).await.map_err(Into::into)
             })
         })
     }
 
-    pub fn update_sync(id: i32, update_data: ActiveModel) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn find_by_content_hash_sync(content_hash: &str, exclude_path: &str) -> crate::error::Result<Option<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::update(id, update_data).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_by_content_hash(content_hash, exclude_path).await.map_err(Into::into)
             })
         })
     }
 
-    pub fn delete_sync(other_id: i32) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn find_by_sha256_sync(sha256: &str, exclude_path: &str) -> crate::error::Result<Vec<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::delete(other_id).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_by_sha256(sha256, exclude_path).await.map_err(Into::into)
             })
         })
     }
 
-    pub fn find_by_path_sync(other_path: &str) -> Result<Option<Recent>, Box<dyn std::error::Error>> {
+    pub fn find_by_series_sync(series: &str) -> crate::error::Result<Vec<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::find_by_path(other_path).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_by_series(series).await.map_err(Into::into)
             })
         })
     }
@@ -202,18 +414,98 @@ impl RecentDao {
     pub fn update_by_path_sync(
         other_path: &str,
         update_data: ActiveModel,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::update_by_path(other_path, update_data).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn delete_by_path_sync(other_path: &str) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::delete_by_path(other_path).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn trash_sync(ids: &[i32], deleted_at: i64) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::trash(ids, deleted_at).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn trash_all_sync(deleted_at: i64) -> crate::error::Result<Vec<i32>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::trash_all(deleted_at).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn clear_recent_list_sync() -> crate::error::Result<u64> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::clear_recent_list().await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn restore_sync(ids: &[i32]) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::restore(ids).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_archived_sync() -> crate::error::Result<Vec<Recent>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_archived().await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_random_unread_sync() -> crate::error::Result<Option<Recent>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_random_unread().await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_oldest_in_progress_sync() -> crate::error::Result<Option<Recent>> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::update_by_path(other_path, update_data).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::find_oldest_in_progress().await.map_err(Into::into)
             })
         })
     }
 
-    pub fn delete_by_path_sync(other_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn count_trashed_sync() -> crate::error::Result<u64> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::delete_by_path(other_path).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::count_trashed().await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn purge_trashed_sync(before: i64) -> crate::error::Result<u64> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::purge_trashed(before).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn add_read_seconds_sync(path: &str, seconds: i64) -> crate::error::Result<()> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::add_read_seconds(path, seconds).await.map_err(Into::into)
             })
         })
     }
@@ -224,11 +516,492 @@ impl RecentDao {
         Ok(())
     }
 
-    pub fn clear_all_sync() -> Result<(), Box<dyn std::error::Error>> {
+    pub fn clear_all_sync() -> crate::error::Result<()> {
         tokio::task::block_in_place(|| {
             futures::executor::block_on(async {
-                Self::clear_all().await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+                Self::clear_all().await.map_err(Into::into)
             })
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_support::{recent_fixture, setup_memory_db};
+    use crate::decoder::CropMargins;
+
+    #[tokio::test]
+    async fn insert_and_find_by_id() {
+        let _db = setup_memory_db().await;
+
+        let inserted = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        assert!(inserted.id > 0);
+
+        let found = RecentDao::find_by_id(inserted.id).await.unwrap().unwrap();
+        assert_eq!(found.book_path, "/books/a.pdf");
+        assert_eq!(found.name, "a.pdf");
+        assert_eq!(found.ext, "pdf");
+        assert_eq!(found.page_count, 100);
+
+        assert!(RecentDao::find_by_id(inserted.id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn migrations_are_idempotent() {
+        let _db = setup_memory_db().await;
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+
+        let db = crate::dao::get_connection().await.unwrap();
+        crate::dao::run_migrations(&db).await.unwrap();
+
+        assert_eq!(RecentDao::find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn migrations_upgrade_legacy_schema() {
+        let _db = setup_memory_db().await;
+
+        // 最早版本的 recents 表，没有后来加入的列和其他表
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).min_connections(1).sqlx_logging(false);
+        let legacy = Database::connect(options).await.unwrap();
+        legacy.execute_unprepared(r#"
+            CREATE TABLE recents (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                book_path TEXT NOT NULL UNIQUE,
+                update_at INTEGER NOT NULL,
+                page INTEGER DEFAULT 0,
+                page_count INTEGER DEFAULT 0,
+                create_at INTEGER NOT NULL,
+                crop INTEGER DEFAULT 1,
+                reflow INTEGER DEFAULT 0,
+                scroll_ori INTEGER DEFAULT 1,
+                zoom REAL DEFAULT 1.0,
+                scroll_x INTEGER DEFAULT 0,
+                scroll_y INTEGER DEFAULT 0,
+                name TEXT,
+                ext TEXT,
+                size INTEGER,
+                read_times INTEGER DEFAULT 0,
+                progress INTEGER DEFAULT 0,
+                favorited INTEGER DEFAULT 0,
+                in_recent INTEGER DEFAULT 0
+            )
+        "#).await.unwrap();
+        legacy.execute_unprepared(
+            "INSERT INTO recents (book_path, update_at, create_at, page, name, ext, size) \
+             VALUES ('/books/old.pdf', 1000, 1000, 7, 'old.pdf', 'pdf', 0)"
+        ).await.unwrap();
+
+        crate::dao::run_migrations(&legacy).await.unwrap();
+        crate::dao::db_utils::set_connection(legacy).await;
+
+        let old = RecentDao::find_all().await.unwrap().pop().unwrap();
+        assert_eq!(old.page, 7);
+        assert_eq!(old.sha256, "");
+        assert_eq!(old.subject, "");
+        // 升级前的记录都在“打开最近”菜单中
+        assert_eq!(old.in_recent, 1);
+        let db = crate::dao::get_connection().await.unwrap();
+        db.execute_unprepared("SELECT COUNT(*) FROM bookmarks").await.unwrap();
+        db.execute_unprepared("SELECT COUNT(*) FROM book_metadata").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn duplicate_path_is_rejected() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        assert!(RecentDao::insert(recent_fixture("/books/a.pdf", 2000)).await.is_err());
+        assert_eq!(RecentDao::find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn ordered_by_update_at_desc() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/old.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/new.pdf", 3000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/mid.pdf", 2000)).await.unwrap();
+
+        let paths: Vec<String> = RecentDao::find_all_ordered_by_update_at_desc().await.unwrap()
+            .into_iter()
+            .map(|r| r.book_path)
+            .collect();
+        assert_eq!(paths, ["/books/new.pdf", "/books/mid.pdf", "/books/old.pdf"]);
+    }
+
+    #[tokio::test]
+    async fn update_by_id() {
+        let _db = setup_memory_db().await;
+
+        let inserted = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let mut active: ActiveModel = inserted.clone().into();
+        active.page = Set(42);
+        active.zoom = Set(1.5);
+        RecentDao::update(inserted.id, active).await.unwrap();
+
+        let found = RecentDao::find_by_id(inserted.id).await.unwrap().unwrap();
+        assert_eq!(found.page, 42);
+        assert_eq!(found.zoom, 1.5);
+        assert_eq!(found.book_path, "/books/a.pdf");
+    }
+
+    #[tokio::test]
+    async fn update_by_path_only_touches_set_columns() {
+        let _db = setup_memory_db().await;
+
+        let inserted = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 1000)).await.unwrap();
+
+        let update = ActiveModel {
+            page: Set(7),
+            update_at: Set(5000),
+            ..Default::default()
+        };
+        RecentDao::update_by_path("/books/a.pdf", update).await.unwrap();
+
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(a.page, 7);
+        assert_eq!(a.update_at, 5000);
+        // 未设置的列保持原值
+        assert_eq!(a.page_count, inserted.page_count);
+        assert_eq!(a.name, inserted.name);
+        assert_eq!(a.create_at, inserted.create_at);
+
+        let b = RecentDao::find_by_path("/books/b.pdf").await.unwrap().unwrap();
+        assert_eq!(b.page, 0);
+        assert_eq!(b.update_at, 1000);
+    }
+
+    #[tokio::test]
+    async fn update_by_missing_path_is_noop() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let update = ActiveModel {
+            page: Set(7),
+            ..Default::default()
+        };
+        RecentDao::update_by_path("/books/missing.pdf", update).await.unwrap();
+
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(a.page, 0);
+        assert!(RecentDao::find_by_path("/books/missing.pdf").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn crop_margins_round_trip() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert!(CropMargins::decode(&a.crop_margins).is_none());
+
+        let margins = CropMargins::new(0.1, 0.05, 0.12, 0.08);
+        let update = ActiveModel { crop_margins: Set(margins.encode()), ..Default::default() };
+        RecentDao::update_by_path("/books/a.pdf", update).await.unwrap();
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(CropMargins::decode(&a.crop_margins), Some(margins));
+
+        let crop = margins.crop_rect(500.0, 800.0);
+        assert!((crop.left - 50.0).abs() < 0.01 && (crop.right - 440.0).abs() < 0.01);
+        assert!((crop.top - 40.0).abs() < 0.01 && (crop.bottom - 736.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn document_info_round_trip() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let update = ActiveModel {
+            name: Set("Structure and Interpretation of Computer Programs".into()),
+            author: Set("Harold Abelson, Gerald Jay Sussman".into()),
+            subject: Set("Computer science".into()),
+            size: Set(4_200_000),
+            ..Default::default()
+        };
+        RecentDao::update_by_path("/books/a.pdf", update).await.unwrap();
+
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(a.name, "Structure and Interpretation of Computer Programs");
+        assert_eq!(a.author, "Harold Abelson, Gerald Jay Sussman");
+        assert_eq!(a.subject, "Computer science");
+        assert_eq!(a.size, 4_200_000);
+    }
+
+    #[tokio::test]
+    async fn find_by_path_is_exact() {
+        let _db = setup_memory_db().await;
+
+        let paths = ["/书库/三体 第一部.epub", "C:\\Books\\it's.pdf", "/books/100%_a.pdf"];
+        for (i, path) in paths.iter().enumerate() {
+            RecentDao::insert(recent_fixture(path, i as i64)).await.unwrap();
+        }
+        for path in paths {
+            let found = RecentDao::find_by_path(path).await.unwrap().unwrap();
+            assert_eq!(found.book_path, path);
+        }
+        // LIKE 通配符不能被当作模式匹配
+        assert!(RecentDao::find_by_path("/books/100__a.pdf").await.unwrap().is_none());
+        assert!(RecentDao::find_by_path("/书库/三体 第一部").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn find_by_content_hash_skips_own_path() {
+        let _db = setup_memory_db().await;
+
+        let mut original = recent_fixture("/books/a.pdf", 1000);
+        original.content_hash = Set("abc".to_string());
+        RecentDao::insert(original).await.unwrap();
+        let mut newer = recent_fixture("/backup/a.pdf", 2000);
+        newer.content_hash = Set("abc".to_string());
+        RecentDao::insert(newer).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 3000)).await.unwrap();
+
+        let found = RecentDao::find_by_content_hash("abc", "/copy/a.pdf").await.unwrap().unwrap();
+        assert_eq!(found.book_path, "/backup/a.pdf");
+        let found = RecentDao::find_by_content_hash("abc", "/backup/a.pdf").await.unwrap().unwrap();
+        assert_eq!(found.book_path, "/books/a.pdf");
+        // 没有计算过 hash 的记录不能互相匹配
+        assert!(RecentDao::find_by_content_hash("", "/copy/b.pdf").await.unwrap().is_none());
+        assert!(RecentDao::find_by_content_hash("def", "/copy/a.pdf").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn sha256_is_updated_and_matches_copies() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/backup/a.pdf", 2000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 3000)).await.unwrap();
+        for path in ["/books/a.pdf", "/backup/a.pdf"] {
+            let update = ActiveModel { sha256: Set("e3b0".to_string()), ..Default::default() };
+            RecentDao::update_by_path(path, update).await.unwrap();
+        }
+
+        let copies = RecentDao::find_by_sha256("e3b0", "/copy/a.pdf").await.unwrap();
+        let paths: Vec<_> = copies.iter().map(|r| r.book_path.as_str()).collect();
+        assert_eq!(paths, ["/backup/a.pdf", "/books/a.pdf"]);
+        assert_eq!(RecentDao::find_by_sha256("e3b0", "/books/a.pdf").await.unwrap().len(), 1);
+        assert!(RecentDao::find_by_sha256("", "/books/b.pdf").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_by_id_and_path() {
+        let _db = setup_memory_db().await;
+
+        let a = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 2000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/c.pdf", 3000)).await.unwrap();
+
+        RecentDao::delete(a.id).await.unwrap();
+        RecentDao::delete_by_path("/books/b.pdf").await.unwrap();
+        // 删除不存在的记录不报错
+        RecentDao::delete_by_path("/books/missing.pdf").await.unwrap();
+
+        let remaining = RecentDao::find_all().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].book_path, "/books/c.pdf");
+    }
+
+    #[tokio::test]
+    async fn clear_recent_list_keeps_history() {
+        let _db = setup_memory_db().await;
+
+        for (path, update_at) in [("/books/a.pdf", 1000), ("/books/b.pdf", 2000)] {
+            RecentDao::insert(recent_fixture(path, update_at)).await.unwrap();
+            let update = ActiveModel { in_recent: Set(1), ..Default::default() };
+            RecentDao::update_by_path(path, update).await.unwrap();
+        }
+
+        assert_eq!(RecentDao::clear_recent_list().await.unwrap(), 2);
+        let records = RecentDao::find_all_ordered_by_update_at_desc().await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.in_recent == 0 && r.deleted_at == 0));
+        assert_eq!(RecentDao::clear_recent_list().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn trash_hides_and_restore_brings_back() {
+        let _db = setup_memory_db().await;
+
+        let a = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 2000)).await.unwrap();
+
+        RecentDao::trash(&[a.id], 5000).await.unwrap();
+        let visible: Vec<String> = RecentDao::find_all_ordered_by_update_at_desc().await.unwrap()
+            .into_iter()
+            .map(|r| r.book_path)
+            .collect();
+        assert_eq!(visible, ["/books/b.pdf"]);
+        assert_eq!(RecentDao::count_trashed().await.unwrap(), 1);
+        // 回收站中的记录仍按路径可查，重新打开时保留进度
+        assert!(RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap().is_deleted());
+
+        RecentDao::restore(&[a.id]).await.unwrap();
+        assert_eq!(RecentDao::find_all_ordered_by_update_at_desc().await.unwrap().len(), 2);
+        assert_eq!(RecentDao::count_trashed().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn trash_all_returns_only_newly_trashed() {
+        let _db = setup_memory_db().await;
+
+        let a = RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let b = RecentDao::insert(recent_fixture("/books/b.pdf", 2000)).await.unwrap();
+        RecentDao::trash(&[a.id], 5000).await.unwrap();
+
+        let ids = RecentDao::trash_all(6000).await.unwrap();
+        assert_eq!(ids, [b.id]);
+        assert!(RecentDao::find_all_ordered_by_update_at_desc().await.unwrap().is_empty());
+        // 之前删除的记录保留原来的删除时间
+        assert_eq!(RecentDao::find_by_id(a.id).await.unwrap().unwrap().deleted_at, 5000);
+    }
+
+    #[tokio::test]
+    async fn purge_only_removes_expired_trash() {
+        let _db = setup_memory_db().await;
+
+        let old = RecentDao::insert(recent_fixture("/books/old.pdf", 1000)).await.unwrap();
+        let recent = RecentDao::insert(recent_fixture("/books/recent.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/kept.pdf", 1000)).await.unwrap();
+        RecentDao::trash(&[old.id], 2000).await.unwrap();
+        RecentDao::trash(&[recent.id], 9000).await.unwrap();
+
+        assert_eq!(RecentDao::purge_trashed(5000).await.unwrap(), 1);
+        assert!(RecentDao::find_by_id(old.id).await.unwrap().is_none());
+        assert!(RecentDao::find_by_id(recent.id).await.unwrap().is_some());
+
+        assert_eq!(RecentDao::purge_trashed(i64::MAX).await.unwrap(), 1);
+        assert_eq!(RecentDao::find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn find_by_series_orders_by_volume() {
+        let _db = setup_memory_db().await;
+
+        for (path, volume) in [("/comics/Naruto v10.cbz", 10.0), ("/comics/Naruto v02.cbz", 2.0), ("/comics/Naruto v03.5.cbz", 3.5)] {
+            let mut record = recent_fixture(path, 1000);
+            record.series = Set("Naruto".to_string());
+            record.series_index = Set(volume);
+            RecentDao::insert(record).await.unwrap();
+        }
+        let mut other = recent_fixture("/comics/Bleach v01.cbz", 1000);
+        other.series = Set("Bleach".to_string());
+        RecentDao::insert(other).await.unwrap();
+        let trashed = RecentDao::find_by_path("/comics/Naruto v02.cbz").await.unwrap().unwrap();
+
+        let volumes: Vec<f32> = RecentDao::find_by_series("Naruto").await.unwrap()
+            .into_iter()
+            .map(|r| r.series_index)
+            .collect();
+        assert_eq!(volumes, [2.0, 3.5, 10.0]);
+
+        RecentDao::trash(&[trashed.id], 5000).await.unwrap();
+        assert_eq!(RecentDao::find_by_series("Naruto").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn archived_records_are_ordered_by_finish_time() {
+        let _db = setup_memory_db().await;
+
+        for (path, finished_at) in [("/books/a.pdf", 3000), ("/books/b.pdf", 0), ("/books/c.pdf", 5000)] {
+            RecentDao::insert(recent_fixture(path, 1000)).await.unwrap();
+            let update = ActiveModel { finished_at: Set(finished_at), ..Default::default() };
+            RecentDao::update_by_path(path, update).await.unwrap();
+        }
+        let trashed = RecentDao::find_by_path("/books/c.pdf").await.unwrap().unwrap();
+        RecentDao::insert(recent_fixture("/books/d.pdf", 1000)).await.unwrap();
+        RecentDao::update_by_path("/books/d.pdf", ActiveModel { finished_at: Set(4000), ..Default::default() }).await.unwrap();
+
+        let archived = RecentDao::find_archived().await.unwrap();
+        let paths: Vec<_> = archived.iter().map(|r| r.book_path.as_str()).collect();
+        assert_eq!(paths, ["/books/c.pdf", "/books/d.pdf", "/books/a.pdf"]);
+        assert!(archived.iter().all(|r| r.is_finished()));
+
+        // 移入回收站的不算
+        RecentDao::trash(&[trashed.id], 6000).await.unwrap();
+        assert_eq!(RecentDao::find_archived().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn quick_picks_skip_read_finished_and_trashed() {
+        let _db = setup_memory_db().await;
+
+        // (路径, 阅读次数, 进度, 归档时间, 更新时间)
+        for (path, read_times, progress, finished_at, update_at) in [
+            ("/books/unread.pdf", 0, 0, 0, 5000),
+            ("/books/old.pdf", 3, 40, 0, 1000),
+            ("/books/recent.pdf", 1, 10, 0, 4000),
+            ("/books/done.pdf", 2, 100, 0, 500),
+            ("/books/archived.pdf", 0, 30, 3000, 200),
+        ] {
+            RecentDao::insert(recent_fixture(path, update_at)).await.unwrap();
+            let update = ActiveModel {
+                read_times: Set(read_times),
+                progress: Set(progress),
+                finished_at: Set(finished_at),
+                ..Default::default()
+            };
+            RecentDao::update_by_path(path, update).await.unwrap();
+        }
+
+        let unread = RecentDao::find_random_unread().await.unwrap().unwrap();
+        assert_eq!(unread.book_path, "/books/unread.pdf");
+        let oldest = RecentDao::find_oldest_in_progress().await.unwrap().unwrap();
+        assert_eq!(oldest.book_path, "/books/old.pdf");
+
+        RecentDao::trash(&[unread.id, oldest.id], 6000).await.unwrap();
+        assert!(RecentDao::find_random_unread().await.unwrap().is_none());
+        let oldest = RecentDao::find_oldest_in_progress().await.unwrap().unwrap();
+        assert_eq!(oldest.book_path, "/books/recent.pdf");
+    }
+
+    #[tokio::test]
+    async fn add_read_seconds_accumulates() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 2000)).await.unwrap();
+
+        RecentDao::add_read_seconds("/books/a.pdf", 60).await.unwrap();
+        RecentDao::add_read_seconds("/books/a.pdf", 15).await.unwrap();
+        RecentDao::add_read_seconds("/books/missing.pdf", 30).await.unwrap();
+
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(a.read_seconds, 75);
+        // 只累加时长，不算一次新的阅读
+        assert_eq!(a.update_at, 1000);
+        assert_eq!(RecentDao::find_by_path("/books/b.pdf").await.unwrap().unwrap().read_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn update_by_path_keeps_anchor_ratio() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        let update = ActiveModel {
+            page: Set(12),
+            anchor_ratio: Set(0.375),
+            ..Default::default()
+        };
+        RecentDao::update_by_path("/books/a.pdf", update).await.unwrap();
+
+        let a = RecentDao::find_by_path("/books/a.pdf").await.unwrap().unwrap();
+        assert_eq!(a.page, 12);
+        assert_eq!(a.anchor_ratio, 0.375);
+    }
+
+    #[tokio::test]
+    async fn clear_all_removes_everything() {
+        let _db = setup_memory_db().await;
+
+        RecentDao::insert(recent_fixture("/books/a.pdf", 1000)).await.unwrap();
+        RecentDao::insert(recent_fixture("/books/b.pdf", 2000)).await.unwrap();
+        RecentDao::clear_all().await.unwrap();
+
+        assert!(RecentDao::find_all().await.unwrap().is_empty());
+    }
+}
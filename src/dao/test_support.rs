@@ -0,0 +1,33 @@
+use sea_orm::{ConnectOptions, Database, Set};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::entity::recent::ActiveModel;
+use crate::entity::Recent;
+
+/// DAO 通过全局连接访问数据库，测试之间需要串行
+static TEST_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// 为每个测试建立全新的内存数据库并执行迁移，返回的 guard 持有期间独占全局连接
+pub async fn setup_memory_db() -> MutexGuard<'static, ()> {
+    let guard = TEST_LOCK.lock().await;
+
+    // 内存库每个连接都是独立的数据库，连接池只能有一个连接
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1).min_connections(1).sqlx_logging(false);
+    let db = Database::connect(options).await.expect("connect sqlite::memory:");
+    super::run_migrations(&db).await.expect("run migrations");
+    super::db_utils::set_connection(db).await;
+
+    guard
+}
+
+/// 最近阅读记录样例
+pub fn recent_fixture(path: &str, update_at: i64) -> ActiveModel {
+    let mut recent = Recent::new(path.to_string());
+    recent.update_at = Set(update_at);
+    recent.create_at = Set(update_at);
+    recent.name = Set(path.rsplit('/').next().unwrap_or(path).to_string());
+    recent.ext = Set(path.rsplit('.').next().unwrap_or("").to_string());
+    recent.page_count = Set(100);
+    recent
+}
@@ -0,0 +1,95 @@
+use sea_orm::*;
+
+use crate::entity::vocab::{ActiveModel, Column, Entity, Model as VocabEntry};
+
+pub struct VocabDao;
+
+impl VocabDao {
+    /// 保存生词；同一本书里已有这个词时更新释义、页码和上下文，新的释义为空时保留原来的
+    pub async fn save(entry: ActiveModel) -> Result<VocabEntry, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        let (ActiveValue::Set(word), ActiveValue::Set(book_path)) = (&entry.word, &entry.book_path) else {
+            return Err(DbErr::Custom("vocab entry needs word and book_path".to_string()));
+        };
+        let existing = Entity::find()
+            .filter(Column::Word.eq(word.as_str()))
+            .filter(Column::BookPath.eq(book_path.as_str()))
+            .one(&*db)
+            .await?;
+        let Some(existing) = existing else {
+            return entry.insert(&*db).await;
+        };
+
+        let mut update: ActiveModel = existing.into();
+        if let ActiveValue::Set(definition) = entry.definition {
+            if !definition.is_empty() {
+                update.definition = Set(definition);
+            }
+        }
+        update.page = entry.page;
+        update.context = entry.context;
+        update.update(&*db).await
+    }
+
+    /// 所有生词，最近加入的在前
+    pub async fn find_all() -> Result<Vec<VocabEntry>, DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::find()
+            .order_by_desc(Column::CreateAt)
+            .order_by_desc(Column::Id)
+            .all(&*db)
+            .await
+    }
+
+    pub async fn delete(id: i32) -> Result<(), DbErr> {
+        let db = crate::dao::get_connection().await?;
+        Entity::delete_by_id(id).exec(&*db).await?;
+        Ok(())
+    }
+
+    pub fn save_sync(entry: ActiveModel) -> crate::error::Result<VocabEntry> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::save(entry).await.map_err(Into::into)
+            })
+        })
+    }
+
+    pub fn find_all_sync() -> crate::error::Result<Vec<VocabEntry>> {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                Self::find_all().await.map_err(Into::into)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_support::setup_memory_db;
+
+    #[tokio::test]
+    async fn save_updates_same_word_in_same_book() {
+        let _db = setup_memory_db().await;
+
+        let first = VocabDao::save(VocabEntry::new("ephemeral".into(), "short-lived".into(), "/books/a.pdf".into(), 3, "An ephemeral joy.".into())).await.unwrap();
+        let second = VocabDao::save(VocabEntry::new("ephemeral".into(), "".into(), "/books/a.pdf".into(), 9, "Ephemeral fame.".into())).await.unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.definition, "short-lived");
+        assert_eq!(second.page, 9);
+        assert_eq!(second.context, "Ephemeral fame.");
+
+        VocabDao::save(VocabEntry::new("ephemeral".into(), "".into(), "/books/b.pdf".into(), 1, "".into())).await.unwrap();
+        assert_eq!(VocabDao::find_all().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn delete_removes_entry() {
+        let _db = setup_memory_db().await;
+
+        let entry = VocabDao::save(VocabEntry::new("lucid".into(), "".into(), "/books/a.pdf".into(), 0, "".into())).await.unwrap();
+        VocabDao::delete(entry.id).await.unwrap();
+        assert!(VocabDao::find_all().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+/// 空白页检测：用亮度直方图找出纸张底色，偏离底色的像素极少时认为是空白页
+/// 适合双面扫描中只有纸纹、装订阴影的背面页
+
+/// 与纸张底色的亮度差超过该值才算墨迹
+const INK_DELTA: i32 = 48;
+/// 墨迹像素占比低于该值视为空白
+const MAX_INK_RATIO: f32 = 0.002;
+/// 忽略的页边比例，扫描件边缘常有黑边或阴影
+const EDGE_MARGIN: f32 = 0.05;
+/// 最多采样的像素数
+const MAX_SAMPLES: u32 = 200_000;
+
+pub fn is_blank_page(rgba: &[u8], width: u32, height: u32) -> bool {
+    if width == 0 || height == 0 {
+        return false;
+    }
+    let margin_x = (width as f32 * EDGE_MARGIN) as u32;
+    let margin_y = (height as f32 * EDGE_MARGIN) as u32;
+    let area = (width - 2 * margin_x) as u64 * (height - 2 * margin_y) as u64;
+    let step = ((area / MAX_SAMPLES as u64) as f64).sqrt().max(1.0) as usize;
+
+    let mut histogram = [0u32; 256];
+    let mut samples = 0u32;
+    for y in (margin_y..height - margin_y).step_by(step) {
+        for x in (margin_x..width - margin_x).step_by(step) {
+            let i = ((y * width + x) * 4) as usize;
+            let luma = (rgba[i] as u32 * 299 + rgba[i + 1] as u32 * 587 + rgba[i + 2] as u32 * 114) / 1000;
+            histogram[luma as usize] += 1;
+            samples += 1;
+        }
+    }
+    if samples == 0 {
+        return false;
+    }
+
+    // 出现最多的亮度即纸张底色
+    let paper = histogram
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, count)| **count)
+        .map(|(luma, _)| luma as i32)
+        .unwrap_or(255);
+    let ink: u32 = histogram
+        .iter()
+        .enumerate()
+        .filter(|(luma, _)| (*luma as i32 - paper).abs() > INK_DELTA)
+        .map(|(_, count)| *count)
+        .sum();
+    (ink as f32 / samples as f32) < MAX_INK_RATIO
+}
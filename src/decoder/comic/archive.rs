@@ -0,0 +1,402 @@
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use zip::ZipArchive;
+
+use crate::app_paths;
+use crate::storage::FileStore;
+use crate::ui::utils::generate_content_hash;
+
+/// 解压完成的标记文件，内容为解压出的文件数，目录不完整时据此重新解压
+const COMPLETE_MARKER: &str = ".complete";
+/// 最多保留最近打开的几本书的解压结果
+const EXTRACTED_KEEP: usize = 5;
+/// 整个解压缓存的锁文件，检查、解压和清理都在持有它时进行，多个线程或进程同时打开也不会互相删除
+const CACHE_LOCK: &str = ".lock";
+/// 解压用的临时目录前缀，完成后整体改名为正式目录
+const TMP_PREFIX: &str = ".tmp-";
+
+/// 漫画压缩包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Rar,
+    SevenZip,
+}
+
+impl ArchiveKind {
+    /// 按扩展名判断格式，cbz/cbr/cb7 之外也接受普通的 zip/rar/7z
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "cbz" | "zip" => Some(Self::Zip),
+            "cbr" | "rar" => Some(Self::Rar),
+            "cb7" | "7z" => Some(Self::SevenZip),
+            _ => None,
+        }
+    }
+}
+
+enum Source {
+    /// zip 支持按名字随机读取，直接从压缩包里取
+    Zip(RefCell<ZipArchive<File>>),
+    /// rar/7z 常为固实压缩，随机读取要从头解压，打开时整体解压到缓存目录；
+    /// 文件经过 FileStore 写入，开启书库加密时缓存里的页面也是加密的。
+    /// lock 是这本书的共享锁，打开期间清理会跳过这个目录
+    Extracted { dir: PathBuf, _lock: File },
+}
+
+/// 漫画压缩包：列出文件并按名字读取内容
+pub struct ComicArchive {
+    kind: ArchiveKind,
+    source: Source,
+    entries: Vec<String>,
+}
+
+impl ComicArchive {
+    pub fn open(path: &Path) -> Result<Self> {
+        let Some(kind) = ArchiveKind::from_path(path) else {
+            bail!("不是漫画压缩包: {:?}", path);
+        };
+        let (source, entries) = match kind {
+            ArchiveKind::Zip => {
+                let archive = ZipArchive::new(File::open(path)?)?;
+                let entries = archive.file_names()
+                    .filter(|name| !name.ends_with('/'))
+                    .map(str::to_string)
+                    .collect();
+                (Source::Zip(RefCell::new(archive)), entries)
+            }
+            ArchiveKind::Rar | ArchiveKind::SevenZip => {
+                let (dir, lock) = Self::extract(path, kind)?;
+                let entries = Self::list_dir(&dir)?;
+                (Source::Extracted { dir, _lock: lock }, entries)
+            }
+        };
+        info!("[Comic] 打开 {:?}: {:?}, {} 个文件", path, kind, entries.len());
+        Ok(Self { kind, source, entries })
+    }
+
+    pub fn kind(&self) -> ArchiveKind {
+        self.kind
+    }
+
+    /// 压缩包内的文件名，目录分隔符统一为 '/'
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// 读取整个文件
+    pub fn read(&self, name: &str) -> Result<Vec<u8>> {
+        self.read_limited(name, u64::MAX)
+    }
+
+    /// 最多读取 limit 字节，读图片尺寸只需要文件头
+    pub fn read_limited(&self, name: &str, limit: u64) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        match &self.source {
+            Source::Zip(archive) => {
+                let mut archive = archive.borrow_mut();
+                archive.by_name(name)?.take(limit).read_to_end(&mut data)?;
+            }
+            Source::Extracted { dir, .. } if FileStore::is_encrypted() => {
+                // 加密的文件要整体解密
+                data = FileStore::read(&dir.join(name))?;
+                data.truncate(limit.min(data.len() as u64) as usize);
+            }
+            Source::Extracted { dir, .. } => {
+                File::open(dir.join(name))?.take(limit).read_to_end(&mut data)?;
+            }
+        }
+        Ok(data)
+    }
+
+    /// 解压到缓存目录，同一本书（按内容 hash）只解压一次。
+    /// 先解压到临时目录再改名，其他打开者不会看到解压了一半的目录；返回目录和这本书的共享锁
+    fn extract(path: &Path, kind: ArchiveKind) -> Result<(PathBuf, File)> {
+        let hash = generate_content_hash(path)?;
+        let cache_dir = app_paths::comic_cache_dir();
+        fs::create_dir_all(&cache_dir)?;
+        let name = format!("{:016x}", hash);
+        let dir = cache_dir.join(&name);
+        let cache_lock = Self::open_lock(&cache_dir.join(CACHE_LOCK))?;
+        cache_lock.lock()?;
+
+        let book_lock = Self::open_lock(&Self::book_lock_path(&dir))?;
+        if Self::is_complete(&dir) {
+            // 更新标记的修改时间，清理时按它判断最近使用
+            let count = fs::read_to_string(dir.join(COMPLETE_MARKER))?;
+            fs::write(dir.join(COMPLETE_MARKER), count)?;
+            book_lock.lock_shared()?;
+            return Ok((dir, book_lock));
+        }
+
+        let tmp = cache_dir.join(format!("{}{}-{}", TMP_PREFIX, name, std::process::id()));
+        if tmp.exists() {
+            fs::remove_dir_all(&tmp)?;
+        }
+        fs::create_dir_all(&tmp)?;
+        info!("[Comic] 解压 {:?} 到 {:?}", path, dir);
+        let result = match kind {
+            ArchiveKind::Rar => Self::extract_rar(path, &tmp),
+            ArchiveKind::SevenZip => Self::extract_7z(path, &tmp),
+            ArchiveKind::Zip => unreachable!("zip 直接读取，不解压"),
+        };
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&tmp);
+            return Err(e.context(format!("解压失败: {:?}", path)));
+        }
+        let count = Self::list_dir(&tmp)?.len();
+        fs::write(tmp.join(COMPLETE_MARKER), count.to_string())?;
+        // 旧目录不完整（上次解压中断或文件被删），替换掉
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::rename(&tmp, &dir)?;
+        book_lock.lock_shared()?;
+        Self::prune_extracted(&cache_dir, &dir);
+        Ok((dir, book_lock))
+    }
+
+    fn open_lock(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).truncate(false).write(true).open(path)
+    }
+
+    /// 每本书的锁文件放在解压目录旁边，目录被删除时一并删除
+    fn book_lock_path(dir: &Path) -> PathBuf {
+        let mut path = dir.as_os_str().to_owned();
+        path.push(".lock");
+        PathBuf::from(path)
+    }
+
+    /// 删除较早打开的书的解压目录，只留最近 EXTRACTED_KEEP 本。
+    /// 调用方持有整个缓存的锁；正在打开的书持有共享锁，拿不到独占锁时跳过
+    fn prune_extracted(cache_dir: &Path, current: &Path) {
+        let dirs = Self::extracted_dirs(cache_dir, Some(current));
+        for (path, _) in dirs.into_iter().skip(EXTRACTED_KEEP - 1) {
+            Self::remove_extracted(&path);
+        }
+    }
+
+    /// 按配额清理解压缓存，超出时先删最久未打开的书，返回删除的目录数。
+    /// 正在打开的书不删，所以清理后仍可能略超配额
+    pub fn trim_cache(cache_dir: &Path, quota: u64) -> io::Result<usize> {
+        if !cache_dir.is_dir() {
+            return Ok(0);
+        }
+        let cache_lock = Self::open_lock(&cache_dir.join(CACHE_LOCK))?;
+        cache_lock.lock()?;
+
+        let mut dirs: Vec<(PathBuf, u64)> = Self::extracted_dirs(cache_dir, None)
+            .into_iter()
+            .map(|(path, _)| {
+                let size = Self::dir_size(&path);
+                (path, size)
+            })
+            .collect();
+        let mut total: u64 = dirs.iter().map(|(_, size)| size).sum();
+        let mut removed = 0;
+        // extracted_dirs 按最近使用排在前面，从最旧的开始删
+        while total > quota {
+            let Some((path, size)) = dirs.pop() else { break };
+            if Self::remove_extracted(&path) {
+                total -= size;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 缓存中各本书的解压目录和最近使用时间，最近使用的在前；顺带删除中断的解压留下的临时目录。
+    /// 调用方持有整个缓存的锁
+    fn extracted_dirs(cache_dir: &Path, current: Option<&Path>) -> Vec<(PathBuf, SystemTime)> {
+        let Ok(read_dir) = fs::read_dir(cache_dir) else { return Vec::new() };
+        let mut dirs: Vec<(PathBuf, SystemTime)> = Vec::new();
+        for path in read_dir.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if !path.is_dir() || current == Some(path.as_path()) {
+                continue;
+            }
+            let is_tmp = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(TMP_PREFIX));
+            if is_tmp {
+                // 解压都在缓存锁内进行，此时剩下的临时目录是中断的解压
+                info!("[Comic] 清理中断的解压 {:?}", path);
+                let _ = fs::remove_dir_all(&path);
+                continue;
+            }
+            let used = fs::metadata(path.join(COMPLETE_MARKER))
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            dirs.push((path, used));
+        }
+        dirs.sort_by_key(|(_, used)| std::cmp::Reverse(*used));
+        dirs
+    }
+
+    /// 删除一本书的解压目录，正在使用时跳过，删除了返回 true
+    fn remove_extracted(path: &Path) -> bool {
+        let lock_path = Self::book_lock_path(path);
+        let Ok(lock) = Self::open_lock(&lock_path) else { return false };
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                info!("[Comic] 解压缓存正在使用，暂不清理 {:?}", path);
+                return false;
+            }
+            Err(TryLockError::Error(e)) => {
+                warn!("[Comic] 锁定解压缓存失败 {:?}: {}", path, e);
+                return false;
+            }
+        }
+        info!("[Comic] 清理解压缓存 {:?}", path);
+        let removed = match fs::remove_dir_all(path) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("[Comic] 清理解压缓存失败 {:?}: {}", path, e);
+                false
+            }
+        };
+        // Windows 不能删除打开着的文件，先释放再删
+        drop(lock);
+        let _ = fs::remove_file(&lock_path);
+        removed
+    }
+
+    fn dir_size(dir: &Path) -> u64 {
+        let mut size = 0;
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            let Ok(read_dir) = fs::read_dir(&current) else { continue };
+            for entry in read_dir.flatten() {
+                match entry.metadata() {
+                    Ok(meta) if meta.is_dir() => pending.push(entry.path()),
+                    Ok(meta) => size += meta.len(),
+                    Err(_) => {}
+                }
+            }
+        }
+        size
+    }
+
+    /// 逐个文件解压到内存，再由 store 写入缓存目录
+    fn extract_rar(path: &Path, dir: &Path) -> Result<()> {
+        let mut archive = unrar::Archive::new(path)
+            .open_for_processing()
+            .context("无法打开 rar")?;
+        while let Some(header) = archive.read_header()? {
+            archive = if header.entry().is_file() {
+                let name = header.entry().filename.clone();
+                let (data, rest) = header.read()?;
+                Self::store(dir, &name, &data)?;
+                rest
+            } else {
+                header.skip()?
+            };
+        }
+        Ok(())
+    }
+
+    fn extract_7z(path: &Path, dir: &Path) -> Result<()> {
+        let mut archive = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())
+            .map_err(|e| anyhow::anyhow!("无法打开 7z: {}", e))?;
+        archive
+            .for_each_entries(|entry, reader| {
+                if entry.is_directory() {
+                    return Ok(true);
+                }
+                let mut data = Vec::new();
+                reader.read_to_end(&mut data)?;
+                Self::store(dir, Path::new(entry.name()), &data)?;
+                Ok(true)
+            })
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// 写入一个解压出的文件。压缩包里的路径可能带 .. 或是绝对路径，只接受普通的相对路径
+    fn store(dir: &Path, name: &Path, data: &[u8]) -> io::Result<()> {
+        if !name.components().all(|c| matches!(c, Component::Normal(_))) {
+            warn!("[Comic] 跳过不安全的路径: {:?}", name);
+            return Ok(());
+        }
+        let target = dir.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        FileStore::write(&target, data)
+    }
+
+    fn is_complete(dir: &Path) -> bool {
+        let Ok(marker) = fs::read_to_string(dir.join(COMPLETE_MARKER)) else {
+            return false;
+        };
+        let expected: usize = marker.trim().parse().unwrap_or(usize::MAX);
+        Self::list_dir(dir).is_ok_and(|entries| entries.len() == expected)
+    }
+
+    /// 递归列出目录下的文件（不含标记文件），返回相对路径
+    fn list_dir(dir: &Path) -> Result<Vec<String>> {
+        let mut entries = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let Ok(relative) = path.strip_prefix(dir) else { continue };
+                let name = relative.components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if name != COMPLETE_MARKER {
+                    entries.push(name);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(cache_dir: &Path, name: &str, bytes: usize) -> PathBuf {
+        let dir = cache_dir.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("001.jpg"), vec![0u8; bytes]).unwrap();
+        fs::write(dir.join(COMPLETE_MARKER), "1").unwrap();
+        // 标记的修改时间区分先后
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        dir
+    }
+
+    #[test]
+    fn trim_cache_removes_oldest_books_but_not_open_ones() {
+        let cache_dir = std::env::temp_dir().join(format!("rreader-comic-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        let oldest = book(&cache_dir, "a", 100);
+        let open = book(&cache_dir, "b", 100);
+        let newest = book(&cache_dir, "c", 100);
+        let interrupted = cache_dir.join(format!("{}c-1", TMP_PREFIX));
+        fs::create_dir_all(&interrupted).unwrap();
+
+        let lock = ComicArchive::open_lock(&ComicArchive::book_lock_path(&open)).unwrap();
+        lock.lock_shared().unwrap();
+        assert_eq!(ComicArchive::trim_cache(&cache_dir, 250).unwrap(), 1);
+        assert!(!oldest.exists());
+        assert!(open.exists(), "正在打开的书不删");
+        assert!(newest.exists());
+        assert!(!interrupted.exists());
+
+        drop(lock);
+        assert_eq!(ComicArchive::trim_cache(&cache_dir, 150).unwrap(), 1);
+        assert!(!open.exists());
+        assert!(newest.exists());
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+}
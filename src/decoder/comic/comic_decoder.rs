@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::{ImageReader, RgbaImage};
+use log::{debug, info, warn};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::io::Cursor;
+use std::path::Path;
+
+use super::ComicArchive;
+use crate::decoder::{Decoder, Link, PageInfo, Rect};
+use crate::entity::{OutlineItem, ReflowEntry};
+
+/// 页面坐标到像素的比例，和其他解码器的 retina 缩放一致：缩放为 1 时按原图像素显示
+const DPI_SCALE: f32 = 2.0;
+/// 读取图片尺寸时先只读这么多字节的文件头，不够再读整个文件
+const HEADER_PROBE_SIZE: u64 = 256 * 1024;
+/// 作为页面的图片格式
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp"];
+
+/// 漫画压缩包解码器（cbz/cbr/cb7）：压缩包里的每张图片是一页，按文件名自然排序
+pub struct ComicDecoder {
+    archive: ComicArchive,
+    /// 每页对应的压缩包内文件名
+    pages: Vec<String>,
+    pages_info: Vec<PageInfo>,
+    /// 最近解码的一页，预览和全精度、分块渲染反复用同一张图
+    last_image: RefCell<Option<(usize, RgbaImage)>>,
+}
+
+impl ComicDecoder {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let archive = ComicArchive::open(path.as_ref())?;
+        let mut pages: Vec<String> = archive.entries()
+            .iter()
+            .filter(|name| is_page_image(name))
+            .cloned()
+            .collect();
+        pages.sort_by(|a, b| natural_cmp(a, b));
+
+        let mut pages_info = Vec::with_capacity(pages.len());
+        for (index, name) in pages.iter().enumerate() {
+            let (width, height) = Self::probe_size(&archive, name)
+                .with_context(|| format!("无法读取图片尺寸: {}", name))?;
+            pages_info.push(PageInfo::new(index, width as f32 / DPI_SCALE, height as f32 / DPI_SCALE));
+        }
+        info!("[Comic] {} 页", pages.len());
+
+        Ok(Self {
+            archive,
+            pages,
+            pages_info,
+            last_image: RefCell::new(None),
+        })
+    }
+
+    fn probe_size(archive: &ComicArchive, name: &str) -> Result<(u32, u32)> {
+        let header = archive.read_limited(name, HEADER_PROBE_SIZE)?;
+        if let Ok(size) = ImageReader::new(Cursor::new(header)).with_guessed_format()?.into_dimensions() {
+            return Ok(size);
+        }
+        // JPEG 的尺寸可能在很大的 EXIF 之后
+        let data = archive.read(name)?;
+        Ok(ImageReader::new(Cursor::new(data)).with_guessed_format()?.into_dimensions()?)
+    }
+
+    /// 解码一页原图，结果缓存最近一页
+    fn with_image<T>(&self, index: usize, f: impl FnOnce(&RgbaImage) -> T) -> Result<T> {
+        let mut last_image = self.last_image.borrow_mut();
+        if last_image.as_ref().map(|(cached, _)| *cached) != Some(index) {
+            let name = self.pages.get(index).context("Page index out of bounds")?;
+            let data = self.archive.read(name)?;
+            let image = ImageReader::new(Cursor::new(data))
+                .with_guessed_format()?
+                .decode()
+                .with_context(|| format!("图片解码失败: {}", name))?
+                .into_rgba8();
+            *last_image = Some((index, image));
+        }
+        let (_, image) = last_image.as_ref().unwrap();
+        Ok(f(image))
+    }
+
+    /// 把页面坐标中的区域裁出来并缩放到 scale
+    fn render_rect(&self, index: usize, region: Rect, scale: f32) -> Result<(Vec<u8>, u32, u32)> {
+        self.with_image(index, |image| {
+            let left = ((region.left * DPI_SCALE).max(0.0) as u32).min(image.width());
+            let top = ((region.top * DPI_SCALE).max(0.0) as u32).min(image.height());
+            let width = ((region.width() * DPI_SCALE) as u32).min(image.width() - left).max(1);
+            let height = ((region.height() * DPI_SCALE) as u32).min(image.height() - top).max(1);
+            let target_width = ((width as f32 * scale) as u32).max(1);
+            let target_height = ((height as f32 * scale) as u32).max(1);
+            debug!("[Comic] page {} {}x{} -> {}x{}", index, width, height, target_width, target_height);
+
+            let cropped = image::imageops::crop_imm(image, left, top, width, height).to_image();
+            let output = if (target_width, target_height) == (width, height) {
+                cropped
+            } else {
+                image::imageops::resize(&cropped, target_width, target_height, FilterType::Triangle)
+            };
+            (output.into_raw(), target_width, target_height)
+        })
+    }
+}
+
+impl Decoder for ComicDecoder {
+    fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn get_page_size(&self, index: usize) -> Result<(f32, f32)> {
+        let page = self.pages_info.get(index).context("Page index out of bounds")?;
+        Ok((page.width, page.height))
+    }
+
+    fn get_all_pages(&self) -> Result<Vec<PageInfo>> {
+        Ok(self.pages_info.clone())
+    }
+
+    fn render_page(&self, page: &PageInfo, crop: bool) -> Result<(Vec<u8>, u32, u32)> {
+        let bounds = match page.crop_bounds {
+            Some(bounds) if crop => bounds,
+            _ => Rect::new(0.0, 0.0, page.width, page.height),
+        };
+        self.render_rect(page.index, bounds, page.scale)
+    }
+
+    fn render_region(&self, page_index: usize, region: Rect, scale: f32) -> Result<(Vec<u8>, u32, u32)> {
+        self.render_rect(page_index, region, scale)
+    }
+
+    fn get_page_links(&self, page_index: usize) -> Result<Vec<Link>> {
+        Ok(Vec::new())
+    }
+
+    /// 图片没有文字层
+    fn get_page_text(&self, page_index: usize) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn get_outline_items(&self) -> Result<Vec<OutlineItem>> {
+        Ok(Vec::new())
+    }
+
+    fn get_reflow_from_page(&self, start_page: usize) -> Result<Vec<ReflowEntry>> {
+        warn!("[Comic] 漫画不支持 reflow");
+        Ok(Vec::new())
+    }
+
+    fn close(&mut self) {
+        self.last_image.borrow_mut().take();
+    }
+}
+
+/// 图片文件，跳过 macOS 打包时带进来的 __MACOSX 和隐藏文件
+fn is_page_image(name: &str) -> bool {
+    if name.starts_with("__MACOSX/") || name.rsplit('/').next().is_some_and(|file| file.starts_with('.')) {
+        return false;
+    }
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    IMAGE_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// 自然排序：数字按数值比较，page2 排在 page10 前面；其余字符不区分大小写
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_digits(&mut a);
+                let y_digits = take_digits(&mut b);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed.len().cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x_digits.len().cmp(&y_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_order_compares_numbers_by_value() {
+        let mut names = vec!["ch2/p10.jpg", "ch2/p2.jpg", "ch10/p1.jpg", "ch2/P1.jpg", "ch2/p01.jpg"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["ch2/P1.jpg", "ch2/p01.jpg", "ch2/p2.jpg", "ch2/p10.jpg", "ch10/p1.jpg"]);
+    }
+
+    #[test]
+    fn skips_non_images_and_mac_metadata() {
+        assert!(is_page_image("Vol1/001.JPG"));
+        assert!(!is_page_image("ComicInfo.xml"));
+        assert!(!is_page_image("__MACOSX/Vol1/._001.jpg"));
+        assert!(!is_page_image("Vol1/.thumb.png"));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod archive;
+pub mod comic_decoder;
+
+pub use archive::{ArchiveKind, ComicArchive};
+pub use comic_decoder::ComicDecoder;
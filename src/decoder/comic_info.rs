@@ -0,0 +1,128 @@
+use anyhow::Result;
+use regex::Regex;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::decoder::{PageInfo, PageSpread};
+
+/// ComicInfo.xml 最大读取大小，避免异常文件占满内存
+const MAX_XML_SIZE: u64 = 1024 * 1024;
+
+static PAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<Page\b([^>]*?)/?>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).unwrap());
+
+/// ComicInfo.xml 中的一页
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComicPage {
+    pub index: usize,
+    /// 页面类型：FrontCover、Story、BackCover 等
+    pub kind: String,
+    /// 跨页（两页拼成的大图）
+    pub double_page: bool,
+}
+
+impl ComicPage {
+    pub fn is_front_cover(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("FrontCover")
+    }
+}
+
+/// CBZ 内 ComicInfo.xml（ComicRack 格式）的元数据
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComicInfo {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub volume: Option<String>,
+    pub writer: Option<String>,
+    pub penciller: Option<String>,
+    pub publisher: Option<String>,
+    pub year: Option<String>,
+    pub summary: Option<String>,
+    pub pages: Vec<ComicPage>,
+}
+
+impl ComicInfo {
+    /// 读取 CBZ 根目录下的 ComicInfo.xml，没有时返回 None
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        let is_cbz = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("cbz"));
+        if !is_cbz {
+            return Ok(None);
+        }
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        let Some(name) = archive.file_names().find(|name| name.eq_ignore_ascii_case("ComicInfo.xml")).map(str::to_string) else {
+            return Ok(None);
+        };
+        let mut xml = String::new();
+        archive.by_name(&name)?.take(MAX_XML_SIZE).read_to_string(&mut xml)?;
+        Ok(Some(Self::parse(&xml)))
+    }
+
+    /// 解析 XML 文本，未知的元素忽略
+    pub fn parse(xml: &str) -> Self {
+        let pages = PAGE.captures_iter(xml)
+            .filter_map(|c| {
+                let attributes: Vec<(String, String)> = ATTRIBUTE.captures_iter(&c[1])
+                    .map(|a| (a[1].to_string(), unescape(&a[2])))
+                    .collect();
+                let attribute = |key: &str| attributes.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+                Some(ComicPage {
+                    index: attribute("Image")?.parse().ok()?,
+                    kind: attribute("Type").unwrap_or("Story").to_string(),
+                    double_page: attribute("DoublePage").is_some_and(|v| v.eq_ignore_ascii_case("true")),
+                })
+            })
+            .collect();
+
+        Self {
+            title: element(xml, "Title"),
+            series: element(xml, "Series"),
+            number: element(xml, "Number"),
+            volume: element(xml, "Volume"),
+            writer: element(xml, "Writer"),
+            penciller: element(xml, "Penciller"),
+            publisher: element(xml, "Publisher"),
+            year: element(xml, "Year"),
+            summary: element(xml, "Summary"),
+            pages,
+        }
+    }
+
+    /// 卷号：优先 Volume，其次 Number
+    pub fn volume_number(&self) -> Option<f32> {
+        [&self.volume, &self.number]
+            .into_iter()
+            .flatten()
+            .find_map(|value| value.trim().parse::<f32>().ok())
+    }
+
+    /// 按页面类型标记双页模式下的排列：跨页占满一行，封面单独一行
+    pub fn apply_page_spreads(&self, pages: &mut [PageInfo]) {
+        for comic_page in &self.pages {
+            let Some(page) = pages.get_mut(comic_page.index) else { continue };
+            if comic_page.double_page {
+                page.spread = PageSpread::Double;
+            } else if comic_page.is_front_cover() {
+                page.spread = PageSpread::Alone;
+            }
+        }
+    }
+}
+
+/// 读取简单元素的文本，空元素返回 None
+fn element(xml: &str, name: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", name))?;
+    let value = unescape(xml[start..end].trim());
+    (!value.is_empty()).then_some(value)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -1,4 +1,3 @@
-use anyhow::Result;
 use log::{debug, info};
 use std::path::{Path, PathBuf};
 use crossbeam_channel::{unbounded, Sender, Receiver};
@@ -6,13 +5,27 @@ use std::sync::Mutex;
 use std::thread::{self, JoinHandle};
 use std::time::{ Instant, Duration};
 use std::hash::{Hash, Hasher};
-use std::collections::{hash_map::DefaultHasher, VecDeque, HashSet};
+use std::collections::{hash_map::DefaultHasher, HashSet};
 use std::fs;
 
-use crate::decoder::pdf::PdfDecoder;
-use crate::decoder::{Decoder, Link, PageInfo, Rect};
+use crate::app_paths;
+use crate::cache::{PageMeta, PageMetaCache};
+use crate::decoder::{blank, deskew, formats};
+use crate::decoder::{ComicInfo, Decoder, DocumentMetadata, Link, OutlineTree, PageInfo, Rect, RenderPool, TextBlock};
+use crate::decoder::outline_tree::INITIAL_OUTLINE_DEPTH;
+use crate::entity::DocumentProperty;
+use crate::error::{RReaderError, Result};
+use crate::reflow::{ReflowCache, ReflowJob, ReflowPipeline};
+use crate::settings::AppSettings;
+use crate::storage::FileStore;
 use crate::ui::utils::generate_thumbnail_hash;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// 渐进渲染时预览图相对全精度的缩放
+const PREVIEW_SCALE: f32 = 0.25;
+/// 全精度渲染超过这个像素数（页面坐标 × 缩放）才先出预览，小页面直接渲染已经够快
+const PREVIEW_MIN_PIXELS: f32 = 1_000_000.0;
 
 /// 可见性检查回调类型：传入页面索引，返回是否可见
 pub type VisibilityChecker = Arc<dyn Fn(usize) -> bool + Send + Sync>;
@@ -24,6 +37,8 @@ pub struct RenderPage {
     pub page_info: PageInfo,
     pub crop: i32,
     pub priority: Priority,
+    /// 提交时的渲染代数，缩放、切边等变化后旧代数的任务作废
+    pub generation: u64,
     /// 可见性检查回调：传入页面bounds，返回是否可见
     pub visibility_checker: Option<VisibilityChecker>,
 }
@@ -35,6 +50,7 @@ impl std::fmt::Debug for RenderPage {
             .field("page_info", &self.page_info)
             .field("crop", &self.crop)
             .field("priority", &self.priority)
+            .field("generation", &self.generation)
             .field("has_visibility_checker", &self.visibility_checker.is_some())
             .finish()
     }
@@ -69,20 +85,90 @@ pub enum DecodeTask {
     RenderPages {
         pages: Vec<RenderPage>,
     },
-    /// 获取大纲
+    /// 作废代数小于 generation 的渲染任务
+    CancelGeneration {
+        generation: u64,
+    },
+    /// 获取大纲的前几层，完整大纲缓存在解码线程
     GetOutline {
         response_tx: Sender<Result<Vec<crate::entity::OutlineItem>>>,
     },
+    /// 获取大纲节点的直接子节点，parent 为 OutlineItem::id
+    GetOutlineChildren {
+        parent: usize,
+        response_tx: Sender<Result<Vec<crate::entity::OutlineItem>>>,
+    },
+    /// 在完整大纲中按标题过滤
+    FilterOutline {
+        query: String,
+        response_tx: Sender<Result<Vec<crate::entity::OutlineItem>>>,
+    },
+    /// 获取页码标签
+    GetPageLabels {
+        response_tx: Sender<Result<Vec<String>>>,
+    },
     /// 获取页面文本
     GetPageText {
         page_index: usize,
         response_tx: Sender<Result<String>>,
     },
+    /// 全文搜索，只返回有匹配的页
+    SearchText {
+        query: String,
+        response_tx: Sender<Result<Vec<PageMatches>>>,
+    },
+    /// 获取页面一块区域（页面坐标）内的文字，按块和行的顺序
+    GetRegionText {
+        page_index: usize,
+        region: Rect,
+        response_tx: Sender<Result<String>>,
+    },
+    /// 查找页面某一点（页面坐标）下的图片和单词
+    HitTest {
+        page_index: usize,
+        x: f32,
+        y: f32,
+        response_tx: Sender<Result<crate::decoder::PageHit>>,
+    },
+    /// 渲染页面上的一块区域（页面坐标），用于保存图片
+    RenderRegion {
+        page_index: usize,
+        region: crate::decoder::Rect,
+        scale: f32,
+        response_tx: Sender<Result<(Vec<u8>, u32, u32)>>,
+    },
     /// 解析reflow数据（从指定页面开始的后续页面）
     ExtractReflowData {
         start_page: usize,
         response_tx: Sender<Result<Vec<crate::entity::ReflowEntry>>>,
     },
+    /// 获取文档属性
+    GetDocumentProperties {
+        response_tx: Sender<Result<Vec<crate::entity::DocumentProperty>>>,
+    },
+    /// 获取文档自带的标题、作者、主题
+    GetDocumentMetadata {
+        response_tx: Sender<Result<DocumentMetadata>>,
+    },
+    /// 流式提取reflow数据，每提取一页就通过 entry_tx 推送
+    StreamReflow {
+        start_page: usize,
+        entry_tx: Sender<crate::entity::ReflowEntry>,
+    },
+    /// 停止推送当前的流式reflow（后台继续补齐缓存）
+    CancelReflow,
+    /// 开关扫描页纠偏，之后渲染的页面生效
+    SetDeskew {
+        enabled: bool,
+    },
+    /// 预览：用临时解码器渲染另一个文档的前几页，不影响当前文档
+    RenderPreview {
+        path: PathBuf,
+        page_count: usize,
+        /// 预览图最长边像素
+        max_size: f32,
+        response_tx: Sender<Result<Vec<PreviewImage>>>,
+    },
     /// 关闭服务
     Shutdown,
 }
@@ -95,98 +181,270 @@ pub struct DecodeResult {
     pub image_width: u32,
     pub image_height: u32,
     pub links: Vec<Link>,
+    /// 低精度预览，随后会有同一个 key 的全精度结果替换
+    pub preview: bool,
+}
+
+/// 渲染失败的页面，界面线程据此降低缩放
+#[derive(Debug, Clone)]
+pub struct RenderFailure {
+    pub page_index: usize,
+    pub scale: f32,
+    pub message: String,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// 一页上的搜索结果，范围为页面坐标
+#[derive(Debug, Clone)]
+pub struct PageMatches {
+    pub page_index: usize,
+    pub rects: Vec<Rect>,
+}
+
+/// 预览渲染结果
+pub struct PreviewImage {
+    pub page_index: usize,
+    pub image_data: Vec<u8>,
+    pub image_width: u32,
+    pub image_height: u32,
+}
+
+/// 渲染优先级，数值小的先渲染
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Priority {
-    Thumbnail = 0, // 最高优先级
-    FullImage = 1, // 中优先级
-    Cropped = 2,   // 低优先级
+    Focus = 0,     // 指针或视口中心所在的页，最先渲染
+    Thumbnail = 1, // 其他屏幕上的页面
+    FullImage = 2, // 中优先级
+    Cropped = 3,   // 低优先级
+}
+
+/// 解码线程的后台工作量，供状态栏显示
+#[derive(Default)]
+pub struct BackgroundActivity {
+    /// 排队中的渲染任务数
+    pub pending_renders: AtomicUsize,
+    /// 是否正在提取reflow文本
+    pub reflow_running: AtomicBool,
+}
+
+/// 渲染后的逐页处理：页面元数据缓存（纠偏角度、空白页标记）和纠偏开关
+#[derive(Default)]
+struct PostProcess {
+    page_meta: Option<PageMetaCache>,
+    deskew: bool,
+}
+
+/// 解码线程和渲染线程共用的输出通道和渲染后处理状态
+#[derive(Clone)]
+pub(crate) struct RenderContext {
+    result_tx: Sender<DecodeResult>,
+    failure_tx: Sender<RenderFailure>,
+    generation: Arc<AtomicU64>,
+    post_process: Arc<Mutex<PostProcess>>,
+    blank_pages: Arc<Mutex<HashSet<usize>>>,
+    /// 屏幕上的大页面先出低精度预览再出全精度图像
+    progressive: bool,
+}
+
+/// 渲染线程总数（含解码线程），设置为 0 时按 CPU 核数取一半，最多 4 个。
+/// 省电配置下额外的渲染线程由 RenderPool 暂停
+fn render_thread_count() -> usize {
+    match AppSettings::get().memory.render_threads {
+        0 => {
+            let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            (cores / 2).clamp(1, 4)
+        }
+        count => count,
+    }
 }
 
-/// 解码服务 - 单线程解码，通过channel通信
+/// 解码服务 - 解码线程处理各类任务并参与渲染，渲染线程池并行渲染页面，通过channel通信
 pub struct DecodeService {
     task_sender: Sender<DecodeTask>,
     result_receiver: Mutex<Receiver<DecodeResult>>,
+    failure_receiver: Mutex<Receiver<RenderFailure>>,
     load_result_sender: Sender<Result<Vec<PageInfo>>>,
     load_result_receiver: Mutex<Receiver<Result<Vec<PageInfo>>>>,
     decode_thread: Option<JoinHandle<()>>,
+    activity: Arc<BackgroundActivity>,
+    /// 当前文档中检测到的空白页，解码线程写入
+    blank_pages: Arc<Mutex<HashSet<usize>>>,
+    /// 当前渲染代数，解码线程据此丢弃正在渲染的过期页面
+    generation: Arc<AtomicU64>,
 }
 
 impl DecodeService {
     /// 保存封面缩略图
-    fn save_cover_thumbnail(path: &Path, dec: &Box<dyn Decoder>, first_page: &PageInfo) {
+    pub(crate) fn save_cover_thumbnail(path: &Path, dec: &Box<dyn Decoder>, first_page: &PageInfo) {
         let path_str = path.to_string_lossy();
         let hash = generate_thumbnail_hash(&path_str);
-        if let Some(data_dir) = dirs::data_dir() {
-            let cache_dir = data_dir.join("RReader").join("images");
-            let cache_path = cache_dir.join(format!("{}.png", hash));
-            if cache_path.exists() {
-                info!("Cover thumbnail already exists: {:?}", cache_path);
-                return;
+        let cache_dir = app_paths::thumbnail_dir();
+        let cache_path = cache_dir.join(format!("{}.png", hash));
+        if cache_path.exists() {
+            info!("Cover thumbnail already exists: {:?}", cache_path);
+            return;
+        }
+        // 计算缩放到最大 300 像素的 scale
+        let max_original = first_page.width.max(first_page.height);
+        let effective_scale = 300.0 / max_original;
+        let new_page_info = PageInfo {
+            index: first_page.index,
+            width: first_page.width,
+            height: first_page.height,
+            scale: effective_scale / 2.0, // 因为内部会乘以 2.0 (DPI scale)
+            crop_bounds: first_page.crop_bounds,
+            spread: first_page.spread,
+        };
+        match dec.render_page(&new_page_info, false) {
+            Ok((pixels, width, height)) => {
+                let rgba_img = image::RgbaImage::from_raw(width, height, pixels).unwrap();
+                let image = image::DynamicImage::ImageRgba8(rgba_img);
+                let mut png = std::io::Cursor::new(Vec::new());
+                if fs::create_dir_all(&cache_dir).is_ok()
+                    && image.write_to(&mut png, image::ImageFormat::Png).is_ok()
+                    && FileStore::write(&cache_path, png.into_inner()).is_ok() {
+                    info!("Saved thumbnail to {:?}", cache_path);
+                }
             }
-            // 计算缩放到最大 300 像素的 scale
-            let max_original = first_page.width.max(first_page.height);
-            let effective_scale = 300.0 / max_original;
-            let new_page_info = PageInfo {
-                index: first_page.index,
-                width: first_page.width,
-                height: first_page.height,
-                scale: effective_scale / 2.0, // 因为内部会乘以 2.0 (DPI scale)
-                crop_bounds: first_page.crop_bounds,
-            };
-            match dec.render_page(&new_page_info, false) {
-                Ok((pixels, width, height)) => {
-                    let rgba_img = image::RgbaImage::from_raw(width, height, pixels).unwrap();
-                    let image = image::DynamicImage::ImageRgba8(rgba_img);
-                    if fs::create_dir_all(&cache_dir).is_ok()
-                        && image.save(&cache_path).is_ok() {
-                        info!("Saved thumbnail to {:?}", cache_path);
+            Err(e) => {
+                info!("Failed to render cover: {}", e);
+            }
+        }
+    }
+}
+
+impl DecodeService {
+    /// 收集文档属性，语言统计来自已有的reflow缓存
+    fn collect_properties(dec: &dyn Decoder, path: &Path) -> Vec<DocumentProperty> {
+        let mut properties = vec![
+            DocumentProperty::new("路径", path.to_string_lossy()),
+            DocumentProperty::new("页数", dec.page_count().to_string()),
+        ];
+        if let Ok(metadata) = fs::metadata(path) {
+            properties.push(DocumentProperty::new("大小", format!("{:.2} MB", metadata.len() as f64 / 1024.0 / 1024.0)));
+        }
+        let metadata = dec.metadata();
+        for (name, value) in [("标题", metadata.title), ("作者", metadata.author), ("主题", metadata.subject)] {
+            if !value.is_empty() {
+                properties.push(DocumentProperty::new(name, value));
+            }
+        }
+
+        let languages = match ReflowCache::load_existing(path) {
+            Some(cache) => {
+                let mut counts: Vec<(String, usize)> = Vec::new();
+                for entry in cache.entries() {
+                    let language = entry.language.clone().unwrap_or_else(|| "?".to_string());
+                    match counts.iter_mut().find(|(lang, _)| *lang == language) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((language, 1)),
                     }
                 }
-                Err(e) => {
-                    info!("Failed to render cover: {}", e);
+                counts.sort_by(|a, b| b.1.cmp(&a.1));
+                let summary = counts.iter()
+                    .map(|(lang, count)| format!("{} ({}页)", lang, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if cache.is_complete() { summary } else { format!("{} (提取中)", summary) }
+            }
+            None => "未提取".to_string(),
+        };
+        properties.push(DocumentProperty::new("语言", languages));
+
+        if let Ok(Some(comic_info)) = ComicInfo::read(path) {
+            let fields = [
+                ("标题", &comic_info.title),
+                ("系列", &comic_info.series),
+                ("卷", &comic_info.volume),
+                ("话", &comic_info.number),
+                ("作者", &comic_info.writer),
+                ("作画", &comic_info.penciller),
+                ("出版社", &comic_info.publisher),
+                ("年份", &comic_info.year),
+                ("简介", &comic_info.summary),
+            ];
+            for (name, value) in fields {
+                if let Some(value) = value {
+                    properties.push(DocumentProperty::new(name, value.clone()));
                 }
             }
         }
+        properties
     }
-}
 
-impl DecodeService {
     pub fn new() -> Self {
         let (task_tx, task_rx) = unbounded::<DecodeTask>();
         let (result_tx, result_rx) = unbounded::<DecodeResult>();
+        let (failure_tx, failure_rx) = unbounded::<RenderFailure>();
         let (load_result_tx, load_result_rx) = unbounded::<Result<Vec<PageInfo>>>();
 
         // 启动解码线程
         let load_result_tx_for_thread = load_result_tx.clone();
+        let activity = Arc::new(BackgroundActivity::default());
+        let activity_for_thread = Arc::clone(&activity);
+        let blank_pages = Arc::new(Mutex::new(HashSet::new()));
+        let blank_pages_for_thread = Arc::clone(&blank_pages);
+        let generation = Arc::new(AtomicU64::new(0));
+        let generation_for_thread = Arc::clone(&generation);
         let decode_thread = thread::spawn(move || {
-            Self::decode_loop(task_rx, result_tx, load_result_tx_for_thread);
+            Self::decode_loop(task_rx, result_tx, failure_tx, load_result_tx_for_thread, activity_for_thread, blank_pages_for_thread, generation_for_thread);
         });
 
         Self {
             task_sender: task_tx,
             result_receiver: Mutex::new(result_rx),
+            failure_receiver: Mutex::new(failure_rx),
             load_result_sender: load_result_tx,
             load_result_receiver: Mutex::new(load_result_rx),
             decode_thread: Some(decode_thread),
+            activity,
+            blank_pages,
+            generation,
         }
     }
 
     /// 解码线程主循环
-    fn decode_loop(task_rx: Receiver<DecodeTask>, result_tx: Sender<DecodeResult>, load_result_tx: Sender<Result<Vec<PageInfo>>>) {
+    fn decode_loop(
+        task_rx: Receiver<DecodeTask>,
+        result_tx: Sender<DecodeResult>,
+        failure_tx: Sender<RenderFailure>,
+        load_result_tx: Sender<Result<Vec<PageInfo>>>,
+        activity: Arc<BackgroundActivity>,
+        blank_pages: Arc<Mutex<HashSet<usize>>>,
+        generation: Arc<AtomicU64>,
+    ) {
         let mut decoder: Option<Box<dyn Decoder>> = None;
-        let mut task_queue: VecDeque<RenderPage> = VecDeque::new();
+        let mut document_path: Option<PathBuf> = None;
         let mut current_visible: HashSet<RenderPage> = HashSet::new();
+        let mut reflow_job: Option<ReflowJob> = None;
+        let post_process = Arc::new(Mutex::new(PostProcess::default()));
+        let mut outline: Option<OutlineTree> = None;
+        let render_context = RenderContext {
+            result_tx,
+            failure_tx,
+            generation,
+            post_process: Arc::clone(&post_process),
+            blank_pages: Arc::clone(&blank_pages),
+            progressive: AppSettings::get().memory.progressive_render,
+        };
+        // 解码线程本身也渲染，另外再开 N-1 个渲染线程；随解码线程退出而停止
+        let render_pool = RenderPool::start(render_thread_count().saturating_sub(1), render_context.clone());
 
         loop {
+            activity.pending_renders.store(render_pool.len(), Ordering::Relaxed);
+            activity.reflow_running.store(reflow_job.is_some(), Ordering::Relaxed);
+
             // 1. 先检查是否有新任务（非阻塞）
             while let Ok(task) = task_rx.try_recv() {
                 if Self::handle_task(
                     task,
                     &mut decoder,
-                    &mut task_queue,
+                    &mut document_path,
+                    &render_pool,
                     &mut current_visible,
+                    &mut reflow_job,
+                    &post_process,
+                    &mut outline,
+                    &blank_pages,
                     &load_result_tx,
                 ) {
                     // 收到 Shutdown 信号
@@ -194,71 +452,56 @@ impl DecodeService {
                 }
             }
 
-            // 2. 处理队列中的一个任务
-            if let Some(render_page) = task_queue.pop_front() {
-                // 使用回调验证页面是否可见
-                let is_visible = if let Some(ref checker) = render_page.visibility_checker {
-                    checker(render_page.page_info.index)
-                } else {
-                    // 如果没有回调，回退到旧的检查方式
-                    current_visible.contains(&render_page)
+            // 2. 和渲染线程一起从共享队列中取一个任务
+            if let Some(render_page) = render_pool.pop() {
+                // 使用回调验证页面是否可见，没有回调时回退到旧的检查方式
+                let is_visible = match render_page.visibility_checker {
+                    Some(ref checker) => checker(render_page.page_info.index),
+                    None => current_visible.contains(&render_page),
                 };
-
                 if !is_visible {
-                    info!("跳过不可见页: page={}, key={}", 
+                    info!("跳过不可见页: page={}, key={}",
                         render_page.page_info.index, render_page.key);
-                    // 继续处理下一个任务
                     continue;
                 }
-
-                // 执行解码
                 if let Some(ref dec) = decoder {
-                    let start_time = Instant::now();
-                    
-                    match dec.render_page(&render_page.page_info, render_page.crop != 0) {
-                        Ok((image_data, width, height)) => {
-                            //std::thread::sleep(std::time::Duration::from_secs(2));
-                            let links = dec.get_page_links(render_page.page_info.index)
-                                .unwrap_or_default();
-
-                            let duration = start_time.elapsed();
-                            info!(
-                                "页面 {} 解码完成，耗时: {:?}, links: {}",
-                                render_page.page_info.index, duration, links.len()
-                            );
-
-                            let result = DecodeResult {
-                                key: render_page.key.clone(),
-                                page_info: render_page.page_info.clone(),
-                                image_data,
-                                image_width: width,
-                                image_height: height,
-                                links,
-                            };
-
-                            if result_tx.send(result).is_err() {
-                                info!("Result channel closed");
-                                return;
-                            }
-                        }
-                        Err(e) => {
-                            info!("页面 {} 解码失败: {}", render_page.page_info.index, e);
-                        }
+                    if !Self::render_one(dec.as_ref(), render_page, &render_context) {
+                        info!("Result channel closed");
+                        return;
                     }
                 }
-                
+
                 // 解码完一个任务后，继续下一个循环（会先检查新任务）
                 continue;
             }
 
-            // 3. 队列为空，阻塞等待新任务
+            // 3. 渲染队列空闲时，推进一页reflow提取，不阻塞渲染
+            if let Some(ref mut job) = reflow_job {
+                let running = match decoder {
+                    Some(ref dec) => job.step(dec.as_ref()),
+                    None => false,
+                };
+                if !running {
+                    reflow_job = None;
+                }
+                continue;
+            }
+
+            // 4. 队列为空，阻塞等待新任务
+            activity.pending_renders.store(0, Ordering::Relaxed);
+            activity.reflow_running.store(false, Ordering::Relaxed);
             match task_rx.recv() {
                 Ok(task) => {
                     if Self::handle_task(
                         task,
                         &mut decoder,
-                        &mut task_queue,
+                        &mut document_path,
+                        &render_pool,
                         &mut current_visible,
+                        &mut reflow_job,
+                        &post_process,
+                        &mut outline,
+                        &blank_pages,
                         &load_result_tx,
                     ) {
                         // 收到 Shutdown 信号
@@ -277,18 +520,43 @@ impl DecodeService {
     fn handle_task(
         task: DecodeTask,
         decoder: &mut Option<Box<dyn Decoder>>,
-        task_queue: &mut VecDeque<RenderPage>,
+        document_path: &mut Option<PathBuf>,
+        render_pool: &RenderPool,
         current_visible: &mut HashSet<RenderPage>,
+        reflow_job: &mut Option<ReflowJob>,
+        post_process: &Mutex<PostProcess>,
+        outline: &mut Option<OutlineTree>,
+        blank_pages: &Mutex<HashSet<usize>>,
         load_result_tx: &Sender<Result<Vec<PageInfo>>>,
     ) -> bool {
         match task {
             DecodeTask::LoadDocument { path } => {
                 info!("Loading document: {:?}", path);
-                match PdfDecoder::open(&path) {
-                    Ok(pdf_decoder) => {
-                        info!("PdfDecoder::open 成功");
-                        let boxed_decoder = Box::new(pdf_decoder);
-                        let pages_result = boxed_decoder.get_all_pages();
+                *reflow_job = None;
+                *outline = None;
+                *document_path = Some(path.clone());
+                render_pool.set_document(&path);
+                {
+                    let mut post_process = post_process.lock().unwrap();
+                    post_process.deskew = AppSettings::book(&path.to_string_lossy()).deskew;
+                    post_process.page_meta = PageMetaCache::open(&path).ok();
+                    let mut blank_pages = blank_pages.lock().unwrap();
+                    blank_pages.clear();
+                    if let Some(ref meta) = post_process.page_meta {
+                        blank_pages.extend(meta.blank_pages());
+                    }
+                }
+                match formats::open_decoder(&path) {
+                    Ok(boxed_decoder) => {
+                        info!("open_decoder 成功");
+                        let mut pages_result = boxed_decoder.get_all_pages().map_err(RReaderError::from);
+                        if let Ok(ref mut pages) = pages_result {
+                            match ComicInfo::read(&path) {
+                                Ok(Some(comic_info)) => comic_info.apply_page_spreads(pages),
+                                Ok(None) => {}
+                                Err(e) => info!("[ComicInfo] 读取失败: {}", e),
+                            }
+                        }
                         *decoder = Some(boxed_decoder);
                         let first_page = if let Ok(ref pages) = pages_result {
                             if !pages.is_empty() {
@@ -307,8 +575,8 @@ impl DecodeService {
                         }
                     }
                     Err(e) => {
-                        info!("PdfDecoder::open 失败: {}", e);
-                        let _ = load_result_tx.send(Err(e));
+                        info!("open_decoder 失败: {}", e);
+                        let _ = load_result_tx.send(Err(e.into()));
                     }
                 }
                 false
@@ -320,48 +588,167 @@ impl DecodeService {
                 current_visible.clear();
                 current_visible.extend(pages.iter().cloned());
 
-                // 2. 将新任务加入队列（去重：检查队列中是否已存在相同key的任务）
-                for page in pages {
-                    let already_queued = task_queue.iter().any(|p| p.key == page.key);
-                    if !already_queued {
-                        debug!("加入队列: page={}, key={}", page.page_info.index, page.key);
-                        task_queue.push_back(page);
-                    } else {
-                        info!("跳过重复任务: page={}, key={}", page.page_info.index, page.key);
-                    }
+                // 2. 将新任务按优先级加入队列，已在队列中的页面按这一批重新排序
+                let added = render_pool.push_batch(pages);
+
+                info!("当前队列长度: {}, 新加入: {}, 可见页数: {}",
+                    render_pool.len(), added, current_visible.len());
+                false
+            }
+            DecodeTask::CancelGeneration { generation } => {
+                let removed = render_pool.retain(|page| page.generation >= generation);
+                info!("作废旧渲染任务: generation={}, 移除 {} 个", generation, removed);
+                false
+            }
+            DecodeTask::GetPageLabels { response_tx } => {
+                if let Some(ref dec) = decoder {
+                    let _ = response_tx.send(dec.get_page_labels().map_err(Into::into));
+                } else {
+                    let _ = response_tx.send(Ok(Vec::new()));
                 }
-                
-                info!("当前队列长度: {}, 可见页数: {}", 
-                    task_queue.len(), current_visible.len());
                 false
             }
             DecodeTask::GetOutline { response_tx } => {
                 if let Some(ref dec) = decoder {
-                    let outline_result = dec.get_outline_items();
-                    let _ = response_tx.send(outline_result);
+                    let outline_result = dec.get_outline_items().map(|items| {
+                        let tree = OutlineTree::new(items);
+                        let top = tree.top_levels(INITIAL_OUTLINE_DEPTH);
+                        info!("大纲共 {} 项，先加载 {} 项", tree.len(), top.len());
+                        *outline = Some(tree);
+                        top
+                    });
+                    let _ = response_tx.send(outline_result.map_err(Into::into));
                 } else {
                     let _ = response_tx.send(Ok(Vec::new()));
                 }
                 false
             }
+            DecodeTask::GetOutlineChildren { parent, response_tx } => {
+                let children = outline.as_ref().map(|tree| tree.children(parent)).unwrap_or_default();
+                let _ = response_tx.send(Ok(children));
+                false
+            }
+            DecodeTask::FilterOutline { query, response_tx } => {
+                let matches = outline.as_ref().map(|tree| tree.filter(&query)).unwrap_or_default();
+                let _ = response_tx.send(Ok(matches));
+                false
+            }
             DecodeTask::GetPageText { page_index, response_tx } => {
                 if let Some(ref dec) = decoder {
-                    let text_result = dec.get_page_text(page_index);
+                    let text_result = dec.get_page_text(page_index).map_err(Into::into);
+                    let _ = response_tx.send(text_result);
+                } else {
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                }
+                false
+            }
+            DecodeTask::SearchText { query, response_tx } => {
+                let Some(ref dec) = decoder else {
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                    return false;
+                };
+                let start = Instant::now();
+                let mut matches = Vec::new();
+                for page_index in 0..dec.page_count() {
+                    match dec.search_page(page_index, &query) {
+                        Ok(rects) if !rects.is_empty() => matches.push(PageMatches { page_index, rects }),
+                        Ok(_) => {}
+                        Err(e) => debug!("页面 {} 搜索失败: {}", page_index, e),
+                    }
+                }
+                info!("搜索 {:?}: {} 页有匹配，耗时 {:?}", query, matches.len(), start.elapsed());
+                let _ = response_tx.send(Ok(matches));
+                false
+            }
+            DecodeTask::GetRegionText { page_index, region, response_tx } => {
+                if let Some(ref dec) = decoder {
+                    let text_result = dec.get_page_text_blocks(page_index)
+                        .map(|blocks| Self::region_text(&blocks, &region))
+                        .map_err(Into::into);
                     let _ = response_tx.send(text_result);
                 } else {
-                    let _ = response_tx.send(Err(anyhow::anyhow!("No decoder")));
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                }
+                false
+            }
+            DecodeTask::HitTest { page_index, x, y, response_tx } => {
+                if let Some(ref dec) = decoder {
+                    let _ = response_tx.send(dec.hit_test(page_index, x, y).map_err(Into::into));
+                } else {
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                }
+                false
+            }
+            DecodeTask::RenderRegion { page_index, region, scale, response_tx } => {
+                if let Some(ref dec) = decoder {
+                    let _ = response_tx.send(dec.render_region(page_index, region, scale).map_err(Into::into));
+                } else {
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
                 }
                 false
             }
             DecodeTask::ExtractReflowData { start_page, response_tx } => {
                 if let Some(ref dec) = decoder {
-                    let reflow_result = dec.get_reflow_from_page(start_page);
+                    let settings = document_path.as_ref()
+                        .map(|p| AppSettings::book(&p.to_string_lossy()))
+                        .unwrap_or_default();
+                    let mut pipeline = ReflowPipeline::new(&settings, &AppSettings::get().tts);
+                    let reflow_result = dec.get_reflow_from_page(start_page)
+                        .map(|entries| entries.into_iter().filter_map(|e| pipeline.process(e)).collect())
+                        .map_err(Into::into);
                     let _ = response_tx.send(reflow_result);
                 } else {
-                    let _ = response_tx.send(Err(anyhow::anyhow!("No decoder")));
+                    let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                }
+                false
+            }
+            DecodeTask::GetDocumentProperties { response_tx } => {
+                match (decoder.as_ref(), document_path.as_ref()) {
+                    (Some(dec), Some(path)) => {
+                        let _ = response_tx.send(Ok(Self::collect_properties(dec.as_ref(), path)));
+                    }
+                    _ => {
+                        let _ = response_tx.send(Err(RReaderError::decode("No decoder")));
+                    }
+                }
+                false
+            }
+            DecodeTask::GetDocumentMetadata { response_tx } => {
+                let result = decoder.as_ref()
+                    .map(|dec| dec.metadata())
+                    .ok_or_else(|| RReaderError::decode("No decoder"));
+                let _ = response_tx.send(result);
+                false
+            }
+            DecodeTask::StreamReflow { start_page, entry_tx } => {
+                match (decoder.as_ref(), document_path.as_ref()) {
+                    (Some(dec), Some(path)) => {
+                        match ReflowJob::new(path, dec.page_count(), start_page, entry_tx) {
+                            Ok(job) => *reflow_job = Some(job),
+                            Err(e) => info!("[Reflow] 创建提取任务失败: {}", e),
+                        }
+                    }
+                    _ => {
+                        info!("[Reflow] No decoder");
+                    }
+                }
+                false
+            }
+            DecodeTask::CancelReflow => {
+                if let Some(ref mut job) = reflow_job {
+                    job.detach();
                 }
                 false
             }
+            DecodeTask::SetDeskew { enabled } => {
+                info!("[Deskew] enabled={}", enabled);
+                post_process.lock().unwrap().deskew = enabled;
+                false
+            }
+            DecodeTask::RenderPreview { path, page_count, max_size, response_tx } => {
+                let _ = response_tx.send(Self::render_preview_pages(&path, page_count, max_size));
+                false
+            }
             DecodeTask::Shutdown => {
                 info!("Shutting down decode thread");
                 true
@@ -369,13 +756,196 @@ impl DecodeService {
         }
     }
 
+    /// 渲染一页并发送结果，结果通道已关闭时返回 false
+    pub(crate) fn render_one(dec: &dyn Decoder, render_page: RenderPage, context: &RenderContext) -> bool {
+        if render_page.generation < context.generation.load(Ordering::Relaxed) {
+            debug!("跳过过期任务: page={}, generation={}", render_page.page_info.index, render_page.generation);
+            return true;
+        }
+        if context.progressive && Self::wants_preview(&render_page) {
+            Self::render_preview(dec, &render_page, context);
+        }
+        let start_time = Instant::now();
+        match dec.render_page(&render_page.page_info, render_page.crop != 0) {
+            Ok((image_data, width, height)) => {
+                let image_data = Self::post_process_page(
+                    &context.post_process,
+                    &context.blank_pages,
+                    render_page.page_info.index,
+                    image_data,
+                    width,
+                    height,
+                );
+                let links = dec.get_page_links(render_page.page_info.index)
+                    .unwrap_or_default();
+
+                let duration = start_time.elapsed();
+                // 渲染期间缩放或切边已变化，结果不再需要
+                if render_page.generation < context.generation.load(Ordering::Relaxed) {
+                    info!("丢弃过期渲染结果: page={}, 耗时: {:?}", render_page.page_info.index, duration);
+                    return true;
+                }
+                info!(
+                    "页面 {} 解码完成，耗时: {:?}, links: {}",
+                    render_page.page_info.index, duration, links.len()
+                );
+
+                let result = DecodeResult {
+                    key: render_page.key,
+                    page_info: render_page.page_info,
+                    image_data,
+                    image_width: width,
+                    image_height: height,
+                    links,
+                    preview: false,
+                };
+                context.result_tx.send(result).is_ok()
+            }
+            Err(e) => {
+                info!("页面 {} 解码失败: {}", render_page.page_info.index, e);
+                let _ = context.failure_tx.send(RenderFailure {
+                    page_index: render_page.page_info.index,
+                    scale: render_page.page_info.scale,
+                    message: e.to_string(),
+                });
+                true
+            }
+        }
+    }
+
+    /// 只给屏幕上、全精度渲染较慢的页面出预览，预加载的页面不在屏幕上，直接出全精度
+    fn wants_preview(render_page: &RenderPage) -> bool {
+        let page = &render_page.page_info;
+        let crop = render_page.crop != 0;
+        let pixels = page.get_width(crop) * page.get_height(crop) * page.scale * page.scale;
+        render_page.priority <= Priority::Thumbnail && pixels >= PREVIEW_MIN_PIXELS
+    }
+
+    /// 按 PREVIEW_SCALE 快速渲染一遍并立即送出，跳过空白页检测、纠偏和链接
+    fn render_preview(dec: &dyn Decoder, render_page: &RenderPage, context: &RenderContext) {
+        let mut page_info = render_page.page_info.clone();
+        page_info.scale *= PREVIEW_SCALE;
+        let start_time = Instant::now();
+        let (image_data, width, height) = match dec.render_page(&page_info, render_page.crop != 0) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                // 全精度渲染还会再试一次，失败由它上报
+                debug!("页面 {} 预览渲染失败: {}", page_info.index, e);
+                return;
+            }
+        };
+        if render_page.generation < context.generation.load(Ordering::Relaxed) {
+            return;
+        }
+        debug!("页面 {} 预览完成，耗时: {:?}", page_info.index, start_time.elapsed());
+        let _ = context.result_tx.send(DecodeResult {
+            key: render_page.key.clone(),
+            page_info: render_page.page_info.clone(),
+            image_data,
+            image_width: width,
+            image_height: height,
+            links: Vec::new(),
+            preview: true,
+        });
+    }
+
+    /// 区域内的文字：块之间空一行，块内一行一行
+    fn region_text(blocks: &[TextBlock], region: &Rect) -> String {
+        blocks.iter()
+            .map(|block| {
+                block.lines.iter()
+                    .filter(|line| line.bounds.intersection(region).is_some())
+                    .map(|line| line.text.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// 渲染后处理：首次渲染时检测空白页，开启纠偏时转正页面，分析结果保存在页面元数据缓存
+    fn post_process_page(
+        post_process: &Mutex<PostProcess>,
+        blank_pages: &Mutex<HashSet<usize>>,
+        page_index: usize,
+        image_data: Vec<u8>,
+        width: u32,
+        height: u32,
+    ) -> Vec<u8> {
+        // 多个渲染线程共用元数据缓存，检测和旋转在锁外进行
+        let (known_blank, deskew, known_angle) = {
+            let post_process = post_process.lock().unwrap();
+            let Some(page_meta) = post_process.page_meta.as_ref() else {
+                return image_data;
+            };
+            let meta = page_meta.get(page_index);
+            (meta.and_then(|meta| meta.blank), post_process.deskew, meta.and_then(|meta| meta.deskew_angle))
+        };
+        if known_blank.is_none() {
+            let is_blank = blank::is_blank_page(&image_data, width, height);
+            Self::update_page_meta(post_process, page_index, |meta| meta.blank = Some(is_blank));
+            if is_blank {
+                debug!("[Blank] page {} is blank", page_index);
+                blank_pages.lock().unwrap().insert(page_index);
+            }
+        }
+        if !deskew {
+            return image_data;
+        }
+
+        // 纠偏：角度只检测一次并缓存，角度很小时原样返回
+        let angle = match known_angle {
+            Some(angle) => angle,
+            None => {
+                let angle = deskew::detect_skew_angle(&image_data, width, height);
+                debug!("[Deskew] page {} angle={:.2}", page_index, angle);
+                Self::update_page_meta(post_process, page_index, |meta| meta.deskew_angle = Some(angle));
+                angle
+            }
+        };
+        if angle.abs() < deskew::MIN_CORRECTION {
+            return image_data;
+        }
+        deskew::rotate_rgba(&image_data, width, height, angle)
+    }
+
+    fn update_page_meta<F: FnOnce(&mut PageMeta)>(post_process: &Mutex<PostProcess>, page_index: usize, f: F) {
+        if let Some(page_meta) = post_process.lock().unwrap().page_meta.as_mut() {
+            page_meta.update(page_index, f);
+        }
+    }
+
+    /// 打开临时解码器渲染前几页，解码器用完即释放
+    fn render_preview_pages(path: &Path, page_count: usize, max_size: f32) -> Result<Vec<PreviewImage>> {
+        info!("[Preview] 渲染预览: {:?}, pages={}", path, page_count);
+        let dec = formats::open_decoder(path)?;
+        let pages = dec.get_all_pages()?;
+        let mut images = Vec::new();
+        for page in pages.iter().take(page_count) {
+            let max_original = page.width.max(page.height);
+            let preview_page = PageInfo {
+                scale: max_size / max_original / 2.0, // 内部会乘以 2.0 (DPI scale)
+                ..page.clone()
+            };
+            let (image_data, image_width, image_height) = dec.render_page(&preview_page, false)?;
+            images.push(PreviewImage {
+                page_index: page.index,
+                image_data,
+                image_width,
+                image_height,
+            });
+        }
+        Ok(images)
+    }
+
     /// 加载PDF文档（异步）
     pub fn load_pdf<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         self.task_sender
             .send(DecodeTask::LoadDocument {
                 path: path.as_ref().to_path_buf(),
             })
-            .map_err(|e| anyhow::anyhow!("Failed to send load task: {}", e))
+            .map_err(|e| RReaderError::decode(format!("Failed to send load task: {}", e)))
     }
 
     /// 获取大纲（同步等待）
@@ -383,11 +953,47 @@ impl DecodeService {
         let (response_tx, response_rx) = unbounded();
         self.task_sender
             .send(DecodeTask::GetOutline { response_tx })
-            .map_err(|e| anyhow::anyhow!("Failed to send outline task: {}", e))?;
+            .map_err(|e| RReaderError::decode(format!("Failed to send outline task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive outline response: {}", e)))?
+    }
+
+    /// 获取大纲节点的子节点（同步等待）
+    pub fn get_outline_children(&self, parent: usize) -> Result<Vec<crate::entity::OutlineItem>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::GetOutlineChildren { parent, response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send outline children task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive outline children response: {}", e)))?
+    }
+
+    /// 按标题过滤大纲（同步等待）
+    pub fn filter_outline(&self, query: &str) -> Result<Vec<crate::entity::OutlineItem>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::FilterOutline { query: query.to_string(), response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send outline filter task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive outline filter response: {}", e)))?
+    }
+
+    /// 获取页码标签（同步等待）
+    pub fn get_page_labels(&self) -> Result<Vec<String>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::GetPageLabels { response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send page labels task: {}", e)))?;
 
         response_rx
             .recv()
-            .map_err(|e| anyhow::anyhow!("Failed to receive outline response: {}", e))?
+            .map_err(|e| RReaderError::decode(format!("Failed to receive page labels response: {}", e)))?
     }
 
     /// 获取页面文本（同步等待）
@@ -395,11 +1001,56 @@ impl DecodeService {
         let (response_tx, response_rx) = unbounded();
         self.task_sender
             .send(DecodeTask::GetPageText { page_index, response_tx })
-            .map_err(|e| anyhow::anyhow!("Failed to send page text task: {}", e))?;
+            .map_err(|e| RReaderError::decode(format!("Failed to send page text task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive page text response: {}", e)))?
+    }
+
+    /// 获取页面一块区域内的文字（同步等待），行与区域有重叠就算在内
+    pub fn get_region_text(&self, page_index: usize, region: Rect) -> Result<String> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::GetRegionText { page_index, region, response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send region text task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive region text response: {}", e)))?
+    }
+
+    /// 查找页面坐标 (x, y) 下的图片和单词
+    pub fn hit_test(&self, page_index: usize, x: f32, y: f32) -> Result<crate::decoder::PageHit> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::HitTest { page_index, x, y, response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send hit test task: {}", e)))?;
 
         response_rx
             .recv()
-            .map_err(|e| anyhow::anyhow!("Failed to receive page text response: {}", e))?
+            .map_err(|e| RReaderError::decode(format!("Failed to receive hit test response: {}", e)))?
+    }
+
+    /// 全文搜索（不等待），结果从返回的通道取
+    pub fn search_text(&self, query: &str) -> Result<Receiver<Result<Vec<PageMatches>>>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::SearchText { query: query.to_string(), response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send search task: {}", e)))?;
+        Ok(response_rx)
+    }
+
+    /// 渲染页面上的一块区域，返回 RGBA 像素
+    pub fn render_region(&self, page_index: usize, region: crate::decoder::Rect, scale: f32) -> Result<(Vec<u8>, u32, u32)> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::RenderRegion { page_index, region, scale, response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send render region task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive render region response: {}", e)))?
     }
 
     /// 从指定页面开始获取后续页面的reflow数据
@@ -410,14 +1061,79 @@ impl DecodeService {
                 start_page,
                 response_tx
             })
-            .map_err(|e| anyhow::anyhow!("Failed to send reflow task: {}", e))?;
+            .map_err(|e| RReaderError::decode(format!("Failed to send reflow task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive reflow response: {}", e)))?
+    }
+
+    /// 获取文档属性（同步等待）
+    pub fn get_document_properties(&self) -> Result<Vec<DocumentProperty>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::GetDocumentProperties { response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send properties task: {}", e)))?;
+
+        response_rx
+            .recv()
+            .map_err(|e| RReaderError::decode(format!("Failed to receive properties response: {}", e)))?
+    }
+
+    /// 获取文档元数据（同步等待）
+    pub fn get_document_metadata(&self) -> Result<DocumentMetadata> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::GetDocumentMetadata { response_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send metadata task: {}", e)))?;
 
         response_rx
             .recv()
-            .map_err(|e| anyhow::anyhow!("Failed to receive reflow response: {}", e))?
+            .map_err(|e| RReaderError::decode(format!("Failed to receive metadata response: {}", e)))?
+    }
+
+    /// 流式获取从指定页面开始的reflow数据（异步，每提取一页推送一次）
+    pub fn stream_reflow_from_page(&self, start_page: usize, entry_tx: Sender<crate::entity::ReflowEntry>) -> Result<()> {
+        self.task_sender
+            .send(DecodeTask::StreamReflow { start_page, entry_tx })
+            .map_err(|e| RReaderError::decode(format!("Failed to send reflow task: {}", e)))
+    }
+
+    /// 停止推送流式reflow
+    /// 异步渲染预览，结果从返回的通道读取
+    pub fn render_preview<P: AsRef<Path>>(&self, path: P, page_count: usize, max_size: f32) -> Result<Receiver<Result<Vec<PreviewImage>>>> {
+        let (response_tx, response_rx) = unbounded();
+        self.task_sender
+            .send(DecodeTask::RenderPreview {
+                path: path.as_ref().to_path_buf(),
+                page_count,
+                max_size,
+                response_tx,
+            })
+            .map_err(|e| RReaderError::decode(format!("Failed to send task: {}", e)))?;
+        Ok(response_rx)
+    }
+
+    pub fn cancel_reflow(&self) {
+        let _ = self.task_sender.send(DecodeTask::CancelReflow);
+    }
+
+    pub fn set_deskew(&self, enabled: bool) {
+        let _ = self.task_sender.send(DecodeTask::SetDeskew { enabled });
+    }
+
+    /// 检测到的空白页，可在其他线程中读取
+    pub fn blank_pages(&self) -> Arc<Mutex<HashSet<usize>>> {
+        Arc::clone(&self.blank_pages)
     }
 
     /// 批量提交渲染任务（异步，不等待）
+    /// 作废代数小于 generation 的排队任务和正在渲染的页面
+    pub fn cancel_generation(&self, generation: u64) {
+        self.generation.store(generation, Ordering::Relaxed);
+        let _ = self.task_sender.send(DecodeTask::CancelGeneration { generation });
+    }
+
     pub fn render_pages(&self, pages: Vec<RenderPage>) {
         if !pages.is_empty() {
             let _ = self.task_sender.send(DecodeTask::RenderPages { pages });
@@ -425,10 +1141,25 @@ impl DecodeService {
     }
 
     /// 尝试接收解码结果（非阻塞）
+    /// 后台工作量（渲染队列、reflow提取）
+    pub fn activity(&self) -> &BackgroundActivity {
+        &self.activity
+    }
+
+    /// 后台任务需要在其他线程中查看解码线程是否繁忙时使用
+    pub fn activity_handle(&self) -> Arc<BackgroundActivity> {
+        Arc::clone(&self.activity)
+    }
+
     pub fn try_recv_result(&self) -> Option<DecodeResult> {
         self.result_receiver.lock().unwrap().try_recv().ok()
     }
 
+    /// 尝试接收渲染失败（非阻塞）
+    pub fn try_recv_failure(&self) -> Option<RenderFailure> {
+        self.failure_receiver.lock().unwrap().try_recv().ok()
+    }
+
     /// 尝试接收加载结果（非阻塞）
     pub fn try_recv_load_result(&self) -> Option<Result<Vec<PageInfo>>> {
         //info!("try_recv_load_result");
@@ -1,4 +1,4 @@
-use crate::{decoder::{Link, PageInfo, Rect}, entity::OutlineItem};
+use crate::{decoder::{DocumentMetadata, Link, PageHit, PageInfo, Rect, TextBlock, TextLine}, entity::OutlineItem};
 use crate::entity::ReflowEntry;
 use std::path::{Path};
 
@@ -37,8 +37,48 @@ pub trait Decoder {
     /// 获取页面文本（用于搜索/TTS）
     fn get_page_text(&self, page_index: usize) -> anyhow::Result<String>;
 
+    /// 获取页面的结构化文本（块/行，带位置和字号），用于reflow分段
+    /// 默认把整页文本当作一个块，不支持结构化提取的解码器可以不实现
+    fn get_page_text_blocks(&self, page_index: usize) -> anyhow::Result<Vec<TextBlock>> {
+        let (width, height) = self.get_page_size(page_index)?;
+        let text = self.get_page_text(page_index)?;
+        let bounds = Rect::new(0.0, 0.0, width, height);
+        let lines = text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| TextLine {
+                bounds,
+                font_size: 0.0,
+                text: line.to_string(),
+            })
+            .collect();
+        Ok(vec![TextBlock { bounds, lines }])
+    }
+
+    /// 查找页面坐标 (x, y) 下的图片和单词，用于右键菜单
+    /// 默认什么都不命中，不支持的解码器可以不实现
+    fn hit_test(&self, page_index: usize, x: f32, y: f32) -> anyhow::Result<PageHit> {
+        Ok(PageHit::default())
+    }
+
+    /// 在页面中搜索文字（不区分大小写），返回每处匹配的范围（页面坐标）
+    /// 默认没有匹配，没有文字层的解码器可以不实现
+    fn search_page(&self, page_index: usize, query: &str) -> anyhow::Result<Vec<Rect>> {
+        Ok(Vec::new())
+    }
+
     fn get_outline_items(&self) -> anyhow::Result<Vec<OutlineItem>>;
 
+    /// 获取每页的页码标签（如 "xiv"、"A-3"），文档没有定义时返回空
+    fn get_page_labels(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// 文档自带的标题、作者、主题
+    /// 默认没有元数据，不支持的解码器可以不实现
+    fn metadata(&self) -> DocumentMetadata {
+        DocumentMetadata::default()
+    }
+
     /// 从指定页面开始获取后续页面的reflow数据
     /// - start_page: 起始页面索引
     fn get_reflow_from_page(&self, start_page: usize) -> anyhow::Result<Vec<ReflowEntry>>;
@@ -0,0 +1,84 @@
+/// 扫描页纠偏：从渲染结果检测文字行的倾斜角度，再把图像转正
+/// 检测用投影法：按候选角度把深色像素投影到纵轴，文字行对齐时各行计数最集中
+
+/// 检测的最大倾斜角度（度）
+const MAX_ANGLE: f32 = 5.0;
+/// 角度搜索步长（度）
+const ANGLE_STEP: f32 = 0.1;
+/// 检测时最多采样的宽度，大图按步长抽样
+const SAMPLE_WIDTH: u32 = 800;
+/// 深色像素的亮度阈值
+const DARK_THRESHOLD: u32 = 128;
+/// 小于该角度不旋转，避免无谓的重采样
+pub const MIN_CORRECTION: f32 = 0.15;
+
+/// 检测倾斜角度（度），文字太少时返回 0
+pub fn detect_skew_angle(rgba: &[u8], width: u32, height: u32) -> f32 {
+    if width == 0 || height == 0 {
+        return 0.0;
+    }
+    let step = (width / SAMPLE_WIDTH).max(1);
+    let mut points = Vec::new();
+    for y in (0..height).step_by(step as usize) {
+        for x in (0..width).step_by(step as usize) {
+            let i = ((y * width + x) * 4) as usize;
+            let luma = (rgba[i] as u32 * 299 + rgba[i + 1] as u32 * 587 + rgba[i + 2] as u32 * 114) / 1000;
+            if luma < DARK_THRESHOLD {
+                points.push((x as f32, y as f32));
+            }
+        }
+    }
+    // 几乎空白或全黑的页面无法判断
+    let sampled = (width / step) as usize * (height / step) as usize;
+    if points.len() < 100 || points.len() > sampled / 2 {
+        return 0.0;
+    }
+
+    let bin_size = step as f32;
+    let mut best_angle = 0.0;
+    let mut best_score = 0.0f64;
+    let steps = (MAX_ANGLE / ANGLE_STEP).round() as i32;
+    let mut bins = vec![0u32; (height as f32 * 2.0 / bin_size) as usize + 2];
+    for k in -steps..=steps {
+        let angle = k as f32 * ANGLE_STEP;
+        let (sin, cos) = angle.to_radians().sin_cos();
+        bins.iter_mut().for_each(|b| *b = 0);
+        for &(x, y) in &points {
+            let projected = y * cos - x * sin + height as f32 * 0.5;
+            let bin = (projected / bin_size).max(0.0) as usize;
+            if let Some(count) = bins.get_mut(bin) {
+                *count += 1;
+            }
+        }
+        let score: f64 = bins.iter().map(|&c| (c as f64) * (c as f64)).sum();
+        if score > best_score {
+            best_score = score;
+            best_angle = angle;
+        }
+    }
+    best_angle
+}
+
+/// 按检测到的角度反向旋转图像，尺寸不变，空出的角落填白色
+pub fn rotate_rgba(rgba: &[u8], width: u32, height: u32, angle: f32) -> Vec<u8> {
+    let mut output = vec![255u8; rgba.len()];
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    for y in 0..height {
+        let dy = y as f32 - cy;
+        for x in 0..width {
+            let dx = x as f32 - cx;
+            // 输出像素对应原图中沿倾斜方向的位置
+            let sx = (dx * cos - dy * sin + cx).round();
+            let sy = (dx * sin + dy * cos + cy).round();
+            if sx < 0.0 || sy < 0.0 || sx >= width as f32 || sy >= height as f32 {
+                continue;
+            }
+            let src = ((sy as u32 * width + sx as u32) * 4) as usize;
+            let dst = ((y * width + x) * 4) as usize;
+            output[dst..dst + 4].copy_from_slice(&rgba[src..src + 4]);
+        }
+    }
+    output
+}
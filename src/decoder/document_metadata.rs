@@ -0,0 +1,45 @@
+/// 文档自带的元数据，没有的字段为空
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentMetadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+}
+
+/// 办公软件导出 PDF 时常把文件名写进标题，这类标题不如文件名有用
+const JUNK_TITLE_PREFIXES: &[&str] = &["microsoft word - ", "microsoft powerpoint - ", "untitled"];
+const JUNK_TITLE_SUFFIXES: &[&str] = &[".doc", ".docx", ".ppt", ".pptx", ".pdf", ".tex", ".dvi", ".indd", ".qxd"];
+
+impl DocumentMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.author.is_empty() && self.subject.is_empty()
+    }
+
+    /// 可以代替文件名显示的标题
+    pub fn display_title(&self) -> Option<&str> {
+        let title = self.title.trim();
+        let lower = title.to_lowercase();
+        let junk = title.chars().count() < 2
+            || JUNK_TITLE_PREFIXES.iter().any(|prefix| lower.starts_with(prefix))
+            || JUNK_TITLE_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix));
+        (!junk).then_some(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_title(title: &str) -> DocumentMetadata {
+        DocumentMetadata { title: title.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn display_title_skips_file_names_and_placeholders() {
+        assert_eq!(with_title("  The Rust Programming Language ").display_title(), Some("The Rust Programming Language"));
+        assert_eq!(with_title("Microsoft Word - thesis.docx").display_title(), None);
+        assert_eq!(with_title("report_final.pdf").display_title(), None);
+        assert_eq!(with_title("Untitled-1").display_title(), None);
+        assert_eq!(with_title("").display_title(), None);
+    }
+}
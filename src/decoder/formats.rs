@@ -0,0 +1,87 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::decoder::pdf::PdfDecoder;
+use crate::decoder::{ComicDecoder, Decoder, TextDecoder};
+use crate::error::RReaderError;
+
+/// 支持的文档格式：扩展名、显示名、解码器工厂
+/// 文件对话框、命令行和文件扫描都从这里取格式列表，新增格式只需在 FORMATS 里加一项
+pub struct DocumentFormat {
+    pub extension: &'static str,
+    pub display_name: &'static str,
+    open: fn(&Path) -> Result<Box<dyn Decoder>>,
+}
+
+impl DocumentFormat {
+    pub fn open(&self, path: &Path) -> Result<Box<dyn Decoder>> {
+        (self.open)(path)
+    }
+}
+
+fn open_with_mupdf(path: &Path) -> Result<Box<dyn Decoder>> {
+    Ok(Box::new(PdfDecoder::open(path)?))
+}
+
+fn open_comic(path: &Path) -> Result<Box<dyn Decoder>> {
+    Ok(Box::new(ComicDecoder::open(path)?))
+}
+
+fn open_text(path: &Path) -> Result<Box<dyn Decoder>> {
+    Ok(Box::new(TextDecoder::open(path)?))
+}
+
+pub static FORMATS: &[DocumentFormat] = &[
+    DocumentFormat { extension: "pdf", display_name: "PDF", open: open_with_mupdf },
+    DocumentFormat { extension: "epub", display_name: "EPUB", open: open_with_mupdf },
+    DocumentFormat { extension: "mobi", display_name: "MOBI", open: open_with_mupdf },
+    DocumentFormat { extension: "xps", display_name: "XPS", open: open_with_mupdf },
+    DocumentFormat { extension: "cbz", display_name: "Comic Book", open: open_comic },
+    DocumentFormat { extension: "cbr", display_name: "Comic Book", open: open_comic },
+    DocumentFormat { extension: "cb7", display_name: "Comic Book", open: open_comic },
+    DocumentFormat { extension: "docx", display_name: "Word", open: open_with_mupdf },
+    DocumentFormat { extension: "tif", display_name: "TIFF", open: open_with_mupdf },
+    DocumentFormat { extension: "tiff", display_name: "TIFF", open: open_with_mupdf },
+    DocumentFormat { extension: "txt", display_name: "Text", open: open_text },
+    DocumentFormat { extension: "md", display_name: "Markdown", open: open_text },
+    DocumentFormat { extension: "markdown", display_name: "Markdown", open: open_text },
+];
+
+/// 按扩展名查找格式（不区分大小写）
+pub fn find_format(path: &Path) -> Option<&'static DocumentFormat> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    FORMATS.iter().find(|f| f.extension == extension)
+}
+
+pub fn is_supported(path: &Path) -> bool {
+    find_format(path).is_some()
+}
+
+pub fn supported_extensions() -> Vec<&'static str> {
+    FORMATS.iter().map(|f| f.extension).collect()
+}
+
+/// 文件对话框过滤器：先是全部支持的格式，再按显示名分组
+pub fn dialog_filters() -> Vec<(String, Vec<&'static str>)> {
+    let mut filters: Vec<(String, Vec<&'static str>)> = vec![("All Supported".to_string(), supported_extensions())];
+    for format in FORMATS {
+        match filters.iter_mut().skip(1).find(|(name, _)| name == format.display_name) {
+            Some((_, extensions)) => extensions.push(format.extension),
+            None => filters.push((format.display_name.to_string(), vec![format.extension])),
+        }
+    }
+    filters
+}
+
+/// 按格式选择解码器打开文档
+pub fn open_decoder(path: &Path) -> Result<Box<dyn Decoder>> {
+    match find_format(path) {
+        Some(format) => format.open(path),
+        None => {
+            let extension = path.extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Err(RReaderError::UnsupportedFormat(extension).into())
+        }
+    }
+}
@@ -17,6 +17,30 @@ pub struct Link {
     pub link_type: LinkType,
 }
 
+impl Link {
+    /// 内部链接目标在页面中的纵坐标（页面坐标）
+    /// mupdf 生成的形式：`#page=3&zoom=nan,72,400`、`#page=3&view=FitH,400`
+    pub fn target_top(&self) -> Option<f32> {
+        let fragment = self.page.as_deref()?.rsplit_once('#')?.1;
+        fragment.split('&').find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            let values: Vec<f32> = value.split(',').filter_map(|v| v.trim().parse().ok()).collect();
+            let top = match key {
+                // zoom=缩放,左,上
+                "zoom" => values.get(2),
+                // view=FitH,上 / FitBH,上 / XYZ,左,上 / FitR,左,上,右,下
+                "view" => match value.split(',').next()? {
+                    "FitH" | "FitBH" => values.first(),
+                    "XYZ" | "FitR" => values.get(1),
+                    _ => None,
+                },
+                _ => None,
+            };
+            top.copied().filter(|top| top.is_finite())
+        })
+    }
+}
+
 /*#[derive(Debug, Clone)]
 pub struct PdfLink {
     pub bounds: MuRect,
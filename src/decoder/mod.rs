@@ -1,15 +1,41 @@
+pub mod blank;
+pub mod comic;
+pub mod comic_info;
 pub mod decode_service;
 pub mod decoder;
+pub mod deskew;
+pub mod document_metadata;
+pub mod formats;
 pub mod link;
+pub mod outline_tree;
+pub mod page_hit;
 pub mod page_info;
+pub mod page_label;
 pub mod pdf;
 pub mod rect;
+pub mod render_pool;
+pub mod render_queue;
+pub mod text;
+pub mod text_block;
+pub mod verify;
 
 pub use self::decode_service::DecodeService;
 pub use self::decode_service::DecodeTask;
+pub use self::decode_service::PageMatches;
 pub use self::decode_service::Priority;
+pub use self::decode_service::RenderFailure;
 pub use self::decoder::Decoder;
+pub use self::document_metadata::DocumentMetadata;
 pub use self::link::Link;
 pub use self::link::LinkType;
-pub use self::page_info::PageInfo;
+pub use self::outline_tree::OutlineTree;
+pub use self::comic::ComicDecoder;
+pub use self::comic_info::ComicInfo;
+pub use self::page_hit::{PageHit, PageWord};
+pub use self::page_info::{CropMargins, PageInfo, PageSpread};
 pub use self::rect::Rect;
+pub use self::render_pool::RenderPool;
+pub use self::render_queue::RenderQueue;
+pub use self::text::TextDecoder;
+pub use self::text_block::{TextBlock, TextLine};
+pub use self::verify::{PageProblem, VerifyEvent, VerifyJob, VerifyReport};
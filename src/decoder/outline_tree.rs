@@ -0,0 +1,99 @@
+use crate::entity::OutlineItem;
+
+/// 打开文档时先给出的大纲层数，更深的节点展开时再取
+pub const INITIAL_OUTLINE_DEPTH: i32 = 2;
+
+/// 解码线程缓存的完整大纲（先序展开），界面只取需要显示的部分，
+/// 几万个节点的大纲也不会拖慢打开
+pub struct OutlineTree {
+    items: Vec<OutlineItem>,
+}
+
+impl OutlineTree {
+    pub fn new(mut items: Vec<OutlineItem>) -> Self {
+        for i in 0..items.len() {
+            let level = items[i].level;
+            items[i].id = i;
+            items[i].has_children = items.get(i + 1).is_some_and(|next| next.level > level);
+        }
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 层级小于 depth 的节点，保持先序
+    pub fn top_levels(&self, depth: i32) -> Vec<OutlineItem> {
+        self.items.iter().filter(|item| item.level < depth).cloned().collect()
+    }
+
+    /// 节点的直接子节点，节点不存在时为空
+    pub fn children(&self, parent: usize) -> Vec<OutlineItem> {
+        let Some(parent_item) = self.items.get(parent) else {
+            return Vec::new();
+        };
+        self.items[parent + 1..]
+            .iter()
+            .take_while(|item| item.level > parent_item.level)
+            .filter(|item| item.level == parent_item.level + 1)
+            .cloned()
+            .collect()
+    }
+
+    /// 标题包含 query（不区分大小写）的节点及其所有上级，保持先序，上级用来显示所在的章节
+    pub fn filter(&self, query: &str) -> Vec<OutlineItem> {
+        let query = query.to_lowercase();
+        let mut keep = vec![false; self.items.len()];
+        // 当前节点的各级上级
+        let mut ancestors: Vec<usize> = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            while ancestors.last().is_some_and(|&a| self.items[a].level >= item.level) {
+                ancestors.pop();
+            }
+            if item.title.to_lowercase().contains(&query) {
+                keep[i] = true;
+                for &a in &ancestors {
+                    keep[a] = true;
+                }
+            }
+            ancestors.push(i);
+        }
+        self.items.iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(items: &[(&str, i32)]) -> OutlineTree {
+        OutlineTree::new(items.iter().enumerate()
+            .map(|(page, (title, level))| OutlineItem::new(title.to_string(), None, page as i32, *level))
+            .collect())
+    }
+
+    #[test]
+    fn filter_keeps_matches_with_their_ancestors() {
+        let tree = tree(&[
+            ("Part I", 0),
+            ("Networking", 1),
+            ("TCP Handshake", 2),
+            ("UDP", 2),
+            ("Storage", 1),
+            ("Part II", 0),
+            ("tcp tuning", 1),
+        ]);
+        let titles: Vec<String> = tree.filter("TCP").into_iter().map(|item| item.title).collect();
+        assert_eq!(titles, vec!["Part I", "Networking", "TCP Handshake", "Part II", "tcp tuning"]);
+        assert!(tree.filter("missing").is_empty());
+    }
+}
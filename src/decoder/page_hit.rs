@@ -0,0 +1,20 @@
+use super::Rect;
+
+/// 页面上的一个单词，以及它所在的文本块
+#[derive(Debug, Clone)]
+pub struct PageWord {
+    pub text: String,
+    pub bounds: Rect,
+    /// 单词所在文本块的文字，每行一个换行
+    pub paragraph: String,
+    /// 文本块中每行的范围，高亮整段时使用
+    pub lines: Vec<Rect>,
+}
+
+/// 页面上某一点（页面坐标）下的内容，用于右键菜单
+#[derive(Debug, Clone, Default)]
+pub struct PageHit {
+    /// 图片的范围
+    pub image: Option<Rect>,
+    pub word: Option<PageWord>,
+}
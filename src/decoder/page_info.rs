@@ -1,5 +1,19 @@
+use serde::{Deserialize, Serialize};
+
 use super::Rect;
 
+/// 双页模式下页面的排列方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageSpread {
+    /// 和相邻页组成一行
+    #[default]
+    Normal,
+    /// 单独一行，占一栏宽度（封面）
+    Alone,
+    /// 单独一行，占满两栏（跨页）
+    Double,
+}
+
 /// 页面信息
 #[derive(Debug, Clone)]
 pub struct PageInfo {
@@ -8,6 +22,7 @@ pub struct PageInfo {
     pub height: f32,
     pub scale: f32,
     pub crop_bounds: Option<Rect>,
+    pub spread: PageSpread,
 }
 
 impl PageInfo {
@@ -18,6 +33,7 @@ impl PageInfo {
             height,
             scale: 1.0,
             crop_bounds: None,
+            spread: PageSpread::Normal,
         }
     }
 
@@ -43,3 +59,53 @@ impl PageInfo {
         self.crop_bounds.is_some()
     }
 }
+
+/// 手动切边的页边距，按页面宽高的比例（0~1），对所有页面使用同一组边距
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CropMargins {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl CropMargins {
+    /// 切边后至少保留页面的这一比例
+    const MIN_REMAINING: f32 = 0.1;
+
+    /// 限制在合理范围内：每边不小于 0，切完至少保留一部分页面
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        let limit = 1.0 - Self::MIN_REMAINING;
+        let left = left.clamp(0.0, limit);
+        let top = top.clamp(0.0, limit);
+        Self {
+            left,
+            top,
+            right: right.clamp(0.0, limit - left),
+            bottom: bottom.clamp(0.0, limit - top),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left <= 0.0 && self.top <= 0.0 && self.right <= 0.0 && self.bottom <= 0.0
+    }
+
+    /// 按页面尺寸换算成切边范围（页面坐标）
+    pub fn crop_rect(&self, width: f32, height: f32) -> Rect {
+        Rect::new(width * self.left, height * self.top, width * (1.0 - self.right), height * (1.0 - self.bottom))
+    }
+
+    /// 数据库中保存为 JSON，空字符串或无法解析时返回 None
+    pub fn decode(text: &str) -> Option<Self> {
+        if text.is_empty() {
+            return None;
+        }
+        serde_json::from_str::<CropMargins>(text).ok()
+            .map(|margins| Self::new(margins.left, margins.top, margins.right, margins.bottom))
+            .filter(|margins| !margins.is_empty())
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
@@ -0,0 +1,89 @@
+/// 页码标签的编号样式（PDF PageLabels 的 /S）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// 1, 2, 3
+    Decimal,
+    /// I, II, III
+    UpperRoman,
+    /// i, ii, iii
+    LowerRoman,
+    /// A..Z, AA..ZZ
+    UpperLetters,
+    /// a..z, aa..zz
+    LowerLetters,
+}
+
+impl LabelStyle {
+    pub fn from_pdf_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"D" => Some(Self::Decimal),
+            b"R" => Some(Self::UpperRoman),
+            b"r" => Some(Self::LowerRoman),
+            b"A" => Some(Self::UpperLetters),
+            b"a" => Some(Self::LowerLetters),
+            _ => None,
+        }
+    }
+
+    fn format(self, number: u32) -> String {
+        match self {
+            Self::Decimal => number.to_string(),
+            Self::UpperRoman => to_roman(number),
+            Self::LowerRoman => to_roman(number).to_lowercase(),
+            Self::UpperLetters => to_letters(number),
+            Self::LowerLetters => to_letters(number).to_lowercase(),
+        }
+    }
+}
+
+/// 从 start_page 开始的一段页码标签，直到下一段开始
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelRange {
+    pub start_page: usize,
+    /// 没有样式时只有前缀
+    pub style: Option<LabelStyle>,
+    pub prefix: String,
+    /// 这一段第一页的编号，默认 1
+    pub first: u32,
+}
+
+/// 按分段生成每一页的标签；第一段之前的页面没有标签，用空串表示
+pub fn build_labels(ranges: &[LabelRange], page_count: usize) -> Vec<String> {
+    let mut ranges: Vec<&LabelRange> = ranges.iter().filter(|r| r.start_page < page_count).collect();
+    ranges.sort_by_key(|r| r.start_page);
+
+    let mut labels = vec![String::new(); page_count];
+    for (i, range) in ranges.iter().enumerate() {
+        let end = ranges.get(i + 1).map_or(page_count, |next| next.start_page);
+        for (offset, label) in labels[range.start_page..end].iter_mut().enumerate() {
+            let number = range.style.map(|style| style.format(range.first + offset as u32)).unwrap_or_default();
+            *label = format!("{}{}", range.prefix, number);
+        }
+    }
+    labels
+}
+
+fn to_roman(mut number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    roman
+}
+
+/// PDF 规范的字母编号：A..Z 之后是 AA..ZZ，再之后 AAA..ZZZ
+fn to_letters(number: u32) -> String {
+    if number == 0 {
+        return String::new();
+    }
+    let letter = (b'A' + ((number - 1) % 26) as u8) as char;
+    let repeat = (number - 1) / 26 + 1;
+    std::iter::repeat_n(letter, repeat as usize).collect()
+}
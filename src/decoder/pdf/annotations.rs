@@ -0,0 +1,295 @@
+use anyhow::{Context as _, Result};
+use crossbeam_channel::{unbounded, Receiver};
+use log::info;
+use mupdf::pdf::{PdfDocument, PdfObject};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::decoder::Rect;
+
+/// 要写入 PDF 的一处高亮，范围为页面坐标（左上角为原点）
+#[derive(Debug, Clone)]
+pub struct PdfHighlight {
+    /// 从 0 开始的页码
+    pub page: usize,
+    pub rects: Vec<Rect>,
+    /// RGB，取值 0~1
+    pub color: [f32; 3],
+    /// 笔记，写入 /Contents，其他阅读器显示为弹出注释
+    pub note: String,
+}
+
+/// 把高亮作为标准的 /Highlight 注释写进副本，其他阅读器按 QuadPoints 绘制，
+/// 原文件不变。返回写入的注释数
+pub fn save_with_highlights(source: &Path, target: &Path, highlights: &[PdfHighlight]) -> Result<usize> {
+    let document = PdfDocument::open(&source.to_string_lossy())
+        .with_context(|| format!("failed to open {:?} as PDF", source))?;
+    let page_count = document.page_count()? as usize;
+
+    let mut written = 0;
+    for highlight in highlights.iter().filter(|h| h.page < page_count && !h.rects.is_empty()) {
+        let mut page = document.find_page(highlight.page as i32)?;
+        let transform = PageTransform::load(&page)?;
+        let annotation = highlight_dict(&document, highlight, &transform)?;
+        let annotation = document.add_object(&annotation)?;
+
+        match page.get_dict("Annots")? {
+            Some(mut annots) if annots.is_array()? => annots.array_push(annotation)?,
+            _ => {
+                let mut annots = document.new_array()?;
+                annots.array_push(annotation)?;
+                page.dict_put("Annots", annots)?;
+            }
+        }
+        written += 1;
+    }
+
+    // 先写临时文件，成功后再替换，避免失败时留下半个文件
+    let partial = target.with_extension("pdf.part");
+    document
+        .save(&partial.to_string_lossy())
+        .with_context(|| format!("failed to write {:?}", partial))?;
+    fs::rename(&partial, target)?;
+    info!("[PdfAnnotations] {:?} -> {:?}: {} highlights", source, target, written);
+    Ok(written)
+}
+
+/// 页面坐标（mupdf 的页面坐标：可见区域左上角为原点，已按 /Rotate 旋转，y 轴向下）与 PDF 用户空间之间的换算，
+/// 与 mupdf 的 pdf_page_transform 一致：可见区域为 CropBox 与 MediaBox 的交集，再乘上 UserUnit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PageTransform {
+    /// 页面坐标 -> PDF 坐标，[a b c d e f]，x' = a*x + c*y + e，y' = b*x + d*y + f
+    matrix: [f32; 6],
+    /// 可见区域在 PDF 坐标中的范围：左、下、右、上
+    pub crop_box: [f32; 4],
+}
+
+impl PageTransform {
+    pub fn new(media_box: [f32; 4], crop_box: Option<[f32; 4]>, rotate: i32, user_unit: f32) -> Self {
+        let media_box = normalize(media_box);
+        let crop_box = crop_box
+            .map(|crop_box| intersect(normalize(crop_box), media_box))
+            .filter(|b| b[2] > b[0] && b[3] > b[1])
+            .unwrap_or(media_box);
+        // 不是 90 的倍数时按 0 处理
+        let rotate = match rotate.rem_euclid(360) {
+            r @ (0 | 90 | 180 | 270) => r,
+            _ => 0,
+        };
+        let unit = if user_unit > 0.0 { user_unit } else { 1.0 };
+
+        // 先旋转 -rotate 度，再缩放并翻转 y 轴，最后把可见区域移到原点
+        let (sin, cos) = match rotate {
+            90 => (-1.0, 0.0),
+            180 => (0.0, -1.0),
+            270 => (1.0, 0.0),
+            _ => (0.0, 1.0),
+        };
+        let rotation = [cos, sin, -sin, cos, 0.0, 0.0];
+        let ctm = concat(rotation, [unit, 0.0, 0.0, -unit, 0.0, 0.0]);
+        let corners = [(crop_box[0], crop_box[1]), (crop_box[2], crop_box[1]), (crop_box[0], crop_box[3]), (crop_box[2], crop_box[3])]
+            .map(|(x, y)| apply(&ctm, x, y));
+        let x0 = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+        let y0 = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+        let ctm = concat(ctm, [1.0, 0.0, 0.0, 1.0, -x0, -y0]);
+
+        Self { matrix: invert(&ctm), crop_box }
+    }
+
+    /// 按页面字典中（可以从父节点继承）的 MediaBox、CropBox、Rotate 和 UserUnit 计算
+    pub fn load(page: &PdfObject) -> Result<Self> {
+        // 没有 MediaBox 时和 mupdf 一样按 Letter 尺寸
+        let media_box = inherited(page, "MediaBox")?
+            .map(|value| read_box(&value))
+            .transpose()?
+            .flatten()
+            .unwrap_or([0.0, 0.0, 612.0, 792.0]);
+        let crop_box = inherited(page, "CropBox")?.map(|value| read_box(&value)).transpose()?.flatten();
+        let rotate = match inherited(page, "Rotate")? {
+            Some(rotate) => rotate.as_int()?,
+            None => 0,
+        };
+        let user_unit = match page.get_dict("UserUnit")? {
+            Some(unit) => unit.as_float()?,
+            None => 1.0,
+        };
+        Ok(Self::new(media_box, crop_box, rotate, user_unit))
+    }
+
+    pub fn map_point(&self, x: f32, y: f32) -> (f32, f32) {
+        apply(&self.matrix, x, y)
+    }
+
+    /// 矩形四角在 PDF 坐标中的位置，按 QuadPoints 的顺序：左上、右上、左下、右下（页面坐标中的方向）
+    pub fn quad(&self, rect: &Rect) -> [(f32, f32); 4] {
+        [(rect.left, rect.top), (rect.right, rect.top), (rect.left, rect.bottom), (rect.right, rect.bottom)]
+            .map(|(x, y)| self.map_point(x, y))
+    }
+
+    /// 矩形在 PDF 坐标中的外接矩形：左、下、右、上
+    pub fn bounds(&self, rect: &Rect) -> [f32; 4] {
+        let quad = self.quad(rect);
+        [
+            quad.iter().map(|p| p.0).fold(f32::INFINITY, f32::min),
+            quad.iter().map(|p| p.1).fold(f32::INFINITY, f32::min),
+            quad.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max),
+            quad.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max),
+        ]
+    }
+}
+
+fn apply(m: &[f32; 6], x: f32, y: f32) -> (f32, f32) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+/// 先做 first 再做 second
+fn concat(first: [f32; 6], second: [f32; 6]) -> [f32; 6] {
+    let (a, b) = (first, second);
+    [
+        a[0] * b[0] + a[1] * b[2],
+        a[0] * b[1] + a[1] * b[3],
+        a[2] * b[0] + a[3] * b[2],
+        a[2] * b[1] + a[3] * b[3],
+        a[4] * b[0] + a[5] * b[2] + b[4],
+        a[4] * b[1] + a[5] * b[3] + b[5],
+    ]
+}
+
+fn invert(m: &[f32; 6]) -> [f32; 6] {
+    let det = m[0] * m[3] - m[1] * m[2];
+    let (a, b, c, d) = (m[3] / det, -m[1] / det, -m[2] / det, m[0] / det);
+    [a, b, c, d, -(m[4] * a + m[5] * c), -(m[4] * b + m[5] * d)]
+}
+
+fn normalize(b: [f32; 4]) -> [f32; 4] {
+    [b[0].min(b[2]), b[1].min(b[3]), b[0].max(b[2]), b[1].max(b[3])]
+}
+
+fn intersect(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [a[0].max(b[0]), a[1].max(b[1]), a[2].min(b[2]), a[3].min(b[3])]
+}
+
+fn read_box(value: &PdfObject) -> Result<Option<[f32; 4]>> {
+    if !value.is_array()? || value.len()? != 4 {
+        return Ok(None);
+    }
+    let mut result = [0.0; 4];
+    for (i, slot) in result.iter_mut().enumerate() {
+        if let Some(number) = value.get_array(i as i32)? {
+            *slot = number.as_float()?;
+        }
+    }
+    Ok(Some(result))
+}
+
+/// 页面自身没有时沿 Parent 向上查找可继承的属性
+pub(crate) fn inherited(page: &PdfObject, key: &str) -> Result<Option<PdfObject>> {
+    if let Some(value) = page.get_dict(key)? {
+        return Ok(Some(value));
+    }
+    let mut parent = page.get_dict("Parent")?;
+    // 防止损坏的文件出现循环引用
+    for _ in 0..32 {
+        let Some(node) = parent else { break };
+        if let Some(value) = node.get_dict(key)? {
+            return Ok(Some(value));
+        }
+        parent = node.get_dict("Parent")?;
+    }
+    Ok(None)
+}
+
+fn highlight_dict(document: &PdfDocument, highlight: &PdfHighlight, transform: &PageTransform) -> Result<PdfObject> {
+    let bounds = highlight.rects.iter().skip(1).fold(highlight.rects[0], |acc, r| acc.union(r));
+
+    let mut quads = document.new_array()?;
+    for rect in &highlight.rects {
+        // 每个四边形依次为左上、右上、左下、右下
+        for (x, y) in transform.quad(rect) {
+            quads.array_push(document.new_real(x)?)?;
+            quads.array_push(document.new_real(y)?)?;
+        }
+    }
+
+    let mut rect = document.new_array()?;
+    for value in transform.bounds(&bounds) {
+        rect.array_push(document.new_real(value)?)?;
+    }
+
+    let mut color = document.new_array()?;
+    for value in highlight.color {
+        color.array_push(document.new_real(value)?)?;
+    }
+
+    let mut dict = document.new_dict()?;
+    dict.dict_put("Type", document.new_name("Annot")?)?;
+    dict.dict_put("Subtype", document.new_name("Highlight")?)?;
+    dict.dict_put("Rect", rect)?;
+    dict.dict_put("QuadPoints", quads)?;
+    dict.dict_put("C", color)?;
+    // 4 = Print，打印时也显示
+    dict.dict_put("F", document.new_int(4)?)?;
+    dict.dict_put("T", document.new_string("RReader")?)?;
+    if !highlight.note.is_empty() {
+        dict.dict_put("Contents", document.new_string(&highlight.note)?)?;
+    }
+    Ok(dict)
+}
+
+/// 在独立线程里写入，mupdf 上下文按线程创建，不影响解码线程
+pub fn save_with_highlights_in_background(source: PathBuf, target: PathBuf, highlights: Vec<PdfHighlight>) -> Receiver<Result<usize>> {
+    let (result_tx, result_rx) = unbounded();
+    thread::spawn(move || {
+        let result = save_with_highlights(&source, &target, &highlights);
+        if result.is_err() {
+            let _ = fs::remove_file(target.with_extension("pdf.part"));
+        }
+        let _ = result_tx.send(result);
+    });
+    result_rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn crop_box_moves_the_origin() {
+        let transform = PageTransform::new([0.0, 0.0, 400.0, 300.0], Some([50.0, 20.0, 350.0, 280.0]), 0, 1.0);
+        // 页面左上角是 CropBox 的左上角
+        assert!(close(transform.map_point(0.0, 0.0), (50.0, 280.0)));
+        assert!(close(transform.map_point(300.0, 260.0), (350.0, 20.0)));
+        assert_eq!(transform.crop_box, [50.0, 20.0, 350.0, 280.0]);
+    }
+
+    #[test]
+    fn rotation_follows_mupdf() {
+        // 顺时针旋转 90 度后，原来的左下角在左上角，页面宽高对调
+        let transform = PageTransform::new([0.0, 0.0, 400.0, 300.0], None, 90, 1.0);
+        assert!(close(transform.map_point(0.0, 0.0), (0.0, 0.0)));
+        assert!(close(transform.map_point(300.0, 0.0), (0.0, 300.0)));
+        assert!(close(transform.map_point(0.0, 400.0), (400.0, 0.0)));
+
+        let transform = PageTransform::new([0.0, 0.0, 400.0, 300.0], None, -90, 1.0);
+        assert!(close(transform.map_point(0.0, 0.0), (400.0, 300.0)));
+
+        let transform = PageTransform::new([0.0, 0.0, 400.0, 300.0], None, 180, 1.0);
+        assert!(close(transform.map_point(0.0, 0.0), (400.0, 0.0)));
+        assert_eq!(
+            transform.bounds(&Rect::new(10.0, 20.0, 30.0, 40.0)).map(f32::round),
+            [370.0, 20.0, 390.0, 40.0],
+        );
+    }
+
+    #[test]
+    fn crop_box_is_clipped_to_media_box() {
+        let transform = PageTransform::new([0.0, 0.0, 200.0, 200.0], Some([-50.0, 100.0, 300.0, 300.0]), 45, 0.0);
+        assert_eq!(transform.crop_box, [0.0, 100.0, 200.0, 200.0]);
+        assert!(close(transform.map_point(0.0, 0.0), (0.0, 200.0)));
+    }
+}
@@ -1,4 +1,14 @@
+pub mod annotations;
 pub mod pdf_decoder;
+pub mod redaction;
+pub mod split;
 pub mod utils;
+pub mod watermark;
+pub mod writer;
 
+pub use annotations::{save_with_highlights, save_with_highlights_in_background, PdfHighlight};
 pub use pdf_decoder::PdfDecoder;
+pub use redaction::{save_redacted, save_redacted_in_background, PdfRedaction};
+pub use split::{PdfChapter, SplitEvent, SplitJob};
+pub use watermark::Watermark;
+pub use writer::{rewrite_pdf, rewrite_pdf_in_background, RewriteOptions, RewriteStats};
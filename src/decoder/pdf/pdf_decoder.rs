@@ -1,10 +1,13 @@
+use crate::cache::PageGeometryCache;
 use crate::decoder::pdf::utils::mupdf_to_pixels;
-use crate::decoder::{Decoder, Link, LinkType, PageInfo, Rect};
-use crate::entity::{ReflowEntry, ReflowData};
+use crate::decoder::{Decoder, DocumentMetadata, Link, LinkType, PageInfo, Rect, TextBlock, TextLine};
+use crate::entity::ReflowEntry;
+use crate::error::RReaderError;
+use crate::reflow::ReflowCache;
 use anyhow::Result;
 use image::DynamicImage;
 use log::{info, debug};
-use mupdf::{Colorspace, Context, Device, Document, Matrix, Pixmap};
+use mupdf::{Colorspace, Context, Device, Document, Matrix, MetadataName, Pixmap};
 use regex::Regex;
 use std::cell::RefCell;
 use std::fs;
@@ -82,9 +85,20 @@ impl PdfDecoder {
         let path_str = path.as_ref().to_string_lossy().to_lowercase();
         info!("Opening document: {:?}", &path_str);
         
+        let extension = path.as_ref().extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !Document::recognize(&extension).unwrap_or(false) {
+            return Err(RReaderError::UnsupportedFormat(extension).into());
+        }
+
         let mut document = Document::open(&path_str)?;
+        if document.needs_password()? {
+            return Err(RReaderError::PasswordRequired(path_str).into());
+        }
         info!("Document opened");
-        if path_str.ends_with(".epub") || path_str.ends_with(".mobi") {
+        let reflowable = path_str.ends_with(".epub") || path_str.ends_with(".mobi");
+        if reflowable {
             let css = Self::generate_font_css(None, "20px");
             info!("应用自定义CSS: {}", css);
 
@@ -104,15 +118,29 @@ impl PdfDecoder {
         let page_count = document.page_count()? as usize;
         info!("Document opened with {} pages", page_count);
 
-        // 预加载所有页面尺寸
-        let mut pages_info = Vec::with_capacity(page_count);
-        for i in 0..page_count {
-            let page = document.load_page(i as i32)?;
-            let bounds = page.bounds()?;
-            let width = bounds.x1 - bounds.x0;
-            let height = bounds.y1 - bounds.y0;
-            pages_info.push(PageInfo::new(i, width, height));
-        }
+        // 固定版式的文档尺寸不变，优先用上次保存的尺寸，省去逐页加载
+        let cached = if reflowable { None } else { PageGeometryCache::load(path.as_ref(), page_count) };
+        let pages_info = match cached {
+            Some(pages_info) => {
+                info!("使用缓存的页面尺寸: {} 页", pages_info.len());
+                pages_info
+            }
+            None => {
+                // 预加载所有页面尺寸
+                let mut pages_info = Vec::with_capacity(page_count);
+                for i in 0..page_count {
+                    let page = document.load_page(i as i32)?;
+                    let bounds = page.bounds()?;
+                    let width = bounds.x1 - bounds.x0;
+                    let height = bounds.y1 - bounds.y0;
+                    pages_info.push(PageInfo::new(i, width, height));
+                }
+                if !reflowable {
+                    PageGeometryCache::save(path.as_ref(), &pages_info);
+                }
+                pages_info
+            }
+        };
 
         Ok(Self {
             document: RefCell::new(document),
@@ -124,59 +152,10 @@ impl PdfDecoder {
 }
 
 impl PdfDecoder {
-    fn get_cache_path(pdf_path: &Path) -> PathBuf {
-        use std::path::PathBuf;
-        let file_name = pdf_path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
-        dirs::data_dir()
-            .expect("Cannot get data directory")
-            .join("RReader")
-            .join("reflow")
-            .join(format!("{}_reflow.json", file_name))
-    }
-
-    fn get_or_create_reflow_data(&self, pdf_path: &Path) -> Result<ReflowData> {
-        let cache_path = Self::get_cache_path(pdf_path);
-
-        if cache_path.exists() {
-            return self.load_reflow_from_cache(&cache_path);
-        }
-
-        let page_count = self.page_count();
-        let file_size = fs::metadata(pdf_path)?.len();
-
-        let mut reflow = Vec::new();
-        for page in 0..page_count {
-            let text = self.get_page_text(page)?;
-            // 过滤掉字符数 <= 5 的页面
-            if text.chars().count() > 5 {
-                reflow.push(ReflowEntry {
-                    data: text,
-                    page: page.to_string(),
-                });
-            }
-        }
-
-        let reflow_data = ReflowData {
-            page_count: reflow.len(),
-            file_size,
-            reflow,
-        };
-
-        if let Some(parent) = cache_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        let json = serde_json::to_string_pretty(&reflow_data)?;
-        fs::write(&cache_path, json)?;
-
-        Ok(reflow_data)
-    }
-
-    fn load_reflow_from_cache(&self, cache_path: &PathBuf) -> Result<ReflowData> {
-        let content = fs::read_to_string(cache_path)?;
-        let reflow_data: ReflowData = serde_json::from_str(&content)?;
-        Ok(reflow_data)
+    fn get_or_create_reflow_data(&self) -> Result<ReflowCache> {
+        let mut cache = ReflowCache::open(&self.pdf_path)?;
+        cache.fill_all(self)?;
+        Ok(cache)
     }
 }
 
@@ -291,20 +270,97 @@ impl Decoder for PdfDecoder {
         Ok(text_page.to_text()?)
     }
 
+    fn get_page_text_blocks(&self, page_index: usize) -> Result<Vec<TextBlock>> {
+        let document = self.document.borrow();
+        let page = document.load_page(page_index as i32)?;
+        let text_page = page.to_text_page(mupdf::TextPageFlags::empty())?;
+
+        let mut blocks = Vec::new();
+        for block in text_page.blocks() {
+            // 图片块没有文字
+            if block.r#type() != mupdf::text_page::TextBlockType::Text {
+                continue;
+            }
+
+            let mut lines = Vec::new();
+            for line in block.lines() {
+                let mut text = String::new();
+                let mut size_sum = 0.0;
+                let mut char_count = 0;
+                for ch in line.chars() {
+                    if let Some(c) = ch.char() {
+                        text.push(c);
+                        size_sum += ch.size();
+                        char_count += 1;
+                    }
+                }
+                if text.trim().is_empty() {
+                    continue;
+                }
+                let b = line.bounds();
+                lines.push(TextLine {
+                    bounds: Rect::new(b.x0, b.y0, b.x1, b.y1),
+                    font_size: if char_count > 0 { size_sum / char_count as f32 } else { 0.0 },
+                    text,
+                });
+            }
+
+            if !lines.is_empty() {
+                let b = block.bounds();
+                blocks.push(TextBlock {
+                    bounds: Rect::new(b.x0, b.y0, b.x1, b.y1),
+                    lines,
+                });
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    fn hit_test(&self, page_index: usize, x: f32, y: f32) -> Result<crate::decoder::PageHit> {
+        crate::decoder::pdf::utils::hit_test_page(&self.document.borrow(), page_index, x, y)
+    }
+
+    fn search_page(&self, page_index: usize, query: &str) -> Result<Vec<crate::decoder::Rect>> {
+        crate::decoder::pdf::utils::search_page(&self.document.borrow(), page_index, query)
+    }
+
     fn get_outline_items(&self) -> Result<Vec<crate::entity::OutlineItem>> {
         use crate::decoder::pdf::utils::load_outline_items;
         Ok(load_outline_items(&self.document.borrow()))
     }
 
+    fn get_page_labels(&self) -> Result<Vec<String>> {
+        use crate::decoder::pdf::utils::load_page_label_ranges;
+        // 只有 PDF 有页码标签，同一个解码器打开的 EPUB 等格式跳过
+        let is_pdf = self.pdf_path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+        if !is_pdf {
+            return Ok(Vec::new());
+        }
+        let ranges = load_page_label_ranges(&self.pdf_path)?;
+        Ok(crate::decoder::page_label::build_labels(&ranges, self.page_count))
+    }
+
+    fn metadata(&self) -> DocumentMetadata {
+        let document = self.document.borrow();
+        let field = |name: MetadataName| document.metadata(name).map(|value| value.trim().to_string()).unwrap_or_default();
+        DocumentMetadata {
+            title: field(MetadataName::Title),
+            author: field(MetadataName::Author),
+            subject: field(MetadataName::Subject),
+        }
+    }
+
     fn get_reflow_from_page(&self, start_page: usize) -> Result<Vec<ReflowEntry>> {
-        let reflow_data = self.get_or_create_reflow_data(&self.pdf_path)?;
+        let cache = self.get_or_create_reflow_data()?;
+        let entries = cache.entries();
 
-        let start_index = reflow_data.reflow
+        let start_index = entries
             .iter()
             .position(|entry| entry.page.parse::<usize>().unwrap_or(0) >= start_page)
-            .unwrap_or(reflow_data.reflow.len());
+            .unwrap_or(entries.len());
 
-        Ok(reflow_data.reflow[start_index..].to_vec())
+        Ok(entries[start_index..].to_vec())
     }
 
     fn close(&mut self) {
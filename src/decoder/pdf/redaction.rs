@@ -0,0 +1,189 @@
+use anyhow::{bail, Context as _, Result};
+use crossbeam_channel::{unbounded, Receiver};
+use log::{debug, info};
+use mupdf::pdf::{PdfDocument, PdfObject, PdfPage, PdfWriteOptions};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::decoder::pdf::annotations::PageTransform;
+use crate::decoder::pdf::Watermark;
+use crate::decoder::Rect;
+
+/// 要涂黑的一块区域，范围为页面坐标（左上角为原点）
+#[derive(Debug, Clone, Copy)]
+pub struct PdfRedaction {
+    /// 从 0 开始的页码
+    pub page: usize,
+    pub rect: Rect,
+}
+
+/// 把区域写成 /Redact 注释后由 mupdf 应用：区域内的文字、图像和图形从内容流中删除，
+/// 再画上黑框，副本中无法再复制或搜索到原内容；设置了水印时一并加上。
+/// 应用后重新提取文字检查，区域内还有文字时报错，不写出副本。原文件不变，返回涂黑的区域数
+pub fn save_redacted(source: &Path, target: &Path, redactions: &[PdfRedaction], watermark: Option<&Watermark>) -> Result<usize> {
+    let document = PdfDocument::open(&source.to_string_lossy())
+        .with_context(|| format!("failed to open {:?} as PDF", source))?;
+    let page_count = document.page_count()? as usize;
+
+    let mut by_page: BTreeMap<usize, Vec<Rect>> = BTreeMap::new();
+    for redaction in redactions.iter().filter(|r| r.page < page_count && r.rect.width() > 0.0 && r.rect.height() > 0.0) {
+        by_page.entry(redaction.page).or_default().push(redaction.rect);
+    }
+
+    let mut applied = 0;
+    for (&index, rects) in &by_page {
+        let mut page = document.find_page(index as i32)?;
+        let transform = PageTransform::load(&page)?;
+        let before = chars_in(&document, index, rects)?;
+        for rect in rects {
+            let annotation = document.add_object(&redact_dict(&document, rect, &transform)?)?;
+            match page.get_dict("Annots")? {
+                Some(mut annots) if annots.is_array()? => annots.array_push(annotation)?,
+                _ => {
+                    let mut annots = document.new_array()?;
+                    annots.array_push(annotation)?;
+                    page.dict_put("Annots", annots)?;
+                }
+            }
+        }
+        // 加载页面时才读入新写的注释，应用后注释本身也会被移除
+        let mut pdf_page = PdfPage::try_from(document.load_page(index as i32)?)?;
+        pdf_page.redact()
+            .with_context(|| format!("failed to redact page {}", index + 1))?;
+        let remaining = chars_in(&document, index, rects)?;
+        if remaining > 0 {
+            bail!("{} characters are still inside the redacted areas on page {}", remaining, index + 1);
+        }
+        debug!("[PdfRedaction] 第 {} 页删除 {} 个字符", index + 1, before);
+        applied += rects.len();
+    }
+    if let Some(watermark) = watermark {
+        watermark.stamp_pdf(&document)?;
+    }
+
+    // 清理掉不再引用的对象，被删掉的内容不会残留在文件里
+    let mut write_options = PdfWriteOptions::default();
+    write_options
+        .set_garbage_level(4)
+        .set_clean(true)
+        .set_sanitize(true)
+        .set_compress(true);
+    let partial = target.with_extension("pdf.part");
+    document
+        .save_with_options(&partial.to_string_lossy(), write_options)
+        .with_context(|| format!("failed to write {:?}", partial))?;
+    fs::rename(&partial, target)?;
+    info!("[PdfRedaction] {:?} -> {:?}: {} regions on {} pages", source, target, applied, by_page.len());
+    Ok(applied)
+}
+
+/// 中心落在区域内的字符数，页面坐标
+fn chars_in(document: &PdfDocument, index: usize, rects: &[Rect]) -> Result<usize> {
+    let page = document.load_page(index as i32)?;
+    let text_page = page.to_text_page(mupdf::TextPageFlags::empty())?;
+    let mut count = 0;
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            for ch in line.chars() {
+                if ch.char().is_none_or(char::is_whitespace) {
+                    continue;
+                }
+                let q = ch.quad();
+                let (x, y) = ((q.ul.x + q.lr.x) / 2.0, (q.ul.y + q.lr.y) / 2.0);
+                if rects.iter().any(|rect| rect.contains(x, y)) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    Ok(count)
+}
+
+fn redact_dict(document: &PdfDocument, rect: &Rect, transform: &PageTransform) -> Result<PdfObject> {
+    let mut quads = document.new_array()?;
+    for (x, y) in transform.quad(rect) {
+        quads.array_push(document.new_real(x)?)?;
+        quads.array_push(document.new_real(y)?)?;
+    }
+
+    let mut pdf_rect = document.new_array()?;
+    for value in transform.bounds(rect) {
+        pdf_rect.array_push(document.new_real(value)?)?;
+    }
+
+    // 应用后区域填充为黑色
+    let mut fill = document.new_array()?;
+    for _ in 0..3 {
+        fill.array_push(document.new_real(0.0)?)?;
+    }
+
+    let mut dict = document.new_dict()?;
+    dict.dict_put("Type", document.new_name("Annot")?)?;
+    dict.dict_put("Subtype", document.new_name("Redact")?)?;
+    dict.dict_put("Rect", pdf_rect)?;
+    dict.dict_put("QuadPoints", quads)?;
+    dict.dict_put("IC", fill)?;
+    dict.dict_put("T", document.new_string("RReader")?)?;
+    Ok(dict)
+}
+
+/// 在独立线程里写入，mupdf 上下文按线程创建，不影响解码线程
+pub fn save_redacted_in_background(source: PathBuf, target: PathBuf, redactions: Vec<PdfRedaction>, watermark: Option<Watermark>) -> Receiver<Result<usize>> {
+    let (result_tx, result_rx) = unbounded();
+    thread::spawn(move || {
+        let result = save_redacted(&source, &target, &redactions, watermark.as_ref());
+        if result.is_err() {
+            let _ = fs::remove_file(target.with_extension("pdf.part"));
+        }
+        let _ = result_tx.send(result);
+    });
+    result_rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::pdf::utils::search_page;
+    use mupdf::Document;
+
+    /// 带 CropBox 偏移和 /Rotate 的单页 PDF，没有 xref，由 mupdf 修复
+    fn write_rotated_pdf(path: &Path) {
+        let content = "BT /F1 24 Tf 80 200 Td (SECRET) Tj ET\nBT /F1 24 Tf 80 80 Td (PUBLIC) Tj ET\n";
+        let pdf = format!(
+            "%PDF-1.7\n\
+             1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+             2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+             3 0 obj << /Type /Page /Parent 2 0 R /MediaBox [0 0 400 300] /CropBox [50 20 350 280] /Rotate 90 \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >> endobj\n\
+             4 0 obj << /Length {} >> stream\n{}endstream endobj\n\
+             5 0 obj << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> endobj\n\
+             trailer << /Root 1 0 R >>\n%%EOF\n",
+            content.len(),
+            content,
+        );
+        fs::write(path, pdf).unwrap();
+    }
+
+    #[test]
+    fn redacts_text_on_cropped_and_rotated_page() {
+        let dir = std::env::temp_dir().join(format!("rreader-redaction-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.pdf");
+        let target = dir.join("redacted.pdf");
+        write_rotated_pdf(&source);
+
+        let document = Document::open(&source.to_string_lossy()).unwrap();
+        let hits = search_page(&document, 0, "SECRET").unwrap();
+        assert_eq!(hits.len(), 1);
+        let redactions = [PdfRedaction { page: 0, rect: hits[0] }];
+
+        assert_eq!(save_redacted(&source, &target, &redactions, None).unwrap(), 1);
+
+        let redacted = Document::open(&target.to_string_lossy()).unwrap();
+        assert!(search_page(&redacted, 0, "SECRET").unwrap().is_empty());
+        assert_eq!(search_page(&redacted, 0, "PUBLIC").unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::{Context as _, Result};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{info, warn};
+use mupdf::pdf::{PdfDocument, PdfWriteOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::decoder::pdf::Watermark;
+
+/// 文件名中标题部分的最大字符数
+const MAX_TITLE_CHARS: usize = 80;
+
+/// 第一章之前的封面、目录等页面单独成一个文件，使用这个标题
+const FRONT_MATTER_TITLE: &str = "前置页面";
+
+/// 一章的页码范围，从 0 开始，包含 last_page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfChapter {
+    pub title: String,
+    pub first_page: usize,
+    pub last_page: usize,
+}
+
+impl PdfChapter {
+    /// 按各章起始页算出页码范围：每章到下一章的前一页为止，最后一章到文档末尾。
+    /// 起始页要递增，和前一章同页或更靠前的条目（大纲顺序有误）跳过；
+    /// 第一章不从第一页开始时，前面的页面作为单独的一部分放在最前
+    pub fn from_starts(starts: &[(String, usize)], page_count: usize) -> Vec<PdfChapter> {
+        let mut chapters: Vec<PdfChapter> = Vec::new();
+        for (title, first_page) in starts {
+            if *first_page >= page_count || chapters.last().is_some_and(|last| *first_page <= last.first_page) {
+                continue;
+            }
+            if let Some(last) = chapters.last_mut() {
+                last.last_page = first_page - 1;
+            }
+            chapters.push(PdfChapter { title: title.clone(), first_page: *first_page, last_page: page_count - 1 });
+        }
+        if let Some(first) = chapters.first().filter(|first| first.first_page > 0) {
+            let last_page = first.first_page - 1;
+            chapters.insert(0, PdfChapter { title: FRONT_MATTER_TITLE.to_string(), first_page: 0, last_page });
+        }
+        chapters
+    }
+
+    /// 文件名：两位序号加大纲标题，去掉文件系统不允许的字符
+    pub fn file_name(&self, number: usize) -> String {
+        let title: String = self.title
+            .chars()
+            .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+            .take(MAX_TITLE_CHARS)
+            .collect();
+        // Windows 不允许以点或空格结尾
+        let title = title.trim().trim_end_matches('.').trim();
+        if title.is_empty() {
+            format!("{:02}.pdf", number)
+        } else {
+            format!("{:02} - {}.pdf", number, title)
+        }
+    }
+}
+
+/// 拆分进度事件
+#[derive(Debug)]
+pub enum SplitEvent {
+    Progress { done: usize, total: usize },
+    /// 写出的文件，出错或取消时为已写出的部分和错误
+    Finished { written: Vec<PathBuf>, error: Option<String> },
+}
+
+/// 按章拆分：每章另存为一个 PDF，在独立线程里逐章写出，可以在两章之间取消
+pub struct SplitJob {
+    event_rx: Receiver<SplitEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl SplitJob {
+    pub fn start(source: PathBuf, dir: PathBuf, chapters: Vec<PdfChapter>, watermark: Option<Watermark>) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancelled_for_thread = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let mut written = Vec::new();
+            let result = Self::split(&source, &dir, &chapters, watermark.as_ref(), &event_tx, &cancelled_for_thread, &mut written);
+            info!("[PdfSplit] {:?} -> {:?}: {}/{} chapters", source, dir, written.len(), chapters.len());
+            let error = result.err().map(|e| {
+                warn!("[PdfSplit] 拆分失败: {:#}", e);
+                format!("{:#}", e)
+            });
+            let _ = event_tx.send(SplitEvent::Finished { written, error });
+        });
+        Self { event_rx, cancelled }
+    }
+
+    pub fn try_recv(&self) -> Option<SplitEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn split(
+        source: &Path,
+        dir: &Path,
+        chapters: &[PdfChapter],
+        watermark: Option<&Watermark>,
+        event_tx: &Sender<SplitEvent>,
+        cancelled: &AtomicBool,
+        written: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let document = PdfDocument::open(&source.to_string_lossy())
+            .with_context(|| format!("failed to open {:?} as PDF", source))?;
+        for (i, chapter) in chapters.iter().enumerate() {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let target = dir.join(chapter.file_name(i + 1));
+            if target == source {
+                warn!("[PdfSplit] 跳过与原文件同名的章节: {:?}", target);
+                continue;
+            }
+            if let Err(e) = save_chapter(&document, chapter, &target, watermark) {
+                let _ = fs::remove_file(target.with_extension("pdf.part"));
+                return Err(e);
+            }
+            written.push(target);
+            let _ = event_tx.send(SplitEvent::Progress { done: i + 1, total: chapters.len() });
+        }
+        Ok(())
+    }
+}
+
+/// 把一章的页面复制到新文档，链接、注释等页面资源随页面一起复制
+fn save_chapter(document: &PdfDocument, chapter: &PdfChapter, target: &Path, watermark: Option<&Watermark>) -> Result<()> {
+    let mut output = PdfDocument::new();
+    for page in chapter.first_page..=chapter.last_page {
+        output.graft_page(-1, document, page as i32)
+            .with_context(|| format!("failed to copy page {}", page + 1))?;
+    }
+    if let Some(watermark) = watermark {
+        watermark.stamp_pdf(&output)?;
+    }
+
+    let mut write_options = PdfWriteOptions::default();
+    write_options
+        .set_garbage_level(4)
+        .set_compress(true)
+        .set_compress_fonts(true);
+    let partial = target.with_extension("pdf.part");
+    output
+        .save_with_options(&partial.to_string_lossy(), write_options)
+        .with_context(|| format!("failed to write {:?}", partial))?;
+    fs::rename(&partial, target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(items: &[(&str, usize)]) -> Vec<(String, usize)> {
+        items.iter().map(|(title, page)| (title.to_string(), *page)).collect()
+    }
+
+    #[test]
+    fn chapters_end_before_next_start() {
+        let chapters = PdfChapter::from_starts(&starts(&[("前言", 0), ("第一章", 3), ("第二章", 10)]), 20);
+        let ranges: Vec<(usize, usize)> = chapters.iter().map(|c| (c.first_page, c.last_page)).collect();
+        assert_eq!(ranges, vec![(0, 2), (3, 9), (10, 19)]);
+    }
+
+    #[test]
+    fn out_of_order_and_out_of_range_starts_are_skipped() {
+        let chapters = PdfChapter::from_starts(&starts(&[("A", 2), ("B", 2), ("C", 1), ("D", 5), ("E", 30)]), 8);
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec![FRONT_MATTER_TITLE, "A", "D"]);
+        assert_eq!(chapters[1].last_page, 4);
+        assert_eq!(chapters[2].last_page, 7);
+    }
+
+    #[test]
+    fn pages_before_first_chapter_are_kept() {
+        let chapters = PdfChapter::from_starts(&starts(&[("第一章", 4), ("第二章", 9)]), 12);
+        let ranges: Vec<(&str, usize, usize)> = chapters.iter().map(|c| (c.title.as_str(), c.first_page, c.last_page)).collect();
+        assert_eq!(ranges, vec![(FRONT_MATTER_TITLE, 0, 3), ("第一章", 4, 8), ("第二章", 9, 11)]);
+        assert!(PdfChapter::from_starts(&[], 12).is_empty());
+    }
+
+    #[test]
+    fn file_name_removes_invalid_characters() {
+        let chapter = |title: &str| PdfChapter { title: title.into(), first_page: 0, last_page: 0 };
+        assert_eq!(chapter("Part 1: Intro / Basics").file_name(1), "01 - Part 1_ Intro _ Basics.pdf");
+        assert_eq!(chapter("第三章 算法…").file_name(12), "12 - 第三章 算法….pdf");
+        assert_eq!(chapter(" ... ").file_name(3), "03.pdf");
+    }
+}
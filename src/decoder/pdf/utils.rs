@@ -1,9 +1,12 @@
 use image::{DynamicImage, ImageBuffer, Rgba};
 use log::debug;
+use mupdf::pdf::{PdfDocument, PdfObject};
 use mupdf::{Document, Matrix, Outline, Pixmap};
-use regex::Regex;
+#[cfg(feature = "gui")]
 use slint::{Image, Rgba8Pixel, SharedPixelBuffer};
 
+use crate::decoder::page_label::{LabelRange, LabelStyle};
+use crate::decoder::{PageHit, PageWord, Rect};
 use crate::{entity::OutlineItem, page::Page};
 
 pub fn create_matrix(zoom: f32, rotation: f32) -> Matrix {
@@ -61,6 +64,7 @@ pub fn mupdf_to_pixels(pixmap: &Pixmap) -> (Vec<u8>, u32, u32) {
     (buffer, width, height)
 }
 
+#[cfg(feature = "gui")]
 pub fn convert_to_slint_image(image: &image::DynamicImage) -> Image {
     let rgba_image = image.to_rgba8();
     let (width, height) = rgba_image.dimensions();
@@ -70,10 +74,11 @@ pub fn convert_to_slint_image(image: &image::DynamicImage) -> Image {
     )
 }
 
-pub fn generate_thumbnail_key(page: &Page) -> String {
+/// 页面图像的缓存键，document 区分先后打开的文档
+pub fn generate_thumbnail_key(document: u64, page: &Page) -> String {
     format!(
-        "{}-{}-{}",
-        page.info.index, page.info.width, page.info.height
+        "{}-{}-{}-{}",
+        document, page.info.index, page.info.width, page.info.height
     )
 }
 
@@ -96,12 +101,8 @@ fn process_outline_hierarchy(
     for outline in outlines {
         let title = outline.title.clone();
         let uri = outline.uri.clone();
-        let page = if let Some(dest) = &outline.dest {
-            dest.loc.page_number as i32
-        } else {
-            extract_page_from_uri(uri.clone().unwrap_or_default()) as i32
-        };
-        //debug!("extract_page_from_uri:{:?}, {:?}", page, uri.clone());
+        // 没有解析出目标页的条目留给 PageViewState 按 uri（可能是页码标签）解析
+        let page = outline.dest.as_ref().map_or(-1, |dest| dest.loc.page_number as i32);
 
         let item = OutlineItem::new(title, uri, page, level);
         //debug!("outline:{:?}, {:?}", level, item.clone());
@@ -113,22 +114,133 @@ fn process_outline_hierarchy(
     }
 }
 
-fn extract_page_from_uri(uri: String) -> i32 {
-    let pattern = Regex::new(r"#page=(\d+)").unwrap();
-    if let Some(captures) = pattern.captures(&uri) {
-        if let Some(page_match) = captures.get(1) {
-            if let Ok(page) = page_match.as_str().parse::<i32>() {
-                // PDFs are typically 1-based, but convert to 0-based for array indexing
-                return (page - 1).max(0);
+/// 单页最多返回的匹配数
+const MAX_SEARCH_HITS_PER_PAGE: u32 = 500;
+
+/// 用 MuPDF 的搜索在页面中查找文字，返回每处匹配的外接矩形（页面坐标），PDF、DjVu、TIFF 共用
+pub fn search_page(document: &Document, page_index: usize, query: &str) -> anyhow::Result<Vec<Rect>> {
+    let page = document.load_page(page_index as i32)?;
+    let quads = page.search(query, MAX_SEARCH_HITS_PER_PAGE)?;
+    Ok(quads.iter()
+        .map(|q| Rect::new(
+            q.ul.x.min(q.ll.x),
+            q.ul.y.min(q.ur.y),
+            q.ur.x.max(q.lr.x),
+            q.ll.y.max(q.lr.y),
+        ))
+        .collect())
+}
+
+/// 页面坐标 (x, y) 下的图片块和单词，PDF、DjVu、TIFF 共用
+pub fn hit_test_page(document: &Document, page_index: usize, x: f32, y: f32) -> anyhow::Result<PageHit> {
+    let page = document.load_page(page_index as i32)?;
+    let text_page = page.to_text_page(mupdf::TextPageFlags::PRESERVE_IMAGES)?;
+
+    let mut hit = PageHit::default();
+    for block in text_page.blocks() {
+        let b = block.bounds();
+        let bounds = Rect::new(b.x0, b.y0, b.x1, b.y1);
+        if !bounds.contains(x, y) {
+            continue;
+        }
+        match block.r#type() {
+            mupdf::text_page::TextBlockType::Image => hit.image = Some(bounds),
+            mupdf::text_page::TextBlockType::Text => {
+                if hit.word.is_none() {
+                    hit.word = word_in_block(&block, x, y);
+                }
             }
+            _ => {}
         }
     }
+    Ok(hit)
+}
 
-    // Try to parse the whole URI as a page number
-    if let Ok(page) = uri.parse::<i32>() {
-        return (page - 1).max(0);
+/// 在文本块中查找包含该点的单词；字母数字连续的一段算一个单词，中文按标点分段
+fn word_in_block(block: &mupdf::text_page::TextBlock, x: f32, y: f32) -> Option<PageWord> {
+    let mut paragraph = String::new();
+    let mut lines = Vec::new();
+    let mut found: Option<(String, Rect)> = None;
+    for line in block.lines() {
+        let b = line.bounds();
+        lines.push(Rect::new(b.x0, b.y0, b.x1, b.y1));
+        let mut current: Option<(String, Rect)> = None;
+        for ch in line.chars() {
+            let Some(c) = ch.char() else { continue };
+            paragraph.push(c);
+            if !(c.is_alphanumeric() || c == '\'' || c == '-') {
+                if let Some(word) = current.take() {
+                    found = found.or_else(|| word.1.contains(x, y).then_some(word));
+                }
+                continue;
+            }
+            let q = ch.quad();
+            let bounds = Rect::new(
+                q.ul.x.min(q.ll.x),
+                q.ul.y.min(q.ur.y),
+                q.ur.x.max(q.lr.x),
+                q.ll.y.max(q.lr.y),
+            );
+            match current.as_mut() {
+                Some((text, word_bounds)) => {
+                    text.push(c);
+                    *word_bounds = word_bounds.union(&bounds);
+                }
+                None => current = Some((c.to_string(), bounds)),
+            }
+        }
+        if let Some(word) = current.take() {
+            found = found.or_else(|| word.1.contains(x, y).then_some(word));
+        }
+        paragraph.push('\n');
     }
+    found.map(|(text, bounds)| PageWord { text, bounds, paragraph, lines })
+}
+
+/// 读取 PDF 目录中的 /PageLabels 数字树，没有时返回空
+pub fn load_page_label_ranges(path: &std::path::Path) -> anyhow::Result<Vec<LabelRange>> {
+    let document = PdfDocument::open(&path.to_string_lossy())?;
+    let Some(labels) = document.catalog()?.get_dict("PageLabels")? else {
+        return Ok(Vec::new());
+    };
+    let mut ranges = Vec::new();
+    collect_label_ranges(&labels, &mut ranges, 0)?;
+    Ok(ranges)
+}
 
-    // Default to page 0
-    0
+/// 数字树：叶子节点的 /Nums 为 [页索引 标签字典 ...]，中间节点的 /Kids 指向子树
+fn collect_label_ranges(node: &PdfObject, ranges: &mut Vec<LabelRange>, depth: usize) -> anyhow::Result<()> {
+    // 防止损坏的文件出现循环引用
+    if depth > 32 {
+        return Ok(());
+    }
+    if let Some(nums) = node.get_dict("Nums")? {
+        for i in (0..nums.len()?).step_by(2) {
+            let (Some(key), Some(dict)) = (nums.get_array(i as i32)?, nums.get_array(i as i32 + 1)?) else { continue };
+            if !key.is_int()? || !dict.is_dict()? {
+                continue;
+            }
+            let style = match dict.get_dict("S")? {
+                Some(style) if style.is_name()? => LabelStyle::from_pdf_name(style.as_name()?),
+                _ => None,
+            };
+            let prefix = match dict.get_dict("P")? {
+                Some(prefix) if prefix.is_string()? => prefix.as_string()?.to_string(),
+                _ => String::new(),
+            };
+            let first = match dict.get_dict("St")? {
+                Some(start) if start.is_int()? => start.as_int()?.max(1) as u32,
+                _ => 1,
+            };
+            ranges.push(LabelRange { start_page: key.as_int()?.max(0) as usize, style, prefix, first });
+        }
+    }
+    if let Some(kids) = node.get_dict("Kids")? {
+        for i in 0..kids.len()? {
+            if let Some(kid) = kids.get_array(i as i32)? {
+                collect_label_ranges(&kid, ranges, depth + 1)?;
+            }
+        }
+    }
+    Ok(())
 }
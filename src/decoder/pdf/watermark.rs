@@ -0,0 +1,214 @@
+use anyhow::{Context as _, Result};
+use image::RgbaImage;
+use log::{debug, info};
+use mupdf::pdf::{PdfDocument, PdfObject};
+use mupdf::{Colorspace, Device, Document, Matrix, Pixmap};
+use std::collections::HashMap;
+
+use crate::decoder::pdf::annotations::{inherited, PageTransform};
+use crate::decoder::text::paginate::text_width;
+use crate::settings::{AppSettings, WatermarkSettings};
+
+/// 水印文字的灰度
+const WATERMARK_GRAY: u8 = 0x80;
+/// 文字沿对角线占的比例
+const DIAGONAL_FILL: f32 = 0.6;
+/// 写入 PDF 时每点的像素数，最长边不超过 MAX_PDF_PIXELS
+const PDF_PIXELS_PER_POINT: f32 = 1.5;
+const MAX_PDF_PIXELS: f32 = 2000.0;
+
+/// 文字水印：沿页面对角线居中绘制一行半透明的灰色文字，
+/// 由 mupdf 排版，中文等非拉丁文字也能正确显示
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    pub text: String,
+    /// 不透明度，0~1
+    pub opacity: f32,
+}
+
+impl Watermark {
+    pub fn new(settings: &WatermarkSettings) -> Option<Self> {
+        settings.is_enabled().then(|| Self {
+            text: settings.text.trim().to_string(),
+            opacity: settings.opacity.clamp(0.0, 1.0),
+        })
+    }
+
+    /// 设置中配置了水印时返回
+    pub fn from_settings() -> Option<Self> {
+        Self::new(&AppSettings::get().watermark)
+    }
+
+    /// 水印的不透明度遮罩，每像素一个字节，已乘上 opacity
+    pub fn render_mask(&self, width: u32, height: u32) -> Result<Vec<u8>> {
+        let (w, h) = (width as f32, height as f32);
+        let diagonal = (w * w + h * h).sqrt();
+        let font_size = (diagonal * DIAGONAL_FILL / text_width(&self.text, 1.0).max(1.0)).min(h / 4.0);
+        let line_height = font_size * 1.4;
+
+        let html = format!(
+            "<html><head><style>@page {{ margin: 0; }} body {{ margin: 0; }} \
+             div {{ white-space: pre; text-align: center; font-weight: bold; color: black; \
+             font-size: {:.2}pt; line-height: {:.2}pt; }}</style></head><body><div>{}</div></body></html>",
+            font_size,
+            line_height,
+            escape_html(&self.text),
+        );
+        let mut document = Document::from_bytes(html.as_bytes(), "html")?;
+        document.layout(diagonal, line_height, font_size)?;
+        let page = document.load_page(0)?;
+
+        // 以文字中心为原点旋转到图像中心，y 轴向下，从左下到右上为负角
+        let (sin, cos) = (-h.atan2(w)).sin_cos();
+        let (cx0, cy0) = (diagonal / 2.0, line_height / 2.0);
+        let (cx1, cy1) = (w / 2.0, h / 2.0);
+        let matrix = Matrix::new(cos, sin, -sin, cos, cx1 - (cos * cx0 - sin * cy0), cy1 - (sin * cx0 + cos * cy0));
+
+        let pixmap = Pixmap::new(&Colorspace::device_rgb(), 0, 0, width as i32, height as i32, true)?;
+        pixmap.clear()?;
+        let device = Device::from_pixmap(&pixmap)?;
+        page.run(&device, &matrix)?;
+
+        // 只用 alpha，文字颜色在合成时统一
+        let n = pixmap.n() as usize;
+        let opacity = self.opacity;
+        Ok(pixmap.samples()
+            .chunks_exact(n)
+            .map(|pixel| (pixel[n - 1] as f32 * opacity).round() as u8)
+            .collect())
+    }
+
+    /// 把水印画进导出的图片
+    pub fn apply_to_image(&self, image: &mut RgbaImage) -> Result<()> {
+        let mask = self.render_mask(image.width(), image.height())?;
+        for (pixel, alpha) in image.pixels_mut().zip(mask) {
+            for channel in &mut pixel.0[..3] {
+                *channel = blend(*channel, WATERMARK_GRAY, alpha);
+            }
+        }
+        Ok(())
+    }
+
+    /// 把水印作为带透明遮罩的图像盖在每一页的内容之上，尺寸相同的页面共用一张图像。返回加水印的页数
+    pub fn stamp_pdf(&self, document: &PdfDocument) -> Result<usize> {
+        let page_count = document.page_count()? as usize;
+        // 页面尺寸 -> 资源名和图像的对象号
+        let mut images: HashMap<(u32, u32), (String, i32)> = HashMap::new();
+        for index in 0..page_count {
+            let mut page = document.find_page(index as i32)?;
+            // 盖住未旋转的内容坐标系中的可见区域
+            let [left, bottom, right, top] = PageTransform::load(&page)?.crop_box;
+            let (width, height) = (right - left, top - bottom);
+            if width <= 0.0 || height <= 0.0 {
+                continue;
+            }
+
+            let scale = PDF_PIXELS_PER_POINT.min(MAX_PDF_PIXELS / width.max(height));
+            let size = ((width * scale).round().max(1.0) as u32, (height * scale).round().max(1.0) as u32);
+            if !images.contains_key(&size) {
+                let image = self.image_object(document, size.0, size.1)?;
+                images.insert(size, (format!("RRWatermark{}", images.len()), image.as_indirect()?));
+            }
+            let (name, image) = &images[&size];
+
+            let mut resources = match inherited(&page, "Resources")? {
+                Some(resources) => resources,
+                None => document.new_dict()?,
+            };
+            if !resources.get_dict("XObject")?.map(|xobjects| xobjects.is_dict()).transpose()?.unwrap_or(false) {
+                resources.dict_put("XObject", document.new_dict()?)?;
+            }
+            let mut xobjects = resources.get_dict("XObject")?.context("missing XObject dictionary")?;
+            xobjects.dict_put(name, document.new_indirect(*image, 0)?)?;
+            page.dict_put("Resources", resources)?;
+
+            // 原内容包在 q/Q 中，避免其中的图形状态影响水印
+            let prefix = content_stream(document, b"q\n")?;
+            let stamp = format!("\nQ\nq {:.2} 0 0 {:.2} {:.2} {:.2} cm /{} Do Q\n", width, height, left, bottom, name);
+            let suffix = content_stream(document, stamp.as_bytes())?;
+            let mut contents = document.new_array()?;
+            contents.array_push(prefix)?;
+            match page.get_dict("Contents")? {
+                Some(existing) if existing.is_array()? => {
+                    for i in 0..existing.len()? {
+                        if let Some(stream) = existing.get_array(i as i32)? {
+                            contents.array_push(stream)?;
+                        }
+                    }
+                }
+                Some(existing) => contents.array_push(existing)?,
+                None => {}
+            }
+            contents.array_push(suffix)?;
+            page.dict_put("Contents", contents)?;
+        }
+        info!("[Watermark] 加水印 {} 页, {} 种页面尺寸", page_count, images.len());
+        Ok(page_count)
+    }
+
+    /// 灰色图像加上水印遮罩作为 SMask
+    fn image_object(&self, document: &PdfDocument, width: u32, height: u32) -> Result<PdfObject> {
+        debug!("[Watermark] 渲染 {}x{}", width, height);
+        let mask = self.render_mask(width, height)?;
+
+        let mut smask = document.new_dict()?;
+        smask.dict_put("Type", document.new_name("XObject")?)?;
+        smask.dict_put("Subtype", document.new_name("Image")?)?;
+        smask.dict_put("Width", document.new_int(width as i32)?)?;
+        smask.dict_put("Height", document.new_int(height as i32)?)?;
+        smask.dict_put("ColorSpace", document.new_name("DeviceGray")?)?;
+        smask.dict_put("BitsPerComponent", document.new_int(8)?)?;
+        let mut smask = document.add_object(&smask)?;
+        smask.write_raw_stream(&mask)?;
+
+        let mut image = document.new_dict()?;
+        image.dict_put("Type", document.new_name("XObject")?)?;
+        image.dict_put("Subtype", document.new_name("Image")?)?;
+        image.dict_put("Width", document.new_int(width as i32)?)?;
+        image.dict_put("Height", document.new_int(height as i32)?)?;
+        image.dict_put("ColorSpace", document.new_name("DeviceGray")?)?;
+        image.dict_put("BitsPerComponent", document.new_int(8)?)?;
+        image.dict_put("SMask", smask)?;
+        let mut image = document.add_object(&image)?;
+        image.write_raw_stream(&vec![WATERMARK_GRAY; (width * height) as usize])
+            .context("failed to write watermark image")?;
+        Ok(image)
+    }
+}
+
+fn blend(base: u8, color: u8, alpha: u8) -> u8 {
+    let alpha = alpha as u32;
+    ((base as u32 * (255 - alpha) + color as u32 * alpha + 127) / 255) as u8
+}
+
+fn content_stream(document: &PdfDocument, content: &[u8]) -> Result<PdfObject> {
+    let mut stream = document.add_object(&document.new_dict()?)?;
+    stream.write_raw_stream(content)?;
+    Ok(stream)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_keeps_base_without_alpha() {
+        assert_eq!(blend(200, WATERMARK_GRAY, 0), 200);
+        assert_eq!(blend(200, WATERMARK_GRAY, 255), WATERMARK_GRAY);
+        assert_eq!(blend(255, 0, 64), 191);
+    }
+
+    #[test]
+    fn empty_text_disables_watermark() {
+        let settings = WatermarkSettings { text: "  ".into(), opacity: 0.5 };
+        assert!(Watermark::new(&settings).is_none());
+        let settings = WatermarkSettings { text: " 机密 ".into(), opacity: 2.0 };
+        let watermark = Watermark::new(&settings).unwrap();
+        assert_eq!(watermark.text, "机密");
+        assert_eq!(watermark.opacity, 1.0);
+    }
+}
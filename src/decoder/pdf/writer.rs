@@ -0,0 +1,135 @@
+use anyhow::{Context as _, Result};
+use crossbeam_channel::{unbounded, Receiver};
+use image::codecs::jpeg::JpegEncoder;
+use log::{debug, info};
+use mupdf::pdf::{PdfDocument, PdfObject, PdfWriteOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// 重写 PDF 的选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RewriteOptions {
+    /// 线性化（快速网页查看），输出可以边下载边显示
+    pub linearize: bool,
+    /// 重新压缩 JPEG 图像的质量（1~100），None 表示保留原图
+    pub image_quality: Option<u8>,
+}
+
+/// 重写前后的文件大小
+#[derive(Debug, Clone, Copy)]
+pub struct RewriteStats {
+    pub before: u64,
+    pub after: u64,
+    /// 重新压缩后变小而被替换的图像数
+    pub images_recompressed: usize,
+}
+
+/// 通过 mupdf 的 pdf 写入器重写文档：打开时 mupdf 会自动修复损坏的交叉引用表，
+/// 写出时清理内容流、回收无用对象并重新编号，很多其他工具打不开的文件这样处理后就能打开
+pub fn rewrite_pdf(source: &Path, target: &Path, options: RewriteOptions) -> Result<RewriteStats> {
+    let before = fs::metadata(source)?.len();
+    let document = PdfDocument::open(&source.to_string_lossy())
+        .with_context(|| format!("failed to open {:?} as PDF", source))?;
+
+    let images_recompressed = match options.image_quality {
+        Some(quality) => recompress_images(&document, quality)?,
+        None => 0,
+    };
+
+    let mut write_options = PdfWriteOptions::default();
+    write_options
+        .set_garbage_level(4)
+        .set_clean(true)
+        .set_sanitize(true)
+        .set_compress(true)
+        .set_compress_fonts(true)
+        .set_linear(options.linearize);
+
+    // 先写临时文件，成功后再替换，避免失败时留下半个文件
+    let partial = target.with_extension("pdf.part");
+    document
+        .save_with_options(&partial.to_string_lossy(), write_options)
+        .with_context(|| format!("failed to write {:?}", partial))?;
+    fs::rename(&partial, target)?;
+
+    let after = fs::metadata(target)?.len();
+    info!(
+        "[PdfWriter] rewrite {:?} -> {:?}: {} -> {} bytes, images recompressed: {}",
+        source, target, before, after, images_recompressed
+    );
+    Ok(RewriteStats { before, after, images_recompressed })
+}
+
+/// 以指定质量重新编码文档中的 JPEG（DCTDecode）图像，只替换变小的图像
+/// 扫描件的体积几乎都来自这些图像；其他编码的图像保持不变
+fn recompress_images(document: &PdfDocument, quality: u8) -> Result<usize> {
+    let quality = quality.clamp(1, 100);
+    let mut replaced = 0;
+    for num in 1..document.count_objects()? {
+        let Ok(mut object) = document.new_indirect(num, 0) else { continue };
+        let Some(gray) = jpeg_image_is_gray(&object)? else { continue };
+        let original = object.read_raw_stream()?;
+        let Ok(decoded) = image::load_from_memory_with_format(&original, image::ImageFormat::Jpeg) else {
+            // CMYK 等 image 无法解码的 JPEG 保留原样
+            continue;
+        };
+
+        let mut encoded = Vec::new();
+        let encoder = JpegEncoder::new_with_quality(&mut encoded, quality);
+        // 输出的通道数要和字典里的 ColorSpace 一致
+        let written = if gray {
+            decoded.to_luma8().write_with_encoder(encoder)
+        } else {
+            decoded.to_rgb8().write_with_encoder(encoder)
+        };
+        if written.is_err() || encoded.len() >= original.len() {
+            continue;
+        }
+
+        debug!("[PdfWriter] image {}: {} -> {} bytes", num, original.len(), encoded.len());
+        object.write_raw_stream(&encoded)?;
+        replaced += 1;
+    }
+    Ok(replaced)
+}
+
+/// 只处理单一 DCTDecode 过滤器的 RGB/灰度图像，带 Decode 数组或多级过滤器的跳过
+/// 可以处理时返回是否为灰度图
+fn jpeg_image_is_gray(object: &PdfObject) -> Result<Option<bool>> {
+    if !object.is_stream()? {
+        return Ok(None);
+    }
+    let is_name = |key: &str, expected: &[u8]| -> Result<bool> {
+        Ok(match object.get_dict(key)? {
+            Some(value) => value.is_name()? && value.as_name()? == expected,
+            None => false,
+        })
+    };
+    if !is_name("Subtype", b"Image")? || !is_name("Filter", b"DCTDecode")? {
+        return Ok(None);
+    }
+    if object.get_dict("Decode")?.is_some() || object.get_dict("SMaskInData")?.is_some() {
+        return Ok(None);
+    }
+    if is_name("ColorSpace", b"DeviceGray")? {
+        Ok(Some(true))
+    } else if is_name("ColorSpace", b"DeviceRGB")? {
+        Ok(Some(false))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 在独立线程里重写，mupdf 上下文按线程创建，不影响解码线程
+pub fn rewrite_pdf_in_background(source: PathBuf, target: PathBuf, options: RewriteOptions) -> Receiver<Result<RewriteStats>> {
+    let (result_tx, result_rx) = unbounded();
+    thread::spawn(move || {
+        let result = rewrite_pdf(&source, &target, options);
+        if result.is_err() {
+            let _ = fs::remove_file(target.with_extension("pdf.part"));
+        }
+        let _ = result_tx.send(result);
+    });
+    result_rx
+}
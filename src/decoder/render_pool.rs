@@ -0,0 +1,143 @@
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::decoder::decode_service::{RenderContext, RenderPage};
+use crate::decoder::{formats, DecodeService, Decoder, RenderQueue};
+use crate::platform::power;
+
+/// 省电配置下渲染线程暂停，隔这么久检查一次是否恢复
+const LOW_POWER_POLL: Duration = Duration::from_secs(1);
+
+/// 渲染线程共享的状态：优先级渲染队列和当前文档
+#[derive(Default)]
+struct PoolState {
+    queue: RenderQueue,
+    /// 文档序号和路径，序号变化时渲染线程重新打开解码器
+    document: Option<(u64, PathBuf)>,
+    shutdown: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<PoolState>,
+    ready: Condvar,
+}
+
+/// 渲染线程池：每个线程为同一文件打开自己的解码器（独立的 mupdf 文档句柄），
+/// 空闲的线程从共享的优先级队列中取下一个任务，可见的多个大页面并行渲染。
+/// 解码线程也从同一队列取任务，drop 时停止并等待所有渲染线程退出。
+/// 省电配置下渲染线程不取任务，只由解码线程渲染
+pub struct RenderPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl RenderPool {
+    pub(crate) fn start(worker_count: usize, context: RenderContext) -> Self {
+        let shared = Arc::new(Shared::default());
+        let workers = (0..worker_count)
+            .map(|id| {
+                let shared = Arc::clone(&shared);
+                let context = context.clone();
+                thread::spawn(move || Self::worker_loop(id, shared, context))
+            })
+            .collect();
+        info!("[RenderPool] 渲染线程: {}", worker_count);
+        Self { shared, workers }
+    }
+
+    /// 切换文档：清空队列，渲染线程在取到下一个任务时重新打开解码器
+    pub fn set_document(&self, path: &Path) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.queue.clear();
+        let serial = state.document.as_ref().map_or(0, |(serial, _)| serial + 1);
+        state.document = Some((serial, path.to_path_buf()));
+    }
+
+    /// 加入一批渲染请求并唤醒空闲的渲染线程，返回新加入的数量
+    pub fn push_batch(&self, pages: Vec<RenderPage>) -> usize {
+        let added = self.shared.state.lock().unwrap().queue.push_batch(pages);
+        self.shared.ready.notify_all();
+        added
+    }
+
+    /// 只保留满足条件的任务，返回移除的数量
+    pub fn retain<F: FnMut(&RenderPage) -> bool>(&self, keep: F) -> usize {
+        let mut state = self.shared.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(keep);
+        before - state.queue.len()
+    }
+
+    /// 解码线程不阻塞地取一个任务
+    pub fn pop(&self) -> Option<RenderPage> {
+        self.shared.state.lock().unwrap().queue.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().unwrap().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn worker_loop(id: usize, shared: Arc<Shared>, context: RenderContext) {
+        let mut decoder: Option<(u64, Box<dyn Decoder>)> = None;
+        loop {
+            let (render_page, document) = {
+                let mut state = shared.state.lock().unwrap();
+                loop {
+                    if state.shutdown {
+                        return;
+                    }
+                    if power::is_low_power() {
+                        state = shared.ready.wait_timeout(state, LOW_POWER_POLL).unwrap().0;
+                        continue;
+                    }
+                    if let Some(page) = state.queue.pop() {
+                        break (page, state.document.clone());
+                    }
+                    state = shared.ready.wait(state).unwrap();
+                }
+            };
+            let Some((serial, path)) = document else { continue };
+
+            let visible = render_page.visibility_checker.as_ref()
+                .is_none_or(|checker| checker(render_page.page_info.index));
+            if !visible {
+                continue;
+            }
+
+            if decoder.as_ref().map(|(current, _)| *current) != Some(serial) {
+                decoder = match formats::open_decoder(&path) {
+                    Ok(dec) => Some((serial, dec)),
+                    Err(e) => {
+                        // 交还任务给其他线程，本线程退出
+                        warn!("[RenderPool] 线程 {} 无法打开文档 {:?}，退出: {}", id, path, e);
+                        shared.state.lock().unwrap().queue.push_batch(vec![render_page]);
+                        shared.ready.notify_all();
+                        return;
+                    }
+                };
+            }
+            let Some((_, ref dec)) = decoder else { continue };
+            if !DecodeService::render_one(dec.as_ref(), render_page, &context) {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for RenderPool {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.ready.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::decoder::decode_service::{Priority, RenderPage};
+
+/// 排序键：优先级、批次（新批次在前）、批次内的提交顺序
+type QueueKey = (Priority, Reverse<u64>, u64);
+
+/// 解码线程的渲染队列：按优先级出队，正在阅读的页最先，屏幕上的页面排在预加载页面前面；
+/// 同一优先级中新批次在前，批次内保持提交顺序（锚点页最先）
+#[derive(Default)]
+pub struct RenderQueue {
+    entries: BTreeMap<QueueKey, RenderPage>,
+    by_key: HashMap<String, QueueKey>,
+    batch: u64,
+    seq: u64,
+}
+
+impl RenderQueue {
+    /// 加入一批渲染请求，已在队列中的页面按新批次重新排序，返回新加入的数量
+    pub fn push_batch(&mut self, pages: Vec<RenderPage>) -> usize {
+        self.batch += 1;
+        let mut added = 0;
+        for page in pages {
+            if let Some(old) = self.by_key.remove(&page.key) {
+                self.entries.remove(&old);
+            } else {
+                added += 1;
+            }
+            self.seq += 1;
+            let key = (page.priority, Reverse(self.batch), self.seq);
+            self.by_key.insert(page.key.clone(), key);
+            self.entries.insert(key, page);
+        }
+        added
+    }
+
+    pub fn pop(&mut self) -> Option<RenderPage> {
+        let (_, page) = self.entries.pop_first()?;
+        self.by_key.remove(&page.key);
+        Some(page)
+    }
+
+    /// 只保留满足条件的任务
+    pub fn retain<F: FnMut(&RenderPage) -> bool>(&mut self, mut keep: F) {
+        let by_key = &mut self.by_key;
+        self.entries.retain(|_, page| {
+            let kept = keep(page);
+            if !kept {
+                by_key.remove(&page.key);
+            }
+            kept
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.by_key.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::PageInfo;
+
+    fn page(index: usize, priority: Priority) -> RenderPage {
+        RenderPage {
+            key: format!("page-{}", index),
+            page_info: PageInfo::new(index, 100.0, 100.0),
+            crop: 0,
+            priority,
+            generation: 0,
+            visibility_checker: None,
+        }
+    }
+
+    fn drain(queue: &mut RenderQueue) -> Vec<usize> {
+        std::iter::from_fn(|| queue.pop()).map(|p| p.page_info.index).collect()
+    }
+
+    #[test]
+    fn visible_pages_jump_ahead_of_preload() {
+        let mut queue = RenderQueue::default();
+        queue.push_batch(vec![page(5, Priority::FullImage), page(6, Priority::FullImage)]);
+        queue.push_batch(vec![page(1, Priority::Thumbnail), page(2, Priority::Thumbnail)]);
+        assert_eq!(drain(&mut queue), vec![1, 2, 5, 6]);
+    }
+
+    #[test]
+    fn new_batch_reprioritizes_queued_pages() {
+        let mut queue = RenderQueue::default();
+        assert_eq!(queue.push_batch(vec![page(1, Priority::Thumbnail), page(2, Priority::FullImage)]), 2);
+        // 滚动后页面 2 进入屏幕，页面 1 变成预加载
+        assert_eq!(queue.push_batch(vec![page(2, Priority::Thumbnail), page(1, Priority::FullImage)]), 0);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(drain(&mut queue), vec![2, 1]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn focus_page_is_not_starved_by_newer_batches() {
+        let mut queue = RenderQueue::default();
+        queue.push_batch(vec![page(3, Priority::Focus), page(4, Priority::Thumbnail)]);
+        // 缩放、切边等批量失效时后面的批次不会把正在阅读的页挤到后面
+        queue.push_batch(vec![page(5, Priority::Thumbnail), page(6, Priority::Thumbnail)]);
+        assert_eq!(drain(&mut queue), vec![3, 5, 6, 4]);
+    }
+}
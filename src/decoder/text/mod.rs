@@ -0,0 +1,4 @@
+pub mod paginate;
+pub mod text_decoder;
+
+pub use text_decoder::TextDecoder;
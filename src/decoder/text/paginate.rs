@@ -0,0 +1,274 @@
+//! 纯文本/Markdown 的解析和分页：按字号估算字符宽度折行，再按页高切成虚拟页面
+//! 只做几何计算，不涉及渲染，便于单独测试
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::settings::TextLayoutSettings;
+
+/// 行高相对字号
+const LINE_HEIGHT: f32 = 1.5;
+/// 标题字号相对正文
+const HEADING_SCALE: f32 = 1.4;
+/// 段落之间的额外空白相对字号
+const PARAGRAPH_GAP: f32 = 0.5;
+
+static MD_IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+static MD_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+static MD_EMPHASIS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\*\*|__|`)").unwrap());
+static MD_LIST: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*([-*+]|\d+[.)])\s+").unwrap());
+
+/// 解析后的段落
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paragraph {
+    pub text: String,
+    /// 标题级别（0 起），正文为 None
+    pub heading: Option<usize>,
+    /// 代码块：保留原样，不合并行
+    pub preformatted: bool,
+}
+
+impl Paragraph {
+    fn body(text: String) -> Self {
+        Self { text, heading: None, preformatted: false }
+    }
+}
+
+/// 排好的一行，坐标为页面坐标
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaidLine {
+    pub text: String,
+    pub font_size: f32,
+    pub top: f32,
+    /// 所属段落在全文中的序号
+    pub paragraph: usize,
+}
+
+impl LaidLine {
+    pub fn bottom(&self) -> f32 {
+        self.top + self.font_size * LINE_HEIGHT
+    }
+}
+
+/// 纯文本：每个非空行是一段
+pub fn parse_plain(content: &str) -> Vec<Paragraph> {
+    content.lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Paragraph::body(line.to_string()))
+        .collect()
+}
+
+/// Markdown：空行分段，段内换行合并；识别标题、列表和代码块，去掉行内标记
+pub fn parse_markdown(content: &str) -> Vec<Paragraph> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_code = false;
+
+    let flush = |current: &mut Vec<&str>, paragraphs: &mut Vec<Paragraph>| {
+        if !current.is_empty() {
+            paragraphs.push(Paragraph::body(strip_inline(&current.join(" "))));
+            current.clear();
+        }
+    };
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush(&mut current, &mut paragraphs);
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            paragraphs.push(Paragraph { text: line.trim_end().to_string(), heading: None, preformatted: true });
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut current, &mut paragraphs);
+            continue;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush(&mut current, &mut paragraphs);
+            let title = strip_inline(trimmed[level..].trim().trim_end_matches('#').trim());
+            paragraphs.push(Paragraph { text: title, heading: Some(level - 1), preformatted: false });
+            continue;
+        }
+        if let Some(marker) = MD_LIST.find(line) {
+            flush(&mut current, &mut paragraphs);
+            current.push("•");
+            current.push(&line[marker.end()..]);
+            continue;
+        }
+        current.push(trimmed.trim_start_matches('>').trim());
+    }
+    flush(&mut current, &mut paragraphs);
+    paragraphs.retain(|p| p.preformatted || !p.text.is_empty());
+    paragraphs
+}
+
+/// 去掉图片、链接和强调标记，只留文字
+fn strip_inline(text: &str) -> String {
+    let text = MD_IMAGE.replace_all(text, "$1");
+    let text = MD_LINK.replace_all(&text, "$1");
+    MD_EMPHASIS.replace_all(&text, "").trim().to_string()
+}
+
+/// 估算字符宽度：全角字符占一个字号，其余按半个多一点算，宁可折得早一些也不要超出页面
+pub fn char_width(c: char, font_size: f32) -> f32 {
+    if is_wide(c) {
+        font_size
+    } else if c == ' ' {
+        font_size * 0.3
+    } else {
+        font_size * 0.6
+    }
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 | 0x20000..=0x2FFFD)
+}
+
+pub fn text_width(text: &str, font_size: f32) -> f32 {
+    text.chars().map(|c| char_width(c, font_size)).sum()
+}
+
+/// 按宽度折行：西文在空格处断开，全角字符和过长的单词可以在任意字符处断开
+pub fn wrap(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut width = 0.0;
+
+    for token in tokens(text) {
+        let token_width = text_width(token, font_size);
+        if width + token_width <= max_width {
+            line.push_str(token);
+            width += token_width;
+            continue;
+        }
+        if token.trim().is_empty() {
+            // 断行处的空格不带到下一行
+            lines.push(std::mem::take(&mut line));
+            width = 0.0;
+            continue;
+        }
+        if !line.is_empty() && token_width <= max_width {
+            lines.push(std::mem::take(&mut line));
+            line.push_str(token);
+            width = token_width;
+            continue;
+        }
+        for c in token.chars() {
+            let w = char_width(c, font_size);
+            if width + w > max_width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                width = 0.0;
+            }
+            line.push(c);
+            width += w;
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines.iter_mut().for_each(|l| l.truncate(l.trim_end().len()));
+    lines
+}
+
+/// 切成可断行的片段：连续的西文字符、连续空白、单个全角字符
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<u8> = None;
+    for (i, c) in text.char_indices() {
+        let kind = if is_wide(c) { 2 } else if c.is_whitespace() { 1 } else { 0 };
+        if i > start && (kind == 2 || previous != Some(kind)) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        previous = Some(kind);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// 排版全部段落，返回每页的行；没有内容时也保留一个空白页
+pub fn paginate(paragraphs: &[Paragraph], settings: &TextLayoutSettings) -> Vec<Vec<LaidLine>> {
+    let content_width = (settings.page_width - settings.margin * 2.0).max(settings.font_size);
+    let bottom_limit = settings.page_height - settings.margin;
+
+    let mut pages = Vec::new();
+    let mut page: Vec<LaidLine> = Vec::new();
+    let mut y = settings.margin;
+
+    for (index, paragraph) in paragraphs.iter().enumerate() {
+        let font_size = match paragraph.heading {
+            Some(_) => settings.font_size * HEADING_SCALE,
+            None => settings.font_size,
+        };
+        let line_height = font_size * LINE_HEIGHT;
+        if !page.is_empty() && !paragraph.preformatted {
+            y += settings.font_size * PARAGRAPH_GAP;
+        }
+        for text in wrap(&paragraph.text, content_width, font_size) {
+            if y + line_height > bottom_limit && !page.is_empty() {
+                pages.push(std::mem::take(&mut page));
+                y = settings.margin;
+            }
+            page.push(LaidLine { text, font_size, top: y, paragraph: index });
+            y += line_height;
+        }
+    }
+    if !page.is_empty() || pages.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> TextLayoutSettings {
+        TextLayoutSettings { font_size: 10.0, page_width: 120.0, page_height: 100.0, margin: 10.0 }
+    }
+
+    #[test]
+    fn wraps_at_spaces_and_wide_characters() {
+        assert_eq!(wrap("aaa bbb ccc", 45.0, 10.0), ["aaa bbb", "ccc"]);
+        assert_eq!(wrap("一二三四五", 30.0, 10.0), ["一二三", "四五"]);
+        assert_eq!(wrap("abcdefghij", 30.0, 10.0), ["abcde", "fghij"]);
+    }
+
+    #[test]
+    fn markdown_headings_lists_and_code() {
+        let paragraphs = parse_markdown("# Title\n\nsome **bold**\ntext with [a link](http://x)\n\n- item\n```\n  code\n```\n");
+        assert_eq!(paragraphs[0], Paragraph { text: "Title".into(), heading: Some(0), preformatted: false });
+        assert_eq!(paragraphs[1].text, "some bold text with a link");
+        assert_eq!(paragraphs[2].text, "• item");
+        assert_eq!(paragraphs[3], Paragraph { text: "  code".into(), heading: None, preformatted: true });
+    }
+
+    #[test]
+    fn lines_never_cross_the_bottom_margin() {
+        let paragraphs = parse_plain(&"line of text\n".repeat(20));
+        let pages = paginate(&paragraphs, &settings());
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(!page.is_empty());
+            assert!(page.iter().all(|line| line.bottom() <= 90.0));
+        }
+        let total: usize = pages.iter().map(Vec::len).sum();
+        assert_eq!(total, 20);
+    }
+
+    #[test]
+    fn empty_document_has_one_page() {
+        assert_eq!(paginate(&[], &settings()).len(), 1);
+    }
+}
@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use log::{debug, info};
+use mupdf::{Colorspace, Device, Document, Matrix, Pixmap};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::paginate::{self, LaidLine, Paragraph};
+use crate::decoder::pdf::utils::mupdf_to_pixels;
+use crate::decoder::{Decoder, Link, PageInfo, Rect, TextBlock, TextLine};
+use crate::entity::{OutlineItem, ReflowEntry};
+use crate::reflow::ReflowCache;
+use crate::settings::{AppSettings, TextLayoutSettings};
+
+/// 页面坐标到像素的比例，和 PdfDecoder 的 retina 缩放一致
+const DPI_SCALE: f32 = 2.0;
+
+/// 纯文本/Markdown 解码器：按设置中的字号和页面尺寸把文本排成虚拟页面，
+/// 每页生成一小段 HTML 交给 mupdf 绘制，文字层直接来自排版结果
+pub struct TextDecoder {
+    paragraphs: Vec<Paragraph>,
+    pages: Vec<Vec<LaidLine>>,
+    settings: TextLayoutSettings,
+}
+
+impl TextDecoder {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with(path, AppSettings::get().text_layout)
+    }
+
+    pub fn open_with<P: AsRef<Path>>(path: P, settings: TextLayoutSettings) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("无法读取文本: {:?}", path))?;
+        let content = String::from_utf8_lossy(&bytes);
+        let content = content.trim_start_matches('\u{feff}');
+
+        let markdown = path.extension()
+            .map(|e| matches!(e.to_string_lossy().to_lowercase().as_str(), "md" | "markdown"))
+            .unwrap_or(false);
+        let paragraphs = if markdown {
+            paginate::parse_markdown(content)
+        } else {
+            paginate::parse_plain(content)
+        };
+        let pages = paginate::paginate(&paragraphs, &settings);
+        info!("[Text] {:?}: {} 段, {} 页", path, paragraphs.len(), pages.len());
+
+        Ok(Self { paragraphs, pages, settings })
+    }
+
+    fn lines(&self, index: usize) -> Result<&[LaidLine]> {
+        self.pages.get(index).map(Vec::as_slice).context("Page index out of bounds")
+    }
+
+    /// 一页的 HTML：每行一个不折行的块，上边距补足排版时的段间距，保证 mupdf 不会再分页
+    fn page_html(&self, index: usize) -> Result<String> {
+        let mut html = format!(
+            "<html><head><style>@page {{ margin: 0; }} body {{ margin: 0; padding: 0 {0}pt; }} \
+             div {{ margin: 0; white-space: pre; }}</style></head><body>",
+            self.settings.margin
+        );
+        let mut y = 0.0;
+        for line in self.lines(index)? {
+            let heading = self.paragraphs.get(line.paragraph).is_some_and(|p| p.heading.is_some());
+            html.push_str(&format!(
+                "<div style=\"margin-top: {:.2}pt; font-size: {:.2}pt; line-height: {:.2}pt;{}\">{}</div>",
+                line.top - y,
+                line.font_size,
+                line.bottom() - line.top,
+                if heading { " font-weight: bold;" } else { "" },
+                escape_html(&line.text),
+            ));
+            y = line.bottom();
+        }
+        html.push_str("</body></html>");
+        Ok(html)
+    }
+
+    /// 渲染页面坐标中的一块区域
+    fn render_rect(&self, index: usize, region: Rect, scale: f32) -> Result<(Vec<u8>, u32, u32)> {
+        let html = self.page_html(index)?;
+        let mut document = Document::from_bytes(html.as_bytes(), "html")?;
+        document.layout(self.settings.page_width, self.settings.page_height, self.settings.font_size)?;
+        let page = document.load_page(0)?;
+
+        let final_scale = scale * DPI_SCALE;
+        let mut matrix = Matrix::new(final_scale, 0.0, 0.0, final_scale, 0.0, 0.0);
+        matrix.e = -region.left * final_scale;
+        matrix.f = -region.top * final_scale;
+
+        let width = (region.width() * final_scale) as i32;
+        let height = (region.height() * final_scale) as i32;
+        debug!("[Text] render page {} {}x{}", index, width, height);
+
+        let colorspace = Colorspace::device_rgb();
+        let mut pixmap = Pixmap::new(&colorspace, 0, 0, width, height, true)?;
+        pixmap.clear()?;
+        let device = Device::from_pixmap(&pixmap)?;
+        page.run(&device, &matrix)?;
+
+        Ok(mupdf_to_pixels(&pixmap))
+    }
+}
+
+impl Decoder for TextDecoder {
+    fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn get_page_size(&self, index: usize) -> Result<(f32, f32)> {
+        self.lines(index)?;
+        Ok((self.settings.page_width, self.settings.page_height))
+    }
+
+    fn get_all_pages(&self) -> Result<Vec<PageInfo>> {
+        Ok((0..self.pages.len())
+            .map(|index| PageInfo::new(index, self.settings.page_width, self.settings.page_height))
+            .collect())
+    }
+
+    fn render_page(&self, page: &PageInfo, crop: bool) -> Result<(Vec<u8>, u32, u32)> {
+        let bounds = match page.crop_bounds {
+            Some(bounds) if crop => bounds,
+            _ => Rect::new(0.0, 0.0, page.width, page.height),
+        };
+        self.render_rect(page.index, bounds, page.scale)
+    }
+
+    fn render_region(&self, page_index: usize, region: Rect, scale: f32) -> Result<(Vec<u8>, u32, u32)> {
+        self.render_rect(page_index, region, scale)
+    }
+
+    fn get_page_links(&self, page_index: usize) -> Result<Vec<Link>> {
+        Ok(Vec::new())
+    }
+
+    /// 段落之间空一行，和 reflow 的整页文本一致
+    fn get_page_text(&self, page_index: usize) -> Result<String> {
+        let blocks = self.get_page_text_blocks(page_index)?;
+        Ok(blocks.iter()
+            .map(|block| block.lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+
+    /// 每段一个块，行的宽度按排版时的估算值
+    fn get_page_text_blocks(&self, page_index: usize) -> Result<Vec<TextBlock>> {
+        let left = self.settings.margin;
+        let mut blocks: Vec<(usize, TextBlock)> = Vec::new();
+        for line in self.lines(page_index)? {
+            let bounds = Rect::new(left, line.top, left + paginate::text_width(&line.text, line.font_size), line.bottom());
+            let text_line = TextLine { bounds, font_size: line.font_size, text: line.text.clone() };
+            match blocks.last_mut() {
+                Some((paragraph, block)) if *paragraph == line.paragraph => {
+                    block.bounds = block.bounds.union(&bounds);
+                    block.lines.push(text_line);
+                }
+                _ => blocks.push((line.paragraph, TextBlock { bounds, lines: vec![text_line] })),
+            }
+        }
+        Ok(blocks.into_iter().map(|(_, block)| block).collect())
+    }
+
+    fn search_page(&self, page_index: usize, query: &str) -> Result<Vec<Rect>> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let left = self.settings.margin;
+        let mut rects = Vec::new();
+        for line in self.lines(page_index)? {
+            let text = line.text.to_lowercase();
+            for (start, matched) in text.match_indices(&query) {
+                let x = left + paginate::text_width(&text[..start], line.font_size);
+                let width = paginate::text_width(matched, line.font_size);
+                rects.push(Rect::new(x, line.top, x + width, line.bottom()));
+            }
+        }
+        Ok(rects)
+    }
+
+    /// Markdown 标题作为大纲，纯文本没有大纲
+    fn get_outline_items(&self) -> Result<Vec<OutlineItem>> {
+        let mut items = Vec::new();
+        let mut seen = HashSet::new();
+        for (page, lines) in self.pages.iter().enumerate() {
+            for line in lines {
+                let paragraph = &self.paragraphs[line.paragraph];
+                // 跨页的标题只记开始的那页
+                if let Some(level) = paragraph.heading.filter(|_| seen.insert(line.paragraph)) {
+                    items.push(OutlineItem::new(paragraph.text.clone(), None, page as i32, level as i32));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// 排版结果就在内存里，直接逐页提取，不写缓存
+    fn get_reflow_from_page(&self, start_page: usize) -> Result<Vec<ReflowEntry>> {
+        let mut entries = Vec::new();
+        for page in start_page..self.pages.len() {
+            if let Some(entry) = ReflowCache::extract_page(self, page)? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn close(&mut self) {
+        self.pages.clear();
+        self.paragraphs.clear();
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
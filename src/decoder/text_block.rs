@@ -0,0 +1,35 @@
+use super::Rect;
+
+/// 文本行（页面坐标）
+#[derive(Debug, Clone)]
+pub struct TextLine {
+    pub bounds: Rect,
+    /// 行内字符的平均字号
+    pub font_size: f32,
+    pub text: String,
+}
+
+/// 文本块，对应底层库给出的一段连续文本
+#[derive(Debug, Clone)]
+pub struct TextBlock {
+    pub bounds: Rect,
+    pub lines: Vec<TextLine>,
+}
+
+impl TextBlock {
+    /// 按字符数加权的平均字号
+    pub fn font_size(&self) -> f32 {
+        let mut total = 0.0;
+        let mut count = 0usize;
+        for line in &self.lines {
+            let chars = line.text.chars().count();
+            total += line.font_size * chars as f32;
+            count += chars;
+        }
+        if count == 0 {
+            0.0
+        } else {
+            total / count as f32
+        }
+    }
+}
@@ -150,6 +150,14 @@ impl Decoder for TiffDecoder {
         Ok(text_page.to_text()?)
     }
 
+    fn hit_test(&self, page_index: usize, x: f32, y: f32) -> Result<crate::decoder::PageHit> {
+        crate::decoder::pdf::utils::hit_test_page(&self.document.borrow(), page_index, x, y)
+    }
+
+    fn search_page(&self, page_index: usize, query: &str) -> Result<Vec<crate::decoder::Rect>> {
+        crate::decoder::pdf::utils::search_page(&self.document.borrow(), page_index, query)
+    }
+
     fn get_outline_items(&self) -> Result<Vec<crate::entity::OutlineItem>> {
         use crate::decoder::pdf::utils::load_outline_items;
         Ok(load_outline_items(&*self.document.borrow()))
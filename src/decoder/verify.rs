@@ -0,0 +1,123 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::decoder::{formats, PageInfo};
+
+/// 检查渲染时使用的缩放，只需要走一遍渲染流程，不需要清晰的图像
+const VERIFY_RENDER_SCALE: f32 = 0.25;
+
+/// 某一页的问题
+#[derive(Debug, Clone)]
+pub struct PageProblem {
+    pub page_index: usize,
+    /// 出错的环节：尺寸、渲染、链接、文本
+    pub stage: &'static str,
+    pub message: String,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub page_count: usize,
+    /// 实际检查的页数，取消时小于总页数
+    pub checked: usize,
+    pub problems: Vec<PageProblem>,
+    /// 文档本身无法打开时的错误
+    pub open_error: Option<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.open_error.is_none() && self.problems.is_empty()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.open_error.is_some() || self.checked == self.page_count
+    }
+}
+
+/// 检查进度事件
+#[derive(Debug, Clone)]
+pub enum VerifyEvent {
+    Progress { checked: usize, total: usize },
+    Finished(VerifyReport),
+}
+
+/// 文档完整性检查：在独立线程里用单独的解码器逐页解析、渲染、提取链接和文本，
+/// 记录失败的页面，用来区分是文件损坏还是阅读器的问题
+pub struct VerifyJob {
+    event_rx: Receiver<VerifyEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl VerifyJob {
+    pub fn start(path: PathBuf) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancelled_for_thread = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let report = Self::verify(&path, &event_tx, &cancelled_for_thread);
+            info!(
+                "[Verify] {:?}: checked={}/{}, problems={}, open_error={:?}",
+                path, report.checked, report.page_count, report.problems.len(), report.open_error
+            );
+            let _ = event_tx.send(VerifyEvent::Finished(report));
+        });
+        Self { event_rx, cancelled }
+    }
+
+    pub fn try_recv(&self) -> Option<VerifyEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn verify(path: &Path, event_tx: &Sender<VerifyEvent>, cancelled: &AtomicBool) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        let decoder = match formats::open_decoder(path) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                report.open_error = Some(format!("{:#}", e));
+                return report;
+            }
+        };
+        report.page_count = decoder.page_count();
+
+        for index in 0..report.page_count {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let mut problem = |stage: &'static str, error: anyhow::Error| {
+                warn!("[Verify] page {} {} failed: {:#}", index, stage, error);
+                report.problems.push(PageProblem { page_index: index, stage, message: format!("{:#}", error) });
+            };
+
+            match decoder.get_page_size(index) {
+                Ok((width, height)) => {
+                    let page = PageInfo { scale: VERIFY_RENDER_SCALE, ..PageInfo::new(index, width, height) };
+                    if let Err(e) = decoder.render_page(&page, false) {
+                        problem("渲染", e);
+                    }
+                }
+                Err(e) => problem("尺寸", e),
+            }
+            if let Err(e) = decoder.get_page_links(index) {
+                problem("链接", e);
+            }
+            if let Err(e) = decoder.get_page_text(index) {
+                problem("文本", e);
+            }
+
+            report.checked = index + 1;
+            let _ = event_tx.send(VerifyEvent::Progress { checked: report.checked, total: report.page_count });
+        }
+        report
+    }
+}
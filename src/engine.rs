@@ -0,0 +1,473 @@
+//! 嵌入用的门面：把解码服务、页面布局和像素缓存包在一起，接口里不出现 Slint 类型，
+//! 其他 Rust 界面可以只用它完成打开文档、布局、按视口渲染和取像素
+//!
+//! 和界面一样是轮询模型：`open` 之后周期性调用 `poll`，根据返回的事件刷新显示
+//!
+//! ```no_run
+//! use rreader::engine::{EngineEvent, RReaderEngine};
+//!
+//! let mut engine = RReaderEngine::new();
+//! engine.set_viewport(800.0, 600.0);
+//! engine.open("book.pdf")?;
+//! loop {
+//!     for event in engine.poll() {
+//!         if let EngineEvent::PageRendered(index) = event {
+//!             let page = engine.page_image(index).unwrap();
+//!             // page.pixels 是 page.width × page.height 的预乘 RGBA
+//!         }
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_millis(100));
+//! }
+//! # Ok::<(), rreader::error::RReaderError>(())
+//! ```
+
+use log::{debug, info};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::decoder::decode_service::{DecodeResult, RenderPage};
+use crate::decoder::{formats, DecodeService, Link, PageInfo, PageMatches, Priority, Rect, RenderFailure};
+use crate::entity::OutlineItem;
+use crate::error::{RReaderError, Result};
+use crate::page::layout::{self, Layout, LayoutPage, LayoutParams};
+use crate::page::Orientation;
+
+/// 默认像素缓存上限
+const DEFAULT_CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+/// 渲染好的一页
+#[derive(Debug)]
+pub struct RenderedPage {
+    pub index: usize,
+    /// 渲染时的缩放，和当前布局不一致时说明图像已过期
+    pub scale: f32,
+    pub width: u32,
+    pub height: u32,
+    /// 预乘 RGBA
+    pub pixels: Vec<u8>,
+    /// 低精度预览，稍后会被全精度图像替换
+    pub preview: bool,
+    pub links: Vec<Link>,
+}
+
+/// `poll` 返回的事件
+#[derive(Debug)]
+pub enum EngineEvent {
+    /// 文档加载完成
+    Loaded { page_count: usize },
+    LoadFailed(RReaderError),
+    /// 该页有了新图像，通过 `page_image` 取
+    PageRendered(usize),
+    RenderFailed(RenderFailure),
+}
+
+/// 按字节数限制的页面像素缓存，超出时淘汰最久未访问的页
+struct PixelCache {
+    pages: HashMap<usize, (Arc<RenderedPage>, Instant)>,
+    max_bytes: usize,
+    total_bytes: usize,
+}
+
+impl PixelCache {
+    fn new(max_bytes: usize) -> Self {
+        Self { pages: HashMap::new(), max_bytes, total_bytes: 0 }
+    }
+
+    fn get(&mut self, index: usize) -> Option<Arc<RenderedPage>> {
+        self.pages.get_mut(&index).map(|(page, accessed)| {
+            *accessed = Instant::now();
+            Arc::clone(page)
+        })
+    }
+
+    fn put(&mut self, page: RenderedPage) {
+        self.remove(page.index);
+        self.total_bytes += page.pixels.len();
+        self.pages.insert(page.index, (Arc::new(page), Instant::now()));
+        // 至少保留刚放进来的一页
+        while self.total_bytes > self.max_bytes && self.pages.len() > 1 {
+            let oldest = self.pages.iter().min_by_key(|(_, (_, accessed))| *accessed).map(|(index, _)| *index);
+            match oldest {
+                Some(index) => self.remove(index),
+                None => break,
+            }
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        if let Some((old, _)) = self.pages.remove(&index) {
+            self.total_bytes -= old.pixels.len();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.pages.clear();
+        self.total_bytes = 0;
+    }
+}
+
+/// 渲染引擎门面，不依赖界面线程和 Slint 窗口
+pub struct RReaderEngine {
+    decode_service: DecodeService,
+    path: Option<PathBuf>,
+    pages: Vec<PageInfo>,
+    params: LayoutParams,
+    layout: Layout,
+    crop: bool,
+    /// 视口左上角在文档坐标中的位置
+    scroll: (f32, f32),
+    cache: PixelCache,
+    /// 布局变化后递增，作废按旧缩放提交的渲染任务
+    generation: u64,
+    loading: bool,
+}
+
+impl RReaderEngine {
+    pub fn new() -> Self {
+        Self::with_cache_bytes(DEFAULT_CACHE_BYTES)
+    }
+
+    /// 指定像素缓存上限（字节）
+    pub fn with_cache_bytes(max_bytes: usize) -> Self {
+        Self {
+            decode_service: DecodeService::new(),
+            path: None,
+            pages: Vec::new(),
+            params: LayoutParams {
+                orientation: Orientation::Vertical,
+                dual_page: false,
+                view_width: 0.0,
+                view_height: 0.0,
+                zoom: 1.0,
+                gap: 0.0,
+                rtl: false,
+                paged: false,
+            },
+            layout: Layout { slots: Vec::new(), total_width: 0.0, total_height: 0.0 },
+            crop: false,
+            scroll: (0.0, 0.0),
+            cache: PixelCache::new(max_bytes),
+            generation: 0,
+            loading: false,
+        }
+    }
+
+    /// 打开文档（异步），加载结果通过 `poll` 返回
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if !formats::is_supported(path) {
+            return Err(RReaderError::UnsupportedFormat(path.to_string_lossy().to_string()));
+        }
+        info!("engine open: {:?}", path);
+        self.pages.clear();
+        self.layout = Layout { slots: Vec::new(), total_width: 0.0, total_height: 0.0 };
+        self.scroll = (0.0, 0.0);
+        self.cache.clear();
+        self.invalidate_renders();
+        self.decode_service.load_pdf(path)?;
+        self.path = Some(path.to_path_buf());
+        self.loading = true;
+        Ok(())
+    }
+
+    /// 当前文档路径
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 收取解码线程的结果，返回这段时间发生的事件
+    pub fn poll(&mut self) -> Vec<EngineEvent> {
+        let mut events = Vec::new();
+
+        if self.loading {
+            if let Some(result) = self.decode_service.try_recv_load_result() {
+                self.loading = false;
+                match result {
+                    Ok(pages) => {
+                        self.pages = pages;
+                        self.relayout();
+                        events.push(EngineEvent::Loaded { page_count: self.pages.len() });
+                    }
+                    Err(e) => events.push(EngineEvent::LoadFailed(e)),
+                }
+            }
+        }
+
+        while let Some(result) = self.decode_service.try_recv_result() {
+            if let Some(index) = self.accept_result(result) {
+                events.push(EngineEvent::PageRendered(index));
+            }
+        }
+        while let Some(failure) = self.decode_service.try_recv_failure() {
+            events.push(EngineEvent::RenderFailed(failure));
+        }
+        events
+    }
+
+    /// 旧缩放下的结果直接丢弃，否则放入缓存
+    fn accept_result(&mut self, result: DecodeResult) -> Option<usize> {
+        let index = result.page_info.index;
+        let slot = self.layout.slots.get(index)?;
+        if (slot.scale - result.page_info.scale).abs() > 0.001 {
+            debug!("engine drop stale result: page={}, scale={}", index, result.page_info.scale);
+            return None;
+        }
+        // 全精度图像已在缓存时不再用预览覆盖
+        if result.preview && self.cache.get(index).is_some_and(|page| !page.preview && page.scale == slot.scale) {
+            return None;
+        }
+        self.cache.put(RenderedPage {
+            index,
+            scale: slot.scale,
+            width: result.image_width,
+            height: result.image_height,
+            pixels: result.image_data,
+            preview: result.preview,
+            links: result.links,
+        });
+        Some(index)
+    }
+
+    /// 视口尺寸变化后重新布局
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        if self.params.view_width == width && self.params.view_height == height {
+            return;
+        }
+        self.params.view_width = width;
+        self.params.view_height = height;
+        self.relayout();
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        if (self.params.zoom - zoom).abs() <= 0.001 {
+            return;
+        }
+        self.params.zoom = zoom;
+        self.relayout();
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.params.zoom
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        if self.params.orientation != orientation {
+            self.params.orientation = orientation;
+            self.relayout();
+        }
+    }
+
+    /// 双页并排（仅垂直滚动）
+    pub fn set_dual_page(&mut self, dual_page: bool) {
+        if self.params.dual_page != dual_page {
+            self.params.dual_page = dual_page;
+            self.relayout();
+        }
+    }
+
+    /// 从右向左排列
+    pub fn set_rtl(&mut self, rtl: bool) {
+        if self.params.rtl != rtl {
+            self.params.rtl = rtl;
+            self.relayout();
+        }
+    }
+
+    /// 开关切边，切边范围来自解码器
+    pub fn set_crop(&mut self, crop: bool) {
+        if self.crop != crop {
+            self.crop = crop;
+            self.cache.clear();
+            self.relayout();
+        }
+    }
+
+    /// 滚动到文档坐标 (x, y)，即视口左上角的位置
+    pub fn scroll_to(&mut self, x: f32, y: f32) {
+        let max_x = (self.layout.total_width - self.params.view_width).max(0.0);
+        let max_y = (self.layout.total_height - self.params.view_height).max(0.0);
+        self.scroll = (x.clamp(0.0, max_x), y.clamp(0.0, max_y));
+        self.request_visible();
+    }
+
+    pub fn scroll_position(&self) -> (f32, f32) {
+        self.scroll
+    }
+
+    /// 跳到指定页的开头
+    pub fn jump_to_page(&mut self, index: usize) -> bool {
+        let Some(slot) = self.layout.slots.get(index) else {
+            return false;
+        };
+        let (x, y) = match self.params.orientation {
+            Orientation::Vertical => (self.scroll.0, slot.bounds.top),
+            Orientation::Horizontal => (slot.bounds.left, self.scroll.1),
+        };
+        self.scroll_to(x, y);
+        true
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// 页面原始信息（尺寸、切边范围）
+    pub fn page_info(&self, index: usize) -> Option<&PageInfo> {
+        self.pages.get(index)
+    }
+
+    /// 页面在文档坐标中的位置
+    pub fn page_bounds(&self, index: usize) -> Option<Rect> {
+        self.layout.slots.get(index).map(|slot| slot.bounds)
+    }
+
+    /// 文档总尺寸
+    pub fn document_size(&self) -> (f32, f32) {
+        (self.layout.total_width, self.layout.total_height)
+    }
+
+    /// 和视口相交的页面
+    pub fn visible_pages(&self) -> Range<usize> {
+        let viewport = self.viewport_rect();
+        let mut visible = self.layout.slots.iter().enumerate()
+            .filter(|(_, slot)| slot.bounds.intersection(&viewport).is_some())
+            .map(|(index, _)| index);
+        match visible.next() {
+            Some(first) => first..visible.last().unwrap_or(first) + 1,
+            None => 0..0,
+        }
+    }
+
+    /// 页面图像，可能是预览或旧缩放下的图像，尚未渲染时为 None
+    pub fn page_image(&mut self, index: usize) -> Option<Arc<RenderedPage>> {
+        self.cache.get(index)
+    }
+
+    pub fn page_text(&self, index: usize) -> Result<String> {
+        self.decode_service.get_page_text(index)
+    }
+
+    /// 页面一块区域（页面坐标）内的文字
+    pub fn region_text(&self, index: usize, region: Rect) -> Result<String> {
+        self.decode_service.get_region_text(index, region)
+    }
+
+    /// 大纲的前几层，子节点用 `outline_children` 按需获取
+    pub fn outline(&self) -> Result<Vec<OutlineItem>> {
+        self.decode_service.get_outline()
+    }
+
+    pub fn outline_children(&self, parent: usize) -> Result<Vec<OutlineItem>> {
+        self.decode_service.get_outline_children(parent)
+    }
+
+    /// 全文搜索（同步等待），范围为页面坐标
+    pub fn search(&self, query: &str) -> Result<Vec<PageMatches>> {
+        let receiver = self.decode_service.search_text(query)?;
+        let mut matches = Vec::new();
+        for batch in receiver.iter() {
+            matches.extend(batch?);
+        }
+        Ok(matches)
+    }
+
+    fn viewport_rect(&self) -> Rect {
+        Rect::new(
+            self.scroll.0,
+            self.scroll.1,
+            self.scroll.0 + self.params.view_width,
+            self.scroll.1 + self.params.view_height,
+        )
+    }
+
+    /// 作废已提交的渲染任务
+    fn invalidate_renders(&mut self) {
+        self.generation += 1;
+        self.decode_service.cancel_generation(self.generation);
+    }
+
+    fn relayout(&mut self) {
+        let pages: Vec<LayoutPage> = self.pages.iter().map(|info| LayoutPage::from_info(info, self.crop)).collect();
+        self.layout = layout::compute(&pages, &self.params);
+        for (info, slot) in self.pages.iter_mut().zip(&self.layout.slots) {
+            info.scale = slot.scale;
+        }
+        self.invalidate_renders();
+        self.scroll_to(self.scroll.0, self.scroll.1);
+    }
+
+    /// 提交视口内缺少当前缩放图像的页面
+    fn request_visible(&mut self) {
+        let viewport = self.viewport_rect();
+        let (center_x, center_y) = (viewport.left + viewport.width() / 2.0, viewport.top + viewport.height() / 2.0);
+        let mut render_pages = Vec::new();
+        for index in self.visible_pages() {
+            let info = &self.pages[index];
+            let fresh = self.cache.get(index).is_some_and(|page| !page.preview && page.scale == info.scale);
+            if fresh || info.scale <= 0.0 {
+                continue;
+            }
+            render_pages.push(RenderPage {
+                key: format!("engine-{}-{}", index, info.scale),
+                page_info: info.clone(),
+                crop: self.crop as i32,
+                // 视口中心所在的页最先渲染
+                priority: if self.layout.slots[index].bounds.contains(center_x, center_y) {
+                    Priority::Focus
+                } else {
+                    Priority::Thumbnail
+                },
+                generation: self.generation,
+                visibility_checker: None,
+            });
+        }
+        self.decode_service.render_pages(render_pages);
+    }
+}
+
+impl Default for RReaderEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(index: usize, bytes: usize) -> RenderedPage {
+        RenderedPage {
+            index,
+            scale: 1.0,
+            width: 1,
+            height: 1,
+            pixels: vec![0; bytes],
+            preview: false,
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pixel_cache_evicts_least_recently_used() {
+        let mut cache = PixelCache::new(100);
+        cache.put(page(0, 40));
+        cache.put(page(1, 40));
+        assert!(cache.get(0).is_some());
+        cache.put(page(2, 40));
+        assert!(cache.get(1).is_none());
+        assert!(cache.get(0).is_some());
+        assert_eq!(cache.total_bytes, 80);
+    }
+
+    #[test]
+    fn pixel_cache_keeps_single_oversized_page() {
+        let mut cache = PixelCache::new(10);
+        cache.put(page(0, 40));
+        cache.put(page(0, 50));
+        assert_eq!(cache.total_bytes, 50);
+        assert!(cache.get(0).is_some());
+    }
+}
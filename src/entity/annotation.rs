@@ -0,0 +1,78 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Set, NotSet};
+
+use crate::decoder::Rect;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "annotations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub book_path: String,
+    /// 从 0 开始的页码
+    pub page: i32,
+    /// 高亮范围（页面坐标），JSON 数组，每项为 [left, top, right, bottom]
+    pub rects: String,
+    /// 高亮颜色，如 "#ffeb3b"
+    pub color: String,
+    pub note: String,
+    pub create_at: i64,
+    pub update_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 一处高亮及其笔记
+pub type Annotation = Model;
+
+impl Annotation {
+    /// 默认的高亮颜色
+    pub const DEFAULT_COLOR: &'static str = "#ffeb3b";
+
+    pub fn new(book_path: String, page: i32, rects: &[Rect], color: String, note: String) -> ActiveModel {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        ActiveModel {
+            id: NotSet,
+            book_path: Set(book_path),
+            page: Set(page),
+            rects: Set(encode_rects(rects)),
+            color: Set(color),
+            note: Set(note),
+            create_at: Set(now),
+            update_at: Set(now),
+        }
+    }
+
+    /// 解析高亮范围，格式不对的项跳过
+    pub fn rect_list(&self) -> Vec<Rect> {
+        serde_json::from_str::<Vec<[f32; 4]>>(&self.rects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|[left, top, right, bottom]| Rect::new(left, top, right, bottom))
+            .collect()
+    }
+
+    /// 颜色的 RGB 分量，格式不对时用默认黄色
+    pub fn rgb(&self) -> [u8; 3] {
+        let parse = |hex: &str| u32::from_str_radix(hex.trim_start_matches('#'), 16).ok().filter(|_| hex.len() == 7);
+        let value = parse(&self.color).or_else(|| parse(Self::DEFAULT_COLOR)).unwrap_or(0xffeb3b);
+        [(value >> 16) as u8, (value >> 8) as u8, value as u8]
+    }
+
+    /// 页面坐标 (x, y) 是否落在高亮范围内
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.rect_list().iter().any(|rect| rect.contains(x, y))
+    }
+}
+
+fn encode_rects(rects: &[Rect]) -> String {
+    let values: Vec<[f32; 4]> = rects.iter().map(|r| [r.left, r.top, r.right, r.bottom]).collect();
+    serde_json::to_string(&values).unwrap_or_else(|_| "[]".to_string())
+}
@@ -0,0 +1,56 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Set, NotSet};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "book_metadata")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub book_path: String,
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    pub isbn: String,
+    /// OpenLibrary 的作品 key，如 /works/OL45883W，手动填写时为空
+    pub source_key: String,
+    /// 下载的封面图片，没有封面时为空
+    pub cover_path: String,
+    /// 用户手动改过为 1，在线查到的结果为 0
+    pub edited: i32,
+    pub update_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 书的规范书名、作者、简介，来自 OpenLibrary 或手动修正
+pub type BookMetadata = Model;
+
+impl BookMetadata {
+    pub fn new(book_path: String, title: String, author: String, description: String) -> ActiveModel {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        ActiveModel {
+            id: NotSet,
+            book_path: Set(book_path),
+            title: Set(title),
+            author: Set(author),
+            description: Set(description),
+            isbn: Set(String::new()),
+            source_key: Set(String::new()),
+            cover_path: Set(String::new()),
+            edited: Set(0),
+            update_at: Set(now),
+        }
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.edited != 0
+    }
+}
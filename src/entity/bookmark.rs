@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Set, NotSet};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "bookmarks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub book_path: String,
+    /// 从 0 开始的页码
+    pub page: i32,
+    /// 视口顶部在页内的相对位置，和 Recent 的 anchor_ratio 一致
+    pub anchor_ratio: f32,
+    pub label: String,
+    pub create_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 书中的一个书签
+pub type Bookmark = Model;
+
+impl Bookmark {
+    pub fn new(book_path: String, page: i32, anchor_ratio: f32, label: String) -> ActiveModel {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        ActiveModel {
+            id: NotSet,
+            book_path: Set(book_path),
+            page: Set(page),
+            anchor_ratio: Set(anchor_ratio),
+            label: Set(label),
+            create_at: Set(now),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+/// 文档属性（名称-值），用于属性对话框
+#[derive(Debug, Clone)]
+pub struct DocumentProperty {
+    pub name: String,
+    pub value: String,
+}
+
+impl DocumentProperty {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
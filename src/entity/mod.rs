@@ -1,7 +1,27 @@
+#[cfg(feature = "db")]
+pub mod annotation;
+#[cfg(feature = "db")]
+pub mod book_metadata;
+#[cfg(feature = "db")]
+pub mod bookmark;
+pub mod document_property;
+#[cfg(feature = "db")]
 pub mod recent;
 pub mod outline_item;
 pub mod reflow;
+#[cfg(feature = "db")]
+pub mod vocab;
 
+#[cfg(feature = "db")]
+pub use annotation::Annotation;
+#[cfg(feature = "db")]
+pub use book_metadata::BookMetadata;
+#[cfg(feature = "db")]
+pub use bookmark::Bookmark;
+pub use document_property::DocumentProperty;
+#[cfg(feature = "db")]
 pub use recent::Recent;
 pub use outline_item::OutlineItem;
-pub use reflow::{ReflowEntry, ReflowData};
+pub use reflow::{ReflowBlock, ReflowBlockType, ReflowEntry, ReflowData};
+#[cfg(feature = "db")]
+pub use vocab::VocabEntry;
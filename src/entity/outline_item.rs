@@ -4,6 +4,10 @@ pub struct OutlineItem {
     pub uri: Option<String>,
     pub page: i32,
     pub level: i32,
+    /// 在完整大纲（先序展开）中的位置，用于按需取子节点
+    pub id: usize,
+    /// 是否有子节点，子节点可能还没有加载
+    pub has_children: bool,
 }
 
 impl OutlineItem {
@@ -12,7 +16,9 @@ impl OutlineItem {
             title,
             uri,
             page,
-            level
+            level,
+            id: 0,
+            has_children: false,
         }
     }
 }
\ No newline at end of file
@@ -24,6 +24,28 @@ pub struct Model {
     pub progress: i64,
     pub favorited: i32,
     pub in_recent: i32,
+    /// 文件内容hash，同一本书复制到别处后用来找回阅读记录
+    pub content_hash: String,
+    /// 移入回收站的时间（毫秒），0 表示未删除
+    pub deleted_at: i64,
+    /// 从文件名识别的系列名，不属于系列时为空
+    pub series: String,
+    /// 系列中的卷号
+    pub series_index: f32,
+    /// 累计阅读时长（秒），休息和离开时不计
+    pub read_seconds: i64,
+    /// 视口上沿在 page 页内的位置（0~1），-1 表示旧记录，按 scroll_x/scroll_y 恢复
+    pub anchor_ratio: f32,
+    /// 整个文件的 SHA-256，打开后在后台计算，为空表示还没算过
+    pub sha256: String,
+    /// 归入“已读完”的时间（毫秒），0 表示没有归档
+    pub finished_at: i64,
+    /// 手动切边的页边距（CropMargins 的 JSON），为空表示使用自动切边
+    pub crop_margins: String,
+    /// 文档自带的作者，没有时为空
+    pub author: String,
+    /// 文档自带的主题
+    pub subject: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +56,29 @@ impl ActiveModelBehavior for ActiveModel {}
 pub type Recent = Model;
 
 impl Recent {
+    /// 阅读进度百分比（0~100），page 从 1 开始
+    pub fn compute_progress(page: i32, page_count: i32) -> i64 {
+        if page_count <= 0 {
+            return 0;
+        }
+        ((page.clamp(0, page_count) as f64 / page_count as f64) * 100.0).round() as i64
+    }
+
+    /// 在回收站中，超过保留期后彻底删除
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at > 0
+    }
+
+    /// 读完了，不再出现在“继续阅读”
+    pub fn is_finished(&self) -> bool {
+        self.progress >= 100 || self.is_archived()
+    }
+
+    /// 归入了“已读完”：读到最后一页后确认或手动标记，不再出现在书架上
+    pub fn is_archived(&self) -> bool {
+        self.finished_at > 0
+    }
+
     pub fn new(book_path: String) -> ActiveModel {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -60,6 +105,17 @@ impl Recent {
             progress: Set(0),
             favorited: Set(0),
             in_recent: Set(0),
+            content_hash: Set("".to_string()),
+            deleted_at: Set(0),
+            series: Set("".to_string()),
+            series_index: Set(0.0),
+            read_seconds: Set(0),
+            anchor_ratio: Set(0.0),
+            sha256: Set("".to_string()),
+            finished_at: Set(0),
+            crop_margins: Set("".to_string()),
+            author: Set("".to_string()),
+            subject: Set("".to_string()),
         }
     }
 
@@ -106,6 +162,17 @@ impl Recent {
             progress: Set(progress),
             favorited: Set(favorited),
             in_recent: Set(in_recent),
+            content_hash: Set("".to_string()),
+            deleted_at: Set(0),
+            series: Set("".to_string()),
+            series_index: Set(0.0),
+            read_seconds: Set(0),
+            anchor_ratio: Set(0.0),
+            sha256: Set("".to_string()),
+            finished_at: Set(0),
+            crop_margins: Set("".to_string()),
+            author: Set("".to_string()),
+            subject: Set("".to_string()),
         }
     }
 }
@@ -1,14 +1,59 @@
 use serde::{Deserialize, Serialize};
 
+/// reflow 文本块类型
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReflowBlockType {
+    Heading,
+    Paragraph,
+    /// 公式块，提取后由后处理识别
+    Math,
+}
+
+/// reflow 文本块，按阅读顺序排列
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReflowBlock {
+    pub block_type: ReflowBlockType,
+    /// 块内文本，行之间以换行分隔
+    pub text: String,
+    /// 块在页面中的上下边界（相对页面高度 0.0~1.0）
+    pub top: f32,
+    pub bottom: f32,
+    pub font_size: f32,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ReflowEntry {
     pub data: String,
     pub page: String,
+    /// 结构化文本块，旧缓存没有该字段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ReflowBlock>,
+    /// 检测到的页面主要语言（ISO 639-1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ReflowData {
+    /// 缓存格式版本，提取逻辑变化时递增使旧缓存失效
+    #[serde(default)]
+    pub version: u32,
     pub page_count: usize,
     pub file_size: u64,
+    /// 源文件修改时间（秒），与当前文件不一致时缓存失效
+    #[serde(default)]
+    pub file_mtime: u64,
     pub reflow: Vec<ReflowEntry>,
-}
\ No newline at end of file
+    /// 是否已完成整本书的提取
+    #[serde(default = "default_complete")]
+    pub complete: bool,
+    /// 已提取过的页面（包括被过滤掉的空白页），提取完成后清空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extracted: Vec<usize>,
+}
+
+// 旧版本缓存没有该字段，都是一次性完整提取的
+fn default_complete() -> bool {
+    true
+}
@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Set, NotSet};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "vocab")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub word: String,
+    /// 释义，没有查过时为空
+    pub definition: String,
+    pub book_path: String,
+    /// 从 0 开始的页码
+    pub page: i32,
+    /// 单词所在的句子
+    pub context: String,
+    pub create_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 生词本中的一条
+pub type VocabEntry = Model;
+
+impl VocabEntry {
+    pub fn new(word: String, definition: String, book_path: String, page: i32, context: String) -> ActiveModel {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        ActiveModel {
+            id: NotSet,
+            word: Set(word),
+            definition: Set(definition),
+            book_path: Set(book_path),
+            page: Set(page),
+            context: Set(context),
+            create_at: Set(now),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(feature = "db")]
+use sea_orm::DbErr;
+use thiserror::Error;
+
+/// 应用统一错误类型，界面按错误种类给出不同提示，不再匹配错误字符串
+/// 解码器内部仍使用 anyhow，在 DecodeService 边界转换为该类型
+#[derive(Debug, Error)]
+pub enum RReaderError {
+    /// 文档解析/渲染失败，或解码线程不可用
+    #[error("decode error: {0}")]
+    Decode(String),
+
+    #[cfg(feature = "db")]
+    #[error("database error: {0}")]
+    Db(#[from] DbErr),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("tts error: {0}")]
+    Tts(String),
+
+    #[error("unsupported format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("password required: {0}")]
+    PasswordRequired(String),
+
+    /// 剪贴板、文件管理器等系统集成失败
+    #[error("platform error: {0}")]
+    Platform(String),
+
+    /// AI 助手接口请求失败
+    #[error("assistant error: {0}")]
+    Assistant(String),
+}
+
+pub type Result<T> = std::result::Result<T, RReaderError>;
+
+impl RReaderError {
+    pub fn decode(message: impl Into<String>) -> Self {
+        RReaderError::Decode(message.into())
+    }
+
+    /// 界面上显示的简短提示
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            RReaderError::Decode(_) => "打开文档失败",
+            #[cfg(feature = "db")]
+            RReaderError::Db(_) => "数据库访问失败",
+            RReaderError::Io(_) => "文件无法读取",
+            RReaderError::Tts(_) => "朗读失败",
+            RReaderError::UnsupportedFormat(_) => "不支持的文件格式",
+            RReaderError::PasswordRequired(_) => "文档已加密，需要密码",
+            RReaderError::Platform(_) => "系统操作失败",
+            RReaderError::Assistant(_) => "AI 助手请求失败",
+        }
+    }
+}
+
+/// 解码器返回的 anyhow 错误：已经是具体类型的保留原类型，其余归为解码错误
+impl From<anyhow::Error> for RReaderError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<RReaderError>() {
+            Ok(e) => return e,
+            Err(error) => error,
+        };
+        let error = match error.downcast::<std::io::Error>() {
+            Ok(e) => return RReaderError::Io(e),
+            Err(error) => error,
+        };
+        #[cfg(feature = "db")]
+        let error = match error.downcast::<DbErr>() {
+            Ok(e) => return RReaderError::Db(e),
+            Err(error) => error,
+        };
+        RReaderError::Decode(format!("{:#}", error))
+    }
+}
+
+impl From<std::time::SystemTimeError> for RReaderError {
+    fn from(error: std::time::SystemTimeError) -> Self {
+        RReaderError::Io(std::io::Error::other(error))
+    }
+}
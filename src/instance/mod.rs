@@ -0,0 +1,3 @@
+pub mod single_instance;
+
+pub use single_instance::{InstanceGuard, InstanceMessage};
@@ -0,0 +1,253 @@
+use crossbeam_channel::{unbounded, Receiver};
+use log::{debug, error, info};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::app_paths;
+
+/// 连接已有实例和等待应答的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 其他实例发来的请求
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstanceMessage {
+    /// 打开指定文件
+    Open(String),
+    /// 仅激活窗口
+    Activate,
+}
+
+impl InstanceMessage {
+    fn encode(&self) -> String {
+        match self {
+            InstanceMessage::Open(path) => format!("open {}\n", path),
+            InstanceMessage::Activate => "activate\n".to_string(),
+        }
+    }
+
+    fn decode(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if let Some(path) = line.strip_prefix("open ") {
+            Some(InstanceMessage::Open(path.to_string()))
+        } else if line == "activate" {
+            Some(InstanceMessage::Activate)
+        } else {
+            None
+        }
+    }
+}
+
+/// 握手时双方发送的标识，端口被其他程序占用时不会误认
+const HANDSHAKE: &str = "rreader-instance 1";
+/// 主实例刚启动、还没写出端口文件时，后启动的实例重试的次数和间隔
+const HAND_OFF_ATTEMPTS: u32 = 10;
+const HAND_OFF_RETRY: Duration = Duration::from_millis(200);
+
+/// 单实例锁：主实例在运行期间持有 instance.lock 的文件锁，进程退出（包括崩溃）时由系统释放，
+/// 抢锁是原子的，两个同时启动的实例只有一个能成为主实例。
+/// instance.port 记录主实例监听的本地端口和 pid，后启动的实例通过该端口把要打开的文件交给主实例，
+/// 主实例要用握手标识和自己的 pid 回复，避免两个进程同时写 book.db
+pub struct InstanceGuard {
+    /// 持有期间锁不释放
+    lock_file: Option<File>,
+    port_path: PathBuf,
+    receiver: Receiver<InstanceMessage>,
+}
+
+impl InstanceGuard {
+    fn lock_path() -> PathBuf {
+        app_paths::data_dir().join("instance.lock")
+    }
+
+    fn port_path() -> PathBuf {
+        app_paths::data_dir().join("instance.port")
+    }
+
+    /// 尝试成为主实例
+    /// 已有实例在运行时把消息转交给它并返回 None，调用方应直接退出
+    pub fn acquire(message: InstanceMessage) -> Option<Self> {
+        let lock_path = Self::lock_path();
+        let port_path = Self::port_path();
+        if let Some(parent) = lock_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let lock_file = match OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path) {
+            Ok(file) => file,
+            Err(e) => {
+                error!("[Instance] 无法打开锁文件 {:?}: {}", lock_path, e);
+                return Some(Self::standalone(port_path));
+            }
+        };
+        match lock_file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                for _ in 0..HAND_OFF_ATTEMPTS {
+                    if let Some((port, pid)) = Self::read_port(&port_path) {
+                        if Self::hand_off(port, pid, &message) {
+                            info!("[Instance] 已有实例在运行 (pid {})，已转交: {:?}", pid, message);
+                            return None;
+                        }
+                    }
+                    thread::sleep(HAND_OFF_RETRY);
+                }
+                // 锁被占用却联系不上主实例，不能悄悄退出，按独立实例启动
+                error!("[Instance] 已有实例持有锁但没有应答，按独立实例启动");
+                return Some(Self::standalone(port_path));
+            }
+            Err(TryLockError::Error(e)) => {
+                error!("[Instance] 锁定 {:?} 失败: {}", lock_path, e);
+                return Some(Self::standalone(port_path));
+            }
+        }
+
+        let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+            Ok(listener) => listener,
+            Err(e) => {
+                // 没有 IPC 也能运行，只是无法接收转交
+                error!("[Instance] 无法监听本地端口: {}", e);
+                let (_tx, receiver) = unbounded();
+                return Some(Self { lock_file: Some(lock_file), port_path, receiver });
+            }
+        };
+        let port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
+        // 先写临时文件再改名，后启动的实例不会读到半个文件
+        let mut tmp_path = port_path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let written = fs::write(&tmp_path, format!("{}\n{}\n", port, std::process::id()))
+            .and_then(|_| fs::rename(&tmp_path, &port_path));
+        if let Err(e) = written {
+            error!("[Instance] 写入端口文件失败: {}", e);
+        }
+
+        let (tx, receiver) = unbounded();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                if let Some(message) = Self::serve(stream) {
+                    debug!("[Instance] 收到: {:?}", message);
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        info!("[Instance] 主实例，端口: {}", port);
+        Some(Self { lock_file: Some(lock_file), port_path, receiver })
+    }
+
+    /// 不持有锁、不接收转交的实例
+    fn standalone(port_path: PathBuf) -> Self {
+        let (_tx, receiver) = unbounded();
+        Self { lock_file: None, port_path, receiver }
+    }
+
+    /// 端口和主实例的 pid
+    fn read_port(port_path: &Path) -> Option<(u16, u32)> {
+        let content = fs::read_to_string(port_path).ok()?;
+        let mut lines = content.lines();
+        let port = lines.next()?.trim().parse().ok()?;
+        let pid = lines.next()?.trim().parse().ok()?;
+        Some((port, pid))
+    }
+
+    /// 处理一个连接：先核对握手标识，再读一条请求，回复握手标识和本进程的 pid
+    fn serve(stream: TcpStream) -> Option<InstanceMessage> {
+        let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+        let mut reader = BufReader::new(stream.try_clone().ok()?);
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        if line.trim_end() != HANDSHAKE {
+            debug!("[Instance] 忽略未知连接");
+            return None;
+        }
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let message = InstanceMessage::decode(&line)?;
+        let mut stream = stream;
+        stream.write_all(format!("{} {}\n", HANDSHAKE, std::process::id()).as_bytes()).ok()?;
+        Some(message)
+    }
+
+    /// 只有收到主实例带 pid 的应答才算转交成功
+    fn hand_off(port: u16, pid: u32, message: &InstanceMessage) -> bool {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let Ok(mut stream) = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) else { return false };
+        let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+        let request = format!("{}\n{}", HANDSHAKE, message.encode());
+        if stream.write_all(request.as_bytes()).is_err() {
+            return false;
+        }
+        let mut reply = String::new();
+        if BufReader::new(stream).read_line(&mut reply).is_err() {
+            return false;
+        }
+        reply.trim_end() == format!("{} {}", HANDSHAKE, pid)
+    }
+
+    /// 取出其他实例转交过来的请求，由界面线程定时调用
+    pub fn try_recv(&self) -> Option<InstanceMessage> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        // 只删除自己写的端口文件，锁随文件关闭释放
+        if self.lock_file.is_some() && Self::read_port(&self.port_path).is_some_and(|(_, pid)| pid == std::process::id()) {
+            let _ = fs::remove_file(&self.port_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listen() -> (TcpListener, u16) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (listener, port)
+    }
+
+    #[test]
+    fn hand_off_requires_reply_from_primary() {
+        let (listener, port) = listen();
+        let server = thread::spawn(move || InstanceGuard::serve(listener.accept().unwrap().0));
+        let message = InstanceMessage::Open("/books/a.pdf".into());
+        assert!(InstanceGuard::hand_off(port, std::process::id(), &message));
+        assert_eq!(server.join().unwrap(), Some(message));
+    }
+
+    #[test]
+    fn hand_off_rejects_other_pid_and_other_programs() {
+        let (listener, port) = listen();
+        let server = thread::spawn(move || InstanceGuard::serve(listener.accept().unwrap().0));
+        // 锁文件里的 pid 与应答不符：端口已被另一个实例重新使用
+        assert!(!InstanceGuard::hand_off(port, std::process::id() + 1, &InstanceMessage::Activate));
+        server.join().unwrap();
+
+        // 端口被别的程序占用：能连上，也能写入，但没有握手应答
+        let (listener, port) = listen();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            let _ = BufReader::new(stream).read_line(&mut line);
+        });
+        assert!(!InstanceGuard::hand_off(port, std::process::id(), &InstanceMessage::Activate));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn serve_ignores_connections_without_handshake() {
+        let (listener, port) = listen();
+        let server = thread::spawn(move || InstanceGuard::serve(listener.accept().unwrap().0));
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream.write_all(b"open /etc/passwd\n").unwrap();
+        assert_eq!(server.join().unwrap(), None);
+    }
+}
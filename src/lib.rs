@@ -1,15 +1,43 @@
+//! RReader 库
+//!
+//! 其他 Rust 界面嵌入渲染和布局引擎时使用 [`engine::RReaderEngine`]，它只依赖解码、布局和缓存，
+//! 不需要 Slint 窗口；`controllers`、`ui` 等模块属于 RReader 自己的界面
+//!
+//! cargo 特性：`gui` 桌面界面（Slint、文件对话框），`db` 阅读记录数据库（sea-orm），`tts` 朗读，
+//! `ocr` 预留。`--no-default-features` 只构建解码、布局和 reflow 核心
+
 #![allow(unused)]
 #![allow(dead_code)]
 
+#[cfg(feature = "gui")]
 pub mod app_handler;
+pub mod app_paths;
+pub mod assistant;
 pub mod cache;
+pub mod cli;
+#[cfg(feature = "gui")]
 pub mod controllers;
+#[cfg(feature = "db")]
 pub mod dao;
 pub mod decoder;
+pub mod engine;
 pub mod entity;
+pub mod error;
+pub mod instance;
+pub mod library;
+pub mod net;
 pub mod page;
+pub mod platform;
+pub mod reflow;
+pub mod settings;
+pub mod storage;
+pub mod sync;
+#[cfg(feature = "tts")]
 pub mod tts;
 pub mod ui;
 
+pub use engine::{EngineEvent, RReaderEngine, RenderedPage};
+
 // 导出Slint生成的类型
+#[cfg(feature = "gui")]
 slint::include_modules!();
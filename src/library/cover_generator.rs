@@ -0,0 +1,102 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, info};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::decoder::decode_service::BackgroundActivity;
+use crate::decoder::{formats, DecodeService};
+use crate::platform::power;
+use crate::ui::utils::get_thumbnail_path;
+
+/// 每生成一个封面后的间隔，避免占满磁盘和 CPU
+const THROTTLE: Duration = Duration::from_millis(150);
+/// 阅读中的页面还在渲染时的等待间隔
+const BUSY_WAIT: Duration = Duration::from_millis(300);
+
+/// 封面生成进度，由界面线程轮询
+#[derive(Debug, Clone)]
+pub enum CoverEvent {
+    /// 已处理 done 个，共 total 个缺少封面的文档
+    Progress { done: usize, total: usize },
+    /// 某本书的封面已生成
+    Generated(String),
+    Finished { generated: usize },
+}
+
+/// 书库封面预生成：在后台为缺少封面的书生成缩略图，
+/// 解码线程忙于渲染当前阅读的文档时让路
+pub struct CoverGenerator {
+    event_rx: Receiver<CoverEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CoverGenerator {
+    pub fn start(paths: Vec<String>, activity: Arc<BackgroundActivity>) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancelled_for_thread = Arc::clone(&cancelled);
+        thread::spawn(move || Self::generate(paths, activity, event_tx, cancelled_for_thread));
+        Self { event_rx, cancelled }
+    }
+
+    pub fn try_recv(&self) -> Option<CoverEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    fn generate(paths: Vec<String>, activity: Arc<BackgroundActivity>, event_tx: Sender<CoverEvent>, cancelled: Arc<AtomicBool>) {
+        let missing: Vec<String> = paths
+            .into_iter()
+            .filter(|path| get_thumbnail_path(path).is_empty() && Path::new(path).is_file())
+            .collect();
+        info!("[Covers] 需要生成封面: {}", missing.len());
+
+        let total = missing.len();
+        let mut generated = 0;
+        for (index, path) in missing.into_iter().enumerate() {
+            // 用电池时暂停，不占用第二个解码线程
+            while (activity.pending_renders.load(Ordering::Relaxed) > 0 || power::is_low_power()) && !cancelled.load(Ordering::Relaxed) {
+                thread::sleep(BUSY_WAIT);
+            }
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+
+            if Self::generate_one(&path) {
+                generated += 1;
+                let _ = event_tx.send(CoverEvent::Generated(path));
+            }
+            if event_tx.send(CoverEvent::Progress { done: index + 1, total }).is_err() {
+                break;
+            }
+            thread::sleep(THROTTLE);
+        }
+
+        info!("[Covers] 封面生成结束: {}/{}", generated, total);
+        let _ = event_tx.send(CoverEvent::Finished { generated });
+    }
+
+    fn generate_one(path: &str) -> bool {
+        let file = Path::new(path);
+        let decoder = match formats::open_decoder(file) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                debug!("[Covers] 无法打开 {}: {}", path, e);
+                return false;
+            }
+        };
+        let Some(first_page) = decoder.get_all_pages().ok().and_then(|pages| pages.into_iter().next()) else {
+            return false;
+        };
+        DecodeService::save_cover_thumbnail(file, &decoder, &first_page);
+        !get_thumbnail_path(path).is_empty()
+    }
+}
+
+impl Drop for CoverGenerator {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
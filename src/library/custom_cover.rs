@@ -0,0 +1,61 @@
+use image::DynamicImage;
+use log::info;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::app_paths;
+use crate::error::Result;
+use crate::storage::FileStore;
+use crate::ui::utils::generate_thumbnail_hash;
+
+/// 封面最长边，和自动生成的首页封面一致
+const COVER_SIZE: u32 = 300;
+
+/// 自定义封面：书中的某一页或外部图片，优先于自动生成的首页封面；
+/// 和缩略图缓存分开存放，清空缓存或按配额清理时不会丢失
+pub struct CustomCover;
+
+impl CustomCover {
+    pub fn path(book_path: &str) -> PathBuf {
+        app_paths::custom_cover_dir().join(format!("{}.png", generate_thumbnail_hash(book_path)))
+    }
+
+    pub fn exists(book_path: &str) -> bool {
+        Self::path(book_path).is_file()
+    }
+
+    /// 缩小到封面尺寸后保存为 PNG
+    pub fn set_image(book_path: &str, image: &DynamicImage) -> Result<()> {
+        let cover = image.thumbnail(COVER_SIZE, COVER_SIZE);
+        let mut png = io::Cursor::new(Vec::new());
+        cover.write_to(&mut png, image::ImageFormat::Png).map_err(invalid_image)?;
+        fs::create_dir_all(app_paths::custom_cover_dir())?;
+        let path = Self::path(book_path);
+        FileStore::write(&path, png.into_inner())?;
+        info!("[Cover] {} 使用自定义封面: {:?}", book_path, path);
+        Ok(())
+    }
+
+    /// 以外部图片文件作为封面
+    pub fn set_from_file(book_path: &str, image_path: &Path) -> Result<()> {
+        let image = image::load_from_memory(&fs::read(image_path)?).map_err(invalid_image)?;
+        Self::set_image(book_path, &image)
+    }
+
+    /// 去掉自定义封面，恢复自动生成的首页封面
+    pub fn remove(book_path: &str) -> Result<()> {
+        match fs::remove_file(Self::path(book_path)) {
+            Ok(()) => {
+                info!("[Cover] {} 恢复自动封面", book_path);
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn invalid_image(error: image::ImageError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
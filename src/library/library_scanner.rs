@@ -0,0 +1,126 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, error, info};
+use sea_orm::ActiveValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::dao::RecentDao;
+use crate::decoder::formats;
+use crate::library::SeriesInfo;
+use crate::entity::Recent;
+use crate::ui::utils::content_hash_string;
+
+/// 每找到多少个文件上报一次进度
+const PROGRESS_STEP: usize = 10;
+
+/// 扫描进度事件，由界面线程轮询
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// 已找到 found 个文档，其中 added 个是新加入的
+    Progress { found: usize, added: usize },
+    Finished { found: usize, added: usize },
+}
+
+/// 书库扫描：遍历书库目录，把支持的文档加入书架
+pub struct LibraryScanner {
+    event_rx: Receiver<ScanEvent>,
+}
+
+impl LibraryScanner {
+    /// 在后台开始扫描，返回的扫描器用于接收进度
+    pub fn start(folders: Vec<String>) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        tokio::spawn(async move {
+            Self::scan(folders, event_tx).await;
+        });
+        Self { event_rx }
+    }
+
+    pub fn try_recv(&self) -> Option<ScanEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    async fn scan(folders: Vec<String>, event_tx: Sender<ScanEvent>) {
+        info!("[Library] 开始扫描书库: {:?}", folders);
+        let files = tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            for folder in &folders {
+                Self::collect_documents(Path::new(folder), &mut files);
+            }
+            files
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut added = 0;
+        for (index, path) in files.iter().enumerate() {
+            match Self::add_document(path).await {
+                Ok(true) => added += 1,
+                Ok(false) => {}
+                Err(e) => error!("[Library] 添加文档失败 {:?}: {}", path, e),
+            }
+            if (index + 1) % PROGRESS_STEP == 0 {
+                let _ = event_tx.send(ScanEvent::Progress { found: index + 1, added });
+            }
+        }
+
+        info!("[Library] 扫描完成，共 {} 个文档，新增 {}", files.len(), added);
+        let _ = event_tx.send(ScanEvent::Finished { found: files.len(), added });
+    }
+
+    /// 递归收集目录下支持的文档，跳过隐藏目录
+    fn collect_documents(dir: &Path, files: &mut Vec<PathBuf>) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("[Library] 无法读取目录 {:?}: {}", dir, e);
+                return;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() {
+                if !hidden {
+                    Self::collect_documents(&path, files);
+                }
+            } else if !hidden && formats::is_supported(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    /// 加入书架，已存在的记录不改动，返回是否新增
+    async fn add_document(path: &Path) -> Result<bool, sea_orm::DbErr> {
+        let path_str = path.to_string_lossy().to_string();
+        if RecentDao::find_by_path(&path_str).await?.is_some() {
+            return Ok(false);
+        }
+
+        let metadata = fs::metadata(path).ok();
+        // 用文件修改时间排序，避免扫描出的书排在真正读过的书前面
+        let modified = metadata.as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+
+        let mut record = Recent::new(path_str);
+        record.name = ActiveValue::Set(
+            path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+        );
+        record.ext = ActiveValue::Set(
+            path.extension().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default(),
+        );
+        record.size = ActiveValue::Set(metadata.map(|m| m.len() as i64).unwrap_or(0));
+        record.update_at = ActiveValue::Set(modified);
+        record.content_hash = ActiveValue::Set(content_hash_string(path).unwrap_or_default());
+        if let Some(series) = SeriesInfo::detect(path) {
+            record.series = ActiveValue::Set(series.name);
+            record.series_index = ActiveValue::Set(series.volume);
+        }
+        RecentDao::insert(record).await?;
+        Ok(true)
+    }
+}
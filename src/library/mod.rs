@@ -0,0 +1,11 @@
+pub mod cover_generator;
+pub mod custom_cover;
+#[cfg(feature = "db")]
+pub mod library_scanner;
+pub mod series;
+
+pub use cover_generator::{CoverEvent, CoverGenerator};
+pub use custom_cover::CustomCover;
+#[cfg(feature = "db")]
+pub use library_scanner::{LibraryScanner, ScanEvent};
+pub use series::SeriesInfo;
@@ -0,0 +1,80 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::decoder::ComicInfo;
+
+/// 文件名中的扫描组、年份等标签：[Group] (2004) {HQ}
+static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[[^\]]*\]|\([^)]*\)|\{[^}]*\}").unwrap());
+/// Naruto v03 / Naruto Vol.3 / Naruto Volume 03 / Naruto #3 / Asterix Tome 5
+static VOLUME_MARK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?P<name>.+?)[\s_.\-]*(?:v|vol\.?|volume|tome|band|#)\s*(?P<num>\d+(?:\.\d+)?)\b").unwrap()
+});
+/// 火影忍者 第3卷 / 海贼王第12话
+static VOLUME_CJK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<name>.+?)\s*第\s*(?P<num>\d+(?:\.\d+)?)\s*[卷册冊话話集部]").unwrap()
+});
+/// One Piece - 012 / Berserk 37
+static TRAILING_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<name>.+?)[\s_\-]+(?P<num>\d{1,4}(?:\.\d+)?)$").unwrap()
+});
+
+/// 从文件名识别出的系列和卷号
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesInfo {
+    pub name: String,
+    pub volume: f32,
+}
+
+impl SeriesInfo {
+    /// 识别系列：CBZ 优先使用 ComicInfo.xml 中的系列和卷号，其次按文件名
+    pub fn detect(path: &Path) -> Option<Self> {
+        if let Ok(Some(comic_info)) = ComicInfo::read(path) {
+            if let Some(name) = comic_info.series.clone() {
+                let volume = comic_info.volume_number()
+                    .or_else(|| Self::from_path(path).map(|info| info.volume))
+                    .unwrap_or(0.0);
+                return Some(Self { name, volume });
+            }
+        }
+        Self::from_path(path)
+    }
+
+    /// 根据文件名识别系列，不像系列的返回 None
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let stem = path.file_stem()?.to_string_lossy();
+        Self::parse(&stem)
+    }
+
+    pub fn parse(stem: &str) -> Option<Self> {
+        let stem = TAGS.replace_all(stem, " ");
+        let stem = stem.trim();
+        [&*VOLUME_MARK, &*VOLUME_CJK, &*TRAILING_NUMBER]
+            .iter()
+            .find_map(|re| re.captures(stem))
+            .and_then(|c| {
+                let name = Self::clean_name(&c["name"]);
+                let volume = c["num"].parse::<f32>().ok()?;
+                (!name.is_empty()).then_some(Self { name, volume })
+            })
+    }
+
+    /// 下划线和点当作空格，去掉末尾的分隔符
+    fn clean_name(name: &str) -> String {
+        let name = name.replace(['_', '.'], " ");
+        name.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end_matches([' ', '-', ',', ':'])
+            .to_string()
+    }
+
+    /// 卷号的显示形式，整数卷不带小数
+    pub fn volume_label(volume: f32) -> String {
+        if volume.fract() == 0.0 {
+            format!("{}", volume as i64)
+        } else {
+            format!("{}", volume)
+        }
+    }
+}
@@ -15,12 +15,24 @@ use slint::{ComponentHandle, ModelRc, VecModel};
 slint::include_modules!();
 
 mod app_handler;
+mod app_paths;
+mod assistant;
 mod cache;
+mod cli;
 mod controllers;
 mod dao;
 mod decoder;
 mod entity;
+mod error;
+mod instance;
+mod library;
+mod net;
 mod page;
+mod platform;
+mod reflow;
+mod settings;
+mod storage;
+mod sync;
 mod tts;
 mod ui;
 
@@ -28,12 +40,30 @@ use app_handler::AppHandler;
 use page::{PageViewState, Orientation};
 use tts::TtsService;
 use crate::decoder::pdf::utils::{generate_thumbnail_key, convert_to_slint_image};
-use crate::controllers::DocumentController;
+use crate::controllers::{DocumentController, IdleController, SimpleModeController, StatusController, ThemeController};
+use crate::decoder::formats;
+use crate::instance::{InstanceGuard, InstanceMessage};
 
-use crate::ui::MainViewmodel;
+use crate::decoder::decode_service::DecodeResult;
+use crate::ui::{GpuTextures, MainViewmodel};
 use crate::dao::RecentDao;
 use crate::entity::{Recent};
 use crate::ui::utils::get_thumbnail_path;
+use crate::storage::{DatabaseVault, FileStore};
+use crate::net::DownloadJob;
+
+/// 解码结果轮询间隔，空闲时放慢
+const DECODE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+const IDLE_DECODE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// 解码结果转为 CPU 图像（RGBA 已预乘）
+fn page_image(result: &DecodeResult) -> slint::Image {
+    slint::Image::from_rgba8_premultiplied(slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(
+        &result.image_data,
+        result.image_width,
+        result.image_height,
+    ))
+}
 
 /// 设置文档相关回调
 fn setup_document_callbacks(app: &AppWindow, document_controller: Rc<RefCell<DocumentController>>) {
@@ -41,17 +71,15 @@ fn setup_document_callbacks(app: &AppWindow, document_controller: Rc<RefCell<Doc
     let document_controller_clone = Rc::clone(&document_controller);
 
     app.on_open_file(move || {
-        let file_path = rfd::FileDialog::new()
-            .add_filter("PDF Files", &["pdf"])
-            .add_filter("PDF Files", &["epub"])
-            .add_filter("PDF Files", &["mobi"])
-            .add_filter("All Files", &["cbz"])
-            .add_filter("All Files", &["docx"])
-            .add_filter("All Files", &["xps"])
-            .add_filter("All Files", &["djvu"])
-            .add_filter("All Files", &["tif"])
-            .add_filter("All Files", &["tiff"])
-            .set_title("Select PDF File")
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(folder) = SimpleModeController::start_folder() {
+            dialog = dialog.set_directory(folder);
+        }
+        for (name, extensions) in formats::dialog_filters() {
+            dialog = dialog.add_filter(name, &extensions);
+        }
+        let file_path = dialog
+            .set_title("Select Document")
             .pick_file();
 
         if let Some(path) = file_path {
@@ -69,18 +97,52 @@ async fn main() -> Result<()> {
         Env::default().default_filter_or("info")  // 默认日志级别：info
     ).init();
 
+    // 书库加密时设置和数据库都需要钥匙串中的密钥
+    FileStore::init();
+    if FileStore::is_locked() {
+        anyhow::bail!("The library is encrypted but its key is not available in the system keychain");
+    }
+
+    // 命令行子命令（如 narrate）不启动界面
+    if cli::run_from_args() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(app_paths::data_dir()).expect("Unable to create app data directory");
+
+    // 已有实例时把文件交给它打开，避免两个进程同时写数据库
+    let startup_file = cli::file_argument();
+    let message = match &startup_file {
+        Some(path) => InstanceMessage::Open(path.clone()),
+        None => InstanceMessage::Activate,
+    };
+    let Some(instance) = InstanceGuard::acquire(message) else {
+        println!("RReader is already running, request handed off to the existing instance");
+        return Ok(());
+    };
+
     let app = AppWindow::new()?;
 
-    let data_dir = dirs::data_dir().expect("Unable to get data directory");
-    let app_data_dir = data_dir.join("RReader");
-    fs::create_dir_all(&app_data_dir).expect("Unable to create app data directory");
+    app_paths::migrate_legacy_caches();
+    std::thread::spawn(app_paths::enforce_cache_quotas);
+    std::thread::spawn(DownloadJob::cleanup_abandoned);
+    std::thread::spawn(platform::register_file_types);
 
-    let db_path = app_data_dir.join("book.db");
+    let db_path = app_paths::database_path();
     let database_url = format!("sqlite:///{}", db_path.display());
     debug!("Database path: {:?}", db_path);
     debug!("Database URL: {}", database_url);
     std::env::set_var("DATABASE_URL", &database_url);
 
+    if DatabaseVault::has_leftover_plaintext(&db_path) {
+        tokio::task::block_in_place(|| {
+            futures::executor::block_on(async {
+                crate::dao::checkpoint_database(&db_path).await.expect("Failed to recover leftover database");
+            });
+        });
+        DatabaseVault::seal(&db_path).expect("Failed to re-encrypt leftover database");
+    }
+    DatabaseVault::unseal(&db_path).expect("Failed to decrypt database");
     tokio::task::block_in_place(|| {
         futures::executor::block_on(async {
             crate::dao::ensure_database_ready(&db_path).await.expect("Failed to initialize database");
@@ -109,13 +171,18 @@ async fn main() -> Result<()> {
     let decode_timer = {
         let weak_app = app.as_weak();
         let state_clone = Rc::clone(&app_handler.document_controller().borrow().page_view_state());
-        let timer = slint::Timer::default();
+        let timer = Rc::new(slint::Timer::default());
         let timer_count = Rc::new(RefCell::new(0));
         let timer_count_clone = Rc::clone(&timer_count);
+        let tts_service_for_status = Arc::clone(&tts_service);
+        let document_controller_for_theme = app_handler.document_controller();
+        let pending_pages: Rc<RefCell<Vec<DecodeResult>>> = Rc::new(RefCell::new(Vec::new()));
+        let pending_pages_clone = Rc::clone(&pending_pages);
+        let state_for_frame = Rc::clone(&state_clone);
 
         timer.start(
             slint::TimerMode::Repeated,
-            std::time::Duration::from_millis(100),
+            DECODE_INTERVAL,
             move || {
                 let mut count = timer_count_clone.borrow_mut();
                 *count += 1;
@@ -124,36 +191,59 @@ async fn main() -> Result<()> {
                 }
 
                 if let Some(app) = weak_app.upgrade() {
+                    if *count % 50 == 0 {
+                        ThemeController::poll_system(&app, &document_controller_for_theme);
+                        debug!("[Cache] 页面图像占用 {} KB", state_clone.borrow().memory_bytes() / 1024);
+                    }
+                    if *count % 5 == 0 {
+                        let speaking = tts_service_for_status.lock().unwrap().is_speaking();
+                        // 听朗读也算在阅读，不进入空闲
+                        if speaking {
+                            IdleController::touch();
+                        }
+                        StatusController::update_activity(&app, state_clone.borrow().decode_service.activity(), speaking);
+                    }
+
+                    document_controller_for_theme.borrow().handle_render_failures(&app);
+
                     let mut had_results = false;
                     let mut result_count = 0;
                     {
                         let mut state = state_clone.borrow_mut();
-                        while let Some(result) = state.decode_service.try_recv_result() {
+                        while let Some(mut result) = state.decode_service.try_recv_result() {
                             had_results = true;
                             result_count += 1;
-                            debug!("[Main] 收到解码结果: page={}, key={}, size={}x{}",
-                                result.page_info.index, result.key, result.image_width, result.image_height);
-
-                            // 注意：mupdf_to_pixels 返回的 RGBA 数据中 alpha 值为未预乘，若 Slint 期望预乘则需后续处理
-                            let slint_image = slint::Image::from_rgba8_premultiplied(
-                                slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(
-                                    &result.image_data,
-                                    result.image_width,
-                                    result.image_height,
-                                ),
-                            );
-
-                            // 更新缓存
-                            state.cache.put_thumbnail(result.key.clone(), slint_image);
+                            debug!("[Main] 收到解码结果: page={}, key={}, size={}x{}, preview={}",
+                                result.page_info.index, result.key, result.image_width, result.image_height, result.preview);
+
+                            if state.night_mode {
+                                crate::ui::utils::invert_rgba(&mut result.image_data);
+                            }
+
+                            // 更新链接，预览不带链接，等全精度结果
+                            if !result.preview {
+                                state.page_links
+                                    .borrow_mut()
+                                    .insert(result.page_info.index, std::mem::take(&mut result.links));
+                            }
+
+                            // OpenGL 渲染时留到下一帧渲染前上传为纹理
+                            if GpuTextures::is_active() {
+                                pending_pages_clone.borrow_mut().push(result);
+                                app.window().request_redraw();
+                                continue;
+                            }
+                            state.cache.put_thumbnail(result.key.clone(), page_image(&result));
                             info!("[Main] 已更新缓存: key={}", result.key);
-
-                            // 更新链接
-                            state.page_links
-                                .borrow_mut()
-                                .insert(result.page_info.index, result.links);
                         }
                     }
 
+                    // 超过内存上限时回收屏幕外页面，压力解除后恢复全精度
+                    if state_clone.borrow_mut().check_memory_pressure() {
+                        state_clone.borrow_mut().update_visible_pages();
+                        had_results = true;
+                    }
+
                     if had_results {
                         debug!("[Main] 处理了 {} 个解码结果，刷新视图", result_count);
                         use crate::controllers::DocumentController;
@@ -162,13 +252,89 @@ async fn main() -> Result<()> {
                 }
             },
         );
+
+        // 渲染前把等待的页面上传为纹理，上传失败时退回 CPU 图像
+        {
+            let weak_app = app.as_weak();
+            GpuTextures::install(app.window(), move || {
+                let results = std::mem::take(&mut *pending_pages.borrow_mut());
+                if results.is_empty() {
+                    return;
+                }
+                {
+                    let state = state_for_frame.borrow();
+                    for result in results {
+                        match GpuTextures::upload(&result.image_data, result.image_width, result.image_height) {
+                            Some((image, texture)) => state.cache.put_thumbnail_texture(result.key.clone(), image, texture),
+                            None => state.cache.put_thumbnail(result.key.clone(), page_image(&result)),
+                        };
+                        info!("[Main] 已更新缓存: key={}", result.key);
+                    }
+                }
+                // 渲染回调里不能改模型，这一帧结束后再刷新
+                let weak_app = weak_app.clone();
+                let state_for_frame = Rc::clone(&state_for_frame);
+                slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+                    let Some(app) = weak_app.upgrade() else { return };
+                    crate::controllers::DocumentController::refresh_view(&app, &state_for_frame.borrow());
+                });
+            });
+        }
+
+        // 空闲时放慢轮询，有操作时恢复
+        let weak_timer = Rc::downgrade(&timer);
+        IdleController::on_idle_changed(move |idle| {
+            if let Some(timer) = weak_timer.upgrade() {
+                timer.set_interval(if idle { IDLE_DECODE_INTERVAL } else { DECODE_INTERVAL });
+            }
+        });
+        timer
+    };
+
+    if let Some(path) = startup_file {
+        app_handler.document_controller().borrow().open_document(&app, &path);
+    }
+
+    // 处理其他实例转交过来的打开请求
+    let instance_timer = {
+        let weak_app = app.as_weak();
+        let document_controller = app_handler.document_controller();
+        let timer = slint::Timer::default();
+        timer.start(
+            slint::TimerMode::Repeated,
+            std::time::Duration::from_millis(300),
+            move || {
+                while let Some(message) = instance.try_recv() {
+                    let Some(app) = weak_app.upgrade() else { return };
+                    info!("[Main] 处理其他实例的请求: {:?}", message);
+                    if let InstanceMessage::Open(path) = message {
+                        document_controller.borrow().open_document(&app, &path);
+                    }
+                    let _ = app.show();
+                }
+            },
+        );
         timer
     };
 
     app.run()?;
 
+    instance_timer.stop();
     decode_timer.stop();
     app_handler.save();
 
+    let closed = tokio::task::block_in_place(|| {
+        futures::executor::block_on(async { crate::dao::close_db().await })
+    });
+    match closed {
+        // 连接没有关闭时加密会丢数据，保留明文，下次启动时重新加密
+        Err(e) => error!("[Storage] 数据库连接没有关闭，本次不加密，明文数据库留到下次启动处理: {}", e),
+        Ok(()) => {
+            if let Err(e) = DatabaseVault::seal(&db_path) {
+                error!("[Storage] 数据库加密保存失败: {}", e);
+            }
+        }
+    }
+
     Ok(())
 }
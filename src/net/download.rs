@@ -0,0 +1,306 @@
+use anyhow::{bail, Result};
+use base64::Engine;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use futures::StreamExt;
+use log::{debug, info, warn};
+use reqwest::header::{HeaderMap, CONTENT_DISPOSITION, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use super::{HttpClient, Throttle};
+use crate::app_paths;
+use crate::ui::utils::sha256_file;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// 暂停时检查恢复和取消的间隔
+const PAUSE_POLL: Duration = Duration::from_millis(200);
+/// 超过这么久没有续传的半截文件视为放弃，启动时清理
+const ABANDONED_AFTER: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// 来源提供的 SHA-256 校验值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum(Vec<u8>);
+
+impl Checksum {
+    /// 十六进制字符串，如 OPDS 或网页上给出的校验值
+    pub fn sha256_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        if hex.len() != 64 {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()
+            .map(Checksum)
+    }
+
+    /// 响应头 `Repr-Digest: sha-256=:<base64>:` 或旧式的 `Digest: SHA-256=<base64>`
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        ["repr-digest", "digest"].iter()
+            .filter_map(|name| headers.get(*name)?.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(|item| {
+                let (algorithm, value) = item.trim().split_once('=')?;
+                if !algorithm.eq_ignore_ascii_case("sha-256") {
+                    return None;
+                }
+                base64::engine::general_purpose::STANDARD.decode(value.trim_matches(':')).ok().map(Checksum)
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    /// total 在服务器没有给出长度时为 None
+    Progress { received: u64, total: Option<u64> },
+    Finished(std::result::Result<PathBuf, String>),
+}
+
+/// 后台下载：半截文件放在缓存目录，中断或取消后再次下载同一网址时断点续传；
+/// 来源提供了校验值时下载完成后验证，不一致则删除重下
+pub struct DownloadJob {
+    event_rx: Receiver<DownloadEvent>,
+    cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+}
+
+impl DownloadJob {
+    pub fn start(url: String, checksum: Option<Checksum>) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancelled_for_task = Arc::clone(&cancelled);
+        let paused = Arc::new(AtomicBool::new(false));
+        let paused_for_task = Arc::clone(&paused);
+        tokio::spawn(async move {
+            let result = Self::download(&url, checksum, &event_tx, &cancelled_for_task, &paused_for_task)
+                .await
+                .map_err(|e| e.to_string());
+            info!("[Download] {}: {:?}", url, result);
+            let _ = event_tx.send(DownloadEvent::Finished(result));
+        });
+        Self { event_rx, cancelled, paused }
+    }
+
+    pub fn try_recv(&self) -> Option<DownloadEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    /// 取消后保留半截文件，下次续传
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// 暂停时连接保持，不再读取数据
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    fn partial_path(url: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        app_paths::download_cache_dir().join(format!("{:016x}.part", hasher.finish()))
+    }
+
+    /// 保存 ETag/Last-Modified，续传时用 If-Range 确认服务器上的文件没有变
+    fn validator_path(partial: &Path) -> PathBuf {
+        partial.with_extension("part.validator")
+    }
+
+    async fn download(url: &str, checksum: Option<Checksum>, event_tx: &Sender<DownloadEvent>, cancelled: &AtomicBool, paused: &AtomicBool) -> Result<PathBuf> {
+        let partial = Self::partial_path(url);
+        let validator_path = Self::validator_path(&partial);
+        fs::create_dir_all(app_paths::download_cache_dir())?;
+
+        let mut offset = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+        let client = HttpClient::builder()?.connect_timeout(CONNECT_TIMEOUT).build()?;
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+            if let Ok(validator) = fs::read_to_string(&validator_path) {
+                request = request.header(IF_RANGE, validator.trim());
+            }
+        }
+        let response = request.send().await?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            let _ = fs::remove_file(&partial);
+            let _ = fs::remove_file(&validator_path);
+            bail!("续传位置无效，已清除半截文件，请重新下载");
+        }
+        let response = response.error_for_status()?;
+
+        // 服务器不支持续传或文件已变化时返回完整内容，从头开始
+        let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+        if resumed {
+            info!("[Download] 从 {} 字节处续传: {}", offset, url);
+        } else {
+            offset = 0;
+        }
+        let headers = response.headers().clone();
+        match headers.get(ETAG).or_else(|| headers.get(LAST_MODIFIED)).and_then(|v| v.to_str().ok()) {
+            Some(validator) => fs::write(&validator_path, validator)?,
+            None => {
+                let _ = fs::remove_file(&validator_path);
+            }
+        }
+        let expected = checksum.or_else(|| Checksum::from_headers(&headers));
+        let total = response.content_length().map(|len| len + offset);
+
+        let mut file = if resumed {
+            OpenOptions::new().append(true).open(&partial)?
+        } else {
+            File::create(&partial)?
+        };
+        let mut received = offset;
+        let mut throttle = Throttle::from_settings();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            if paused.load(Ordering::Relaxed) {
+                while paused.load(Ordering::Relaxed) && !cancelled.load(Ordering::Relaxed) {
+                    tokio::time::sleep(PAUSE_POLL).await;
+                }
+                throttle.restart();
+            }
+            if cancelled.load(Ordering::Relaxed) {
+                bail!("已取消，下次从 {} 字节处续传", received);
+            }
+            let chunk = chunk?;
+            file.write_all(&chunk)?;
+            received += chunk.len() as u64;
+            let _ = event_tx.send(DownloadEvent::Progress { received, total });
+            if let Some(delay) = throttle.delay_for(chunk.len() as u64) {
+                tokio::time::sleep(delay).await;
+            }
+        }
+        file.sync_all()?;
+        drop(file);
+
+        if let Some(expected) = expected {
+            if sha256_file(&partial)? != expected.0 {
+                let _ = fs::remove_file(&partial);
+                let _ = fs::remove_file(&validator_path);
+                bail!("校验失败，文件已损坏，请重新下载");
+            }
+            debug!("[Download] 校验通过: {}", url);
+        }
+
+        let target = Self::unique_target(&app_paths::downloads_dir(), &Self::file_name(url, &headers))?;
+        // 缓存目录和下载目录可能不在同一分区
+        if fs::rename(&partial, &target).is_err() {
+            fs::copy(&partial, &target)?;
+            fs::remove_file(&partial)?;
+        }
+        let _ = fs::remove_file(&validator_path);
+        Ok(target)
+    }
+
+    /// 优先用 Content-Disposition 中的文件名，否则取网址最后一段
+    fn file_name(url: &str, headers: &HeaderMap) -> String {
+        let from_header = headers.get(CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').find_map(|part| part.trim().strip_prefix("filename=")))
+            .map(|name| name.trim_matches('"').to_string());
+        let name = from_header.unwrap_or_else(|| {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            path.rsplit('/').next().unwrap_or_default().to_string()
+        });
+        Self::sanitize_file_name(&name)
+    }
+
+    /// 服务器给的文件名不可信：去掉路径分隔符、控制字符和 Windows 不允许的字符，
+    /// 去掉开头的点（隐藏文件、..）和结尾的点与空格，设备名（CON、NUL 等）前加下划线
+    fn sanitize_file_name(name: &str) -> String {
+        let name: String = name.chars()
+            .filter(|c| !c.is_control() && !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+            .collect();
+        let name = name.trim().trim_start_matches('.').trim_end_matches(['.', ' ']).trim();
+        if name.is_empty() {
+            return "download".to_string();
+        }
+        let stem = name.split('.').next().unwrap_or_default().trim_end().to_ascii_uppercase();
+        let reserved = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+            || (stem.len() == 4
+                && (stem.starts_with("COM") || stem.starts_with("LPT"))
+                && stem.as_bytes()[3].is_ascii_digit()
+                && stem.as_bytes()[3] != b'0');
+        if reserved { format!("_{}", name) } else { name.to_string() }
+    }
+
+    /// 同名文件已存在时加上序号
+    fn unique_target(dir: &Path, name: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let target = dir.join(name);
+        if !target.exists() {
+            return Ok(target);
+        }
+        let path = Path::new(name);
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+        (1..)
+            .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+            .find(|candidate| !candidate.exists())
+            .ok_or_else(|| io::Error::other("no free file name"))
+    }
+
+    /// 删除长时间没有续传的半截文件
+    pub fn cleanup_abandoned() {
+        let Ok(entries) = fs::read_dir(app_paths::download_cache_dir()) else { return };
+        let now = SystemTime::now();
+        for entry in entries.flatten() {
+            let abandoned = entry.metadata()
+                .and_then(|m| m.modified())
+                .map(|modified| now.duration_since(modified).unwrap_or_default() > ABANDONED_AFTER)
+                .unwrap_or(false);
+            if abandoned {
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => info!("[Download] 清理放弃的下载: {:?}", entry.path()),
+                    Err(e) => warn!("[Download] 清理失败 {:?}: {}", entry.path(), e),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for DownloadJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_rejects_unsafe_names() {
+        let sanitize = DownloadJob::sanitize_file_name;
+        assert_eq!(sanitize("book.pdf"), "book.pdf");
+        assert_eq!(sanitize("../../etc/passwd"), "etcpasswd");
+        assert_eq!(sanitize(".."), "download");
+        assert_eq!(sanitize(" . "), "download");
+        assert_eq!(sanitize(".hidden.pdf"), "hidden.pdf");
+        assert_eq!(sanitize("name.pdf. "), "name.pdf");
+        assert_eq!(sanitize("a\u{0}b\r\nc\u{7f}.pdf"), "abc.pdf");
+        assert_eq!(sanitize("CON"), "_CON");
+        assert_eq!(sanitize("nul.txt"), "_nul.txt");
+        assert_eq!(sanitize("com1.pdf"), "_com1.pdf");
+        assert_eq!(sanitize("COM0.pdf"), "COM0.pdf");
+        assert_eq!(sanitize("console.pdf"), "console.pdf");
+    }
+
+    #[test]
+    fn file_name_prefers_content_disposition() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(DownloadJob::file_name("https://example.com/a/b.pdf?x=1#y", &headers), "b.pdf");
+        assert_eq!(DownloadJob::file_name("https://example.com/", &headers), "download");
+        headers.insert(CONTENT_DISPOSITION, "attachment; filename=\"AUX.epub\"".parse().unwrap());
+        assert_eq!(DownloadJob::file_name("https://example.com/a/b.pdf", &headers), "_AUX.epub");
+    }
+}
@@ -0,0 +1,28 @@
+use log::debug;
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+
+use crate::settings::{AppSettings, ProxyMode, ProxySettings};
+
+/// 所有联网功能共用的 HTTP 客户端工厂，统一应用代理设置
+pub struct HttpClient;
+
+impl HttpClient {
+    /// 按当前代理设置创建客户端构造器，调用方再加上自己的超时等选项
+    pub fn builder() -> reqwest::Result<ClientBuilder> {
+        Self::builder_with(&AppSettings::get().proxy)
+    }
+
+    pub fn builder_with(proxy: &ProxySettings) -> reqwest::Result<ClientBuilder> {
+        let builder = reqwest::Client::builder();
+        match proxy.mode {
+            // reqwest 默认读取 HTTP_PROXY/HTTPS_PROXY/NO_PROXY 环境变量
+            ProxyMode::System => Ok(builder),
+            ProxyMode::None => Ok(builder.no_proxy()),
+            ProxyMode::Manual => {
+                debug!("[Net] 使用代理: {}", proxy.url);
+                let manual = Proxy::all(proxy.url.trim())?.no_proxy(NoProxy::from_string(&proxy.no_proxy));
+                Ok(builder.proxy(manual))
+            }
+        }
+    }
+}
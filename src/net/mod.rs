@@ -0,0 +1,9 @@
+pub mod download;
+pub mod http_client;
+pub mod open_library;
+pub mod throttle;
+
+pub use download::{Checksum, DownloadEvent, DownloadJob};
+pub use http_client::HttpClient;
+pub use open_library::{find_isbn, MetadataQuery, OnlineMetadata, OpenLibrary};
+pub use throttle::Throttle;
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use crossbeam_channel::{unbounded, Receiver};
+use log::{info, warn};
+use serde_json::Value;
+use std::time::Duration;
+
+use super::HttpClient;
+
+const SEARCH_URL: &str = "https://openlibrary.org/search.json";
+const WORK_URL: &str = "https://openlibrary.org";
+const COVER_URL: &str = "https://covers.openlibrary.org/b/id";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// OpenLibrary 要求带上能识别应用的 User-Agent
+const USER_AGENT: &str = concat!("RReader/", env!("CARGO_PKG_VERSION"), " (https://github.com/archko/RReader)");
+
+/// 查询条件：有 ISBN 时按 ISBN 查，否则按书名和作者查
+#[derive(Debug, Clone, Default)]
+pub struct MetadataQuery {
+    pub isbn: Option<String>,
+    pub title: String,
+    pub author: String,
+}
+
+/// OpenLibrary 上查到的一本书
+#[derive(Debug, Clone, Default)]
+pub struct OnlineMetadata {
+    /// 作品 key，如 /works/OL45883W
+    pub key: String,
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    pub isbn: String,
+    /// 封面图片（JPEG），没有封面时为 None
+    pub cover: Option<Vec<u8>>,
+}
+
+/// OpenLibrary 书目查询，在 tokio 运行时中进行，结果通过通道取回
+pub struct OpenLibrary;
+
+impl OpenLibrary {
+    /// 没有找到时结果为 Ok(None)
+    pub fn lookup(query: MetadataQuery) -> Receiver<std::result::Result<Option<OnlineMetadata>, String>> {
+        let (result_tx, result_rx) = unbounded();
+        tokio::spawn(async move {
+            let result = Self::fetch(&query).await.map_err(|e| e.to_string());
+            let outcome = match &result {
+                Ok(Some(book)) => book.key.as_str(),
+                Ok(None) => "未找到",
+                Err(e) => e.as_str(),
+            };
+            info!("[OpenLibrary] 查询 {:?}: {}", query, outcome);
+            let _ = result_tx.send(result);
+        });
+        result_rx
+    }
+
+    async fn fetch(query: &MetadataQuery) -> Result<Option<OnlineMetadata>> {
+        let client = HttpClient::builder()?
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()?;
+
+        let mut request = client.get(SEARCH_URL).query(&[("limit", "1"), ("fields", "key,title,author_name,cover_i,isbn")]);
+        request = match &query.isbn {
+            Some(isbn) => request.query(&[("isbn", isbn)]),
+            None if query.author.trim().is_empty() => request.query(&[("title", query.title.trim())]),
+            None => request.query(&[("title", query.title.trim()), ("author", query.author.trim())]),
+        };
+        let search: Value = request.send().await?.error_for_status()?.json().await?;
+        let Some(doc) = search["docs"].get(0) else { return Ok(None) };
+
+        let key = doc["key"].as_str().unwrap_or_default().to_string();
+        let authors: Vec<&str> = doc["author_name"].as_array()
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let isbn = query.isbn.clone()
+            .or_else(|| doc["isbn"].get(0).and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_default();
+
+        // 简介和封面取不到时不影响书名和作者
+        let description = if key.is_empty() {
+            String::new()
+        } else {
+            Self::fetch_description(&client, &key).await.unwrap_or_else(|e| {
+                warn!("[OpenLibrary] 获取简介失败 {}: {}", key, e);
+                String::new()
+            })
+        };
+        let cover = match doc["cover_i"].as_i64() {
+            Some(cover_id) => Self::fetch_cover(&client, cover_id).await.map_err(|e| warn!("[OpenLibrary] 获取封面失败 {}: {}", cover_id, e)).ok(),
+            None => None,
+        };
+
+        Ok(Some(OnlineMetadata {
+            key,
+            title: doc["title"].as_str().unwrap_or_default().to_string(),
+            author: authors.join(", "),
+            description,
+            isbn,
+            cover,
+        }))
+    }
+
+    /// 作品的 description 可能是字符串，也可能是 `{ "type": ..., "value": ... }`
+    async fn fetch_description(client: &reqwest::Client, key: &str) -> Result<String> {
+        let work: Value = client.get(format!("{}{}.json", WORK_URL, key)).send().await?.error_for_status()?.json().await?;
+        let description = match &work["description"] {
+            Value::String(text) => text.as_str(),
+            other => other["value"].as_str().unwrap_or_default(),
+        };
+        Ok(description.trim().to_string())
+    }
+
+    async fn fetch_cover(client: &reqwest::Client, cover_id: i64) -> Result<Vec<u8>> {
+        let response = client.get(format!("{}/{}-L.jpg", COVER_URL, cover_id)).send().await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// 从文件名等文字中找出 ISBN，去掉连字符并校验；找不到时返回 None
+pub fn find_isbn(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '-' || c == 'X' || c == 'x'))
+        .map(|run| run.chars().filter(|c| *c != '-').collect::<String>().to_ascii_uppercase())
+        .find(|candidate| is_valid_isbn(candidate))
+}
+
+fn is_valid_isbn(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().map(|c| c.to_digit(10).unwrap_or(10)).collect();
+    match digits.len() {
+        13 => {
+            !candidate.contains('X')
+                && (candidate.starts_with("978") || candidate.starts_with("979"))
+                && digits.iter().enumerate().map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 }).sum::<u32>() % 10 == 0
+        }
+        // ISBN-10 只有校验位可以是 X
+        10 => {
+            !candidate[..9].contains('X')
+                && digits.iter().enumerate().map(|(i, d)| d * (10 - i as u32)).sum::<u32>() % 11 == 0
+        }
+        _ => false,
+    }
+}
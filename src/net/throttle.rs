@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+use crate::settings::AppSettings;
+
+/// 后台下载限速：按开始以来的平均速度控制，超出时返回需要等待的时间
+pub struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+    transferred: u64,
+}
+
+impl Throttle {
+    /// 0 表示不限速
+    pub fn new(limit_kbps: u32) -> Self {
+        Self { bytes_per_sec: limit_kbps as u64 * 1024, started: Instant::now(), transferred: 0 }
+    }
+
+    pub fn from_settings() -> Self {
+        Self::new(AppSettings::get().download_limit_kbps)
+    }
+
+    /// 记录刚传输的字节数
+    pub fn delay_for(&mut self, bytes: u64) -> Option<Duration> {
+        if self.bytes_per_sec == 0 {
+            return None;
+        }
+        self.transferred += bytes;
+        let expected = Duration::from_secs_f64(self.transferred as f64 / self.bytes_per_sec as f64);
+        expected.checked_sub(self.started.elapsed()).filter(|delay| !delay.is_zero())
+    }
+
+    /// 暂停恢复后重新计时，暂停的时间不能用来突发传输
+    pub fn restart(&mut self) {
+        self.started = Instant::now();
+        self.transferred = 0;
+    }
+}
@@ -0,0 +1,466 @@
+//! 页面布局计算：只依赖页面尺寸和视图参数，不涉及缓存和解码，便于单独测试
+
+use crate::decoder::{PageInfo, PageSpread, Rect};
+
+/// 滚动方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Vertical,
+    Horizontal,
+}
+
+/// 参与布局的页面：原始尺寸（已按切边取值）和双页排列方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutPage {
+    pub width: f32,
+    pub height: f32,
+    pub spread: PageSpread,
+}
+
+impl LayoutPage {
+    pub fn from_info(info: &PageInfo, use_crop: bool) -> Self {
+        Self {
+            width: info.get_width(use_crop),
+            height: info.get_height(use_crop),
+            spread: info.spread,
+        }
+    }
+}
+
+/// 布局参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutParams {
+    pub orientation: Orientation,
+    pub dual_page: bool,
+    /// 视口尺寸
+    pub view_width: f32,
+    pub view_height: f32,
+    pub zoom: f32,
+    /// 沿滚动方向的页间距，双页时也是两栏之间的间距
+    pub gap: f32,
+    /// 从右向左排列：双页时第一页在右栏，横向滚动时第一页在最右边
+    pub rtl: bool,
+    /// 单页翻页：每页独占一屏，缩放后整页放进视口并居中，此时双页和页间距不生效
+    pub paged: bool,
+}
+
+/// 单页的布局结果
+#[derive(Debug, Clone, Copy)]
+pub struct PageSlot {
+    /// 文档坐标
+    pub bounds: Rect,
+    /// 原始尺寸到显示尺寸的比例
+    pub scale: f32,
+}
+
+/// 整份文档的布局结果
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub slots: Vec<PageSlot>,
+    pub total_width: f32,
+    pub total_height: f32,
+}
+
+/// 计算所有页面的位置；视口为空时返回空布局
+pub fn compute(pages: &[LayoutPage], params: &LayoutParams) -> Layout {
+    if params.view_width <= 0.0 || params.view_height <= 0.0 {
+        return Layout { slots: Vec::new(), total_width: 0.0, total_height: 0.0 };
+    }
+    if params.paged {
+        return paged(pages, params);
+    }
+    match params.orientation {
+        Orientation::Vertical if params.dual_page => vertical_dual(pages, params),
+        Orientation::Vertical => vertical(pages, params),
+        Orientation::Horizontal => horizontal(pages, params),
+    }
+}
+
+/// 按目标边长缩放；尺寸无效的页面缩成 0
+fn scale_to(target: f32, size: f32) -> f32 {
+    if size > 0.0 { target / size } else { 0.0 }
+}
+
+/// 垂直布局：每页占满缩放后的宽度，从上到下排列
+fn vertical(pages: &[LayoutPage], params: &LayoutParams) -> Layout {
+    let scaled_width = params.view_width * params.zoom;
+    let mut slots = Vec::with_capacity(pages.len());
+    let mut current_y = 0.0;
+
+    for (i, page) in pages.iter().enumerate() {
+        if i > 0 {
+            current_y += params.gap;
+        }
+        let scale = scale_to(scaled_width, page.width);
+        let scaled_height = page.height * scale;
+        slots.push(PageSlot {
+            bounds: Rect::new(0.0, current_y, scaled_width, current_y + scaled_height),
+            scale,
+        });
+        current_y += scaled_height;
+    }
+
+    Layout { slots, total_width: scaled_width, total_height: current_y }
+}
+
+/// 双页垂直布局：每行两页，各占一半宽度，行高取两页中较高的
+/// 封面单独一行居中，跨页单独一行占满两栏（来自 ComicInfo.xml）
+fn vertical_dual(pages: &[LayoutPage], params: &LayoutParams) -> Layout {
+    let scaled_width = params.view_width * params.zoom;
+    let column_width = ((scaled_width - params.gap) / 2.0).max(0.0);
+    let mut slots = Vec::with_capacity(pages.len());
+    let mut current_y = 0.0;
+
+    let mut index = 0;
+    while index < pages.len() {
+        if index > 0 {
+            current_y += params.gap;
+        }
+        let spread = pages[index].spread;
+        let pairs_with_next = spread == PageSpread::Normal
+            && pages.get(index + 1).is_some_and(|next| next.spread == PageSpread::Normal);
+        let row_len = if pairs_with_next { 2 } else { 1 };
+
+        let mut row_height: f32 = 0.0;
+        for (column, page) in pages[index..index + row_len].iter().enumerate() {
+            let column = if params.rtl { 1 - column } else { column };
+            let (left, width) = match spread {
+                PageSpread::Double => (0.0, scaled_width),
+                PageSpread::Alone => ((scaled_width - column_width) / 2.0, column_width),
+                PageSpread::Normal => (column as f32 * (column_width + params.gap), column_width),
+            };
+            let scale = scale_to(width, page.width);
+            let scaled_height = page.height * scale;
+            slots.push(PageSlot {
+                bounds: Rect::new(left, current_y, left + width, current_y + scaled_height),
+                scale,
+            });
+            row_height = row_height.max(scaled_height);
+        }
+        current_y += row_height;
+        index += row_len;
+    }
+
+    Layout { slots, total_width: scaled_width, total_height: current_y }
+}
+
+/// 水平布局：每页占满缩放后的高度，从左到右（RTL 时从右到左）排列
+fn horizontal(pages: &[LayoutPage], params: &LayoutParams) -> Layout {
+    let scaled_height = params.view_height * params.zoom;
+    let mut slots = Vec::with_capacity(pages.len());
+    let mut current_x = 0.0;
+
+    for (i, page) in pages.iter().enumerate() {
+        if i > 0 {
+            current_x += params.gap;
+        }
+        let scale = scale_to(scaled_height, page.height);
+        let scaled_width = page.width * scale;
+        slots.push(PageSlot {
+            bounds: Rect::new(current_x, 0.0, current_x + scaled_width, scaled_height),
+            scale,
+        });
+        current_x += scaled_width;
+    }
+
+    if params.rtl {
+        for slot in &mut slots {
+            let (left, right) = (current_x - slot.bounds.right, current_x - slot.bounds.left);
+            slot.bounds.left = left;
+            slot.bounds.right = right;
+        }
+    }
+
+    Layout { slots, total_width: current_x, total_height: scaled_height }
+}
+
+/// 单页翻页布局：每页占一个视口大小（乘以缩放）的格子，格子沿滚动方向排列，
+/// 页面按比例放进格子并居中，翻页时偏移量正好是整数个格子
+fn paged(pages: &[LayoutPage], params: &LayoutParams) -> Layout {
+    let (cell_width, cell_height) = (params.view_width * params.zoom, params.view_height * params.zoom);
+    let mut slots = Vec::with_capacity(pages.len());
+
+    for (i, page) in pages.iter().enumerate() {
+        let scale = scale_to(cell_width, page.width).min(scale_to(cell_height, page.height));
+        let (width, height) = (page.width * scale, page.height * scale);
+        let (cell_left, cell_top) = match params.orientation {
+            Orientation::Vertical => (0.0, i as f32 * cell_height),
+            Orientation::Horizontal if params.rtl => ((pages.len() - 1 - i) as f32 * cell_width, 0.0),
+            Orientation::Horizontal => (i as f32 * cell_width, 0.0),
+        };
+        let left = cell_left + (cell_width - width) / 2.0;
+        let top = cell_top + (cell_height - height) / 2.0;
+        slots.push(PageSlot { bounds: Rect::new(left, top, left + width, top + height), scale });
+    }
+
+    let count = pages.len() as f32;
+    match params.orientation {
+        Orientation::Vertical => Layout { slots, total_width: cell_width, total_height: cell_height * count },
+        Orientation::Horizontal => Layout { slots, total_width: cell_width * count, total_height: cell_height },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn page(width: f32, height: f32) -> LayoutPage {
+        LayoutPage { width, height, spread: PageSpread::Normal }
+    }
+
+    fn params(orientation: Orientation) -> LayoutParams {
+        LayoutParams {
+            orientation,
+            dual_page: false,
+            view_width: 800.0,
+            view_height: 600.0,
+            zoom: 1.0,
+            gap: 0.0,
+            rtl: false,
+            paged: false,
+        }
+    }
+
+    fn dual() -> LayoutParams {
+        LayoutParams { dual_page: true, ..params(Orientation::Vertical) }
+    }
+
+    fn assert_rect(rect: &Rect, left: f32, top: f32, right: f32, bottom: f32) {
+        let actual = [rect.left, rect.top, rect.right, rect.bottom];
+        let expected = [left, top, right, bottom];
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn empty_viewport_gives_empty_layout() {
+        let layout = compute(&[page(100.0, 200.0)], &LayoutParams { view_width: 0.0, ..params(Orientation::Vertical) });
+        assert!(layout.slots.is_empty());
+        assert_eq!(layout.total_height, 0.0);
+    }
+
+    #[test]
+    fn vertical_mixed_sizes_fill_width() {
+        let pages = [page(400.0, 600.0), page(800.0, 400.0), page(200.0, 200.0)];
+        let layout = compute(&pages, &params(Orientation::Vertical));
+
+        assert_rect(&layout.slots[0].bounds, 0.0, 0.0, 800.0, 1200.0);
+        assert_rect(&layout.slots[1].bounds, 0.0, 1200.0, 800.0, 1600.0);
+        assert_rect(&layout.slots[2].bounds, 0.0, 1600.0, 800.0, 2400.0);
+        assert_eq!(layout.slots[0].scale, 2.0);
+        assert_eq!(layout.slots[1].scale, 1.0);
+        assert_eq!(layout.total_width, 800.0);
+        assert_eq!(layout.total_height, 2400.0);
+    }
+
+    #[test]
+    fn vertical_zoom_scales_width() {
+        let layout = compute(&[page(400.0, 400.0)], &LayoutParams { zoom: 1.5, ..params(Orientation::Vertical) });
+        assert_rect(&layout.slots[0].bounds, 0.0, 0.0, 1200.0, 1200.0);
+        assert_eq!(layout.slots[0].scale, 3.0);
+    }
+
+    #[test]
+    fn vertical_gap_between_pages_only() {
+        let pages = [page(800.0, 100.0), page(800.0, 100.0)];
+        let layout = compute(&pages, &LayoutParams { gap: 10.0, ..params(Orientation::Vertical) });
+        assert_rect(&layout.slots[1].bounds, 0.0, 110.0, 800.0, 210.0);
+        assert_eq!(layout.total_height, 210.0);
+    }
+
+    #[test]
+    fn crop_bounds_change_page_size() {
+        let mut info = PageInfo::new(0, 600.0, 800.0);
+        info.crop_bounds = Some(Rect::new(50.0, 50.0, 450.0, 650.0));
+
+        let cropped = compute(&[LayoutPage::from_info(&info, true)], &params(Orientation::Vertical));
+        assert_rect(&cropped.slots[0].bounds, 0.0, 0.0, 800.0, 1200.0);
+        assert_eq!(cropped.slots[0].scale, 2.0);
+
+        let full = compute(&[LayoutPage::from_info(&info, false)], &params(Orientation::Vertical));
+        assert!((full.total_height - 800.0 * 800.0 / 600.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn zero_sized_page_collapses() {
+        let layout = compute(&[page(0.0, 300.0), page(400.0, 400.0)], &params(Orientation::Vertical));
+        assert_eq!(layout.slots[0].scale, 0.0);
+        assert_rect(&layout.slots[1].bounds, 0.0, 0.0, 800.0, 800.0);
+    }
+
+    #[test]
+    fn dual_pairs_pages_and_uses_tallest_row() {
+        let pages = [page(400.0, 600.0), page(400.0, 400.0), page(400.0, 200.0)];
+        let layout = compute(&pages, &dual());
+
+        assert_rect(&layout.slots[0].bounds, 0.0, 0.0, 400.0, 600.0);
+        assert_rect(&layout.slots[1].bounds, 400.0, 0.0, 800.0, 400.0);
+        // 最后一页落单，仍在左栏
+        assert_rect(&layout.slots[2].bounds, 0.0, 600.0, 400.0, 800.0);
+        assert_eq!(layout.total_height, 800.0);
+    }
+
+    #[test]
+    fn dual_cover_and_spread_take_own_rows() {
+        let pages = [
+            LayoutPage { spread: PageSpread::Alone, ..page(400.0, 600.0) },
+            page(400.0, 600.0),
+            LayoutPage { spread: PageSpread::Double, ..page(800.0, 600.0) },
+            page(400.0, 600.0),
+        ];
+        let layout = compute(&pages, &dual());
+
+        assert_rect(&layout.slots[0].bounds, 200.0, 0.0, 600.0, 600.0);
+        // 封面后面的页和跨页不能配对
+        assert_rect(&layout.slots[1].bounds, 0.0, 600.0, 400.0, 1200.0);
+        assert_rect(&layout.slots[2].bounds, 0.0, 1200.0, 800.0, 1800.0);
+        assert_rect(&layout.slots[3].bounds, 0.0, 1800.0, 400.0, 2400.0);
+    }
+
+    #[test]
+    fn dual_gap_between_columns_and_rows() {
+        let pages = [page(390.0, 390.0), page(390.0, 390.0), page(390.0, 390.0)];
+        let layout = compute(&pages, &LayoutParams { gap: 20.0, ..dual() });
+
+        assert_rect(&layout.slots[0].bounds, 0.0, 0.0, 390.0, 390.0);
+        assert_rect(&layout.slots[1].bounds, 410.0, 0.0, 800.0, 390.0);
+        assert_rect(&layout.slots[2].bounds, 0.0, 410.0, 390.0, 800.0);
+    }
+
+    #[test]
+    fn dual_rtl_puts_first_page_on_the_right() {
+        let pages = [page(400.0, 600.0), page(400.0, 600.0)];
+        let layout = compute(&pages, &LayoutParams { rtl: true, ..dual() });
+
+        assert_rect(&layout.slots[0].bounds, 400.0, 0.0, 800.0, 600.0);
+        assert_rect(&layout.slots[1].bounds, 0.0, 0.0, 400.0, 600.0);
+    }
+
+    #[test]
+    fn horizontal_fills_height() {
+        let pages = [page(300.0, 600.0), page(600.0, 300.0)];
+        let layout = compute(&pages, &params(Orientation::Horizontal));
+
+        assert_rect(&layout.slots[0].bounds, 0.0, 0.0, 300.0, 600.0);
+        assert_rect(&layout.slots[1].bounds, 300.0, 0.0, 1500.0, 600.0);
+        assert_eq!(layout.total_width, 1500.0);
+        assert_eq!(layout.total_height, 600.0);
+    }
+
+    #[test]
+    fn horizontal_rtl_mirrors_positions() {
+        let pages = [page(300.0, 600.0), page(600.0, 300.0)];
+        let layout = compute(&pages, &LayoutParams { rtl: true, gap: 10.0, ..params(Orientation::Horizontal) });
+
+        assert_rect(&layout.slots[0].bounds, 1210.0, 0.0, 1510.0, 600.0);
+        assert_rect(&layout.slots[1].bounds, 0.0, 0.0, 1200.0, 600.0);
+        assert_eq!(layout.total_width, 1510.0);
+    }
+
+    #[test]
+    fn paged_centers_each_page_in_its_own_screen() {
+        let pages = [page(400.0, 600.0), page(800.0, 200.0)];
+        let layout = compute(&pages, &LayoutParams { paged: true, dual_page: true, gap: 10.0, ..params(Orientation::Vertical) });
+
+        // 竖页按高度放满，横页按宽度放满，都在各自的 800x600 格子里居中
+        assert_rect(&layout.slots[0].bounds, 200.0, 0.0, 600.0, 600.0);
+        assert_rect(&layout.slots[1].bounds, 0.0, 800.0, 800.0, 1000.0);
+        assert_eq!(layout.slots[1].scale, 1.0);
+        assert_eq!(layout.total_width, 800.0);
+        assert_eq!(layout.total_height, 1200.0);
+
+        let layout = compute(&pages, &LayoutParams { paged: true, zoom: 2.0, ..params(Orientation::Horizontal) });
+        assert_rect(&layout.slots[0].bounds, 400.0, 0.0, 1200.0, 1200.0);
+        assert_rect(&layout.slots[1].bounds, 1600.0, 400.0, 3200.0, 800.0);
+        assert_eq!(layout.total_width, 3200.0);
+    }
+
+    fn layout_page() -> impl Strategy<Value = LayoutPage> {
+        (1.0f32..2000.0, 1.0f32..2000.0, prop_oneof![
+            6 => Just(PageSpread::Normal),
+            1 => Just(PageSpread::Alone),
+            1 => Just(PageSpread::Double),
+        ])
+            .prop_map(|(width, height, spread)| LayoutPage { width, height, spread })
+    }
+
+    fn layout_params() -> impl Strategy<Value = LayoutParams> {
+        (
+            prop_oneof![Just(Orientation::Vertical), Just(Orientation::Horizontal)],
+            any::<bool>(),
+            100.0f32..4000.0,
+            100.0f32..4000.0,
+            0.5f32..5.0,
+            0.0f32..40.0,
+            any::<bool>(),
+            any::<bool>(),
+        )
+            .prop_map(|(orientation, dual_page, view_width, view_height, zoom, gap, rtl, paged)| LayoutParams {
+                orientation,
+                dual_page,
+                view_width,
+                view_height,
+                zoom,
+                gap,
+                rtl,
+                paged,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn every_page_fits_inside_the_document(pages in prop::collection::vec(layout_page(), 0..40), params in layout_params()) {
+            let layout = compute(&pages, &params);
+            prop_assert_eq!(layout.slots.len(), pages.len());
+            let eps = 0.01 * (1.0 + layout.total_width.max(layout.total_height));
+            for slot in &layout.slots {
+                let b = slot.bounds;
+                prop_assert!(b.left >= -eps && b.top >= -eps);
+                prop_assert!(b.right <= layout.total_width + eps && b.bottom <= layout.total_height + eps);
+                prop_assert!(b.width() >= 0.0 && b.height() >= 0.0);
+            }
+        }
+
+        #[test]
+        fn pages_keep_their_aspect_ratio(pages in prop::collection::vec(layout_page(), 1..40), params in layout_params()) {
+            let layout = compute(&pages, &params);
+            // 坐标很大时 right - left 有舍入误差
+            let rounding = 1e-5 * layout.total_width.max(layout.total_height);
+            for (page, slot) in pages.iter().zip(&layout.slots) {
+                let b = slot.bounds;
+                prop_assert!((b.width() - page.width * slot.scale).abs() <= rounding + 1e-3 * b.width().max(1.0));
+                prop_assert!((b.height() - page.height * slot.scale).abs() <= rounding + 1e-3 * b.height().max(1.0));
+            }
+        }
+
+        #[test]
+        fn pages_advance_along_the_scroll_axis(pages in prop::collection::vec(layout_page(), 2..40), params in layout_params()) {
+            let layout = compute(&pages, &params);
+            for pair in layout.slots.windows(2) {
+                let (a, b) = (pair[0].bounds, pair[1].bounds);
+                match (params.orientation, params.rtl) {
+                    // 双页同一行的两页 top 相同
+                    (Orientation::Vertical, _) => prop_assert!(b.top >= a.top - 1e-3),
+                    (Orientation::Horizontal, false) => prop_assert!(b.left >= a.right - 1e-3),
+                    (Orientation::Horizontal, true) => prop_assert!(b.right <= a.left + 1e-3),
+                }
+            }
+        }
+
+        #[test]
+        fn pages_do_not_overlap(pages in prop::collection::vec(layout_page(), 2..40), params in layout_params()) {
+            let layout = compute(&pages, &params);
+            let eps = 1e-2;
+            for (i, a) in layout.slots.iter().enumerate() {
+                for b in &layout.slots[i + 1..] {
+                    let (a, b) = (a.bounds, b.bounds);
+                    let overlap_x = a.left.max(b.left) < a.right.min(b.right) - eps;
+                    let overlap_y = a.top.max(b.top) < a.bottom.min(b.bottom) - eps;
+                    prop_assert!(!(overlap_x && overlap_y), "{:?} overlaps {:?}", a, b);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,17 @@
+pub mod layout;
 pub mod page;
 pub mod page_node;
+pub mod search_results;
+#[cfg(feature = "gui")]
 pub mod view_state;
+pub mod visible_range;
+pub mod waypoints;
 
 pub use page::Page;
 pub use page_node::PageNode;
-pub use view_state::{Orientation, PageViewState};
+pub use search_results::SearchResults;
+pub use layout::Orientation;
+#[cfg(feature = "gui")]
+pub use view_state::{LayoutAnchor, PageViewState};
+pub use visible_range::VisibleRange;
+pub use waypoints::Waypoints;
@@ -60,6 +60,11 @@ impl Page {
         self.bounds.top
     }
 
+    /// 各节点渲染图像占用的字节数
+    pub fn memory_bytes(&self) -> usize {
+        self.nodes.iter().map(|node| node.memory_bytes()).sum()
+    }
+
     /// 回收所有节点资源
     pub fn recycle(&mut self) {
         for node in &mut self.nodes {
@@ -58,6 +58,11 @@ impl PageNode {
         self.bitmap.is_none() && !self.is_decoding
     }
 
+    /// 渲染图像占用的字节数
+    pub fn memory_bytes(&self) -> usize {
+        self.bitmap.as_ref().map_or(0, |bitmap| bitmap.as_bytes().len())
+    }
+
     /// 回收资源
     pub fn recycle(&mut self) {
         self.bitmap = None;
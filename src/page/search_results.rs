@@ -0,0 +1,73 @@
+use crate::decoder::{PageMatches, Rect};
+
+/// 当前文档的搜索结果，按页码和页内位置排序，逐个浏览
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub query: String,
+    /// (页码, 页面坐标范围)
+    hits: Vec<(usize, Rect)>,
+    current: Option<usize>,
+}
+
+impl SearchResults {
+    /// 设置新的结果，从 from_page 开始的第一个匹配作为当前项
+    pub fn set(&mut self, query: String, matches: Vec<PageMatches>, from_page: usize) {
+        self.query = query;
+        self.hits = matches.into_iter()
+            .flat_map(|page| {
+                let mut rects = page.rects;
+                rects.sort_by(|a, b| a.top.total_cmp(&b.top).then(a.left.total_cmp(&b.left)));
+                rects.into_iter().map(move |rect| (page.page_index, rect))
+            })
+            .collect();
+        self.current = if self.hits.is_empty() {
+            None
+        } else {
+            Some(self.hits.iter().position(|(page, _)| *page >= from_page).unwrap_or(0))
+        };
+    }
+
+    /// 前后移动 delta 个匹配，首尾循环
+    pub fn step(&mut self, delta: i32) -> Option<(usize, Rect)> {
+        let len = self.hits.len() as i64;
+        if len == 0 {
+            return None;
+        }
+        let current = self.current.map_or(0, |i| (i as i64 + delta as i64).rem_euclid(len)) as usize;
+        self.current = Some(current);
+        self.hits.get(current).copied()
+    }
+
+    pub fn current(&self) -> Option<(usize, Rect)> {
+        self.current.and_then(|i| self.hits.get(i).copied())
+    }
+
+    /// 当前项的序号（从 1 开始），没有结果时为 0
+    pub fn position(&self) -> usize {
+        self.current.map_or(0, |i| i + 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    /// 某页上的匹配和它是否为当前项
+    pub fn on_page(&self, page_index: usize) -> impl Iterator<Item = (Rect, bool)> + '_ {
+        let start = self.hits.partition_point(|(page, _)| *page < page_index);
+        self.hits[start..]
+            .iter()
+            .enumerate()
+            .take_while(move |(_, (page, _))| *page == page_index)
+            .map(move |(i, (_, rect))| (*rect, self.current == Some(start + i)))
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.hits.clear();
+        self.current = None;
+    }
+}
@@ -1,22 +1,24 @@
-use log::{debug, info};
+use log::{debug, info, warn};
 
-use super::Page;
+use super::layout::{self, LayoutPage, LayoutParams};
+use super::{Orientation, Page, SearchResults, VisibleRange, Waypoints};
 use crate::cache::PageCache;
 use crate::decoder::decode_service::{Priority, RenderPage, VisibilityChecker};
 use crate::decoder::pdf::utils::{generate_thumbnail_key};
-use crate::decoder::{DecodeService, Link, Rect};
+use crate::decoder::{CropMargins, DecodeService, Link, Rect};
 use crate::entity::OutlineItem;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
-/// 滚动方向
+/// 重新布局时保持不动的点：视口中心下的页面和页内相对位置（0~1）
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Orientation {
-    Vertical,
-    Horizontal,
+pub struct LayoutAnchor {
+    pub page: usize,
+    pub x_ratio: f32,
+    pub y_ratio: f32,
 }
 
 /// 页面视图状态管理
@@ -42,6 +44,24 @@ pub struct PageViewState {
     /// 是否启用切边
     pub crop: i32,
 
+    /// 手动切边的页边距，None 表示使用自动切边
+    pub crop_margins: Option<CropMargins>,
+
+    /// 解码器给出的切边范围，清除手动切边时恢复
+    auto_crop_bounds: Vec<Option<Rect>>,
+
+    /// 双页并排显示（仅垂直滚动）
+    pub dual_page: bool,
+
+    /// 单页翻页模式：一次一整页，不连续滚动
+    pub paged: bool,
+
+    /// 从右往左阅读（漫画）：页面横向排列，第一页在最右边
+    pub rtl: bool,
+
+    /// 夜间模式，解码结果反色显示
+    pub night_mode: bool,
+
     /// 文档总宽度
     pub total_width: f32,
 
@@ -54,21 +74,54 @@ pub struct PageViewState {
     /// 预加载距离（屏幕数）
     pub preload_screens: f32,
 
-    /// 当前可见页面索引列表
-    pub visible_pages: Vec<usize>,
+    /// 当前可见页面范围
+    pub visible_range: VisibleRange,
 
     /// 页面链接缓存，以页码为键存储链接列表
     pub page_links: Rc<RefCell<HashMap<usize, Vec<Link>>>>,
 
     pub outline_items: Vec<OutlineItem>,
+    /// 大纲的过滤词，为空时显示完整大纲
+    pub outline_filter: String,
+    /// 大跳转前自动记下的位置
+    pub waypoints: Waypoints,
+    /// 全文搜索结果
+    pub search: SearchResults,
+
+    /// 每页的页码标签（如 "xiv"），文档没有定义或与页序一致时为空
+    pub page_labels: Vec<String>,
 
     /// 可见区域（用于跨线程可见性检查）
     visible_rect: Arc<Mutex<Rect>>,
 
     /// 页面bounds映射（用于跨线程可见性检查）
     page_bounds_map: Arc<Mutex<HashMap<usize, Rect>>>,
+
+    /// 页面图像内存上限（字节），0 表示不限制
+    pub memory_ceiling: usize,
+
+    /// 是否处于内存压力状态，此时屏幕外页面降低精度渲染
+    memory_pressure: bool,
+
+    /// 以降低精度渲染的页面，压力解除或进入屏幕后重新渲染
+    downscaled_pages: HashSet<usize>,
+
+    /// 渲染代数，缩放、切边等使已提交的渲染任务失效时递增
+    render_generation: u64,
+
+    /// 文档序号，每次 reset 递增，写进缓存键，旧文档迟到的渲染结果不会被当成新文档的页面
+    document_id: u64,
+
+    /// 指针在视口中的位置，离开页面时为 None
+    cursor: Option<(f32, f32)>,
 }
 
+/// 内存压力下屏幕外页面的渲染比例
+const PRESSURE_RENDER_SCALE: f32 = 0.5;
+
+/// 内存降到上限的该比例以下时恢复全精度
+const PRESSURE_RELEASE_RATIO: f32 = 0.6;
+
 impl PageViewState {
     pub fn new(orientation: Orientation, crop_int: i32) -> Self {
         Self {
@@ -79,44 +132,213 @@ impl PageViewState {
             view_offset: (0.0, 0.0),
             zoom: 1.0,
             crop: crop_int,
+            crop_margins: None,
+            auto_crop_bounds: Vec::new(),
+            dual_page: false,
+            paged: false,
+            rtl: false,
+            night_mode: false,
             total_width: 0.0,
             total_height: 0.0,
             view_size: (0.0, 0.0),
             preload_screens: 1.0,
-            visible_pages: Vec::new(),
+            visible_range: VisibleRange::EMPTY,
             page_links: Rc::new(RefCell::new(HashMap::new())),
             outline_items: Vec::new(),
+            outline_filter: String::new(),
+            waypoints: Waypoints::default(),
+            search: SearchResults::default(),
+            page_labels: Vec::new(),
             visible_rect: Arc::new(Mutex::new(Rect::new(0.0, 0.0, 0.0, 0.0))),
             page_bounds_map: Arc::new(Mutex::new(HashMap::new())),
+            memory_ceiling: crate::settings::AppSettings::get().memory.ceiling_mb * 1024 * 1024,
+            memory_pressure: false,
+            downscaled_pages: HashSet::new(),
+            render_generation: 0,
+            document_id: 0,
+            cursor: None,
         }
     }
 
     /// 打开文档
-    pub fn open_document<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
+    pub fn open_document<P: AsRef<Path>>(&mut self, path: P) -> crate::error::Result<()> {
         Self::reset(self);
         self.decode_service.load_pdf(path)?;
         Ok(())
     }
 
     pub fn set_pages_from_info(&mut self, pages_info: Vec<crate::decoder::PageInfo>) {
+        self.crop_margins = None;
+        self.auto_crop_bounds = pages_info.iter().map(|info| info.crop_bounds).collect();
         let pages: Vec<Page> = pages_info
             .into_iter()
             .map(|info| Page::new(info, 0.0, 0.0, 0.0, 0.0))
             .collect();
         self.pages = pages;
+        self.waypoints.clear();
+        self.search.clear();
 
         self.outline_items = self.decode_service.get_outline().unwrap_or_default();
+        self.outline_filter.clear();
+        let labels = self.decode_service.get_page_labels().unwrap_or_default();
+        // 标签和物理页码完全一致时不需要单独显示
+        let trivial = labels.iter().enumerate().all(|(i, label)| *label == (i + 1).to_string());
+        self.page_labels = if trivial { Vec::new() } else { labels };
+        self.resolve_outline_pages(0..self.outline_items.len());
+    }
+
+    /// 大纲第 row 项的子节点是否已经展开
+    pub fn outline_expanded(&self, row: usize) -> bool {
+        match (self.outline_items.get(row), self.outline_items.get(row + 1)) {
+            (Some(item), Some(next)) => next.level > item.level,
+            _ => false,
+        }
+    }
+
+    /// 展开大纲第 row 项，子节点从解码线程按需获取，返回插入的项数
+    pub fn expand_outline(&mut self, row: usize) -> usize {
+        let Some(item) = self.outline_items.get(row) else { return 0 };
+        if !item.has_children || self.outline_expanded(row) {
+            return 0;
+        }
+        let children = match self.decode_service.get_outline_children(item.id) {
+            Ok(children) => children,
+            Err(e) => {
+                warn!("[Outline] 获取子节点失败: {}", e);
+                return 0;
+            }
+        };
+        let count = children.len();
+        self.outline_items.splice(row + 1..row + 1, children);
+        self.resolve_outline_pages(row + 1..row + 1 + count);
+        count
+    }
+
+    /// 收起大纲第 row 项，移除它下面所有已加载的后代，返回移除的项数
+    pub fn collapse_outline(&mut self, row: usize) -> usize {
+        let Some(level) = self.outline_items.get(row).map(|item| item.level) else { return 0 };
+        let end = (row + 1..self.outline_items.len())
+            .find(|&i| self.outline_items[i].level <= level)
+            .unwrap_or(self.outline_items.len());
+        self.outline_items.drain(row + 1..end);
+        end - row - 1
+    }
+
+    /// 按标题过滤大纲，显示匹配的条目和它们的上级；过滤词为空时恢复完整大纲。返回显示的项数
+    pub fn set_outline_filter(&mut self, query: &str) -> usize {
+        let query = query.trim();
+        if query == self.outline_filter {
+            return self.outline_items.len();
+        }
+        self.outline_filter = query.to_string();
+        let items = if query.is_empty() {
+            self.decode_service.get_outline()
+        } else {
+            self.decode_service.filter_outline(query)
+        };
+        self.outline_items = items.unwrap_or_else(|e| {
+            warn!("[Outline] 过滤大纲失败: {}", e);
+            Vec::new()
+        });
+        self.resolve_outline_pages(0..self.outline_items.len());
+        self.outline_items.len()
+    }
+
+    /// 只显示前 depth 层大纲：先收起到顶层，再逐项展开到指定层数，返回显示的项数；会清除过滤
+    pub fn show_outline_levels(&mut self, depth: i32) -> usize {
+        self.set_outline_filter("");
+        let Some(top) = self.outline_items.iter().map(|item| item.level).min() else { return 0 };
+        self.outline_items.retain(|item| item.level == top);
+        // 展开时子节点插在后面，继续往下遍历就会逐层展开
+        let mut row = 0;
+        while row < self.outline_items.len() {
+            if self.outline_items[row].level < top + depth - 1 {
+                self.expand_outline(row);
+            }
+            row += 1;
+        }
+        self.outline_items.len()
+    }
+
+    /// 页面所在的大纲条目：已显示的条目中目标页不超过该页的最后一项，
+    /// 章节收起时落在最近的上级条目
+    pub fn outline_row_for_page(&self, page: usize) -> Option<usize> {
+        self.outline_items.iter()
+            .enumerate()
+            .filter(|(_, item)| item.page >= 0 && item.page as usize <= page)
+            .max_by_key(|(row, item)| (item.page, *row))
+            .map(|(row, _)| row)
+    }
+
+    /// 解码器没能给出目标页的大纲条目，按 uri 中的页码或页码标签解析
+    fn resolve_outline_pages(&mut self, rows: std::ops::Range<usize>) {
+        let unresolved: Vec<(usize, Option<usize>)> = self.outline_items[rows.clone()].iter()
+            .enumerate()
+            .map(|(i, item)| (rows.start + i, item))
+            .filter(|(_, item)| item.page < 0)
+            .map(|(i, item)| (i, item.uri.as_deref().and_then(|uri| self.resolve_page_ref(uri))))
+            .collect();
+        for (i, page) in unresolved {
+            self.outline_items[i].page = page.map_or(-1, |page| page as i32);
+        }
+    }
+
+    /// 页面的显示标签，没有定义时为空
+    pub fn page_label(&self, index: usize) -> &str {
+        self.page_labels.get(index).map(String::as_str).unwrap_or("")
+    }
+
+    /// 按标签查找页面（不区分大小写），同名标签取第一个
+    pub fn find_page_by_label(&self, label: &str) -> Option<usize> {
+        let label = label.trim();
+        if label.is_empty() {
+            return None;
+        }
+        self.page_labels.iter().position(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    /// 解析页面引用，链接、大纲和跳转对话框共用，返回从 0 开始的页面索引
+    /// - `#page=xiv&zoom=100`、`#12`：PDF 打开参数，数字是物理页码，其他按页码标签查找
+    /// - 用户输入 `xiv`、`12`：先匹配页码标签，再当作物理页码
+    pub fn resolve_page_ref(&self, reference: &str) -> Option<usize> {
+        let reference = reference.trim();
+        let (value, physical_first) = match reference.rsplit_once('#') {
+            Some((_, fragment)) => {
+                let first = fragment.split('&').next().unwrap_or_default();
+                (first.strip_prefix("page=").unwrap_or(first), true)
+            }
+            None => (reference, false),
+        };
+        let physical = value.parse::<usize>().ok()
+            .filter(|number| (1..=self.pages.len()).contains(number))
+            .map(|number| number - 1);
+        if physical_first {
+            physical.or_else(|| self.find_page_by_label(value))
+        } else {
+            self.find_page_by_label(value).or(physical)
+        }
     }
 
     pub fn reset(&mut self) {
         info!("reset");
+        self.cancel_pending_renders();
+        self.document_id += 1;
+        for page in &mut self.pages {
+            page.recycle();
+        }
         self.pages.clear();
         self.total_width = 0.0;
         self.total_height = 0.0;
-        self.visible_pages.clear();
+        self.visible_range = VisibleRange::EMPTY;
         self.cache.clear();
         self.page_links.borrow_mut().clear();
         self.outline_items.clear();
+        self.outline_filter.clear();
+        self.waypoints.clear();
+        self.search.clear();
+        self.page_labels.clear();
+        self.memory_pressure = false;
+        self.downscaled_pages.clear();
     }
 
     /// 更新视图尺寸和缩放
@@ -134,6 +356,11 @@ impl PageViewState {
 
         self.view_size = (width, height);
         self.zoom = zoom;
+        if zoom_changed {
+            self.cancel_pending_renders();
+            // 旧缩放下的图像不会再用到，只保留仍在屏幕上的页面，避免整份文档的图像滞留
+            self.release_hidden_pages();
+        }
         info!(
             "update_view_size. w-h:{:?}-{:?}, zoom:{:?}, view:{:?}-{:?}",
             width, height, zoom, self.view_size.0, self.view_size.1
@@ -157,71 +384,34 @@ impl PageViewState {
             return;
         }
 
-        match self.orientation {
-            Orientation::Vertical => self.layout_vertical(),
-            Orientation::Horizontal => self.layout_horizontal(),
-        }
-    }
-
-    /// 垂直布局
-    fn layout_vertical(&mut self) {
-        let view_width = self.view_size.0;
-        let scaled_width = view_width * self.zoom;
-        let mut current_y = 0.0;
-
-        for page in &mut self.pages {
-            let page_width = page.info.get_width(self.crop == 1);
-            let page_height = page.info.get_height(self.crop == 1);
-
-            // 计算缩放比例
-            let scale = scaled_width / page_width;
-            let scaled_height = page_height * scale;
-
-            // 更新页面
-            let bounds = Rect::new(0.0, current_y, scaled_width, current_y + scaled_height);
-            page.update(scaled_width, scaled_height, bounds);
-            page.info.scale = scale;
-
-            current_y += scaled_height;
+        let use_crop = self.crop == 1;
+        let pages: Vec<LayoutPage> = self.pages.iter().map(|page| LayoutPage::from_info(&page.info, use_crop)).collect();
+        let layout = layout::compute(&pages, &LayoutParams {
+            orientation: self.orientation,
+            dual_page: self.dual_page,
+            view_width: self.view_size.0,
+            view_height: self.view_size.1,
+            zoom: self.zoom,
+            gap: 0.0,
+            rtl: self.is_rtl(),
+            paged: self.paged,
+        });
+        for (page, slot) in self.pages.iter_mut().zip(layout.slots) {
+            page.update(slot.bounds.width(), slot.bounds.height(), slot.bounds);
+            page.info.scale = slot.scale;
         }
 
         debug!(
-            "layout_vertical.end:total_width:{:?}-total_height:{:?}",
-            scaled_width, current_y
+            "recalculate_layout.end:total_width:{:?}-total_height:{:?}",
+            layout.total_width, layout.total_height
         );
-        self.total_width = scaled_width;
-        self.total_height = current_y;
-    }
-
-    /// 水平布局
-    fn layout_horizontal(&mut self) {
-        let view_height = self.view_size.1;
-        let scaled_height = view_height * self.zoom;
-        let mut current_x = 0.0;
-
-        for page in &mut self.pages {
-            let page_width = page.info.get_width(self.crop == 1);
-            let page_height = page.info.get_height(self.crop == 1);
-
-            // 计算缩放比例
-            let scale = scaled_height / page_height;
-            let scaled_width = page_width * scale;
-
-            // 更新页面
-            let bounds = Rect::new(current_x, 0.0, current_x + scaled_width, scaled_height);
-            page.update(scaled_width, scaled_height, bounds);
-            page.info.scale = scale;
-
-            current_x += scaled_width;
-        }
-
-        self.total_width = current_x;
-        self.total_height = scaled_height;
+        self.total_width = layout.total_width;
+        self.total_height = layout.total_height;
     }
 
     /// 更新可见页面列表
     pub fn update_visible_pages(&mut self) {
-        self.visible_pages.clear();
+        self.visible_range = VisibleRange::EMPTY;
 
         let (offset_x, offset_y) = self.view_offset;
         let (view_width, view_height) = self.view_size;
@@ -240,6 +430,13 @@ impl PageViewState {
                 -offset_x + view_width,
                 -offset_y + view_height  + preload_distance,
             ),
+            // 从右往左阅读时向左预加载
+            Orientation::Horizontal if self.is_rtl() => Rect::new(
+                -offset_x - preload_distance,
+                -offset_y,
+                -offset_x + view_width,
+                -offset_y + view_height,
+            ),
             Orientation::Horizontal => Rect::new(
                 -offset_x,
                 -offset_y,
@@ -267,6 +464,13 @@ impl PageViewState {
         debug!("update_visible_pages: first={}, last={}, total_pages={}", 
             first, last, self.pages.len());
 
+        // 被缓存淘汰的页面同步回收节点
+        for index in self.cache.take_evicted_pages() {
+            if let Some(page) = self.pages.get_mut(index) {
+                page.recycle();
+            }
+        }
+
         let mut render_pages = Vec::new();
 
         // 创建可见性检查回调
@@ -296,22 +500,48 @@ impl PageViewState {
         });
 
         if first <= last && first < self.pages.len() {
-            for i in first..=last.min(self.pages.len() - 1) {
-                self.visible_pages.push(i);
+            let last = last.min(self.pages.len() - 1);
+            let (anchor_page, anchor_ratio) = self.anchor_at(first, last);
+            self.visible_range = VisibleRange { first, last, anchor_page, anchor_ratio };
+
+            // 正在阅读的页最先渲染，然后按离锚点的距离向外
+            let focus = self.focus_page();
+            let mut order: Vec<usize> = (first..=last).collect();
+            order.sort_by_key(|&i| (Some(i) != focus, !self.is_on_screen(i), i.abs_diff(anchor_page)));
+            for i in order {
+                let on_screen = self.is_on_screen(i);
+                // 低精度图像进入屏幕后换成全精度
+                if on_screen && self.downscaled_pages.remove(&i) {
+                    self.recycle_page(i);
+                }
 
                 let page = &self.pages[i];
-                let key = generate_thumbnail_key(page);
+                let key = generate_thumbnail_key(self.document_id, page);
                 
                 if page.width > 0.0 && page.height > 0.0 {
                     // 先检查缓存中是否已有该页面
                     if self.cache.get_thumbnail(&key).is_none() {
                         debug!("需要解码: page={}, key={}", page.info.index, key);
+
+                        let mut page_info = page.info.clone();
+                        if self.memory_pressure && !on_screen {
+                            page_info.scale *= PRESSURE_RENDER_SCALE;
+                            self.downscaled_pages.insert(i);
+                        }
                         
                         render_pages.push(RenderPage {
                             key,
-                            page_info: page.info.clone(),
+                            page_info,
                             crop: self.crop,
-                            priority: Priority::Thumbnail,
+                            // 正在阅读的页面最先，其余屏幕上的页面先于预加载的页面渲染
+                            priority: if Some(i) == focus {
+                                Priority::Focus
+                            } else if on_screen {
+                                Priority::Thumbnail
+                            } else {
+                                Priority::FullImage
+                            },
+                            generation: self.render_generation,
                             visibility_checker: Some(Arc::clone(&visibility_checker)),
                         });
                     } else {
@@ -321,7 +551,7 @@ impl PageViewState {
             }
         }
         
-        info!("update_visible_pages完成: visible_pages={:?}", self.visible_pages);
+        info!("update_visible_pages完成: visible_range={:?}", self.visible_range);
 
         // 批量提交解码任务
         if !render_pages.is_empty() {
@@ -342,6 +572,8 @@ impl PageViewState {
 
             let is_visible = match self.orientation {
                 Orientation::Vertical => page.bounds.bottom > visible_rect.top,
+                // 从右往左时页码越大越靠左
+                Orientation::Horizontal if self.is_rtl() => page.bounds.left < visible_rect.right,
                 Orientation::Horizontal => page.bounds.right > visible_rect.left,
             };
 
@@ -368,6 +600,7 @@ impl PageViewState {
 
             let is_visible = match self.orientation {
                 Orientation::Vertical => page.bounds.top < visible_rect.bottom,
+                Orientation::Horizontal if self.is_rtl() => page.bounds.right > visible_rect.left,
                 Orientation::Horizontal => page.bounds.left < visible_rect.right,
             };
 
@@ -389,18 +622,288 @@ impl PageViewState {
         }
 
         let page = &self.pages[page_index];
-        let new_offset = match self.orientation {
-            Orientation::Vertical => (self.view_offset.0, -page.bounds.top),
-            Orientation::Horizontal => (-page.bounds.left, self.view_offset.1),
+        let new_offset = if self.paged {
+            self.paged_page_offset(page_index)
+        } else {
+            match self.orientation {
+                Orientation::Vertical => (self.view_offset.0, -page.bounds.top),
+                // 从右往左时页面的右边对齐视口右边
+                Orientation::Horizontal if self.is_rtl() => (-(page.bounds.right - self.view_size.0).max(0.0), self.view_offset.1),
+                Orientation::Horizontal => (-page.bounds.left, self.view_offset.1),
+            }
         };
 
         self.view_offset = new_offset;
         Some(new_offset)
     }
 
-    /// 获取当前第一个可见页面索引
+    /// 翻页模式下每页所占格子沿滚动方向的长度
+    fn paged_cell(&self) -> f32 {
+        let extent = match self.orientation {
+            Orientation::Vertical => self.total_height,
+            Orientation::Horizontal => self.total_width,
+        };
+        extent / self.pages.len().max(1) as f32
+    }
+
+    /// 翻页模式下显示第 page_index 页时的偏移量：格子的起点对齐视口
+    fn paged_page_offset(&self, page_index: usize) -> (f32, f32) {
+        // 从右往左时第一页在最右边的格子
+        let cell_index = if self.is_rtl() { self.pages.len().saturating_sub(1 + page_index) } else { page_index };
+        self.paged_cell_offset(cell_index)
+    }
+
+    /// 翻页模式下第 cell_index 个格子对齐视口时的偏移量
+    fn paged_cell_offset(&self, cell_index: usize) -> (f32, f32) {
+        let start = -(cell_index as f32 * self.paged_cell());
+        match self.orientation {
+            Orientation::Vertical => (self.view_offset.0, start),
+            Orientation::Horizontal => (start, self.view_offset.1),
+        }
+    }
+
+    /// 翻页模式下把滚动结果换成整页翻动：离开当前页后翻到相邻的页，拖动滚动条时翻到所在的页；
+    /// 放大后页面超出视口的部分仍可在页内滚动。x、y 为滚动后的偏移，返回调整后的偏移
+    pub fn paged_offset(&self, x: f32, y: f32) -> (f32, f32) {
+        let cell = self.paged_cell();
+        if !self.paged || cell <= 0.0 {
+            return (x, y);
+        }
+        let (old, new, view) = match self.orientation {
+            Orientation::Vertical => (-self.view_offset.1, -y, self.view_size.1),
+            Orientation::Horizontal => (-self.view_offset.0, -x, self.view_size.0),
+        };
+        let last = self.pages.len().saturating_sub(1);
+        let current = ((old / cell + 0.001).floor().max(0.0) as usize).min(last);
+        let start = current as f32 * cell;
+        let end = start + (cell - view).max(0.0);
+        // 按格子计算，从右往左时格子和页码的顺序相反，但翻动的方向一致
+        let target = if new > end + 0.5 {
+            ((new / cell).floor() as usize).max(current + 1).min(last)
+        } else if new < start - 0.5 {
+            ((new / cell).floor().max(0.0) as usize).min(current.saturating_sub(1))
+        } else {
+            return (x, y);
+        };
+        self.paged_cell_offset(target)
+    }
+
+    /// 视口末端停在哪一页的页尾：页尾离视口末端不超过视口长度的 threshold 倍，
+    /// 翻页模式下整页都在视口内即可。用于自动翻页
+    pub fn page_end_in_reach(&self, threshold: f32) -> Option<usize> {
+        if self.visible_range.is_empty() {
+            return None;
+        }
+        let (start, view) = match self.orientation {
+            Orientation::Vertical => (-self.view_offset.1, self.view_size.1),
+            Orientation::Horizontal => (-self.view_offset.0, self.view_size.0),
+        };
+        let end = start + view;
+        let rtl = self.is_rtl();
+        (self.visible_range.first..=self.visible_range.last.min(self.pages.len().saturating_sub(1))).find(|&i| {
+            let bounds = &self.pages[i].bounds;
+            let (page_start, page_end) = match self.orientation {
+                Orientation::Vertical => (bounds.top, bounds.bottom),
+                Orientation::Horizontal => (bounds.left, bounds.right),
+            };
+            if self.paged {
+                page_start >= start - 0.5 && page_end <= end + 0.5
+            } else if rtl {
+                // 从右往左时页尾在左边
+                (page_start - start).abs() <= view * threshold
+            } else {
+                (page_end - end).abs() <= view * threshold
+            }
+        })
+    }
+
+    /// 切换单页翻页模式，页面尺寸变化，需要重新解码
+    pub fn set_paged(&mut self, paged: bool) {
+        if self.paged != paged {
+            self.paged = paged;
+            self.cancel_pending_renders();
+            self.recalculate_layout();
+            self.cache.clear();
+            for page in &mut self.pages {
+                page.recycle();
+            }
+            self.update_visible_pages();
+        }
+    }
+
+    /// 切换从右往左阅读：页面改为横向排列，页面尺寸变化，需要重新解码
+    pub fn set_rtl(&mut self, rtl: bool) {
+        if self.rtl != rtl {
+            self.rtl = rtl;
+            self.orientation = if rtl { Orientation::Horizontal } else { Orientation::Vertical };
+            self.cancel_pending_renders();
+            self.recalculate_layout();
+            self.cache.clear();
+            for page in &mut self.pages {
+                page.recycle();
+            }
+            self.update_visible_pages();
+        }
+    }
+
+    /// 横向排列且从右往左
+    fn is_rtl(&self) -> bool {
+        self.rtl && self.orientation == Orientation::Horizontal
+    }
+
+    /// 获取当前第一个可见页面索引（锚点页）
     pub fn get_first_visible_page(&self) -> Option<usize> {
-        self.visible_pages.first().copied()
+        self.visible_range.anchor()
+    }
+
+    /// 视口上沿所在的页和在该页内的位置
+    fn anchor_at(&self, first: usize, last: usize) -> (usize, f32) {
+        let (offset_x, offset_y) = self.view_offset;
+        if self.is_rtl() {
+            // 从右往左时看视口右边所在的页，位置从页面右边算起
+            let edge = -offset_x + self.view_size.0;
+            for i in first..=last {
+                let bounds = &self.pages[i].bounds;
+                if bounds.left < edge {
+                    let width = bounds.right - bounds.left;
+                    let ratio = if width > 0.0 { ((bounds.right - edge) / width).clamp(0.0, 1.0) } else { 0.0 };
+                    return (i, ratio);
+                }
+            }
+            return (first, 0.0);
+        }
+        for i in first..=last {
+            let bounds = &self.pages[i].bounds;
+            let (start, end, edge) = match self.orientation {
+                Orientation::Vertical => (bounds.top, bounds.bottom, -offset_y),
+                Orientation::Horizontal => (bounds.left, bounds.right, -offset_x),
+            };
+            if end > edge {
+                let ratio = if end > start { ((edge - start) / (end - start)).clamp(0.0, 1.0) } else { 0.0 };
+                return (i, ratio);
+            }
+        }
+        (first, 0.0)
+    }
+
+    /// 指针移动时记录它在视口中的位置；x、y 是页面内的像素位置，page_index 为 None 表示离开了页面
+    pub fn set_cursor(&mut self, page_index: Option<usize>, x: f32, y: f32) {
+        self.cursor = page_index.and_then(|index| self.pages.get(index)).map(|page| (
+            page.bounds.left + x + self.view_offset.0,
+            page.bounds.top + y + self.view_offset.1,
+        ));
+    }
+
+    /// 用户正在阅读的页：指针下的页，指针不在页面上时取视口中心所在的页
+    fn focus_page(&self) -> Option<usize> {
+        let under_cursor = self.cursor.and_then(|(x, y)| {
+            let (doc_x, doc_y) = (x - self.view_offset.0, y - self.view_offset.1);
+            self.visible_range.iter()
+                .filter(|&i| self.is_on_screen(i))
+                .find(|&i| self.pages.get(i).is_some_and(|page| page.bounds.contains(doc_x, doc_y)))
+        });
+        under_cursor.or_else(|| self.layout_anchor().map(|anchor| anchor.page))
+    }
+
+    /// 记录视口中心下的文档位置；中心落在页间空隙时取沿滚动方向最近的页
+    pub fn layout_anchor(&self) -> Option<LayoutAnchor> {
+        if self.pages.is_empty() {
+            return None;
+        }
+        let center_x = -self.view_offset.0 + self.view_size.0 / 2.0;
+        let center_y = -self.view_offset.1 + self.view_size.1 / 2.0;
+        let distance = |bounds: &Rect| {
+            let dx = (bounds.left - center_x).max(center_x - bounds.right).max(0.0);
+            let dy = (bounds.top - center_y).max(center_y - bounds.bottom).max(0.0);
+            dx * dx + dy * dy
+        };
+        let candidates = if self.visible_range.is_empty() { 0..=self.pages.len() - 1 } else { self.visible_range.iter() };
+        let page = candidates
+            .filter(|&i| i < self.pages.len())
+            .min_by(|&a, &b| distance(&self.pages[a].bounds).total_cmp(&distance(&self.pages[b].bounds)))?;
+
+        let bounds = &self.pages[page].bounds;
+        let ratio = |value: f32, start: f32, end: f32| if end > start { ((value - start) / (end - start)).clamp(0.0, 1.0) } else { 0.0 };
+        Some(LayoutAnchor {
+            page,
+            x_ratio: ratio(center_x, bounds.left, bounds.right),
+            y_ratio: ratio(center_y, bounds.top, bounds.bottom),
+        })
+    }
+
+    /// 重新布局后把锚点放回视口中心，返回新的偏移量
+    pub fn restore_layout_anchor(&mut self, anchor: LayoutAnchor) -> Option<(f32, f32)> {
+        if self.paged {
+            return self.jump_to_page(anchor.page);
+        }
+        let bounds = &self.pages.get(anchor.page)?.bounds;
+        let point_x = bounds.left + (bounds.right - bounds.left) * anchor.x_ratio;
+        let point_y = bounds.top + (bounds.bottom - bounds.top) * anchor.y_ratio;
+
+        // 偏移量为负值，范围 [-(总尺寸 - 视口), 0]
+        let max_x = (self.total_width - self.view_size.0).max(0.0);
+        let max_y = (self.total_height - self.view_size.1).max(0.0);
+        let new_offset = (
+            (self.view_size.0 / 2.0 - point_x).clamp(-max_x, 0.0),
+            (self.view_size.1 / 2.0 - point_y).clamp(-max_y, 0.0),
+        );
+        self.view_offset = new_offset;
+        Some(new_offset)
+    }
+
+    /// 滚动到锚点页内的指定位置，返回新的偏移量
+    pub fn jump_to_anchor(&mut self, page_index: usize, ratio: f32) -> Option<(f32, f32)> {
+        // 翻页模式只停在整页上
+        if self.paged {
+            return self.jump_to_page(page_index);
+        }
+        let page = self.pages.get(page_index)?;
+        let ratio = ratio.clamp(0.0, 1.0);
+        let new_offset = match self.orientation {
+            Orientation::Vertical => (self.view_offset.0, -(page.bounds.top + (page.bounds.bottom - page.bounds.top) * ratio)),
+            Orientation::Horizontal if self.is_rtl() => {
+                let edge = page.bounds.right - (page.bounds.right - page.bounds.left) * ratio;
+                (-(edge - self.view_size.0).max(0.0), self.view_offset.1)
+            }
+            Orientation::Horizontal => (-(page.bounds.left + (page.bounds.right - page.bounds.left) * ratio), self.view_offset.1),
+        };
+        self.view_offset = new_offset;
+        Some(new_offset)
+    }
+
+    /// 滚动到页面上的一块区域（页面坐标），区域放在视口上部三分之一处
+    pub fn jump_to_rect(&mut self, page_index: usize, rect: Rect) -> Option<(f32, f32)> {
+        let page = self.pages.get(page_index)?;
+        let scale = page.info.scale;
+        let ratio = match self.orientation {
+            Orientation::Vertical => (rect.top * scale - self.view_size.1 / 3.0) / (page.bounds.bottom - page.bounds.top).max(1.0),
+            Orientation::Horizontal if self.is_rtl() => {
+                let width = page.bounds.right - page.bounds.left;
+                (width - rect.right * scale - self.view_size.0 / 3.0) / width.max(1.0)
+            }
+            Orientation::Horizontal => (rect.left * scale - self.view_size.0 / 3.0) / (page.bounds.right - page.bounds.left).max(1.0),
+        };
+        self.jump_to_anchor(page_index, ratio)
+    }
+
+    /// 可见页面上的搜索匹配在文档中的位置 (x, y, 宽, 高, 是否当前项)
+    pub fn search_highlights(&self) -> Vec<(f32, f32, f32, f32, bool)> {
+        if self.search.is_empty() {
+            return Vec::new();
+        }
+        self.visible_range.iter()
+            .filter_map(|index| self.pages.get(index))
+            .flat_map(|page| {
+                let scale = page.info.scale;
+                self.search.on_page(page.info.index).map(move |(rect, current)| (
+                    page.bounds.left + rect.left * scale,
+                    page.bounds.top + rect.top * scale,
+                    rect.width() * scale,
+                    rect.height() * scale,
+                    current,
+                ))
+            })
+            .collect()
     }
 
     /// 处理点击事件
@@ -444,10 +947,115 @@ impl PageViewState {
         None
     }
 
+    /// 页面内像素位置下的链接，只查已缓存的链接，用于悬停
+    pub fn link_at(&self, index: usize, x: f32, y: f32) -> Option<crate::decoder::Link> {
+        let scale = self.pages.get(index)?.info.scale;
+        let links = self.page_links.borrow();
+        links.get(&index)?
+            .iter()
+            .find(|link| link.bounds.contains(x / scale, y / scale))
+            .cloned()
+    }
+
+    /// 视口内（不含预加载）每个可见页面露出的部分，换算为页面坐标，按页序
+    pub fn viewport_regions(&self) -> Vec<(usize, Rect)> {
+        let (offset_x, offset_y) = self.view_offset;
+        let (view_width, view_height) = self.view_size;
+        let viewport = Rect::new(-offset_x, -offset_y, -offset_x + view_width, -offset_y + view_height);
+        let range = &self.visible_range;
+        if range.is_empty() {
+            return Vec::new();
+        }
+        (range.first..=range.last.min(self.pages.len().saturating_sub(1)))
+            .filter_map(|index| {
+                let bounds = &self.pages[index].bounds;
+                let shown = bounds.intersection(&viewport)?;
+                let (left, top) = self.to_page_point(index, shown.left - bounds.left, shown.top - bounds.top)?;
+                let (right, bottom) = self.to_page_point(index, shown.right - bounds.left, shown.bottom - bounds.top)?;
+                Some((self.pages[index].info.index, Rect::new(left, top, right, bottom)))
+            })
+            .collect()
+    }
+
+    /// 页面内的像素位置换算为页面坐标（与链接、文本块的坐标一致）
+    pub fn to_page_point(&self, index: usize, x: f32, y: f32) -> Option<(f32, f32)> {
+        let scale = self.pages.get(index)?.info.scale;
+        (scale > 0.0).then(|| (x / scale, y / scale))
+    }
+
+    /// 页面图像的缓存键
+    pub fn page_key(&self, page: &Page) -> String {
+        generate_thumbnail_key(self.document_id, page)
+    }
+
+    /// 已提交的渲染任务全部作废：排队的不再渲染，正在渲染的结果丢弃
+    fn cancel_pending_renders(&mut self) {
+        self.render_generation += 1;
+        self.decode_service.cancel_generation(self.render_generation);
+    }
+
+    /// 设置双页显示
+    pub fn set_dual_page(&mut self, dual_page: bool) {
+        if self.dual_page != dual_page {
+            self.dual_page = dual_page;
+            self.cancel_pending_renders();
+            self.recalculate_layout();
+
+            // 页面尺寸变化，需要重新解码
+            self.cache.clear();
+            for page in &mut self.pages {
+                page.recycle();
+            }
+            self.update_visible_pages();
+        }
+    }
+
+    /// 设置夜间模式，已缓存的图像颜色不对，需要重新解码
+    pub fn set_night_mode(&mut self, night_mode: bool) {
+        if self.night_mode != night_mode {
+            self.night_mode = night_mode;
+            self.cancel_pending_renders();
+            self.cache.clear();
+            for page in &mut self.pages {
+                page.recycle();
+            }
+            self.update_visible_pages();
+        }
+    }
+
+    /// 跳过空白页后的目标页：从 target 开始沿翻页方向找第一个非空白页，
+    /// 一直到文档末尾都是空白页时保持原目标
+    pub fn skip_blank_target(&self, from: usize, target: usize) -> usize {
+        let blank_pages = self.decode_service.blank_pages();
+        let blank_pages = blank_pages.lock().unwrap();
+        if !blank_pages.contains(&target) {
+            return target;
+        }
+        let last = self.pages.len().saturating_sub(1);
+        let found = if target >= from {
+            (target..=last).find(|index| !blank_pages.contains(index))
+        } else {
+            (0..=target).rev().find(|index| !blank_pages.contains(index))
+        };
+        found.unwrap_or(target)
+    }
+
+    /// 设置扫描页纠偏，已缓存的图像需要重新解码
+    pub fn set_deskew(&mut self, enabled: bool) {
+        self.decode_service.set_deskew(enabled);
+        self.cancel_pending_renders();
+        self.cache.clear();
+        for page in &mut self.pages {
+            page.recycle();
+        }
+        self.update_visible_pages();
+    }
+
     /// 设置切边状态
     pub fn set_crop(&mut self, crop: i32) {
         if self.crop != crop {
             self.crop = crop;
+            self.cancel_pending_renders();
             self.recalculate_layout();
 
             // 清理所有页面缓存
@@ -460,14 +1068,133 @@ impl PageViewState {
         }
     }
 
+    /// 手动切边：所有页面按同一组页边距切边，None 恢复自动切边的范围
+    pub fn set_crop_margins(&mut self, margins: Option<CropMargins>) {
+        if self.crop_margins == margins {
+            return;
+        }
+        self.crop_margins = margins;
+        for (page, auto_crop) in self.pages.iter_mut().zip(&self.auto_crop_bounds) {
+            page.info.crop_bounds = match margins {
+                Some(margins) => Some(margins.crop_rect(page.info.width, page.info.height)),
+                None => *auto_crop,
+            };
+        }
+        if self.crop == 0 {
+            return;
+        }
+        // 切边范围变了，缓存中切过边的图像都不能用了
+        self.cancel_pending_renders();
+        self.cache.clear();
+        self.recalculate_layout();
+        for page in &mut self.pages {
+            page.recycle();
+        }
+        self.update_visible_pages();
+    }
+
     /// 获取页面文本
-    pub fn get_page_text(&self, page_index: usize) -> Result<String, Box<dyn std::error::Error>> {
-        Ok(self.decode_service.get_page_text(page_index)?)
+    pub fn get_page_text(&self, page_index: usize) -> crate::error::Result<String> {
+        self.decode_service.get_page_text(page_index)
     }
 
     /// 从指定页面开始获取后续页面的reflow数据
-    pub fn get_reflow_from_page(&self, start_page: usize) -> Result<Vec<crate::entity::ReflowEntry>, Box<dyn std::error::Error>> {
-        Ok(self.decode_service.get_reflow_from_page(start_page)?)
+    pub fn get_reflow_from_page(&self, start_page: usize) -> crate::error::Result<Vec<crate::entity::ReflowEntry>> {
+        self.decode_service.get_reflow_from_page(start_page)
+    }
+
+    /// 流式获取从指定页面开始的reflow数据
+    pub fn stream_reflow_from_page(&self, start_page: usize, entry_tx: crossbeam_channel::Sender<crate::entity::ReflowEntry>) -> crate::error::Result<()> {
+        self.decode_service.stream_reflow_from_page(start_page, entry_tx)
+    }
+
+    /// 回收单个页面：节点图像和缓存中的该页图像
+    pub fn recycle_page(&mut self, page_index: usize) -> usize {
+        let Some(page) = self.pages.get_mut(page_index) else {
+            return 0;
+        };
+        let freed = page.memory_bytes() + self.cache.remove_page(page_index);
+        page.recycle();
+        freed
+    }
+
+    /// 回收当前不可见的页面
+    fn release_hidden_pages(&mut self) {
+        let mut freed = 0;
+        for index in 0..self.pages.len() {
+            if !self.visible_range.contains(index) {
+                freed += self.recycle_page(index);
+            }
+        }
+        if freed > 0 {
+            debug!("[Cache] 释放不可见页面图像 {} KB，剩余 {} KB", freed / 1024, self.memory_bytes() / 1024);
+        }
+    }
+
+    /// 回收视口外的页面（包括预加载的页面），返回释放的字节数，空闲时调用
+    pub fn release_offscreen_pages(&mut self) -> usize {
+        let mut freed = 0;
+        for index in 0..self.pages.len() {
+            if !self.is_on_screen(index) {
+                freed += self.recycle_page(index);
+            }
+        }
+        freed
+    }
+
+    /// 页面是否与视口（不含预加载区域）相交
+    fn is_on_screen(&self, page_index: usize) -> bool {
+        let (offset_x, offset_y) = self.view_offset;
+        let (view_width, view_height) = self.view_size;
+        let Some(page) = self.pages.get(page_index) else {
+            return false;
+        };
+        match self.orientation {
+            Orientation::Vertical => page.bounds.bottom > -offset_y && page.bounds.top < -offset_y + view_height,
+            Orientation::Horizontal => page.bounds.right > -offset_x && page.bounds.left < -offset_x + view_width,
+        }
+    }
+
+    /// 根据内存上限切换压力状态，状态变化时返回 true，调用方需重新计算可见页面
+    pub fn check_memory_pressure(&mut self) -> bool {
+        if self.memory_ceiling == 0 {
+            return false;
+        }
+        let used = self.memory_bytes();
+        if !self.memory_pressure && used > self.memory_ceiling {
+            self.memory_pressure = true;
+            // 激进回收：屏幕外的页面全部释放，之后以低精度重新渲染
+            let mut freed = 0;
+            for index in 0..self.pages.len() {
+                if !self.is_on_screen(index) {
+                    freed += self.recycle_page(index);
+                }
+            }
+            info!(
+                "[Cache] 内存超过上限 {} MB，进入压力模式，释放 {} KB",
+                self.memory_ceiling / 1024 / 1024,
+                freed / 1024
+            );
+            return true;
+        }
+        if self.memory_pressure && (used as f32) < self.memory_ceiling as f32 * PRESSURE_RELEASE_RATIO {
+            self.memory_pressure = false;
+            for index in std::mem::take(&mut self.downscaled_pages) {
+                self.recycle_page(index);
+            }
+            info!("[Cache] 内存压力解除，恢复全精度渲染: used={} KB", used / 1024);
+            return true;
+        }
+        false
+    }
+
+    pub fn is_under_memory_pressure(&self) -> bool {
+        self.memory_pressure
+    }
+
+    /// 页面图像实际占用的字节数（缓存 + 页面节点）
+    pub fn memory_bytes(&self) -> usize {
+        self.cache.total_bytes() + self.pages.iter().map(|page| page.memory_bytes()).sum::<usize>()
     }
 
     /// 回收资源
@@ -478,7 +1205,7 @@ impl PageViewState {
             page.recycle();
         }
         self.pages.clear();
-        self.visible_pages.clear();
+        self.visible_range = VisibleRange::EMPTY;
 
         self.page_links.borrow_mut().clear();
         self.outline_items.clear();
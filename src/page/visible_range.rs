@@ -0,0 +1,43 @@
+/// 可见页面范围（含预加载区域），页码从 0 开始，first..=last
+///
+/// anchor_page 是视口上沿（横向时为左沿）所在的页，anchor_ratio 是上沿在该页内的位置（0~1），
+/// 缩放或窗口大小变化后按锚点恢复，比保存滚动偏移更精确
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibleRange {
+    pub first: usize,
+    pub last: usize,
+    pub anchor_page: usize,
+    pub anchor_ratio: f32,
+}
+
+impl VisibleRange {
+    /// 没有可见页面
+    pub const EMPTY: VisibleRange = VisibleRange { first: 1, last: 0, anchor_page: 0, anchor_ratio: 0.0 };
+
+    pub fn is_empty(&self) -> bool {
+        self.first > self.last
+    }
+
+    pub fn len(&self) -> usize {
+        if self.is_empty() { 0 } else { self.last - self.first + 1 }
+    }
+
+    pub fn contains(&self, page_index: usize) -> bool {
+        self.first <= page_index && page_index <= self.last
+    }
+
+    pub fn iter(&self) -> std::ops::RangeInclusive<usize> {
+        self.first..=self.last
+    }
+
+    /// 锚点页，没有可见页面时为 None
+    pub fn anchor(&self) -> Option<usize> {
+        (!self.is_empty()).then_some(self.anchor_page)
+    }
+}
+
+impl Default for VisibleRange {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
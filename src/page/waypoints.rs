@@ -0,0 +1,48 @@
+/// 跳转多于这么多页时自动记下跳转前的位置
+pub const WAYPOINT_MIN_JUMP: usize = 10;
+/// 最多保留的路标数
+const MAX_WAYPOINTS: usize = 20;
+
+/// 大跳转（链接、大纲、跳页）前的阅读位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Waypoint {
+    pub page: usize,
+    /// 视口上沿在页内的位置（0~1）
+    pub ratio: f32,
+}
+
+/// 当前文档的路标，最近的在前；误跳转后可以从列表回到原处
+#[derive(Debug, Default)]
+pub struct Waypoints {
+    items: Vec<Waypoint>,
+}
+
+impl Waypoints {
+    /// 从 from 跳到 to 时调用，跳得足够远才记录跳转前的位置
+    pub fn record_jump(&mut self, from: usize, ratio: f32, to: usize) -> bool {
+        if from.abs_diff(to) <= WAYPOINT_MIN_JUMP {
+            return false;
+        }
+        // 同一页只保留最近一次
+        self.items.retain(|waypoint| waypoint.page != from);
+        self.items.insert(0, Waypoint { page: from, ratio });
+        self.items.truncate(MAX_WAYPOINTS);
+        true
+    }
+
+    pub fn get(&self, index: usize) -> Option<Waypoint> {
+        self.items.get(index).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Waypoint> {
+        self.items.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
@@ -0,0 +1,102 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, info, warn};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::decoder::formats;
+
+/// 剪贴板轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// 剪贴板里识别出的可打开内容
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardCandidate {
+    /// 本地存在且格式支持的文件
+    File(String),
+    /// 指向支持格式的网址，下载后打开
+    Url(String),
+}
+
+impl ClipboardCandidate {
+    /// 识别复制的文本，只接受单行的文件路径或网址
+    pub fn detect(text: &str) -> Option<Self> {
+        let text = text.trim().trim_matches('"');
+        if text.is_empty() || text.contains('\n') {
+            return None;
+        }
+
+        if let Some(path) = text.strip_prefix("file://") {
+            return Self::detect_file(path);
+        }
+        if text.starts_with("http://") || text.starts_with("https://") {
+            // 去掉查询串和锚点再判断扩展名
+            let url_path = text.split(['?', '#']).next().unwrap_or(text);
+            return formats::is_supported(Path::new(url_path)).then(|| ClipboardCandidate::Url(text.to_string()));
+        }
+        Self::detect_file(text)
+    }
+
+    fn detect_file(path: &str) -> Option<Self> {
+        let file = Path::new(path);
+        (file.is_file() && formats::is_supported(file)).then(|| ClipboardCandidate::File(path.to_string()))
+    }
+}
+
+/// 后台剪贴板监视：文本变化且是可打开的内容时发送通知
+/// 需要用户在设置中开启，drop 时停止
+pub struct ClipboardWatcher {
+    candidate_rx: Receiver<ClipboardCandidate>,
+    running: Arc<AtomicBool>,
+}
+
+impl ClipboardWatcher {
+    pub fn start() -> Self {
+        let (candidate_tx, candidate_rx) = unbounded();
+        let running = Arc::new(AtomicBool::new(true));
+        let running_for_thread = Arc::clone(&running);
+        thread::spawn(move || Self::watch_loop(candidate_tx, running_for_thread));
+        info!("[Clipboard] 开始监视剪贴板");
+        Self { candidate_rx, running }
+    }
+
+    pub fn try_recv(&self) -> Option<ClipboardCandidate> {
+        self.candidate_rx.try_recv().ok()
+    }
+
+    fn watch_loop(candidate_tx: Sender<ClipboardCandidate>, running: Arc<AtomicBool>) {
+        let mut clipboard = match arboard::Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                warn!("[Clipboard] 无法访问剪贴板: {}", e);
+                return;
+            }
+        };
+
+        // 启动时已有的内容不算新复制的
+        let mut last_text = clipboard.get_text().unwrap_or_default();
+        while running.load(Ordering::Relaxed) {
+            thread::sleep(POLL_INTERVAL);
+            let Ok(text) = clipboard.get_text() else { continue };
+            if text == last_text {
+                continue;
+            }
+            last_text = text;
+            if let Some(candidate) = ClipboardCandidate::detect(&last_text) {
+                debug!("[Clipboard] 发现可打开的内容: {:?}", candidate);
+                if candidate_tx.send(candidate).is_err() {
+                    break;
+                }
+            }
+        }
+        info!("[Clipboard] 停止监视剪贴板");
+    }
+}
+
+impl Drop for ClipboardWatcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
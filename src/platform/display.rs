@@ -0,0 +1,69 @@
+use sha2::{Digest, Sha256};
+
+/// 显示器组合的短哈希；同一块屏幕在不同系统缩放下也当作不同的组合
+pub fn display_profile_key(signature: &str, scale_factor: f32) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(signature.as_bytes());
+    hasher.update(format!("@{:.2}", scale_factor).as_bytes());
+    hasher.finalize().iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 接了哪些显示器、各自的分辨率；在 macOS 上要调用外部命令，不要在 UI 线程调用。
+/// 无法判断时返回 None
+#[cfg(target_os = "linux")]
+pub fn display_signature() -> Option<String> {
+    // 每个已连接的输出口取名字和首选分辨率，例如 "card1-eDP-1:2880x1800"
+    let mut outputs = Vec::new();
+    for entry in std::fs::read_dir("/sys/class/drm").ok()?.flatten() {
+        let path = entry.path();
+        let status = std::fs::read_to_string(path.join("status")).unwrap_or_default();
+        if status.trim() != "connected" {
+            continue;
+        }
+        let modes = std::fs::read_to_string(path.join("modes")).unwrap_or_default();
+        let mode = modes.lines().next().unwrap_or_default().to_string();
+        outputs.push(format!("{}:{}", entry.file_name().to_string_lossy(), mode));
+    }
+    if outputs.is_empty() {
+        return None;
+    }
+    outputs.sort();
+    log::debug!("[Display] {:?}", outputs);
+    Some(outputs.join(","))
+}
+
+#[cfg(target_os = "macos")]
+pub fn display_signature() -> Option<String> {
+    // 每块显示器一行 "Resolution: 3024 x 1964 Retina"
+    let output = std::process::Command::new("system_profiler").arg("SPDisplaysDataType").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut resolutions: Vec<&str> = text.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("Resolution:"))
+        .collect();
+    if resolutions.is_empty() {
+        return None;
+    }
+    resolutions.sort();
+    log::debug!("[Display] {:?}", resolutions);
+    Some(resolutions.join(","))
+}
+
+#[cfg(target_os = "windows")]
+pub fn display_signature() -> Option<String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CMONITORS, SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN};
+
+    // 显示器数量、主屏和整个虚拟桌面的尺寸
+    let metrics = [SM_CMONITORS, SM_CXSCREEN, SM_CYSCREEN, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN]
+        .map(|index| unsafe { GetSystemMetrics(index) });
+    if metrics[0] == 0 {
+        return None;
+    }
+    log::debug!("[Display] {:?}", metrics);
+    Some(metrics.map(|value| value.to_string()).join(","))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn display_signature() -> Option<String> {
+    None
+}
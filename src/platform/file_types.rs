@@ -0,0 +1,152 @@
+use log::debug;
+
+/// 把支持的文档格式登记到系统，系统维护的最近文档才会出现在 Dock 菜单和任务栏跳转列表里
+/// macOS：由 Info.plist 的 CFBundleDocumentTypes 声明，这里不需要做什么
+/// Windows：写入当前用户的文件关联（HKCU\Software\Classes），只作为“打开方式”候选，不抢默认程序
+/// 其他平台不处理；便携模式不写注册表
+pub fn register_file_types() {
+    if crate::app_paths::is_portable() {
+        debug!("[Platform] 便携模式，不登记文件类型");
+        return;
+    }
+    imp::register_file_types();
+}
+
+/// 关联到 RReader 的 ProgID
+#[cfg(any(target_os = "windows", test))]
+const PROG_ID: &str = "RReader.Document";
+
+/// 注册表中的一项：键（相对 HKCU\Software\Classes）、值名（None 为默认值）、字符串数据
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, PartialEq, Eq)]
+struct RegistryEntry {
+    key: String,
+    name: Option<String>,
+    data: String,
+}
+
+/// 文件关联需要写入的注册表项，exe 为可执行文件的完整路径
+#[cfg(any(target_os = "windows", test))]
+fn registry_entries(exe: &str) -> Vec<RegistryEntry> {
+    let exe_name = exe.rsplit(['\\', '/']).next().unwrap_or(exe);
+    let command = format!("\"{}\" \"%1\"", exe);
+    let entry = |key: String, name: Option<String>, data: String| RegistryEntry { key, name, data };
+
+    let mut entries = vec![
+        entry(PROG_ID.to_string(), None, "RReader Document".to_string()),
+        entry(format!("{}\\DefaultIcon", PROG_ID), None, format!("\"{}\",0", exe)),
+        entry(format!("{}\\shell\\open\\command", PROG_ID), None, command.clone()),
+        entry(format!("Applications\\{}\\shell\\open\\command", exe_name), None, command),
+    ];
+    for format in crate::decoder::formats::FORMATS {
+        let extension = format!(".{}", format.extension);
+        entries.push(entry(format!("{}\\OpenWithProgids", extension), Some(PROG_ID.to_string()), String::new()));
+        entries.push(entry(format!("Applications\\{}\\SupportedTypes", exe_name), Some(extension), String::new()));
+    }
+    entries
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use log::{error, info};
+    use std::ffi::c_void;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ};
+    use windows_sys::Win32::UI::Shell::{SHChangeNotify, SHCNE_ASSOCCHANGED, SHCNF_IDLIST};
+
+    use super::{registry_entries, RegistryEntry};
+
+    const CLASSES: &str = "Software\\Classes\\";
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn read(entry: &RegistryEntry) -> Option<String> {
+        let key = wide(&format!("{}{}", CLASSES, entry.key));
+        let name = entry.name.as_deref().map(wide);
+        let name_ptr = name.as_ref().map_or(ptr::null(), |name| name.as_ptr());
+        let mut buffer = [0u16; 1024];
+        let mut size = (buffer.len() * 2) as u32;
+        let status = unsafe {
+            RegGetValueW(HKEY_CURRENT_USER, key.as_ptr(), name_ptr, RRF_RT_REG_SZ, ptr::null_mut(), buffer.as_mut_ptr().cast::<c_void>(), &mut size)
+        };
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+        let len = (size as usize / 2).saturating_sub(1);
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    fn write(entry: &RegistryEntry) -> bool {
+        let key = wide(&format!("{}{}", CLASSES, entry.key));
+        let name = entry.name.as_deref().map(wide);
+        let name_ptr = name.as_ref().map_or(ptr::null(), |name| name.as_ptr());
+        let data = wide(&entry.data);
+        let status = unsafe {
+            RegSetKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name_ptr, REG_SZ, data.as_ptr().cast::<c_void>(), (data.len() * 2) as u32)
+        };
+        if status != ERROR_SUCCESS {
+            error!("[Platform] 写入注册表失败 {}: {}", entry.key, status);
+        }
+        status == ERROR_SUCCESS
+    }
+
+    pub fn register_file_types() {
+        let exe = match std::env::current_exe() {
+            Ok(exe) => exe.to_string_lossy().to_string(),
+            Err(e) => {
+                error!("[Platform] 无法取得程序路径，不登记文件类型: {}", e);
+                return;
+            }
+        };
+        let mut changed = 0;
+        for entry in registry_entries(&exe) {
+            if read(&entry).as_deref() != Some(entry.data.as_str()) && write(&entry) {
+                changed += 1;
+            }
+        }
+        if changed > 0 {
+            info!("[Platform] 已登记文件类型，更新 {} 项", changed);
+            unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, ptr::null(), ptr::null()) };
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn register_file_types() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::formats::FORMATS;
+
+    #[test]
+    fn registry_entries_cover_every_format() {
+        let entries = registry_entries("C:\\Apps\\RReader\\rreader.exe");
+        let command = entries.iter().find(|e| e.key == "RReader.Document\\shell\\open\\command").unwrap();
+        assert_eq!(command.data, "\"C:\\Apps\\RReader\\rreader.exe\" \"%1\"");
+        for format in FORMATS {
+            let extension = format!(".{}", format.extension);
+            assert!(entries.iter().any(|e| e.key == format!("{}\\OpenWithProgids", extension) && e.name.as_deref() == Some(PROG_ID)));
+            assert!(entries.iter().any(|e| e.key == "Applications\\rreader.exe\\SupportedTypes" && e.name.as_deref() == Some(extension.as_str())));
+        }
+    }
+
+    /// Info.plist 声明的类型要和 FORMATS 一致，不然最近文档不显示或打开失败
+    #[test]
+    fn info_plist_declares_every_format() {
+        let plist = include_str!("../../assets/Info.plist");
+        let declared: Vec<&str> = plist.lines()
+            .map(str::trim)
+            .filter_map(|line| line.strip_prefix("<string>").and_then(|rest| rest.strip_suffix("</string>")))
+            .collect();
+        for format in FORMATS {
+            assert!(declared.contains(&format.extension), "Info.plist 缺少 {}", format.extension);
+        }
+        assert!(plist.contains("<key>CFBundleExecutable</key>"));
+    }
+}
@@ -0,0 +1,14 @@
+pub mod clipboard_watcher;
+pub mod display;
+pub mod file_types;
+pub mod power;
+pub mod recent_documents;
+pub mod share;
+pub mod system_open;
+
+pub use clipboard_watcher::{ClipboardCandidate, ClipboardWatcher};
+pub use display::{display_profile_key, display_signature};
+pub use file_types::register_file_types;
+pub use recent_documents::note_recent_document;
+pub use share::{email_file, share_sheet_available, show_share_sheet, CopyEvent, CopyJob};
+pub use system_open::{is_safe_link, link_scheme, open_with_system, percent_encode};
@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 当前是否使用省电配置，后台线程也会读取
+static LOW_POWER: AtomicBool = AtomicBool::new(false);
+
+pub fn is_low_power() -> bool {
+    LOW_POWER.load(Ordering::Relaxed)
+}
+
+pub fn set_low_power(enabled: bool) {
+    LOW_POWER.store(enabled, Ordering::Relaxed);
+}
+
+/// 是否在用电池供电；台式机或无法判断时返回 None
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> Option<bool> {
+    let mut has_battery = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        let read = |name: &str| std::fs::read_to_string(path.join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+        match read("type").as_str() {
+            // 任一外接电源在线即视为接通电源
+            "Mains" | "USB" if read("online") == "1" => return Some(false),
+            "Battery" if read("scope") != "Device" => has_battery = true,
+            _ => {}
+        }
+    }
+    has_battery.then_some(true)
+}
+
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> Option<bool> {
+    // 第一行形如 "Now drawing from 'Battery Power'"
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let first = text.lines().next()?;
+    log::debug!("[Power] {}", first);
+    if first.contains("Battery Power") {
+        Some(true)
+    } else if first.contains("AC Power") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // ACLineStatus: 0 电池，1 交流电，255 未知；BatteryFlag 128 表示没有电池
+    log::debug!("[Power] ac={}, battery_flag={}", status.ACLineStatus, status.BatteryFlag);
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn on_battery() -> Option<bool> {
+    None
+}
@@ -0,0 +1,51 @@
+use log::debug;
+use std::path::Path;
+
+/// 把打开的文档登记到系统“最近使用”列表
+/// macOS：NSDocumentController 最近文档，Dock 菜单和“最近使用的项目”会显示
+/// Windows：SHAddToRecentDocs，任务栏跳转列表的“最近”分类由系统维护
+/// 两者都只显示应用登记过的文件类型，见 file_types::register_file_types；
+/// 列表内容由系统维护，不另做自定义 Dock 菜单和跳转列表分类。其他平台没有统一接口，不处理
+pub fn note_recent_document(path: &Path) {
+    debug!("[Platform] note recent document: {:?}", path);
+    imp::note_recent_document(path);
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use objc2_app_kit::NSDocumentController;
+    use objc2_foundation::{MainThreadMarker, NSString, NSURL};
+    use std::path::Path;
+
+    pub fn note_recent_document(path: &Path) {
+        // AppKit 只能在主线程调用
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("[Platform] note_recent_document 不在主线程，忽略");
+            return;
+        };
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        let controller = NSDocumentController::sharedDocumentController(mtm);
+        controller.noteNewRecentDocumentURL(&url);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::UI::Shell::{SHAddToRecentDocs, SHARD_PATHW};
+
+    pub fn note_recent_document(path: &Path) {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        unsafe {
+            SHAddToRecentDocs(SHARD_PATHW as u32, wide.as_ptr().cast());
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod imp {
+    use std::path::Path;
+
+    pub fn note_recent_document(_path: &Path) {}
+}
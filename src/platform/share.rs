@@ -0,0 +1,159 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, info};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use super::{open_with_system, percent_encode};
+
+/// 每次复制的块大小
+const COPY_CHUNK: usize = 1024 * 1024;
+
+/// 用邮件发送文件
+/// macOS 用“邮件”新建带附件的邮件，Linux 用 xdg-email --attach，
+/// Windows 没有通用的附件接口，只打开新邮件并把文件名写进主题
+pub fn email_file(path: &Path) -> io::Result<()> {
+    debug!("[Platform] email file: {:?}", path);
+    if cfg!(target_os = "macos") {
+        process::Command::new("open").args(["-a", "Mail"]).arg(path).spawn()?;
+    } else if cfg!(target_os = "windows") {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        open_with_system(&format!("mailto:?subject={}", percent_encode(&name)))?;
+    } else {
+        process::Command::new("xdg-email").arg("--attach").arg(path).spawn()?;
+    }
+    Ok(())
+}
+
+/// 系统分享面板是否可用
+pub fn share_sheet_available() -> bool {
+    cfg!(target_os = "macos")
+}
+
+/// 弹出系统分享面板，目前只有 macOS 支持，返回是否已弹出
+pub fn show_share_sheet(path: &Path) -> bool {
+    debug!("[Platform] share sheet: {:?}", path);
+    imp::show_share_sheet(path)
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use objc2::rc::Retained;
+    use objc2_app_kit::{NSApplication, NSSharingServicePicker};
+    use objc2_foundation::{MainThreadMarker, NSArray, NSRectEdge, NSString, NSURL};
+    use std::path::Path;
+
+    pub fn show_share_sheet(path: &Path) -> bool {
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("[Platform] show_share_sheet 不在主线程，忽略");
+            return false;
+        };
+        let app = NSApplication::sharedApplication(mtm);
+        let Some(view) = app.keyWindow().and_then(|window| window.contentView()) else {
+            return false;
+        };
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        let items = NSArray::from_retained_slice(&[Retained::into_super(Retained::into_super(url))]);
+        let picker = unsafe { NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &items) };
+        // 从窗口顶部中间弹出
+        let bounds = view.bounds();
+        picker.showRelativeToRect_ofView_preferredEdge(bounds, &view, NSRectEdge::MaxY);
+        true
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod imp {
+    use std::path::Path;
+
+    pub fn show_share_sheet(_path: &Path) -> bool {
+        false
+    }
+}
+
+/// 复制任务的进度事件
+#[derive(Debug, Clone)]
+pub enum CopyEvent {
+    Progress { copied: u64, total: u64 },
+    /// 成功时为目标文件路径
+    Finished(Result<PathBuf, String>),
+}
+
+/// 后台复制文件到指定目录（如 Kindle 的 USB 挂载点、Dropbox 目录）
+/// 先写入 .part 临时文件，完成后改名，取消或失败时删除临时文件
+pub struct CopyJob {
+    event_rx: Receiver<CopyEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CopyJob {
+    pub fn start(source: PathBuf, dest_dir: PathBuf) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancelled_for_thread = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let result = Self::copy(&source, &dest_dir, &event_tx, &cancelled_for_thread)
+                .map_err(|e| e.to_string());
+            info!("[Share] 复制 {:?} -> {:?}: {:?}", source, dest_dir, result);
+            let _ = event_tx.send(CopyEvent::Finished(result));
+        });
+        Self { event_rx, cancelled }
+    }
+
+    pub fn try_recv(&self) -> Option<CopyEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn copy(source: &Path, dest_dir: &Path, event_tx: &Sender<CopyEvent>, cancelled: &AtomicBool) -> io::Result<PathBuf> {
+        let name = source
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "source has no file name"))?;
+        let target = dest_dir.join(name);
+        let partial = dest_dir.join(format!("{}.part", name.to_string_lossy()));
+
+        let total = fs::metadata(source)?.len();
+        let mut reader = File::open(source)?;
+        let mut writer = File::create(&partial)?;
+        let mut buffer = vec![0u8; COPY_CHUNK];
+        let mut copied = 0u64;
+
+        let result = (|| {
+            loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+                }
+                let n = reader.read(&mut buffer)?;
+                if n == 0 {
+                    break;
+                }
+                writer.write_all(&buffer[..n])?;
+                copied += n as u64;
+                let _ = event_tx.send(CopyEvent::Progress { copied, total });
+            }
+            // U 盘等可移动设备要确保真正写入
+            writer.sync_all()
+        })();
+
+        drop(writer);
+        if let Err(e) = result {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, &target)?;
+        Ok(target)
+    }
+}
+
+impl Drop for CopyJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,112 @@
+use log::debug;
+use std::io;
+
+/// 不用询问就能打开的链接协议，其他协议（file、smb、自定义协议等）要先让用户确认
+const SAFE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// 用系统默认程序打开文件或网址
+/// 直接调用系统接口，不经过 shell，文档里的链接不会被当作命令解析
+pub fn open_with_system(target: &str) -> io::Result<()> {
+    debug!("[Platform] open with system: {}", target);
+    imp::open(target)
+}
+
+/// 链接的协议，小写；没有协议（相对路径等）时为 None
+pub fn link_scheme(uri: &str) -> Option<String> {
+    let (scheme, _) = uri.trim().split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| scheme.to_ascii_lowercase())
+}
+
+/// 是否是可以直接打开的链接：http、https、mailto
+pub fn is_safe_link(uri: &str) -> bool {
+    link_scheme(uri).is_some_and(|scheme| SAFE_SCHEMES.contains(&scheme.as_str()))
+}
+
+/// 按 RFC 3986 对 URI 的一个组成部分做百分号编码，只保留不需要转义的字符
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::io;
+    use std::process;
+
+    pub fn open(target: &str) -> io::Result<()> {
+        process::Command::new("open").arg(target).spawn().map(|_| ())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::io;
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    pub fn open(target: &str) -> io::Result<()> {
+        let wide = |text: &str| -> Vec<u16> { text.encode_utf16().chain(std::iter::once(0)).collect() };
+        let operation = wide("open");
+        let file = wide(target);
+        let result = unsafe {
+            ShellExecuteW(
+                std::ptr::null_mut(),
+                operation.as_ptr(),
+                file.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+        // 返回值不大于 32 表示失败
+        if result as isize <= 32 {
+            return Err(io::Error::other(format!("ShellExecuteW failed ({})", result as isize)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod imp {
+    use std::io;
+    use std::process;
+
+    pub fn open(target: &str) -> io::Result<()> {
+        process::Command::new("xdg-open").arg(target).spawn().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_and_mail_links_are_safe() {
+        assert!(is_safe_link("https://example.com/a?b=c&d=e"));
+        assert!(is_safe_link("HTTP://example.com"));
+        assert!(is_safe_link("mailto:someone@example.com"));
+        assert!(!is_safe_link("file:///C:/Windows/System32/calc.exe"));
+        assert!(!is_safe_link("smb://server/share"));
+        assert!(!is_safe_link("ms-settings:"));
+        assert!(!is_safe_link("calc.exe"));
+        assert_eq!(link_scheme("x-custom+app://open"), Some("x-custom+app".into()));
+        assert_eq!(link_scheme("1http://a"), None);
+    }
+
+    #[test]
+    fn percent_encode_escapes_reserved_characters() {
+        assert_eq!(percent_encode("a b&c=d"), "a%20b%26c%3Dd");
+        assert_eq!(percent_encode("书.pdf"), "%E4%B9%A6.pdf");
+        assert_eq!(percent_encode("x_y-z~1"), "x_y-z~1");
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use log::{debug, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::language::detect_language;
+use super::layout::{build_reflow_blocks, join_blocks};
+use crate::app_paths;
+use crate::decoder::Decoder;
+use crate::entity::{ReflowData, ReflowEntry};
+use crate::storage::FileStore;
+use crate::ui::utils::generate_content_hash;
+
+/// 缓存格式版本：1 增加了结构化文本块，2 增加了页面语言
+pub const CACHE_VERSION: u32 = 2;
+
+/// 每提取多少页写一次缓存文件
+const FLUSH_INTERVAL: usize = 10;
+
+/// reflow 缓存，支持逐页追加和增量写盘
+pub struct ReflowCache {
+    path: PathBuf,
+    data: ReflowData,
+    pending: usize,
+}
+
+impl ReflowCache {
+    /// 缓存文件以内容hash+文件大小命名，同名的不同书不会互相覆盖
+    fn get_cache_path(content_hash: u64, file_size: u64) -> PathBuf {
+        app_paths::reflow_dir()
+            .join(format!("{:016x}_{}_reflow.json", content_hash, file_size))
+    }
+
+    fn get_file_mtime(metadata: &fs::Metadata) -> u64 {
+        metadata.modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    /// 打开缓存，不存在、损坏或与源文件不匹配时创建一个空的未完成缓存
+    pub fn open(source: &Path) -> Result<Self> {
+        let metadata = fs::metadata(source)?;
+        let file_size = metadata.len();
+        let file_mtime = Self::get_file_mtime(&metadata);
+        let content_hash = generate_content_hash(source)?;
+        let path = Self::get_cache_path(content_hash, file_size);
+
+        let data = match Self::load(&path) {
+            Ok(data) if data.version != CACHE_VERSION => {
+                info!("[Reflow] 缓存版本变化，重新生成: {} -> {}", data.version, CACHE_VERSION);
+                Self::empty_data(file_size, file_mtime)
+            }
+            Ok(data) if data.file_size == file_size && data.file_mtime == file_mtime => data,
+            Ok(data) => {
+                info!("[Reflow] 源文件已变化，重新生成: size {}->{}, mtime {}->{}",
+                    data.file_size, file_size, data.file_mtime, file_mtime);
+                Self::empty_data(file_size, file_mtime)
+            }
+            Err(e) => {
+                if path.exists() {
+                    info!("[Reflow] 缓存无法读取，重新生成: {:?}, {}", path, e);
+                }
+                Self::empty_data(file_size, file_mtime)
+            }
+        };
+
+        Ok(Self { path, data, pending: 0 })
+    }
+
+    fn empty_data(file_size: u64, file_mtime: u64) -> ReflowData {
+        ReflowData {
+            version: CACHE_VERSION,
+            page_count: 0,
+            file_size,
+            file_mtime,
+            reflow: Vec::new(),
+            complete: false,
+            extracted: Vec::new(),
+        }
+    }
+
+    fn load(path: &Path) -> Result<ReflowData> {
+        let content = FileStore::read_to_string(path)?;
+        let reflow_data: ReflowData = serde_json::from_str(&content)?;
+        Ok(reflow_data)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.data.complete
+    }
+
+    /// 页面是否已经提取过
+    pub fn contains_page(&self, page: usize) -> bool {
+        self.data.complete
+            || self.data.extracted.contains(&page)
+    }
+
+    pub fn get(&self, page: usize) -> Option<&ReflowEntry> {
+        self.data.reflow
+            .binary_search_by_key(&page, Self::entry_page)
+            .ok()
+            .map(|idx| &self.data.reflow[idx])
+    }
+
+    pub fn entries(&self) -> &[ReflowEntry] {
+        &self.data.reflow
+    }
+
+    /// 只读方式加载已有缓存，不存在或已失效时返回 None
+    pub fn load_existing(source: &Path) -> Option<Self> {
+        let cache = Self::open(source).ok()?;
+        if cache.data.reflow.is_empty() && !cache.data.complete {
+            None
+        } else {
+            Some(cache)
+        }
+    }
+
+    fn entry_page(entry: &ReflowEntry) -> usize {
+        entry.page.parse::<usize>().unwrap_or(0)
+    }
+
+    /// 提取单页的结构化文本，过滤掉字符数 <= 5 的页面
+    pub fn extract_page(decoder: &dyn Decoder, page: usize) -> Result<Option<ReflowEntry>> {
+        let (width, height) = decoder.get_page_size(page)?;
+        let text_blocks = decoder.get_page_text_blocks(page)?;
+        let blocks = build_reflow_blocks(&text_blocks, width, height);
+        let text = join_blocks(&blocks);
+        if text.chars().count() > 5 {
+            let language = detect_language(&text);
+            Ok(Some(ReflowEntry {
+                data: text,
+                page: page.to_string(),
+                blocks,
+                language,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 记录一页的提取结果（按页码有序插入），定期写盘
+    pub fn insert(&mut self, page: usize, entry: Option<ReflowEntry>) -> Result<()> {
+        if self.contains_page(page) {
+            return Ok(());
+        }
+        if let Some(entry) = entry {
+            let idx = self.data.reflow
+                .binary_search_by_key(&page, Self::entry_page)
+                .unwrap_or_else(|idx| idx);
+            self.data.reflow.insert(idx, entry);
+        }
+        self.data.extracted.push(page);
+        self.data.page_count = self.data.reflow.len();
+
+        self.pending += 1;
+        if self.pending >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 标记整本书提取完成并写盘
+    pub fn finish(&mut self) -> Result<()> {
+        self.data.complete = true;
+        self.data.extracted.clear();
+        self.flush()
+    }
+
+    /// 把当前数据写入缓存文件
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.data)?;
+        FileStore::write(&self.path, json)?;
+        self.pending = 0;
+        debug!("[Reflow] 缓存已写入: {:?}, entries={}, complete={}",
+            self.path, self.data.reflow.len(), self.data.complete);
+        Ok(())
+    }
+
+    /// 同步提取所有未提取的页面
+    pub fn fill_all(&mut self, decoder: &dyn Decoder) -> Result<()> {
+        if self.is_complete() {
+            return Ok(());
+        }
+        for page in 0..decoder.page_count() {
+            if !self.contains_page(page) {
+                let entry = Self::extract_page(decoder, page)?;
+                self.insert(page, entry)?;
+            }
+        }
+        self.finish()
+    }
+}
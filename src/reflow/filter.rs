@@ -0,0 +1,112 @@
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+
+use crate::entity::ReflowBlock;
+
+/// 页面顶部多少比例内的块视为页眉候选
+const HEADER_BAND: f32 = 0.1;
+/// 页面底部多少比例内的块视为页脚候选
+const FOOTER_BAND: f32 = 0.9;
+/// 脚注只在页面下部这个比例之后出现
+const FOOTNOTE_BAND: f32 = 0.7;
+/// 字号小于正文的这个比例视为小字（页脚、脚注）
+const SMALL_FONT_RATIO: f32 = 0.85;
+/// 页眉页脚不会太长
+const EDGE_MAX_CHARS: usize = 100;
+/// 记录最近多少页的页眉页脚用于比较
+const HISTORY_PAGES: usize = 4;
+
+static PAGE_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^[\s\-–—]*(page\s*|第\s*)?(\d+|[ivxlcdm]+)(\s*(/|of)\s*\d+)?(\s*页)?[\s\-–—]*$").unwrap()
+});
+static DIGITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+static SPACES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+/// 页眉/页脚/页码/脚注过滤器
+/// 需要按阅读顺序逐页调用，重复出现的页眉页脚通过与最近几页比较识别
+pub struct HeaderFooterFilter {
+    strip_headers_footers: bool,
+    strip_footnotes: bool,
+    history: VecDeque<Vec<String>>,
+}
+
+impl HeaderFooterFilter {
+    pub fn new(strip_headers_footers: bool, strip_footnotes: bool) -> Self {
+        Self {
+            strip_headers_footers,
+            strip_footnotes,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// 去掉数字和多余空白，"Chapter 3   17" 和 "Chapter 3   18" 归一后相同
+    fn normalize(text: &str) -> String {
+        let text = DIGITS.replace_all(text, "#");
+        SPACES.replace_all(text.trim(), " ").to_lowercase()
+    }
+
+    fn is_edge(block: &ReflowBlock) -> bool {
+        block.text.chars().count() <= EDGE_MAX_CHARS
+            && (block.bottom <= HEADER_BAND || block.top >= FOOTER_BAND)
+    }
+
+    /// 过滤一页的文本块
+    pub fn filter(&mut self, blocks: Vec<ReflowBlock>) -> Vec<ReflowBlock> {
+        if !self.strip_headers_footers && !self.strip_footnotes {
+            return blocks;
+        }
+
+        let body_size = Self::body_font_size(&blocks);
+        let signatures: Vec<String> = blocks.iter()
+            .filter(|b| Self::is_edge(b))
+            .map(|b| Self::normalize(&b.text))
+            .collect();
+
+        let result = blocks.into_iter()
+            .filter(|block| {
+                let small_font = body_size > 0.0 && block.font_size > 0.0
+                    && block.font_size < body_size * SMALL_FONT_RATIO;
+
+                if self.strip_headers_footers && Self::is_edge(block) {
+                    if PAGE_NUMBER.is_match(block.text.trim()) {
+                        return false;
+                    }
+                    let signature = Self::normalize(&block.text);
+                    if self.history.iter().any(|page| page.contains(&signature)) {
+                        return false;
+                    }
+                    if small_font {
+                        return false;
+                    }
+                }
+
+                if self.strip_footnotes && small_font && block.top >= FOOTNOTE_BAND {
+                    return false;
+                }
+                true
+            })
+            .collect();
+
+        self.history.push_back(signatures);
+        if self.history.len() > HISTORY_PAGES {
+            self.history.pop_front();
+        }
+        result
+    }
+
+    /// 正文字号：按字符数加权出现最多的字号
+    fn body_font_size(blocks: &[ReflowBlock]) -> f32 {
+        let mut best = (0.0, 0usize);
+        for block in blocks {
+            let weight: usize = blocks.iter()
+                .filter(|b| (b.font_size - block.font_size).abs() < 0.5)
+                .map(|b| b.text.chars().count())
+                .sum();
+            if weight > best.1 {
+                best = (block.font_size, weight);
+            }
+        }
+        best.0
+    }
+}
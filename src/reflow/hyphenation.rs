@@ -0,0 +1,56 @@
+/// 软连字符，排版软件在断行处插入，不应朗读
+const SOFT_HYPHEN: char = '\u{00AD}';
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 日文假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一表意文字
+        | 0xAC00..=0xD7AF   // 韩文
+        | 0xF900..=0xFAFF   // CJK 兼容表意文字
+        | 0xFF00..=0xFFEF)  // 全角符号
+        || matches!(c, '，' | '。' | '；' | '：' | '？' | '！' | '、' | '“' | '”' | '‘' | '’' | '（' | '）' | '《' | '》')
+}
+
+/// 把硬换行的行合并成句子：
+/// - 行尾连字符 + 下一行小写字母开头：去掉连字符直接拼接（"recog-\nnition" -> "recognition"）
+/// - 两侧都是中日韩文字：直接拼接，不加空格
+/// - 其他情况用一个空格连接
+/// 中文、日文页面的行之间一律不加空格
+pub fn join_lines(text: &str, language: Option<&str>) -> String {
+    let no_space = matches!(language, Some("zh" | "ja"));
+    let mut result = String::with_capacity(text.len());
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if result.is_empty() {
+            result.push_str(line);
+            continue;
+        }
+
+        let next_first = line.chars().next().unwrap_or(' ');
+        let trimmed_len = result.trim_end_matches(SOFT_HYPHEN).len();
+        if trimmed_len < result.len() {
+            // 软连字符总是断词产生的
+            result.truncate(trimmed_len);
+            result.push_str(line);
+            continue;
+        }
+
+        let mut tail = result.chars().rev();
+        let last = tail.next().unwrap_or(' ');
+        let before_last = tail.next().unwrap_or(' ');
+        if last == '-' && before_last.is_alphabetic() && next_first.is_lowercase() {
+            result.pop();
+            result.push_str(line);
+        } else if no_space || (is_cjk(last) && is_cjk(next_first)) {
+            result.push_str(line);
+        } else {
+            result.push(' ');
+            result.push_str(line);
+        }
+    }
+    result
+}
@@ -0,0 +1,108 @@
+use crossbeam_channel::Sender;
+use log::{debug, info};
+use std::path::Path;
+
+use super::{ReflowCache, ReflowPipeline};
+use crate::decoder::Decoder;
+use crate::entity::ReflowEntry;
+use crate::settings::AppSettings;
+
+/// 流式reflow提取任务
+/// 在解码线程上每次处理一页，先从起始页往后提取并立即推送给消费者，
+/// 再回头补齐起始页之前的页面，最终写出完整缓存
+pub struct ReflowJob {
+    cache: ReflowCache,
+    pipeline: ReflowPipeline,
+    start_page: usize,
+    page_count: usize,
+    /// 已处理的页数（按提取顺序计）
+    cursor: usize,
+    entry_tx: Option<Sender<ReflowEntry>>,
+}
+
+impl ReflowJob {
+    pub fn new(path: &Path, page_count: usize, start_page: usize, entry_tx: Sender<ReflowEntry>) -> anyhow::Result<Self> {
+        let cache = ReflowCache::open(path)?;
+        let settings = AppSettings::book(&path.to_string_lossy());
+        info!("[Reflow] 开始流式提取: start_page={}, page_count={}, cached_complete={}",
+            start_page, page_count, cache.is_complete());
+        Ok(Self {
+            cache,
+            pipeline: ReflowPipeline::new(&settings, &AppSettings::get().tts),
+            start_page: start_page.min(page_count),
+            page_count,
+            cursor: 0,
+            entry_tx: Some(entry_tx),
+        })
+    }
+
+    /// 按提取顺序把游标映射为页码：先 start_page..page_count，再 0..start_page
+    fn page_at(&self, cursor: usize) -> usize {
+        let tail = self.page_count - self.start_page;
+        if cursor < tail {
+            self.start_page + cursor
+        } else {
+            cursor - tail
+        }
+    }
+
+    /// 消费者是否还在接收
+    pub fn has_consumer(&self) -> bool {
+        self.entry_tx.is_some()
+    }
+
+    /// 停止推送，剩余页面继续在后台补齐缓存
+    pub fn detach(&mut self) {
+        self.entry_tx = None;
+    }
+
+    /// 处理一页，返回 false 表示任务已结束
+    pub fn step(&mut self, decoder: &dyn Decoder) -> bool {
+        if self.cursor >= self.page_count {
+            if !self.cache.is_complete() {
+                if let Err(e) = self.cache.finish() {
+                    info!("[Reflow] 写入缓存失败: {}", e);
+                }
+            }
+            info!("[Reflow] 流式提取结束");
+            return false;
+        }
+
+        let page = self.page_at(self.cursor);
+        self.cursor += 1;
+
+        let entry = if self.cache.contains_page(page) {
+            self.cache.get(page).cloned()
+        } else {
+            match ReflowCache::extract_page(decoder, page) {
+                Ok(entry) => {
+                    if let Err(e) = self.cache.insert(page, entry.clone()) {
+                        info!("[Reflow] 写入缓存失败: {}", e);
+                    }
+                    entry
+                }
+                Err(e) => {
+                    info!("[Reflow] 页面 {} 文本提取失败: {}", page, e);
+                    None
+                }
+            }
+        };
+
+        // 起始页之前的页面只补缓存，不推送
+        if page >= self.start_page {
+            let entry = entry.and_then(|e| self.pipeline.process(e));
+            if let (Some(entry), Some(tx)) = (entry, self.entry_tx.as_ref()) {
+                debug!("[Reflow] 推送页面 {}", page);
+                if tx.send(entry).is_err() {
+                    debug!("[Reflow] 消费者已关闭，继续在后台补齐缓存");
+                    self.entry_tx = None;
+                }
+            }
+        } else if self.entry_tx.is_some() {
+            // 起始页之后的内容都已推送完毕
+            self.entry_tx = None;
+        }
+
+        true
+    }
+}
@@ -0,0 +1,66 @@
+/// 各拉丁语言的常见虚词，用于区分同为拉丁字母的语言
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "of", "to", "is", "in", "that", "it", "with", "for"]),
+    ("fr", &["le", "la", "les", "et", "des", "est", "une", "dans", "pour", "que"]),
+    ("de", &["der", "die", "und", "das", "ist", "nicht", "mit", "den", "ein", "zu"]),
+    ("es", &["el", "los", "las", "y", "que", "es", "por", "una", "con", "para"]),
+    ("it", &["il", "di", "che", "e", "per", "non", "una", "sono", "della", "gli"]),
+    ("pt", &["o", "os", "que", "e", "do", "da", "em", "um", "para", "não"]),
+];
+
+/// 检测文本的主要语言，返回 ISO 639-1 代码
+/// 先按文字系统判断，拉丁字母再按虚词出现次数区分
+pub fn detect_language(text: &str) -> Option<String> {
+    let mut han = 0;
+    let mut kana = 0;
+    let mut hangul = 0;
+    let mut cyrillic = 0;
+    let mut arabic = 0;
+    let mut greek = 0;
+    let mut latin = 0;
+
+    for c in text.chars() {
+        match c as u32 {
+            0x3040..=0x30FF => kana += 1,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => han += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            0x0600..=0x06FF => arabic += 1,
+            0x0370..=0x03FF => greek += 1,
+            _ if c.is_ascii_alphabetic() || matches!(c as u32, 0x00C0..=0x024F) => latin += 1,
+            _ => {}
+        }
+    }
+
+    let scripts = [
+        ("ja", kana + if kana > 0 { han } else { 0 }),
+        ("zh", if kana > 0 { 0 } else { han }),
+        ("ko", hangul),
+        ("ru", cyrillic),
+        ("ar", arabic),
+        ("el", greek),
+    ];
+    // 汉字一个字就是一个词，给予更高权重，避免中英混排时被字母数量压过
+    let (script, count) = scripts.iter()
+        .max_by_key(|(_, count)| *count)
+        .copied()
+        .unwrap_or(("", 0));
+    if count > 0 && count * 3 >= latin {
+        return Some(script.to_string());
+    }
+    if latin == 0 {
+        return None;
+    }
+
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    STOPWORDS.iter()
+        .map(|(lang, stopwords)| (lang, words.iter().filter(|w| stopwords.contains(w)).count()))
+        .max_by_key(|&(_, hits)| hits)
+        .filter(|&(_, hits)| hits > 0)
+        .map(|(lang, _)| lang.to_string())
+        .or_else(|| Some("en".to_string()))
+}
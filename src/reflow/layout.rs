@@ -0,0 +1,186 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::decoder::{TextBlock, TextLine};
+use crate::entity::{ReflowBlock, ReflowBlockType};
+
+/// 字号达到正文的这个倍数视为标题
+const HEADING_RATIO: f32 = 1.2;
+/// 标题最多几行
+const HEADING_MAX_LINES: usize = 3;
+/// 行间空白超过行高的这个倍数视为新段落
+const PARAGRAPH_GAP_RATIO: f32 = 0.8;
+/// 首行缩进超过字号的这个倍数视为新段落
+const PARAGRAPH_INDENT_RATIO: f32 = 1.5;
+/// 宽度小于页宽这个比例的块才参与分栏检测
+const COLUMN_MAX_WIDTH_RATIO: f32 = 0.6;
+
+/// 把底层文本块整理成按阅读顺序排列的段落/标题
+pub fn build_reflow_blocks(blocks: &[TextBlock], page_width: f32, page_height: f32) -> Vec<ReflowBlock> {
+    let body_size = body_font_size(blocks);
+    let page_height = if page_height > 0.0 { page_height } else { 1.0 };
+
+    let mut result = Vec::new();
+    for block in order_blocks(blocks, page_width) {
+        for lines in split_paragraphs(block) {
+            let text = lines.iter()
+                .map(|line| line.text.trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if text.is_empty() {
+                continue;
+            }
+
+            let font_size = average_font_size(lines);
+            let block_type = if body_size > 0.0
+                && font_size >= body_size * HEADING_RATIO
+                && lines.len() <= HEADING_MAX_LINES {
+                ReflowBlockType::Heading
+            } else {
+                ReflowBlockType::Paragraph
+            };
+
+            let top = lines.iter().map(|l| l.bounds.top).fold(f32::MAX, f32::min);
+            let bottom = lines.iter().map(|l| l.bounds.bottom).fold(f32::MIN, f32::max);
+            result.push(ReflowBlock {
+                block_type,
+                text,
+                top: (top / page_height).clamp(0.0, 1.0),
+                bottom: (bottom / page_height).clamp(0.0, 1.0),
+                font_size,
+            });
+        }
+    }
+    result
+}
+
+/// 把结构化块拼成整页文本，块之间空一行
+pub fn join_blocks(blocks: &[ReflowBlock]) -> String {
+    blocks.iter()
+        .map(|b| b.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 正文字号：按字符数统计出现最多的字号（取整到 0.5pt）
+fn body_font_size(blocks: &[TextBlock]) -> f32 {
+    let mut counts: HashMap<i32, usize> = HashMap::new();
+    for line in blocks.iter().flat_map(|b| b.lines.iter()) {
+        if line.font_size > 0.0 {
+            *counts.entry((line.font_size * 2.0).round() as i32).or_default() += line.text.chars().count();
+        }
+    }
+    counts.into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(size, _)| size as f32 / 2.0)
+        .unwrap_or(0.0)
+}
+
+fn average_font_size(lines: &[TextLine]) -> f32 {
+    let mut total = 0.0;
+    let mut count = 0usize;
+    for line in lines {
+        let chars = line.text.chars().count();
+        total += line.font_size * chars as f32;
+        count += chars;
+    }
+    if count == 0 { 0.0 } else { total / count as f32 }
+}
+
+/// 按阅读顺序排列文本块，检测到双栏时先读左栏再读右栏，通栏块作为分隔
+fn order_blocks(blocks: &[TextBlock], page_width: f32) -> Vec<&TextBlock> {
+    let mut sorted: Vec<&TextBlock> = blocks.iter().collect();
+    sorted.sort_by(|a, b| {
+        a.bounds.top.partial_cmp(&b.bounds.top)
+            .unwrap_or(Ordering::Equal)
+            .then(a.bounds.left.partial_cmp(&b.bounds.left).unwrap_or(Ordering::Equal))
+    });
+
+    let Some(gutter) = find_gutter(&sorted, page_width) else {
+        return sorted;
+    };
+
+    let mut result = Vec::with_capacity(sorted.len());
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    for block in sorted {
+        if block.bounds.right <= gutter {
+            left.push(block);
+        } else if block.bounds.left >= gutter {
+            right.push(block);
+        } else {
+            // 跨栏的块（标题、通栏图注）：先输出它上方的两栏内容
+            result.append(&mut left);
+            result.append(&mut right);
+            result.push(block);
+        }
+    }
+    result.append(&mut left);
+    result.append(&mut right);
+    result
+}
+
+/// 在页面中部寻找没有被窄块跨越的竖直空白作为栏间距
+fn find_gutter(blocks: &[&TextBlock], page_width: f32) -> Option<f32> {
+    if page_width <= 0.0 {
+        return None;
+    }
+    let narrow: Vec<&&TextBlock> = blocks.iter()
+        .filter(|b| b.bounds.width() < page_width * COLUMN_MAX_WIDTH_RATIO)
+        .collect();
+    if narrow.len() < 2 {
+        return None;
+    }
+
+    const STEPS: usize = 40;
+    let mut best: Option<(f32, usize)> = None;
+    for i in 0..=STEPS {
+        let x = page_width * (0.3 + 0.4 * i as f32 / STEPS as f32);
+        if narrow.iter().any(|b| b.bounds.left < x && b.bounds.right > x) {
+            continue;
+        }
+        let left = narrow.iter().filter(|b| b.bounds.right <= x).count();
+        let right = narrow.len() - left;
+        if left == 0 || right == 0 {
+            continue;
+        }
+        // 两侧块数越均衡越像真正的分栏
+        let balance = left.min(right);
+        if best.map_or(true, |(_, b)| balance > b) {
+            best = Some((x, balance));
+        }
+    }
+    best.map(|(x, _)| x)
+}
+
+/// 按行距和首行缩进把一个块拆成多个段落
+fn split_paragraphs(block: &TextBlock) -> Vec<&[TextLine]> {
+    let lines = &block.lines;
+    let mut result = Vec::new();
+    let mut start = 0;
+    for i in 1..lines.len() {
+        let prev = &lines[i - 1];
+        let line = &lines[i];
+        let line_height = prev.bounds.height().max(1.0);
+        let gap = line.bounds.top - prev.bounds.bottom;
+        let indent = line.bounds.left - block.bounds.left;
+        let font_size = if line.font_size > 0.0 { line.font_size } else { line_height };
+
+        let large_gap = gap > line_height * PARAGRAPH_GAP_RATIO;
+        let indented = indent > font_size * PARAGRAPH_INDENT_RATIO
+            && (prev.bounds.right < block.bounds.right - font_size * 2.0
+                || ends_sentence(&prev.text));
+        if large_gap || indented {
+            result.push(&lines[start..i]);
+            start = i;
+        }
+    }
+    if start < lines.len() {
+        result.push(&lines[start..]);
+    }
+    result
+}
+
+fn ends_sentence(text: &str) -> bool {
+    matches!(text.trim_end().chars().last(), Some('.' | '!' | '?' | ':' | '。' | '！' | '？' | '：' | '"' | '”'))
+}
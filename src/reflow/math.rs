@@ -0,0 +1,184 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// 整块判定为公式的数学符号密度阈值
+const BLOCK_DENSITY_THRESHOLD: f32 = 0.3;
+/// 行内连续多少个数学记号才视为公式
+const INLINE_MIN_TOKENS: usize = 3;
+
+static LATEX_INLINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\$?[^$]+\$\$?").unwrap());
+static LATEX_COMMAND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\[a-zA-Z]+(\{[^}]*\})*").unwrap());
+
+/// 朗读时如何处理公式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MathSpeechMode {
+    /// 原样朗读
+    Read,
+    /// 跳过不读
+    Skip,
+    /// 用占位词代替，如 "equation"
+    #[default]
+    Describe,
+}
+
+fn is_math_char(c: char) -> bool {
+    matches!(c as u32,
+        0x2200..=0x22FF      // 数学运算符
+        | 0x2A00..=0x2AFF    // 补充数学运算符
+        | 0x2070..=0x209F    // 上下标
+        | 0x1D400..=0x1D7FF) // 数学字母数字符号
+        || matches!(c, '=' | '+' | '^' | '_' | '\\' | '{' | '}' | '|' | '<' | '>' | '±' | '×' | '÷' | '√' | '∞' | '·')
+}
+
+fn is_greek(c: char) -> bool {
+    matches!(c as u32, 0x0391..=0x03C9)
+}
+
+fn is_operator(c: char) -> bool {
+    is_math_char(c) && !matches!(c, '_' | '\\' | '{' | '}')
+}
+
+/// 记号中的希腊字母是否算作数学符号：单独的 α、β，或与数字、运算符写在一起（2π、α+β）。
+/// 希腊文的普通单词不算，否则整段希腊文会被当成公式
+fn counts_greek(token: &str) -> bool {
+    let letters = token.chars().filter(|c| c.is_alphabetic()).count();
+    letters <= 2 || token.chars().any(|c| c.is_ascii_digit() || is_operator(c))
+}
+
+/// 是否是真正连接两边的运算符：单独的运算符记号（=、+、≤），或夹在字母数字之间（a+b、x=2）。
+/// “C++” 这类末尾的符号不算
+fn has_infix_operator(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().filter(|c| !"()[],.".contains(*c)).collect();
+    if !chars.is_empty() && chars.iter().all(|c| is_operator(*c)) {
+        return true;
+    }
+    chars.iter().enumerate().any(|(i, c)| {
+        is_operator(*c)
+            && chars[..i].iter().any(|c| c.is_alphanumeric())
+            && chars[i + 1..].iter().any(|c| c.is_alphanumeric())
+    })
+}
+
+/// 数学符号占非空白字符的比例
+pub fn math_density(text: &str) -> f32 {
+    let mut total = 0;
+    let mut math = 0;
+    for token in text.split_whitespace() {
+        let greek = counts_greek(token);
+        for c in token.chars() {
+            total += 1;
+            if is_math_char(c) || (greek && is_greek(c)) {
+                math += 1;
+            }
+        }
+    }
+    if total == 0 { 0.0 } else { math as f32 / total as f32 }
+}
+
+/// 整块是否是公式：符号密度高，或由大量单字符记号和运算符组成
+pub fn is_math_block(text: &str) -> bool {
+    if math_density(text) >= BLOCK_DENSITY_THRESHOLD {
+        return true;
+    }
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() < INLINE_MIN_TOKENS {
+        return false;
+    }
+    let short = tokens.iter().filter(|t| t.chars().count() <= 2).count();
+    short * 10 >= tokens.len() * 7 && tokens.iter().any(|t| has_infix_operator(t))
+}
+
+/// 单个记号是否像公式的一部分
+fn is_math_token(token: &str) -> bool {
+    let count = token.chars().count();
+    token.chars().any(is_math_char)
+        || (counts_greek(token) && token.chars().any(is_greek))
+        || (count <= 2 && token.chars().all(|c| c.is_ascii_alphanumeric() || "()[],.".contains(c)))
+}
+
+/// 一串数学记号中真正的公式部分：从第一个运算符的左操作数到最后一个运算符的右操作数，
+/// 两边的短词（I、am、in）留给朗读；没有运算符时返回 None
+fn formula_span(run: &[&str]) -> Option<(usize, usize)> {
+    let first = run.iter().position(|t| has_infix_operator(t))?;
+    let last = run.iter().rposition(|t| has_infix_operator(t))?;
+    let standalone = |t: &str| t.chars().all(|c| is_operator(c) || "()[],.".contains(c));
+    let start = if standalone(run[first]) { first.saturating_sub(1) } else { first };
+    let end = if standalone(run[last]) { (last + 1).min(run.len() - 1) } else { last };
+    Some((start, end))
+}
+
+/// 替换行内公式：LaTeX 片段，以及连续的数学记号（至少包含一个运算符）
+pub fn replace_inline_math(text: &str, mode: MathSpeechMode, placeholder: &str) -> String {
+    if mode == MathSpeechMode::Read {
+        return text.to_string();
+    }
+    let replacement = if mode == MathSpeechMode::Skip { " " } else { placeholder };
+    let text = LATEX_INLINE.replace_all(text, replacement);
+    let text = LATEX_COMMAND.replace_all(&text, replacement);
+
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut result: Vec<&str> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut end = i;
+        while end < tokens.len() && is_math_token(tokens[end]) {
+            end += 1;
+        }
+        let run = &tokens[i..end];
+        let span = formula_span(run).filter(|(start, last)| last - start + 1 >= INLINE_MIN_TOKENS);
+        if let Some((start, last)) = span {
+            result.extend_from_slice(&run[..start]);
+            if mode == MathSpeechMode::Describe {
+                result.push(placeholder);
+            }
+            result.extend_from_slice(&run[last + 1..]);
+            i = end;
+        } else if end > i {
+            result.extend_from_slice(run);
+            i = end;
+        } else {
+            result.push(tokens[i]);
+            i += 1;
+        }
+    }
+    result.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greek_prose_is_not_math() {
+        let text = "Η γλώσσα είναι το κύριο μέσο επικοινωνίας, όπως έγραψε ο συγγραφέας το 1990.";
+        assert!(math_density(text) < BLOCK_DENSITY_THRESHOLD);
+        assert!(!is_math_block(text));
+        assert_eq!(replace_inline_math(text, MathSpeechMode::Describe, "equation"), text);
+    }
+
+    #[test]
+    fn greek_symbols_with_operators_are_math() {
+        assert!(is_math_block("α + β = γ"));
+        assert!(is_math_block("2π r ≤ θ"));
+    }
+
+    #[test]
+    fn trailing_plus_is_not_an_operator() {
+        let text = "I am in C++ now";
+        assert!(!is_math_block(text));
+        assert_eq!(replace_inline_math(text, MathSpeechMode::Describe, "equation"), text);
+    }
+
+    #[test]
+    fn short_equation_is_replaced_without_surrounding_words() {
+        assert!(is_math_block("a = b"));
+        assert_eq!(
+            replace_inline_math("so we get a = b in the end", MathSpeechMode::Describe, "equation"),
+            "so we get equation in the end"
+        );
+        assert_eq!(replace_inline_math("I am in a = b", MathSpeechMode::Skip, "equation"), "I am in");
+        assert_eq!(replace_inline_math("a = b", MathSpeechMode::Read, "equation"), "a = b");
+    }
+}
@@ -0,0 +1,12 @@
+pub mod cache;
+pub mod filter;
+pub mod hyphenation;
+pub mod job;
+pub mod language;
+pub mod layout;
+pub mod math;
+pub mod pipeline;
+
+pub use cache::ReflowCache;
+pub use job::ReflowJob;
+pub use pipeline::ReflowPipeline;
@@ -0,0 +1,66 @@
+use super::filter::HeaderFooterFilter;
+use super::hyphenation::join_lines;
+use super::layout::join_blocks;
+use super::math::{is_math_block, replace_inline_math, MathSpeechMode};
+use crate::entity::{ReflowBlock, ReflowBlockType, ReflowEntry};
+use crate::settings::{BookSettings, TtsSettings};
+
+/// reflow 后处理流水线
+/// 缓存中保存的是原始提取结果，推送给 TTS 等消费者之前按书的设置逐页清理
+pub struct ReflowPipeline {
+    header_footer: HeaderFooterFilter,
+    math_mode: MathSpeechMode,
+    math_placeholder: String,
+}
+
+impl ReflowPipeline {
+    pub fn new(settings: &BookSettings, tts: &TtsSettings) -> Self {
+        Self {
+            header_footer: HeaderFooterFilter::new(settings.strip_headers_footers, settings.strip_footnotes),
+            math_mode: tts.math_mode,
+            math_placeholder: tts.math_placeholder.clone(),
+        }
+    }
+
+    /// 按朗读设置处理公式：整块公式标记为 Math 并跳过或替换，行内公式就地替换
+    fn process_math(&self, blocks: &mut Vec<ReflowBlock>) {
+        for block in blocks.iter_mut() {
+            if block.block_type == ReflowBlockType::Paragraph && is_math_block(&block.text) {
+                block.block_type = ReflowBlockType::Math;
+            }
+        }
+        match self.math_mode {
+            MathSpeechMode::Read => {}
+            MathSpeechMode::Skip => blocks.retain(|b| b.block_type != ReflowBlockType::Math),
+            MathSpeechMode::Describe => {
+                for block in blocks.iter_mut().filter(|b| b.block_type == ReflowBlockType::Math) {
+                    block.text = self.math_placeholder.clone();
+                }
+            }
+        }
+        for block in blocks.iter_mut().filter(|b| b.block_type != ReflowBlockType::Math) {
+            block.text = replace_inline_math(&block.text, self.math_mode, &self.math_placeholder);
+        }
+    }
+
+    /// 处理一页，清理后没有内容的页面返回 None
+    pub fn process(&mut self, mut entry: ReflowEntry) -> Option<ReflowEntry> {
+        if entry.blocks.is_empty() {
+            return Some(entry);
+        }
+
+        entry.blocks = self.header_footer.filter(entry.blocks);
+        // 合并硬换行和断词，一个块变成一段连续的文字
+        for block in &mut entry.blocks {
+            block.text = join_lines(&block.text, entry.language.as_deref());
+        }
+        self.process_math(&mut entry.blocks);
+        entry.data = join_blocks(&entry.blocks);
+
+        if entry.data.trim().is_empty() {
+            None
+        } else {
+            Some(entry)
+        }
+    }
+}
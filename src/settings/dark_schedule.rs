@@ -0,0 +1,147 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 定时深色的依据
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleKind {
+    /// 固定时间
+    #[default]
+    Fixed,
+    /// 按经纬度计算的日落到日出
+    Sun,
+}
+
+/// 定时深色设置：固定时间段或日落到日出
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DarkSchedule {
+    pub kind: ScheduleKind,
+    /// 开始深色的时间，"HH:MM"
+    pub dark_from: String,
+    /// 恢复浅色的时间，"HH:MM"
+    pub dark_until: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Default for DarkSchedule {
+    fn default() -> Self {
+        Self {
+            kind: ScheduleKind::Fixed,
+            dark_from: "20:00".to_string(),
+            dark_until: "07:00".to_string(),
+            latitude: 0.0,
+            longitude: 0.0,
+        }
+    }
+}
+
+/// 某一天的深色时段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DarkWindow {
+    /// 极夜
+    Always,
+    /// 极昼
+    Never,
+    Between(NaiveTime, NaiveTime),
+}
+
+impl DarkSchedule {
+    /// 该时刻按计划是否应为深色
+    pub fn is_dark_at(&self, now: DateTime<Local>) -> bool {
+        let time = now.time();
+        match self.dark_window(now.date_naive()) {
+            DarkWindow::Always => true,
+            DarkWindow::Never => false,
+            // 跨午夜的时段，如 20:00 - 07:00
+            DarkWindow::Between(from, until) if from > until => time >= from || time < until,
+            DarkWindow::Between(from, until) => time >= from && time < until,
+        }
+    }
+
+    fn dark_window(&self, date: NaiveDate) -> DarkWindow {
+        match self.kind {
+            ScheduleKind::Fixed => {
+                let parse = |text: &str, fallback: &str| NaiveTime::parse_from_str(text.trim(), "%H:%M")
+                    .unwrap_or_else(|_| NaiveTime::parse_from_str(fallback, "%H:%M").unwrap());
+                DarkWindow::Between(parse(&self.dark_from, "20:00"), parse(&self.dark_until, "07:00"))
+            }
+            ScheduleKind::Sun => {
+                let sunrise = sun_event_utc(date, self.latitude, self.longitude, true);
+                let sunset = sun_event_utc(date, self.latitude, self.longitude, false);
+                match (sunrise, sunset) {
+                    (SunEvent::At(rise), SunEvent::At(set)) => {
+                        DarkWindow::Between(to_local_time(date, set), to_local_time(date, rise))
+                    }
+                    (SunEvent::NeverRises, _) | (_, SunEvent::NeverRises) => DarkWindow::Always,
+                    _ => DarkWindow::Never,
+                }
+            }
+        }
+    }
+}
+
+enum SunEvent {
+    /// UTC 小时数
+    At(f64),
+    NeverRises,
+    NeverSets,
+}
+
+/// 日出日落时间（NOAA 简化算法，误差在几分钟内）
+fn sun_event_utc(date: NaiveDate, latitude: f64, longitude: f64, sunrise: bool) -> SunEvent {
+    // 包含大气折射的天顶角
+    const ZENITH: f64 = 90.833;
+    let day = date.ordinal() as f64;
+    let lng_hour = longitude / 15.0;
+    let t = day + ((if sunrise { 6.0 } else { 18.0 }) - lng_hour) / 24.0;
+
+    let mean_anomaly = 0.9856 * t - 3.289;
+    let true_longitude = (mean_anomaly + 1.916 * mean_anomaly.to_radians().sin()
+        + 0.020 * (2.0 * mean_anomaly).to_radians().sin() + 282.634).rem_euclid(360.0);
+    let mut right_ascension = (0.91764 * true_longitude.to_radians().tan()).atan().to_degrees().rem_euclid(360.0);
+    // 赤经与黄经在同一象限
+    right_ascension += (true_longitude / 90.0).floor() * 90.0 - (right_ascension / 90.0).floor() * 90.0;
+    let right_ascension = right_ascension / 15.0;
+
+    let sin_dec = 0.39782 * true_longitude.to_radians().sin();
+    let cos_dec = sin_dec.asin().cos();
+    let cos_hour = (ZENITH.to_radians().cos() - sin_dec * latitude.to_radians().sin())
+        / (cos_dec * latitude.to_radians().cos());
+    if cos_hour > 1.0 {
+        return SunEvent::NeverRises;
+    }
+    if cos_hour < -1.0 {
+        return SunEvent::NeverSets;
+    }
+    let hour_angle = if sunrise { 360.0 - cos_hour.acos().to_degrees() } else { cos_hour.acos().to_degrees() } / 15.0;
+    let local_mean_time = hour_angle + right_ascension - 0.06571 * t - 6.622;
+    SunEvent::At((local_mean_time - lng_hour).rem_euclid(24.0))
+}
+
+fn to_local_time(date: NaiveDate, utc_hours: f64) -> NaiveTime {
+    let seconds = (utc_hours * 3600.0).round() as i64;
+    let utc = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()) + chrono::Duration::seconds(seconds);
+    utc.with_timezone(&Local).time()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sun_times_for_equator_equinox() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let SunEvent::At(rise) = sun_event_utc(date, 0.0, 0.0, true) else { panic!("no sunrise") };
+        let SunEvent::At(set) = sun_event_utc(date, 0.0, 0.0, false) else { panic!("no sunset") };
+        assert!((rise - 6.0).abs() < 0.25, "sunrise {}", rise);
+        assert!((set - 18.15).abs() < 0.25, "sunset {}", set);
+    }
+
+    #[test]
+    fn polar_night_is_always_dark() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert!(matches!(sun_event_utc(date, 78.0, 15.0, true), SunEvent::NeverRises));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod app_settings;
+pub mod dark_schedule;
+
+pub use app_settings::{
+    AccessibilitySettings, AppSettings, AutoTurnSettings, BookSettings, HistoryExportSettings, HomeSettings, LibrarySettings, MemorySettings, OrderedItem, PowerMode, ProxyMode, ProxySettings, ReadingTimerSettings, ShareSettings, SimpleModeSettings, AssistantSettings, TextLayoutSettings, ThemeMode, ThemeSettings, ToolbarSettings, TtsSettings,
+    ViewMode, WatermarkSettings, WindowProfile,
+};
+pub use dark_schedule::{DarkSchedule, ScheduleKind};
@@ -0,0 +1,65 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Result};
+
+/// AES-256 密钥长度
+pub const KEY_LEN: usize = 32;
+
+/// 加密文件头，读取时据此区分加密文件和旧的明文文件
+const MAGIC: &[u8] = b"RRENC1";
+const NONCE_LEN: usize = 12;
+
+pub fn generate_key() -> [u8; KEY_LEN] {
+    Aes256Gcm::generate_key(OsRng).into()
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// 格式：文件头 + 随机 nonce + 密文（含认证标签）
+pub fn encrypt(key: &[u8; KEY_LEN], plain: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let sealed = cipher.encrypt(&nonce, plain).map_err(|_| anyhow!("加密失败"))?;
+    let mut data = Vec::with_capacity(MAGIC.len() + NONCE_LEN + sealed.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&sealed);
+    Ok(data)
+}
+
+pub fn decrypt(key: &[u8; KEY_LEN], data: &[u8]) -> Result<Vec<u8>> {
+    if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN {
+        bail!("不是加密文件");
+    }
+    let (nonce, sealed) = data[MAGIC.len()..].split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher.decrypt(Nonce::from_slice(nonce), sealed)
+        .map_err(|_| anyhow!("解密失败，密钥不匹配或文件已损坏"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let key = generate_key();
+        let data = encrypt(&key, b"{\"books\":{}}").unwrap();
+        assert!(is_encrypted(&data));
+        assert_eq!(decrypt(&key, &data).unwrap(), b"{\"books\":{}}");
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let data = encrypt(&generate_key(), b"secret").unwrap();
+        assert!(decrypt(&generate_key(), &data).is_err());
+    }
+
+    #[test]
+    fn plain_data_is_not_encrypted() {
+        assert!(!is_encrypted(b"{\"books\":{}}"));
+        assert!(decrypt(&generate_key(), b"plain").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use log::{info, warn};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::FileStore;
+
+/// 加密模式下数据库平时只以 book.db.enc 存在，运行时解密到原路径，退出时重新加密并删除明文。
+/// SQLite 本身不支持加密，这样不必更换数据库驱动；代价是运行期间磁盘上有明文数据库，
+/// 异常退出（release 版 panic=abort 不执行退出流程）留下的明文在下次启动时先重新加密再删除
+pub struct DatabaseVault;
+
+impl DatabaseVault {
+    fn sealed_path(db_path: &Path) -> PathBuf {
+        Self::suffixed(db_path, ".enc")
+    }
+
+    fn suffixed(db_path: &Path, suffix: &str) -> PathBuf {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+
+    /// 开启了加密，启动时却有明文数据库：上次没有正常退出。
+    /// 这时要先合并 WAL（见 dao::checkpoint_database），再 seal，之后才能 unseal
+    pub fn has_leftover_plaintext(db_path: &Path) -> bool {
+        let leftover = FileStore::is_encrypted() && db_path.exists();
+        if leftover {
+            warn!("[Storage] 发现上次异常退出留下的明文数据库: {:?}", db_path);
+        }
+        leftover
+    }
+
+    /// 启动时在连接数据库之前调用
+    pub fn unseal(db_path: &Path) -> io::Result<()> {
+        let sealed = Self::sealed_path(db_path);
+        if !sealed.is_file() {
+            return Ok(());
+        }
+        if db_path.exists() {
+            // 运行中关闭了加密后异常退出，明文数据库比加密文件新
+            warn!("[Storage] 加密已关闭，继续使用明文数据库: {:?}", db_path);
+            return Ok(());
+        }
+        fs::write(db_path, FileStore::read(&sealed)?)?;
+        info!("[Storage] 数据库已解密: {:?}", db_path);
+        Ok(())
+    }
+
+    /// 退出时在关闭数据库连接之后调用。WAL 中还有数据时说明连接没有关闭，
+    /// 这时加密主文件会丢失已提交的数据，直接报错并保留明文
+    pub fn seal(db_path: &Path) -> io::Result<()> {
+        let sealed = Self::sealed_path(db_path);
+        if !db_path.exists() {
+            return Ok(());
+        }
+        if !FileStore::is_encrypted() {
+            // 运行中关闭了加密，明文数据库已是最新
+            if sealed.exists() {
+                fs::remove_file(&sealed)?;
+            }
+            return Ok(());
+        }
+        let wal = Self::suffixed(db_path, "-wal");
+        if wal.metadata().is_ok_and(|metadata| metadata.len() > 0) {
+            return Err(io::Error::other(format!("{:?} 中还有未合并的数据，数据库连接可能没有关闭", wal)));
+        }
+        FileStore::write(&sealed, fs::read(db_path)?)?;
+        for suffix in ["", "-wal", "-shm"] {
+            let path = Self::suffixed(db_path, suffix);
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    return Err(e);
+                }
+            }
+        }
+        info!("[Storage] 数据库已加密: {:?}", sealed);
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use log::{error, info, warn};
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use super::cipher::{self, KEY_LEN};
+use super::keychain;
+use crate::app_paths;
+use crate::settings::AppSettings;
+
+/// 是否开启了书库加密，以数据目录中的标记文件为准
+static ENCRYPTED: AtomicBool = AtomicBool::new(false);
+/// 书库密钥，从系统钥匙串读取
+static KEY: RwLock<Option<[u8; KEY_LEN]>> = RwLock::new(None);
+
+/// 设置、缓存等文件的读写入口：开启加密后写入的文件都经过 AES-GCM 加密，
+/// 读取时按文件头判断，开启前写下的明文文件仍能读取
+pub struct FileStore;
+
+impl FileStore {
+    /// 启动时调用一次，要在加载设置和打开数据库之前
+    pub fn init() {
+        let enabled = app_paths::encryption_marker_path().is_file();
+        ENCRYPTED.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            return;
+        }
+        match keychain::load_key() {
+            Ok(Some(key)) => {
+                *KEY.write().unwrap() = Some(key);
+                info!("[Storage] 书库已加密，密钥已从钥匙串读取");
+            }
+            Ok(None) => error!("[Storage] 书库已加密，但钥匙串中没有密钥"),
+            Err(e) => error!("[Storage] 读取钥匙串失败: {}", e),
+        }
+    }
+
+    pub fn is_encrypted() -> bool {
+        ENCRYPTED.load(Ordering::Relaxed)
+    }
+
+    /// 开启了加密但拿不到密钥，这时不能读写任何数据，避免用默认值覆盖加密文件
+    pub fn is_locked() -> bool {
+        Self::is_encrypted() && KEY.read().unwrap().is_none()
+    }
+
+    fn key() -> io::Result<[u8; KEY_LEN]> {
+        KEY.read().unwrap().ok_or_else(|| io::Error::new(io::ErrorKind::PermissionDenied, "书库已加密，密钥不可用"))
+    }
+
+    pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+        let data = fs::read(path)?;
+        if !cipher::is_encrypted(&data) {
+            return Ok(data);
+        }
+        cipher::decrypt(&Self::key()?, &data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    pub fn read_to_string(path: &Path) -> io::Result<String> {
+        String::from_utf8(Self::read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// 先写临时文件再替换，避免中途退出留下半个文件
+    pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let data = Self::seal(contents.as_ref())?;
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    fn seal(contents: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+        if !Self::is_encrypted() {
+            return Ok(Cow::Borrowed(contents));
+        }
+        cipher::encrypt(&Self::key()?, contents)
+            .map(Cow::Owned)
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+
+    /// 开关书库加密：缓存都能重新生成，直接清空；设置文件按新状态重写；
+    /// 数据库在退出时由 DatabaseVault 按新状态保存
+    pub fn set_encrypted(enabled: bool) -> anyhow::Result<()> {
+        if enabled == Self::is_encrypted() {
+            return Ok(());
+        }
+        let marker = app_paths::encryption_marker_path();
+        if enabled {
+            let key = match keychain::load_key()? {
+                Some(key) => key,
+                None => {
+                    let key = cipher::generate_key();
+                    keychain::store_key(&key)?;
+                    key
+                }
+            };
+            *KEY.write().unwrap() = Some(key);
+            fs::write(&marker, "")?;
+        } else {
+            fs::remove_file(&marker)?;
+        }
+        ENCRYPTED.store(enabled, Ordering::Relaxed);
+
+        app_paths::clear_caches();
+        AppSettings::update(|_| {});
+        if !enabled {
+            // 明文数据库在退出时写回，之后不再需要密钥
+            *KEY.write().unwrap() = None;
+            if let Err(e) = keychain::delete_key() {
+                warn!("[Storage] 删除钥匙串中的密钥失败: {}", e);
+            }
+        }
+        info!("[Storage] 书库加密: {}", enabled);
+        Ok(())
+    }
+}
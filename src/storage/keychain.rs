@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use keyring::Entry;
+
+use super::cipher::KEY_LEN;
+
+/// 系统钥匙串中的条目：macOS Keychain、Windows 凭据管理器、Linux Secret Service
+const SERVICE: &str = "RReader";
+const ACCOUNT: &str = "library-key";
+/// AI 助手接口的 API key
+const ASSISTANT_ACCOUNT: &str = "assistant-api-key";
+
+fn entry() -> Result<Entry> {
+    Ok(Entry::new(SERVICE, ACCOUNT)?)
+}
+
+/// 读取书库密钥，钥匙串中没有时返回 None
+pub fn load_key() -> Result<Option<[u8; KEY_LEN]>> {
+    match entry()?.get_secret() {
+        Ok(secret) => {
+            let key: [u8; KEY_LEN] = secret.try_into().map_err(|_| anyhow!("钥匙串中的密钥长度不对"))?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn store_key(key: &[u8; KEY_LEN]) -> Result<()> {
+    Ok(entry()?.set_secret(key)?)
+}
+
+pub fn delete_key() -> Result<()> {
+    match entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 读取 AI 助手的 API key，钥匙串中没有时返回 None
+pub fn load_api_key() -> Result<Option<String>> {
+    match Entry::new(SERVICE, ASSISTANT_ACCOUNT)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn store_api_key(key: &str) -> Result<()> {
+    Ok(Entry::new(SERVICE, ASSISTANT_ACCOUNT)?.set_password(key)?)
+}
+
+pub fn delete_api_key() -> Result<()> {
+    match Entry::new(SERVICE, ASSISTANT_ACCOUNT)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cipher;
+pub mod database_vault;
+pub mod file_store;
+pub mod keychain;
+
+pub use database_vault::DatabaseVault;
+pub use file_store::FileStore;
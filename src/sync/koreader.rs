@@ -0,0 +1,279 @@
+use log::{debug, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{RReaderError, Result};
+use crate::sync::lua_table::{self, LuaTable, LuaValue};
+use crate::sync::{SyncBookmark, SyncRecord};
+
+/// 本程序写入的文件校验和，KOReader 不认识但会保留
+const SHA256_KEY: &str = "rreader_sha256";
+
+/// KOReader 的元数据侧车文件：书旁边的 <书名>.sdr/metadata.<扩展名>.lua
+/// KOReader 页码从 1 开始，这里转换为从 0 开始。
+/// 书签在旧版本中存放在 ["bookmarks"]，2024 年以后的版本改为 ["annotations"]，两种都能读写；
+/// 两者都还包含高亮，高亮带有 pos0，这里只处理不带位置的页面书签
+pub struct KoreaderSidecar;
+
+impl KoreaderSidecar {
+    pub fn sidecar_path(book_path: &Path) -> Option<PathBuf> {
+        let stem = book_path.file_stem()?.to_string_lossy().to_string();
+        let ext = book_path.extension()?.to_string_lossy().to_lowercase();
+        let dir = book_path.parent()?.join(format!("{}.sdr", stem));
+        Some(dir.join(format!("metadata.{}.lua", ext)))
+    }
+
+    /// 读取侧车文件，不存在时返回 None
+    pub fn import(book_path: &Path) -> Result<Option<SyncRecord>> {
+        let Some(path) = Self::sidecar_path(book_path) else { return Ok(None) };
+        if !path.is_file() {
+            debug!("[KOReader] 没有侧车文件: {:?}", path);
+            return Ok(None);
+        }
+        let table = Self::read(&path)?;
+        let record = Self::parse(&table).ok_or_else(|| invalid_data("KOReader metadata has no reading position".into()))?;
+        info!("[KOReader] 导入 {:?}: page={}, bookmarks={}", path, record.page, record.bookmarks.len());
+        Ok(Some(record))
+    }
+
+    /// 写回侧车文件：在原有内容上更新阅读位置并补上书签，KOReader 的高亮、设置等字段原样保留。
+    /// 本程序里删除的书签不会从侧车文件中删除，避免误删只在 KOReader 里加的书签
+    pub fn export(book_path: &Path, record: &SyncRecord) -> Result<PathBuf> {
+        let path = Self::sidecar_path(book_path)
+            .ok_or_else(|| RReaderError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid book path")))?;
+        // 解析不了时报错，不能用只有本程序字段的内容覆盖
+        let mut table = if path.is_file() { Self::read(&path)? } else { LuaTable::default() };
+        Self::merge(&mut table, record);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, lua_table::render(&table))?;
+        fs::rename(&tmp_path, &path)?;
+        info!("[KOReader] 导出 {:?}", path);
+        Ok(path)
+    }
+
+    fn read(path: &Path) -> Result<LuaTable> {
+        let content = fs::read_to_string(path)?;
+        lua_table::parse(&content).map_err(|e| invalid_data(format!("invalid KOReader metadata: {}", e)))
+    }
+
+    fn parse(table: &LuaTable) -> Option<SyncRecord> {
+        let number = |key: &str| table.get(key).and_then(LuaValue::as_f64);
+        let page_count = number("doc_pages").map(|pages| pages.max(0.0) as usize).unwrap_or(0);
+        let page = match number("last_page") {
+            Some(page) => (page.max(0.0) as usize).saturating_sub(1),
+            // 重排文档只有百分比
+            None => ((number("percent_finished")? * page_count as f64) as usize).saturating_sub(1),
+        };
+
+        let bookmarks = Self::bookmark_list(table)
+            .map(|(_, list)| {
+                list.items()
+                    .filter_map(LuaValue::as_table)
+                    .filter_map(|entry| {
+                        let page = page_bookmark(entry)?;
+                        let note = entry.get("note").or_else(|| entry.get("notes"))
+                            .and_then(LuaValue::as_str)
+                            .unwrap_or_default()
+                            .to_string();
+                        Some(SyncBookmark { page, note })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let sha256 = table.get(SHA256_KEY).and_then(LuaValue::as_str).unwrap_or_default().to_string();
+
+        Some(SyncRecord { page, page_count, bookmarks, sha256 })
+    }
+
+    /// 书签所在的表：新版本的 annotations 优先，其次是旧版本的 bookmarks
+    fn bookmark_list(table: &LuaTable) -> Option<(&'static str, &LuaTable)> {
+        ["annotations", "bookmarks"]
+            .into_iter()
+            .find_map(|key| table.get(key).and_then(LuaValue::as_table).map(|list| (key, list)))
+    }
+
+    fn merge(table: &mut LuaTable, record: &SyncRecord) {
+        table.set("doc_pages", LuaValue::number(record.page_count));
+        table.set("last_page", LuaValue::number(record.page + 1));
+        table.set("percent_finished", LuaValue::number(format!("{:.4}", record.percent_finished())));
+        if !record.sha256.is_empty() {
+            table.set(SHA256_KEY, LuaValue::String(record.sha256.clone()));
+        }
+        if record.bookmarks.is_empty() {
+            return;
+        }
+
+        // 新文件写旧格式，新旧版本的 KOReader 都能读取
+        let key = Self::bookmark_list(table).map(|(key, _)| key).unwrap_or("bookmarks");
+        let annotations = key == "annotations";
+        let note_key = if annotations { "note" } else { "notes" };
+        if table.get(key).and_then(LuaValue::as_table).is_none() {
+            table.set(key, LuaValue::Table(LuaTable::default()));
+        }
+        let Some(list) = table.get_mut(key).and_then(LuaValue::as_table_mut) else { return };
+
+        let datetime = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        for bookmark in &record.bookmarks {
+            let existing = list.items_mut()
+                .filter_map(LuaValue::as_table_mut)
+                .find(|entry| page_bookmark(entry) == Some(bookmark.page));
+            match existing {
+                Some(entry) => {
+                    if !bookmark.note.is_empty() {
+                        entry.set(note_key, LuaValue::String(bookmark.note.clone()));
+                    }
+                }
+                None => {
+                    let mut entry = LuaTable::default();
+                    entry.set("datetime", LuaValue::String(datetime.clone()));
+                    if !bookmark.note.is_empty() {
+                        entry.set(note_key, LuaValue::String(bookmark.note.clone()));
+                    }
+                    entry.set("page", LuaValue::number(bookmark.page + 1));
+                    if annotations {
+                        entry.set("pageno", LuaValue::number(bookmark.page + 1));
+                    }
+                    list.push(LuaValue::Table(entry));
+                }
+            }
+        }
+        sort_by_page(list, annotations);
+    }
+}
+
+/// 不带位置的页面书签的页码（从 0 开始）；高亮和重排文档中以 xpointer 记录位置的书签返回 None
+fn page_bookmark(entry: &LuaTable) -> Option<usize> {
+    let highlighted = entry.get("highlighted").and_then(LuaValue::as_bool).unwrap_or(false);
+    if highlighted || entry.get("pos0").is_some() {
+        return None;
+    }
+    let page = entry.get("page").and_then(LuaValue::as_f64)?;
+    (page >= 1.0).then(|| page as usize - 1)
+}
+
+/// KOReader 假定列表有序：annotations 按页码升序，旧的 bookmarks 按页码降序。
+/// 有条目没有数字页码（重排文档）时不排序
+fn sort_by_page(list: &mut LuaTable, ascending: bool) {
+    let page = |value: &LuaValue| value.as_table().and_then(|entry| entry.get("page")).and_then(LuaValue::as_f64);
+    if !list.items().all(|value| page(value).is_some()) {
+        return;
+    }
+    let mut items: Vec<LuaValue> = list.items().cloned().collect();
+    items.sort_by(|a, b| {
+        let order = page(a).partial_cmp(&page(b)).unwrap_or(std::cmp::Ordering::Equal);
+        if ascending { order } else { order.reverse() }
+    });
+    list.entries.retain(|(key, _)| !matches!(key, lua_table::LuaKey::Index(_)));
+    for item in items {
+        list.push(item);
+    }
+}
+
+fn invalid_data(message: String) -> RReaderError {
+    RReaderError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = r#"-- we can read Lua syntax here!
+return {
+    ["bookmarks"] = {
+        [1] = {
+            ["datetime"] = "2023-05-01 10:00:00",
+            ["highlighted"] = true,
+            ["notes"] = "高亮的文字 {",
+            ["page"] = 9,
+            ["pos0"] = "9.1",
+        },
+        [2] = {
+            ["datetime"] = "2023-05-01 09:00:00",
+            ["notes"] = "第三页 \"引用\" \\",
+            ["page"] = 3,
+        },
+    },
+    ["doc_pages"] = 120,
+    ["last_page"] = 42,
+    ["percent_finished"] = 0.35,
+    ["readermenu"] = {
+        ["font_size"] = 22,
+    },
+}
+"#;
+
+    #[test]
+    fn parses_legacy_bookmarks_and_skips_highlights() {
+        let record = KoreaderSidecar::parse(&lua_table::parse(LEGACY).unwrap()).unwrap();
+        assert_eq!(record.page, 41);
+        assert_eq!(record.page_count, 120);
+        assert_eq!(record.bookmarks, vec![SyncBookmark { page: 2, note: r#"第三页 "引用" \"#.into() }]);
+    }
+
+    #[test]
+    fn parses_annotations() {
+        let content = r#"return {
+            ["annotations"] = {
+                { ["page"] = 5, ["note"] = "note", ["datetime"] = "2024-02-01 10:00:00" },
+                { ["page"] = 7, ["pos0"] = { ["x"] = 1 }, ["text"] = "highlight" },
+            },
+            ["percent_finished"] = 0.5,
+            ["doc_pages"] = 10,
+        }"#;
+        let record = KoreaderSidecar::parse(&lua_table::parse(content).unwrap()).unwrap();
+        assert_eq!(record.page, 4);
+        assert_eq!(record.bookmarks, vec![SyncBookmark { page: 4, note: "note".into() }]);
+    }
+
+    #[test]
+    fn merge_keeps_koreader_fields() {
+        let mut table = lua_table::parse(LEGACY).unwrap();
+        let record = SyncRecord {
+            page: 59,
+            page_count: 120,
+            bookmarks: vec![
+                SyncBookmark { page: 2, note: "改过的备注".into() },
+                SyncBookmark { page: 19, note: String::new() },
+            ],
+            sha256: "ab".repeat(32),
+        };
+        KoreaderSidecar::merge(&mut table, &record);
+        let table = lua_table::parse(&lua_table::render(&table)).unwrap();
+
+        let font_size = table.get("readermenu").and_then(LuaValue::as_table).and_then(|menu| menu.get("font_size"));
+        assert_eq!(font_size.and_then(LuaValue::as_f64), Some(22.0));
+        let parsed = KoreaderSidecar::parse(&table).unwrap();
+        assert_eq!(parsed.page, 59);
+        assert_eq!(parsed.sha256, record.sha256);
+        // 高亮仍在，旧格式按页码降序
+        let pages: Vec<f64> = table.get("bookmarks").and_then(LuaValue::as_table).unwrap()
+            .items()
+            .filter_map(|item| item.as_table()?.get("page")?.as_f64())
+            .collect();
+        assert_eq!(pages, vec![20.0, 9.0, 3.0]);
+        assert_eq!(parsed.bookmarks, vec![
+            SyncBookmark { page: 19, note: String::new() },
+            SyncBookmark { page: 2, note: "改过的备注".into() },
+        ]);
+    }
+
+    #[test]
+    fn new_sidecar_round_trips() {
+        let record = SyncRecord {
+            page: 3,
+            page_count: 8,
+            bookmarks: vec![SyncBookmark { page: 1, note: "a\nb".into() }],
+            sha256: String::new(),
+        };
+        let mut table = LuaTable::default();
+        KoreaderSidecar::merge(&mut table, &record);
+        let parsed = KoreaderSidecar::parse(&lua_table::parse(&lua_table::render(&table)).unwrap()).unwrap();
+        assert_eq!(parsed, record);
+    }
+}
@@ -0,0 +1,471 @@
+use std::fmt::Write as _;
+
+/// Lua 表中的值。数字保留原文，写回时格式不变
+#[derive(Clone, Debug, PartialEq)]
+pub enum LuaValue {
+    Nil,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Table(LuaTable),
+}
+
+impl LuaValue {
+    pub fn number(value: impl ToString) -> Self {
+        LuaValue::Number(value.to_string())
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            LuaValue::Number(raw) => match raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+                Some(hex) => i64::from_str_radix(hex, 16).ok().map(|n| n as f64),
+                None => raw.parse().ok(),
+            },
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            LuaValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&LuaTable> {
+        match self {
+            LuaValue::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    pub fn as_table_mut(&mut self) -> Option<&mut LuaTable> {
+        match self {
+            LuaValue::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LuaKey {
+    Index(i64),
+    Name(String),
+}
+
+/// 保持原有顺序的 Lua 表，只支持 KOReader 元数据用到的语法：
+/// 字符串/整数键、字符串、数字、布尔、nil 和嵌套表
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LuaTable {
+    pub entries: Vec<(LuaKey, LuaValue)>,
+}
+
+impl LuaTable {
+    pub fn get(&self, name: &str) -> Option<&LuaValue> {
+        self.entries.iter().find(|(key, _)| matches!(key, LuaKey::Name(n) if n == name)).map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut LuaValue> {
+        self.entries.iter_mut().find(|(key, _)| matches!(key, LuaKey::Name(n) if n == name)).map(|(_, value)| value)
+    }
+
+    /// 已有的键原地替换，没有时追加到末尾
+    pub fn set(&mut self, name: &str, value: LuaValue) {
+        match self.get_mut(name) {
+            Some(existing) => *existing = value,
+            None => self.entries.push((LuaKey::Name(name.to_string()), value)),
+        }
+    }
+
+    /// 数组部分（整数键）的值，按表中的顺序
+    pub fn items(&self) -> impl Iterator<Item = &LuaValue> {
+        self.entries.iter().filter(|(key, _)| matches!(key, LuaKey::Index(_))).map(|(_, value)| value)
+    }
+
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut LuaValue> {
+        self.entries.iter_mut().filter(|(key, _)| matches!(key, LuaKey::Index(_))).map(|(_, value)| value)
+    }
+
+    /// 追加到数组末尾，下标为现有最大下标加一
+    pub fn push(&mut self, value: LuaValue) {
+        let next = self.entries.iter()
+            .filter_map(|(key, _)| match key {
+                LuaKey::Index(index) => Some(*index),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0) + 1;
+        self.entries.push((LuaKey::Index(next), value));
+    }
+}
+
+/// 解析 `return { ... }` 形式的文件，允许前后有注释
+pub fn parse(content: &str) -> Result<LuaTable, String> {
+    let mut parser = Parser { src: content.as_bytes(), pos: 0 };
+    parser.skip_space()?;
+    if parser.peek_word() == Some("return") {
+        parser.pos += "return".len();
+    }
+    parser.skip_space()?;
+    let table = parser.parse_table()?;
+    parser.skip_space()?;
+    if parser.pos < parser.src.len() {
+        return Err(parser.error("unexpected content after table"));
+    }
+    Ok(table)
+}
+
+/// 写成 KOReader 的格式，每层缩进四个空格
+pub fn render(table: &LuaTable) -> String {
+    let mut out = String::from("-- we can read Lua syntax here!\nreturn ");
+    render_table(&mut out, table, 0);
+    out.push('\n');
+    out
+}
+
+fn render_table(out: &mut String, table: &LuaTable, depth: usize) {
+    if table.entries.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+    out.push_str("{\n");
+    let indent = "    ".repeat(depth + 1);
+    for (key, value) in &table.entries {
+        out.push_str(&indent);
+        match key {
+            LuaKey::Index(index) => { let _ = write!(out, "[{}] = ", index); }
+            LuaKey::Name(name) => { let _ = write!(out, "[{}] = ", quote(name)); }
+        }
+        match value {
+            LuaValue::Nil => out.push_str("nil"),
+            LuaValue::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            LuaValue::Number(raw) => out.push_str(raw),
+            LuaValue::String(text) => out.push_str(&quote(text)),
+            LuaValue::Table(table) => render_table(out, table, depth + 1),
+        }
+        out.push_str(",\n");
+    }
+    out.push_str(&"    ".repeat(depth));
+    out.push('}');
+}
+
+fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // 三位十进制，后面跟数字时也不会有歧义
+            c if c.is_ascii_control() => { let _ = write!(out, "\\{:03}", c as u32); }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {}", message, self.pos)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn peek_word(&self) -> Option<&str> {
+        let rest = &self.src[self.pos..];
+        let len = rest.iter().take_while(|c| c.is_ascii_alphanumeric() || **c == b'_').count();
+        if len == 0 || rest[0].is_ascii_digit() {
+            return None;
+        }
+        std::str::from_utf8(&rest[..len]).ok()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_space()?;
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected '{}'", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// 跳过空白、行注释和 --[[ ]] 块注释
+    fn skip_space(&mut self) -> Result<(), String> {
+        loop {
+            while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            if !self.src[self.pos..].starts_with(b"--") {
+                return Ok(());
+            }
+            self.pos += 2;
+            if let Some(level) = self.long_bracket_level() {
+                self.long_bracket(level)?;
+            } else {
+                while self.peek().is_some_and(|c| c != b'\n') {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// 当前位置是 [[ 或 [==[ 时返回等号个数
+    fn long_bracket_level(&self) -> Option<usize> {
+        let rest = &self.src[self.pos..];
+        if rest.first() != Some(&b'[') {
+            return None;
+        }
+        let level = rest[1..].iter().take_while(|c| **c == b'=').count();
+        (rest.get(level + 1) == Some(&b'[')).then_some(level)
+    }
+
+    /// 读取长括号中的内容，开头的换行不算
+    fn long_bracket(&mut self, level: usize) -> Result<String, String> {
+        self.pos += level + 2;
+        if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+        let close = format!("]{}]", "=".repeat(level));
+        let start = self.pos;
+        let len = self.src[start..]
+            .windows(close.len())
+            .position(|window| window == close.as_bytes())
+            .ok_or_else(|| self.error("unterminated long bracket"))?;
+        self.pos = start + len + close.len();
+        Ok(String::from_utf8_lossy(&self.src[start..start + len]).into_owned())
+    }
+
+    fn parse_value(&mut self) -> Result<LuaValue, String> {
+        self.skip_space()?;
+        match self.peek() {
+            Some(b'{') => self.parse_table().map(LuaValue::Table),
+            Some(quote @ (b'"' | b'\'')) => self.parse_string(quote).map(LuaValue::String),
+            Some(b'[') if self.long_bracket_level().is_some() => {
+                let level = self.long_bracket_level().unwrap_or(0);
+                self.long_bracket(level).map(LuaValue::String)
+            }
+            Some(c) if c == b'-' || c == b'.' || c.is_ascii_digit() => self.parse_number(),
+            _ => match self.peek_word() {
+                Some("true") => { self.pos += 4; Ok(LuaValue::Bool(true)) }
+                Some("false") => { self.pos += 5; Ok(LuaValue::Bool(false)) }
+                Some("nil") => { self.pos += 3; Ok(LuaValue::Nil) }
+                _ => Err(self.error("unexpected value")),
+            },
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<LuaValue, String> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let hex = self.src[self.pos..].starts_with(b"0x") || self.src[self.pos..].starts_with(b"0X");
+        while let Some(c) = self.peek() {
+            let exponent_sign = (c == b'+' || c == b'-')
+                && !hex
+                && matches!(self.src.get(self.pos - 1), Some(b'e' | b'E'));
+            if c.is_ascii_alphanumeric() || c == b'.' || exponent_sign {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let raw = String::from_utf8_lossy(&self.src[start..self.pos]).into_owned();
+        let value = LuaValue::Number(raw);
+        if value.as_f64().is_none() {
+            return Err(self.error("invalid number"));
+        }
+        Ok(value)
+    }
+
+    fn parse_string(&mut self, quote: u8) -> Result<String, String> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match c {
+                c if c == quote => break,
+                b'\n' => return Err(self.error("unterminated string")),
+                b'\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => bytes.push(b'\n'),
+                        b't' => bytes.push(b'\t'),
+                        b'r' => bytes.push(b'\r'),
+                        b'a' => bytes.push(0x07),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'v' => bytes.push(0x0b),
+                        // %q 把换行写成反斜杠加真正的换行
+                        b'\n' => bytes.push(b'\n'),
+                        b'\\' | b'"' | b'\'' => bytes.push(escaped),
+                        b'z' => {
+                            while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                                self.pos += 1;
+                            }
+                        }
+                        b'x' => {
+                            let hex = self.src.get(self.pos..self.pos + 2).ok_or_else(|| self.error("invalid escape"))?;
+                            let value = std::str::from_utf8(hex).ok()
+                                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error("invalid escape"))?;
+                            bytes.push(value);
+                            self.pos += 2;
+                        }
+                        b'u' => {
+                            if self.peek() != Some(b'{') {
+                                return Err(self.error("invalid escape"));
+                            }
+                            self.pos += 1;
+                            let start = self.pos;
+                            while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                                self.pos += 1;
+                            }
+                            let code = std::str::from_utf8(&self.src[start..self.pos]).ok()
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid escape"))?;
+                            if self.peek() != Some(b'}') {
+                                return Err(self.error("invalid escape"));
+                            }
+                            self.pos += 1;
+                            let mut buffer = [0u8; 4];
+                            bytes.extend_from_slice(code.encode_utf8(&mut buffer).as_bytes());
+                        }
+                        c if c.is_ascii_digit() => {
+                            // \ddd 最多三位十进制
+                            let start = self.pos - 1;
+                            while self.pos < start + 3 && self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                                self.pos += 1;
+                            }
+                            let value = std::str::from_utf8(&self.src[start..self.pos]).ok()
+                                .and_then(|digits| digits.parse::<u8>().ok())
+                                .ok_or_else(|| self.error("invalid escape"))?;
+                            bytes.push(value);
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                c => bytes.push(c),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn parse_table(&mut self) -> Result<LuaTable, String> {
+        self.expect(b'{')?;
+        let mut table = LuaTable::default();
+        let mut next_index = 1;
+        loop {
+            self.skip_space()?;
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(table);
+            }
+
+            let key = if self.peek() == Some(b'[') && self.long_bracket_level().is_none() {
+                self.pos += 1;
+                let key = match self.parse_value()? {
+                    LuaValue::String(name) => LuaKey::Name(name),
+                    LuaValue::Number(raw) => LuaKey::Index(raw.parse().map_err(|_| self.error("unsupported table key"))?),
+                    _ => return Err(self.error("unsupported table key")),
+                };
+                self.expect(b']')?;
+                self.expect(b'=')?;
+                key
+            } else if let Some(name) = self.peek_word().filter(|word| !matches!(*word, "true" | "false" | "nil")) {
+                let name = name.to_string();
+                self.pos += name.len();
+                self.expect(b'=')?;
+                LuaKey::Name(name)
+            } else {
+                let key = LuaKey::Index(next_index);
+                next_index += 1;
+                key
+            };
+            let value = self.parse_value()?;
+            table.entries.push((key, value));
+
+            self.skip_space()?;
+            match self.peek() {
+                Some(b',' | b';') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_koreader_style_table() {
+        let table = parse(r#"-- we can read Lua syntax here!
+return {
+    ["bookmarks"] = {
+        [1] = {
+            ["notes"] = "a } b { \"quoted\" \\ end",
+            ["page"] = 12,
+        },
+    },
+    ["percent_finished"] = 0.25,
+    ["highlighted"] = true, -- 行尾注释
+    plain = 'single',
+    [2] = -1.5e-3,
+    ["multi"] = "line\
+next\0489",
+}
+"#).unwrap();
+        let bookmark = table.get("bookmarks").and_then(LuaValue::as_table).unwrap().items().next().unwrap().as_table().unwrap();
+        assert_eq!(bookmark.get("notes").and_then(LuaValue::as_str), Some(r#"a } b { "quoted" \ end"#));
+        assert_eq!(bookmark.get("page").and_then(LuaValue::as_f64), Some(12.0));
+        assert_eq!(table.get("percent_finished").and_then(LuaValue::as_f64), Some(0.25));
+        assert_eq!(table.get("highlighted").and_then(LuaValue::as_bool), Some(true));
+        assert_eq!(table.get("plain").and_then(LuaValue::as_str), Some("single"));
+        assert_eq!(table.items().next().and_then(LuaValue::as_f64), Some(-0.0015));
+        assert_eq!(table.get("multi").and_then(LuaValue::as_str), Some("line\nnext09"));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        let mut inner = LuaTable::default();
+        inner.set("note", LuaValue::String("换行\n引号\" 反斜杠\\ 控制\u{1}2".into()));
+        inner.set("page", LuaValue::number(3));
+        let mut table = LuaTable::default();
+        table.push(LuaValue::Table(inner));
+        table.set("empty", LuaValue::Table(LuaTable::default()));
+        table.set("flag", LuaValue::Bool(false));
+        let text = render(&table);
+        assert_eq!(parse(&text).unwrap(), table);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse("return { [\"a\"] = }").is_err());
+        assert!(parse("return { [\"a\"] = \"open }").is_err());
+        assert!(parse("return { [\"a\"] = 1 ").is_err());
+        assert!(parse("return { [\"a\"] = 1 } trailing").is_err());
+    }
+}
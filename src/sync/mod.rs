@@ -0,0 +1,37 @@
+//! 与其他阅读器交换阅读位置和书签。目前只支持 KOReader 的侧车文件；
+//! Xodo 只能导出 XFDF 批注，Adobe Digital Editions 的 .annot 以 EPUB CFI/pdfloc 定位，
+//! 都没有能和页码对应的书签，暂不支持
+
+pub mod koreader;
+pub mod lua_table;
+
+pub use koreader::KoreaderSidecar;
+
+/// 与其他阅读器交换的书签
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncBookmark {
+    /// 页码，从 0 开始
+    pub page: usize,
+    pub note: String,
+}
+
+/// 与其他阅读器交换的阅读状态
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncRecord {
+    /// 当前页，从 0 开始
+    pub page: usize,
+    pub page_count: usize,
+    pub bookmarks: Vec<SyncBookmark>,
+    /// 文档的 SHA-256，用来确认记录属于同一个文件，为空表示未知
+    pub sha256: String,
+}
+
+impl SyncRecord {
+    /// 阅读进度 0.0 ~ 1.0
+    pub fn percent_finished(&self) -> f64 {
+        if self.page_count == 0 {
+            return 0.0;
+        }
+        ((self.page + 1) as f64 / self.page_count as f64).min(1.0)
+    }
+}
@@ -1,15 +1,22 @@
-use anyhow::Result;
 use log::{debug, info};
 use crossbeam_channel::{unbounded, Sender, Receiver};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::process;
 use regex::Regex;
 
+use crate::error::{RReaderError, Result};
+
+use crate::settings::AppSettings;
+
 pub enum TtsTask {
     SpeakText {
         text: String,
+        /// 文本语言，用于选择对应语音，None 时使用当前语音
+        language: Option<String>,
     },
     Stop,
     SetVoice {
@@ -20,7 +27,7 @@ pub enum TtsTask {
 
 struct TtsState {
     task_rx: Receiver<TtsTask>,
-    speech_queue: VecDeque<String>,
+    speech_queue: VecDeque<(String, Option<String>)>,
     current_voice: String,
     rate: f32,
     volume: f32,
@@ -29,24 +36,29 @@ struct TtsState {
 
 pub struct TtsService {
     task_sender: Sender<TtsTask>,
+    /// 队列中还有内容或正在朗读
+    speaking: Arc<AtomicBool>,
     thread_handle: Option<JoinHandle<()>>,
 }
 
 impl TtsService {
     pub fn new() -> Self {
         let (task_tx, task_rx) = unbounded::<TtsTask>();
+        let speaking = Arc::new(AtomicBool::new(false));
+        let speaking_for_thread = Arc::clone(&speaking);
 
         let thread_handle = thread::spawn(move || {
-            Self::tts_loop(task_rx);
+            Self::tts_loop(task_rx, speaking_for_thread);
         });
 
         Self {
             task_sender: task_tx,
+            speaking,
             thread_handle: Some(thread_handle),
         }
     }
 
-    fn tts_loop(task_rx: Receiver<TtsTask>) {
+    fn tts_loop(task_rx: Receiver<TtsTask>, speaking: Arc<AtomicBool>) {
         let mut state = TtsState {
             task_rx,
             speech_queue: VecDeque::new(),
@@ -63,13 +75,20 @@ impl TtsService {
                 }
             }
 
-            if let Some(text) = state.speech_queue.pop_front() {
-                if let Err(e) = Self::execute_speech(&text, &state.current_voice, state.rate) {
+            if let Some((text, language)) = state.speech_queue.pop_front() {
+                state.is_speaking = true;
+                speaking.store(true, Ordering::Relaxed);
+                let voice = language.as_deref()
+                    .and_then(Self::voice_for_language)
+                    .unwrap_or_else(|| state.current_voice.clone());
+                if let Err(e) = Self::execute_speech(&text, &voice, state.rate) {
                     info!("[TtsService] TTS 朗读失败: {}", e);
                 }
                 continue;
             }
 
+            state.is_speaking = false;
+            speaking.store(false, Ordering::Relaxed);
             match state.task_rx.recv() {
                 Ok(task) => {
                     if Self::handle_task(task, &mut state) {
@@ -86,9 +105,9 @@ impl TtsService {
 
     fn handle_task(task: TtsTask, state: &mut TtsState) -> bool {
         match task {
-            TtsTask::SpeakText { text } => {
-                debug!("[TtsService] 收到朗读任务: {}", text);
-                state.speech_queue.push_back(text);
+            TtsTask::SpeakText { text, language } => {
+                debug!("[TtsService] 收到朗读任务: {:?}, {}", language, text);
+                state.speech_queue.push_back((text, language));
                 false
             }
             TtsTask::Stop => {
@@ -139,11 +158,11 @@ impl TtsService {
                 process::Command::new("powershell")
                     .args([
                         "-Command",
-                        &format!("Add-Type -AssemblyName System.Speech; $synth = New-Object System.Speech.Synthesis.SpeechSynthesizer; $synth.SelectVoice('{}'); $synth.Rate = {}; $synth.Volume = {}; $synth.Speak('{}'); $synth.Dispose()", voice, 0, 80, escaped_text)
+                        &format!("Add-Type -AssemblyName System.Speech; $synth = New-Object System.Speech.Synthesis.SpeechSynthesizer; $synth.SelectVoice('{}'); $synth.Rate = {}; $synth.Volume = {}; $synth.Speak('{}'); $synth.Dispose()", voice.replace("'", "''"), 0, 80, escaped_text)
                     ])
                     .status()
             } else {
-                return Err(anyhow::anyhow!("Unsupported platform"));
+                return Err(RReaderError::Tts("Unsupported platform".to_string()));
             };
 
             match status {
@@ -162,7 +181,64 @@ impl TtsService {
             }
         }
 
-        Err(anyhow::anyhow!("All TTS variants failed"))
+        Err(RReaderError::Tts("All TTS variants failed".to_string()))
+    }
+
+    /// 文本合成为音频文件（不播放），返回实际写出的文件路径
+    /// macOS 输出 aiff，其他平台输出 wav，扩展名由本函数决定；voice 为 None 时使用系统默认语音
+    pub fn synthesize_to_file(text: &str, voice: Option<&str>, rate: f32, output: &Path) -> Result<PathBuf> {
+        let text = Self::clean_text_for_tts(text);
+        if text.is_empty() {
+            return Err(RReaderError::Tts("No text to synthesize".to_string()));
+        }
+        let rate_value = (rate * 400.0).clamp(100.0, 500.0) as i32;
+
+        let (output, status) = if cfg!(target_os = "macos") {
+            let output = output.with_extension("aiff");
+            let mut command = process::Command::new("say");
+            if let Some(voice) = voice {
+                command.args(["-v", voice]);
+            }
+            let status = command
+                .args(["-r", &rate_value.to_string(), "-o"])
+                .arg(&output)
+                .arg(&text)
+                .status();
+            (output, status)
+        } else if cfg!(target_os = "windows") {
+            let output = output.with_extension("wav");
+            let escaped_text = text.replace("'", "''");
+            let escaped_path = output.to_string_lossy().replace("'", "''");
+            let select_voice = voice
+                .map(|voice| format!("$synth.SelectVoice('{}'); ", voice.replace("'", "''")))
+                .unwrap_or_default();
+            let status = process::Command::new("powershell")
+                .args([
+                    "-Command",
+                    &format!("Add-Type -AssemblyName System.Speech; $synth = New-Object System.Speech.Synthesis.SpeechSynthesizer; {}$synth.SetOutputToWaveFile('{}'); $synth.Speak('{}'); $synth.Dispose()", select_voice, escaped_path, escaped_text)
+                ])
+                .status();
+            (output, status)
+        } else {
+            // Linux 等平台使用 espeak-ng，voice 为语言代码
+            let output = output.with_extension("wav");
+            let mut command = process::Command::new("espeak-ng");
+            if let Some(voice) = voice {
+                command.args(["-v", voice]);
+            }
+            let status = command
+                .args(["-s", &(rate_value / 2).to_string(), "-w"])
+                .arg(&output)
+                .arg(&text)
+                .status();
+            (output, status)
+        };
+
+        match status {
+            Ok(s) if s.success() => Ok(output),
+            Ok(s) => Err(RReaderError::Tts(format!("Synthesis failed with code: {}", s.code().unwrap_or(-1)))),
+            Err(e) => Err(RReaderError::Tts(format!("Failed to start synthesizer: {}", e))),
+        }
     }
 
     fn clean_text_for_tts(text: &str) -> String {
@@ -207,8 +283,22 @@ impl TtsService {
             .join(" ")
     }
 
+    /// 按语言查找设置中配置的语音
+    pub fn voice_for_language(language: &str) -> Option<String> {
+        AppSettings::get().tts.voices.get(language).cloned()
+    }
+
     pub fn speak_text(&self, text: String) {
-        let _ = self.task_sender.send(TtsTask::SpeakText { text });
+        let _ = self.task_sender.send(TtsTask::SpeakText { text, language: None });
+    }
+
+    /// 朗读指定语言的文本，自动切换到该语言的语音
+    pub fn speak_text_with_language(&self, text: String, language: Option<String>) {
+        let _ = self.task_sender.send(TtsTask::SpeakText { text, language });
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaking.load(Ordering::Relaxed)
     }
 
     pub fn stop_speaking(&self) {
@@ -0,0 +1,146 @@
+use glow::HasContext;
+use log::{debug, info, warn};
+use std::cell::{Cell, RefCell};
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+
+/// 缓存已释放、但页面模型里可能还有图像引用的纹理
+static PENDING_DELETE: Mutex<Vec<NonZeroU32>> = Mutex::new(Vec::new());
+/// 页面模型已替换、不再被引用的纹理，下一帧渲染前删除
+static RELEASED: Mutex<Vec<NonZeroU32>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CONTEXT: RefCell<Option<GpuContext>> = const { RefCell::new(None) };
+    /// 只有在渲染回调里 OpenGL 上下文才是当前的
+    static IN_FRAME: Cell<bool> = const { Cell::new(false) };
+}
+
+struct GpuContext {
+    gl: glow::Context,
+    max_size: u32,
+}
+
+/// 缓存中页面图像对应的纹理，丢弃时排队，等页面模型替换后才删除
+pub struct GpuTexture {
+    id: NonZeroU32,
+}
+
+impl Drop for GpuTexture {
+    fn drop(&mut self) {
+        PENDING_DELETE.lock().unwrap().push(self.id);
+    }
+}
+
+/// 解码后的页面直接上传为 OpenGL 纹理，交给 Slint 借用，
+/// 不再在每次刷新时经过 CPU 像素缓冲；软件渲染等后端下自动退回普通图像
+pub struct GpuTextures;
+
+impl GpuTextures {
+    /// 安装渲染回调；on_frame 在每帧渲染前调用，此时可以 upload
+    pub fn install(window: &slint::Window, mut on_frame: impl FnMut() + 'static) {
+        let result = window.set_rendering_notifier(move |state, api| match state {
+            slint::RenderingState::RenderingSetup => Self::setup(api),
+            slint::RenderingState::BeforeRendering => {
+                Self::delete_pending();
+                IN_FRAME.with(|in_frame| in_frame.set(true));
+                on_frame();
+                IN_FRAME.with(|in_frame| in_frame.set(false));
+            }
+            slint::RenderingState::RenderingTeardown => {
+                info!("[Texture] 渲染上下文销毁");
+                CONTEXT.with(|context| *context.borrow_mut() = None);
+                PENDING_DELETE.lock().unwrap().clear();
+                RELEASED.lock().unwrap().clear();
+            }
+            _ => {}
+        });
+        if let Err(e) = result {
+            info!("[Texture] 当前渲染后端不支持纹理共享，使用 CPU 图像: {:?}", e);
+        }
+    }
+
+    /// 页面模型换成了缓存中现有的图像，此前释放的纹理不再被引用，可以删除
+    pub fn models_replaced() {
+        let pending = std::mem::take(&mut *PENDING_DELETE.lock().unwrap());
+        RELEASED.lock().unwrap().extend(pending);
+    }
+
+    /// 是否可以上传纹理
+    pub fn is_active() -> bool {
+        CONTEXT.with(|context| context.borrow().is_some())
+    }
+
+    /// 把预乘 alpha 的 RGBA 像素上传为纹理；不在渲染回调中、没有 OpenGL 或尺寸超限时返回 None
+    pub fn upload(pixels: &[u8], width: u32, height: u32) -> Option<(slint::Image, GpuTexture)> {
+        if !IN_FRAME.with(Cell::get) {
+            return None;
+        }
+        CONTEXT.with(|context| {
+            let context = context.borrow();
+            let context = context.as_ref()?;
+            if width == 0 || height == 0 || width > context.max_size || height > context.max_size {
+                return None;
+            }
+            let gl = &context.gl;
+            unsafe {
+                let texture = gl.create_texture().ok()?;
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+                gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::RGBA as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    glow::PixelUnpackData::Slice(Some(pixels)),
+                );
+                gl.bind_texture(glow::TEXTURE_2D, None);
+
+                let texture = GpuTexture { id: texture.0 };
+                let image = slint::BorrowedOpenGLTextureBuilder::new_gl_2d_rgba_texture(
+                    texture.id,
+                    (width, height).into(),
+                )
+                .build();
+                Some((image, texture))
+            }
+        })
+    }
+
+    fn setup(api: &slint::GraphicsAPI) {
+        let slint::GraphicsAPI::NativeOpenGL { get_proc_address } = api else {
+            info!("[Texture] 非 OpenGL 渲染，使用 CPU 图像");
+            return;
+        };
+        let gl = unsafe { glow::Context::from_loader_function_cstr(|name| get_proc_address(name)) };
+        let max_size = unsafe { gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) }.max(0) as u32;
+        info!("[Texture] 启用 OpenGL 纹理上传，最大尺寸 {}", max_size);
+        CONTEXT.with(|context| *context.borrow_mut() = Some(GpuContext { gl, max_size }));
+    }
+
+    /// 删除不再被页面模型引用的纹理；缓存刚释放、模型还没替换的要等到 models_replaced 之后
+    fn delete_pending() {
+        let pending = std::mem::take(&mut *RELEASED.lock().unwrap());
+        if pending.is_empty() {
+            return;
+        }
+        CONTEXT.with(|context| {
+            let context = context.borrow();
+            let Some(context) = context.as_ref() else {
+                warn!("[Texture] 没有渲染上下文，丢弃 {} 个待删除纹理", pending.len());
+                return;
+            };
+            debug!("[Texture] 删除 {} 个纹理", pending.len());
+            for id in pending {
+                unsafe { context.gl.delete_texture(glow::NativeTexture(id)) };
+            }
+        });
+    }
+}
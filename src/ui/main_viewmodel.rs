@@ -1,9 +1,14 @@
-use crate::dao::RecentDao;
+use crate::dao::{AnnotationDao, BookmarkDao, RecentDao};
+use crate::decoder::DocumentMetadata;
 use crate::entity::Recent;
 use crate::entity::recent::ActiveModel;
+use crate::error::Result;
+use crate::page::VisibleRange;
+use crate::settings::AppSettings;
+use std::path::Path;
 use std::time::SystemTime;
 use log::debug;
-use sea_orm::ActiveValue;
+use sea_orm::{ActiveValue, DbErr};
 
 pub const PAGE_SIZE: usize = 16;
 
@@ -29,12 +34,17 @@ impl MainViewmodel {
     }
 
     /// 加载历史记录，可分页，按update_at倒序
-    pub fn load_history(&mut self, page: usize) -> Result<(), Box<dyn std::error::Error>> {
-        let all_recent = RecentDao::find_all_ordered_by_update_at_desc_sync()?;
+    pub fn load_history(&mut self, page: usize) -> Result<()> {
+        // 归入“已读完”的书在首页单独列出，不占书架
+        let all_recent: Vec<Recent> = RecentDao::find_all_ordered_by_update_at_desc_sync()?
+            .into_iter()
+            .filter(|recent| !recent.is_archived())
+            .collect();
         self.total_records = all_recent.len();
         self.page_index = page;
 
-        let start = page * PAGE_SIZE;
+        // 删除记录后当前页可能已经不存在
+        let start = (page * PAGE_SIZE).min(all_recent.len());
         let end = (start + PAGE_SIZE).min(all_recent.len());
         self.current_page_records = all_recent[start..end].to_vec();
 
@@ -73,7 +83,7 @@ impl MainViewmodel {
     }
 
     /// 下一页
-    pub fn next_page(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn next_page(&mut self) -> Result<()> {
         if self.has_next_page() {
             self.load_history(self.page_index + 1)?;
         }
@@ -81,7 +91,7 @@ impl MainViewmodel {
     }
 
     /// 上一页
-    pub fn prev_page(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn prev_page(&mut self) -> Result<()> {
         if self.has_prev_page() {
             self.load_history(self.page_index - 1)?;
         }
@@ -89,12 +99,12 @@ impl MainViewmodel {
     }
 
     /// 获取指定路径的记录
-    pub fn get_recent_by_path(&self, path: &str) -> Result<Option<Recent>, Box<dyn std::error::Error>> {
+    pub fn get_recent_by_path(&self, path: &str) -> Result<Option<Recent>> {
         RecentDao::find_by_path_sync(path)
     }
 
     /// 更新指定路径的阅读次数
-    pub fn update_read_times(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn update_read_times(&self, path: &str) -> Result<()> {
         if let Some(mut rec) = RecentDao::find_by_path_sync(path)? {
             rec.read_times += 1;
             let now = SystemTime::now()
@@ -111,20 +121,28 @@ impl MainViewmodel {
         Ok(())
     }
 
-    /// 更新指定路径的状态（页面、缩放、滚动位置），同时更新阅读次数和更新时间
-    pub fn update_recent_with_state(&self, path: &str, page: Option<usize>, zoom: f32, scroll_x: f32, scroll_y: f32) -> Result<(), Box<dyn std::error::Error>> {
+    /// 更新指定路径的状态（锚点页和页内位置、缩放、滚动位置、进度），同时更新阅读次数和更新时间
+    pub fn update_recent_with_state(&self, path: &str, visible: &VisibleRange, page_count: usize, zoom: f32, scroll_x: f32, scroll_y: f32) -> Result<()> {
         if let Some(mut rec) = RecentDao::find_by_path_sync(path)? {
-            let page_val = page.map(|p| (p + 1) as i32).unwrap_or(rec.page); // 如果没有提供页面，使用当前值
+            // 没有可见页面时保留原来的位置
+            let (page_val, anchor_ratio) = match visible.anchor() {
+                Some(page) => ((page + 1) as i32, visible.anchor_ratio),
+                None => (rec.page, rec.anchor_ratio),
+            };
             let read_times = rec.read_times + 1; // 增加阅读次数
             let now = SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)?
                 .as_millis() as i64;
+            let page_count = page_count as i32;
             let active = ActiveModel {
                 id: ActiveValue::Set(rec.id),
                 page: ActiveValue::Set(page_val),
+                page_count: ActiveValue::Set(page_count),
+                progress: ActiveValue::Set(Recent::compute_progress(page_val, page_count)),
                 zoom: ActiveValue::Set(zoom),
                 scroll_x: ActiveValue::Set(scroll_x as i32),
                 scroll_y: ActiveValue::Set(scroll_y as i32),
+                anchor_ratio: ActiveValue::Set(anchor_ratio),
                 read_times: ActiveValue::Set(read_times),
                 update_at: ActiveValue::Set(now),
                 ..Default::default()
@@ -134,12 +152,179 @@ impl MainViewmodel {
         Ok(())
     }
 
+    /// 放回“打开最近”菜单
+    pub fn mark_recent(&self, path: &str) -> Result<()> {
+        let active = ActiveModel {
+            in_recent: ActiveValue::Set(1),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 查找同一本书在其他路径下的记录
+    pub fn find_same_content(&self, path: &str, content_hash: &str) -> Result<Option<Recent>> {
+        RecentDao::find_by_content_hash_sync(content_hash, path)
+    }
+
+    /// 补上旧记录缺少的内容hash
+    pub fn set_content_hash(&self, path: &str, content_hash: &str) -> Result<()> {
+        let active = ActiveModel {
+            content_hash: ActiveValue::Set(content_hash.to_string()),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 打开时记下页数、文件大小和文档自带的书名、作者、主题；
+    /// 书名只替换默认的文件名，作者和主题只补空缺，不覆盖手动修正过的信息
+    pub fn set_document_info(&self, path: &str, metadata: &DocumentMetadata, page_count: usize, size: u64) -> Result<()> {
+        let Some(rec) = RecentDao::find_by_path_sync(path)? else { return Ok(()) };
+        let file = Path::new(path);
+        let default_name = rec.name.is_empty()
+            || [file.file_name(), file.file_stem()].into_iter().flatten().any(|name| name.to_string_lossy() == rec.name);
+
+        let mut active = ActiveModel {
+            page_count: ActiveValue::Set(page_count as i32),
+            size: ActiveValue::Set(size as i64),
+            ..Default::default()
+        };
+        if let Some(title) = metadata.display_title().filter(|_| default_name) {
+            active.name = ActiveValue::Set(title.to_string());
+        }
+        if rec.author.is_empty() && !metadata.author.is_empty() {
+            active.author = ActiveValue::Set(metadata.author.clone());
+        }
+        if rec.subject.is_empty() && !metadata.subject.is_empty() {
+            active.subject = ActiveValue::Set(metadata.subject.clone());
+        }
+        debug!("[MainViewmodel] {} 文档信息: {:?}", path, metadata);
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 同一文件在其他路径下的记录，按 SHA-256 比较，最近阅读的在前
+    pub fn find_same_file(&self, path: &str, sha256: &str) -> Result<Vec<Recent>> {
+        RecentDao::find_by_sha256_sync(sha256, path)
+    }
+
+    /// 保存后台算好的 SHA-256
+    pub fn set_sha256(&self, path: &str, sha256: &str) -> Result<()> {
+        let active = ActiveModel {
+            sha256: ActiveValue::Set(sha256.to_string()),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 首页“继续阅读”：最近真正读过且未读完的书；书库扫描加入的书 read_times 为 0，不算读过
+    pub fn continue_reading(&self, limit: usize) -> Result<Vec<Recent>> {
+        Ok(RecentDao::find_all_ordered_by_update_at_desc_sync()?
+            .into_iter()
+            .filter(|recent| recent.read_times > 0 && !recent.is_finished())
+            .take(limit)
+            .collect())
+    }
+
+    /// 首页“收藏”：收藏的书，最近阅读的在前
+    pub fn favorites(&self) -> Result<Vec<Recent>> {
+        Ok(RecentDao::find_all_ordered_by_update_at_desc_sync()?
+            .into_iter()
+            .filter(|recent| recent.favorited > 0)
+            .collect())
+    }
+
+    /// 首页“最近添加”：按加入书架的时间倒序
+    pub fn recently_added(&self, limit: usize) -> Result<Vec<Recent>> {
+        let mut records = RecentDao::find_all_ordered_by_update_at_desc_sync()?;
+        records.sort_by(|a, b| b.create_at.cmp(&a.create_at));
+        records.truncate(limit);
+        Ok(records)
+    }
+
+    /// 首页“已读完”：归档的书，最近读完的在前
+    pub fn finished(&self) -> Result<Vec<Recent>> {
+        RecentDao::find_archived_sync()
+    }
+
+    /// 首页“随便翻翻”：从没读完的书里挑一本，同一天内固定，避免每次刷新首页都换
+    pub fn random_pick(&self) -> Result<Option<Recent>> {
+        let mut candidates: Vec<Recent> = RecentDao::find_all_ordered_by_update_at_desc_sync()?
+            .into_iter()
+            .filter(|recent| !recent.is_finished())
+            .collect();
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+        candidates.sort_by_key(|recent| recent.id);
+        let day = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() / 86400;
+        // 乘一个大奇数打散相邻的日期
+        let index = (day.wrapping_mul(2654435761) % candidates.len() as u64) as usize;
+        Ok(Some(candidates.swap_remove(index)))
+    }
+
+    /// 快捷动作“随便挑一本”：随机一本还没读过的书
+    pub fn random_unread(&self) -> Result<Option<Recent>> {
+        RecentDao::find_random_unread_sync()
+    }
+
+    /// 快捷动作“捡起搁置最久的书”：最久没读的在读的书
+    pub fn oldest_in_progress(&self) -> Result<Option<Recent>> {
+        RecentDao::find_oldest_in_progress_sync()
+    }
+
+    /// 收藏或取消收藏
+    pub fn set_favorited(&self, path: &str, favorited: bool) -> Result<()> {
+        let active = ActiveModel {
+            favorited: ActiveValue::Set(favorited as i32),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)
+    }
+
+    /// 沿用副本的阅读记录：进度、缩放、阅读方式、手动切边、收藏、单本书设置，以及书签和高亮；
+    /// 书签和高亮是复制，副本原来的记录保留
+    pub fn adopt_recent(&self, path: &str, source: &Recent) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as i64;
+        let active = ActiveModel {
+            page: ActiveValue::Set(source.page),
+            page_count: ActiveValue::Set(source.page_count),
+            progress: ActiveValue::Set(source.progress),
+            crop: ActiveValue::Set(source.crop),
+            reflow: ActiveValue::Set(source.reflow),
+            scroll_ori: ActiveValue::Set(source.scroll_ori),
+            zoom: ActiveValue::Set(source.zoom),
+            scroll_x: ActiveValue::Set(source.scroll_x),
+            scroll_y: ActiveValue::Set(source.scroll_y),
+            anchor_ratio: ActiveValue::Set(source.anchor_ratio),
+            read_times: ActiveValue::Set(source.read_times),
+            favorited: ActiveValue::Set(source.favorited),
+            finished_at: ActiveValue::Set(source.finished_at),
+            crop_margins: ActiveValue::Set(source.crop_margins.clone()),
+            update_at: ActiveValue::Set(now),
+            ..Default::default()
+        };
+        RecentDao::update_by_path_sync(path, active)?;
+        AppSettings::copy_book(&source.book_path, path);
+        let bookmarks = BookmarkDao::copy_to_book_sync(&source.book_path, path)?;
+        let annotations = AnnotationDao::copy_to_book_sync(&source.book_path, path)?;
+        debug!("[MainViewmodel] {} 沿用 {} 的记录: {} 个书签, {} 处高亮", path, source.book_path, bookmarks, annotations);
+        Ok(())
+    }
+
+    /// 重新打开回收站中的书时恢复记录
+    pub fn restore_recent(&self, id: i32) -> Result<()> {
+        RecentDao::restore_sync(&[id])
+    }
+
     /// 添加新记录（打开文档时调用）
-    pub fn add_recent(&self, new_recent: ActiveModel) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn add_recent(&self, new_recent: ActiveModel) -> Result<()> {
         // 从 ActiveModel 中获取 book_path
         let book_path = match new_recent.book_path {
             ActiveValue::Set(ref path) => path.clone(),
-            _ => return Err("book_path must be set".into()),
+            _ => return Err(DbErr::Custom("book_path must be set".to_string()).into()),
         };
 
         // 先查找是否已存在
@@ -1,4 +1,10 @@
+#[cfg(feature = "gui")]
+pub mod gpu_texture;
+#[cfg(feature = "gui")]
 pub mod main_viewmodel;
 pub mod utils;
 
+#[cfg(feature = "gui")]
+pub use gpu_texture::{GpuTexture, GpuTextures};
+#[cfg(feature = "gui")]
 pub use main_viewmodel::MainViewmodel;
\ No newline at end of file
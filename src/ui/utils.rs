@@ -1,6 +1,12 @@
-use dirs;
+use crate::app_paths;
+use crate::library::CustomCover;
+use sha2::{Digest, Sha256};
 use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
 use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+#[cfg(feature = "gui")]
 use slint::{Image, SharedPixelBuffer};
 
 // 生成简单hash用于缓存图片名
@@ -10,20 +16,79 @@ pub fn generate_thumbnail_hash(path: &str) -> u64 {
     hasher.finish()
 }
 
+/// 文件内容采样大小：头部和尾部各读取这么多字节
+const CONTENT_SAMPLE_SIZE: u64 = 1024 * 1024;
+
+/// 采样内容的 SHA-256（文件大小加头尾各1MB），同一本书换了路径结果不变。
+/// 不用 DefaultHasher：它的算法可能随 Rust 版本改变，存进数据库的值会失效
+fn sampled_sha256(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let mut buffer = Vec::with_capacity(CONTENT_SAMPLE_SIZE as usize);
+    (&mut file).take(CONTENT_SAMPLE_SIZE).read_to_end(&mut buffer)?;
+    hasher.update(&buffer);
+
+    if size > CONTENT_SAMPLE_SIZE * 2 {
+        buffer.clear();
+        file.seek(SeekFrom::End(-(CONTENT_SAMPLE_SIZE as i64)))?;
+        file.take(CONTENT_SAMPLE_SIZE).read_to_end(&mut buffer)?;
+        hasher.update(&buffer);
+    }
+
+    Ok(hasher.finalize().to_vec())
+}
+
+/// 根据文件内容生成hash，用于缓存文件名
+pub fn generate_content_hash(path: &Path) -> std::io::Result<u64> {
+    let digest = sampled_sha256(path)?;
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// 内容hash的字符串形式，用于数据库记录：64 个十六进制字符，和旧版本 16 个字符的 DefaultHasher 值不会混淆
+pub fn content_hash_string(path: &Path) -> std::io::Result<String> {
+    Ok(sampled_sha256(path)?.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 整个文件的 SHA-256，大文件较慢，应在后台线程调用
+pub fn sha256_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// 整个文件的 SHA-256（小写十六进制）；打开的文档用 ChecksumCache，不重复计算
+pub fn sha256_hex(path: &Path) -> std::io::Result<String> {
+    Ok(sha256_file(path)?.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// RGBA 像素反色（夜间模式），保留 alpha
+pub fn invert_rgba(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel[0] = 255 - pixel[0];
+        pixel[1] = 255 - pixel[1];
+        pixel[2] = 255 - pixel[2];
+    }
+}
+
 // 获取缓存缩略图路径
 pub fn get_thumbnail_path(book_path: &str) -> String {
-    if let Some(data_dir) = dirs::data_dir() {
-        let cache_dir = data_dir.join("RReader").join("images");
-        let hash = generate_thumbnail_hash(book_path);
-        let cache_path = cache_dir.join(format!("{}.png", hash));
-        //log::info!("[Thumbnail] expected cache_path: {:?}, exists: {}", cache_path, cache_path.exists());
-        if cache_path.exists() {
-            cache_path.to_string_lossy().to_string()
-        } else {
-            "".to_string()
-        }
+    // 自定义封面优先
+    let custom_path = CustomCover::path(book_path);
+    if custom_path.is_file() {
+        return custom_path.to_string_lossy().to_string();
+    }
+    let hash = generate_thumbnail_hash(book_path);
+    let cache_path = app_paths::thumbnail_dir().join(format!("{}.png", hash));
+    //log::info!("[Thumbnail] expected cache_path: {:?}, exists: {}", cache_path, cache_path.exists());
+    if cache_path.exists() {
+        cache_path.to_string_lossy().to_string()
     } else {
-        //log::warn!("[Thumbnail] data_dir is None for: {:?}", book_path);
         "".to_string()
     }
 }
@@ -40,6 +105,7 @@ impl CachedImageData {
         Self { data, width, height }
     }
 
+    #[cfg(feature = "gui")]
     fn to_slint_image(&self) -> Image {
         let shared_buffer = SharedPixelBuffer::clone_from_slice(
             &self.data,